@@ -76,6 +76,8 @@ pub fn generate_ts(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
     ServerRequest::export_all_to(out_dir)?;
     export_server_responses(out_dir)?;
     ServerNotification::export_all_to(out_dir)?;
+    // Error payloads are not reachable from any request or response type.
+    <crate::ConfigWriteErrorData as ::ts_rs::TS>::export_all_to(out_dir)?;
 
     generate_index_ts(out_dir)?;
     generate_index_ts(&v2_out_dir)?;
@@ -122,6 +124,7 @@ pub fn generate_json(out_dir: &Path) -> Result<()> {
         |d| write_json_schema_with_return::<crate::protocol::v1::InputItem>(d, "InputItem"),
         |d| write_json_schema_with_return::<ParsedCommand>(d, "ParsedCommand"),
         |d| write_json_schema_with_return::<SandboxPolicy>(d, "SandboxPolicy"),
+        |d| write_json_schema_with_return::<crate::ConfigWriteErrorData>(d, "ConfigWriteErrorData"),
     ];
 
     let mut schemas: Vec<GeneratedSchema> = Vec::new();
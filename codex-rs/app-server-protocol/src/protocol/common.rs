@@ -11,13 +11,18 @@ use crate::protocol::v1;
 use crate::protocol::v2;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::ApprovalRule;
+use codex_protocol::protocol::ElicitationAction;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxCommandAssessment;
-use paste::paste;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::RequestId as McpRequestId;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value as JsonValue;
 use strum_macros::Display;
 use ts_rs::TS;
 
@@ -125,10 +130,22 @@ client_request_definitions! {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadSearch => "thread/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
     ThreadCompact => "thread/compact" {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadFork => "thread/fork" {
+        params: v2::ThreadForkParams,
+        response: v2::ThreadForkResponse,
+    },
+    ThreadRollback => "thread/rollback" {
+        params: v2::ThreadRollbackParams,
+        response: v2::ThreadRollbackResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -137,6 +154,10 @@ client_request_definitions! {
         params: v2::TurnInterruptParams,
         response: v2::TurnInterruptResponse,
     },
+    ReviewStart => "review/start" {
+        params: v2::ReviewStartParams,
+        response: v2::ReviewStartResponse,
+    },
 
     ModelList => "model/list" {
         params: v2::ModelListParams,
@@ -163,6 +184,36 @@ client_request_definitions! {
         response: v2::GetAccountRateLimitsResponse,
     },
 
+    ConfigRead => "config/read" {
+        params: v2::ConfigReadParams,
+        response: v2::ConfigReadResponse,
+    },
+    ConfigWrite => "config/write" {
+        params: v2::ConfigWriteParams,
+        response: v2::ConfigWriteResponse,
+    },
+
+    McpServerList => "mcpServer/list" {
+        params: v2::McpServerListParams,
+        response: v2::McpServerListResponse,
+    },
+    McpServerAdd => "mcpServer/add" {
+        params: v2::McpServerAddParams,
+        response: v2::McpServerAddResponse,
+    },
+    McpServerRemove => "mcpServer/remove" {
+        params: v2::McpServerRemoveParams,
+        response: v2::McpServerRemoveResponse,
+    },
+    McpServerLogin => "mcpServer/login" {
+        params: v2::McpServerLoginParams,
+        response: v2::McpServerLoginResponse,
+    },
+    McpServerSetEnabled => "mcpServer/setEnabled" {
+        params: v2::McpServerSetEnabledParams,
+        response: v2::McpServerSetEnabledResponse,
+    },
+
     FeedbackUpload => "feedback/upload" {
         params: v2::FeedbackUploadParams,
         response: v2::FeedbackUploadResponse,
@@ -173,6 +224,15 @@ client_request_definitions! {
         response: v2::GetAccountResponse,
     },
 
+    ApprovalRuleList => "approvalRule/list" {
+        params: v2::ApprovalRuleListParams,
+        response: v2::ApprovalRuleListResponse,
+    },
+    ApprovalRuleRemove => "approvalRule/remove" {
+        params: v2::ApprovalRuleRemoveParams,
+        response: v2::ApprovalRuleRemoveResponse,
+    },
+
     /// DEPRECATED APIs below
     NewConversation {
         params: v1::NewConversationParams,
@@ -277,34 +337,36 @@ macro_rules! server_request_definitions {
     (
         $(
             $(#[$variant_meta:meta])*
-            $variant:ident
+            $variant:ident $(=> $wire:literal)? {
+                params: $params:ty,
+                response: $response:ty,
+            }
         ),* $(,)?
     ) => {
-        paste! {
-            /// Request initiated from the server and sent to the client.
-            #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
-            #[serde(tag = "method", rename_all = "camelCase")]
-            pub enum ServerRequest {
-                $(
-                    $(#[$variant_meta])*
-                    $variant {
-                        #[serde(rename = "id")]
-                        request_id: RequestId,
-                        params: [<$variant Params>],
-                    },
-                )*
-            }
+        /// Request initiated from the server and sent to the client.
+        #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+        #[serde(tag = "method", rename_all = "camelCase")]
+        pub enum ServerRequest {
+            $(
+                $(#[$variant_meta])*
+                $(#[serde(rename = $wire)] #[ts(rename = $wire)])?
+                $variant {
+                    #[serde(rename = "id")]
+                    request_id: RequestId,
+                    params: $params,
+                },
+            )*
+        }
 
-            #[derive(Debug, Clone, PartialEq, JsonSchema)]
-            pub enum ServerRequestPayload {
-                $( $variant([<$variant Params>]), )*
-            }
+        #[derive(Debug, Clone, PartialEq, JsonSchema)]
+        pub enum ServerRequestPayload {
+            $( $variant($params), )*
+        }
 
-            impl ServerRequestPayload {
-                pub fn request_with_id(self, request_id: RequestId) -> ServerRequest {
-                    match self {
-                        $(Self::$variant(params) => ServerRequest::$variant { request_id, params },)*
-                    }
+        impl ServerRequestPayload {
+            pub fn request_with_id(self, request_id: RequestId) -> ServerRequest {
+                match self {
+                    $(Self::$variant(params) => ServerRequest::$variant { request_id, params },)*
                 }
             }
         }
@@ -312,9 +374,7 @@ macro_rules! server_request_definitions {
         pub fn export_server_responses(
             out_dir: &::std::path::Path,
         ) -> ::std::result::Result<(), ::ts_rs::ExportError> {
-            paste! {
-                $(<[<$variant Response>] as ::ts_rs::TS>::export_all_to(out_dir)?;)*
-            }
+            $(<$response as ::ts_rs::TS>::export_all_to(out_dir)?;)*
             Ok(())
         }
 
@@ -323,9 +383,7 @@ macro_rules! server_request_definitions {
             out_dir: &Path,
         ) -> ::anyhow::Result<Vec<GeneratedSchema>> {
             let mut schemas = Vec::new();
-            paste! {
-                $(schemas.push(crate::export::write_json_schema::<[<$variant Response>]>(out_dir, stringify!([<$variant Response>]))?);)*
-            }
+            $(schemas.push(crate::export::write_json_schema::<$response>(out_dir, stringify!($response))?);)*
             Ok(schemas)
         }
 
@@ -334,9 +392,7 @@ macro_rules! server_request_definitions {
             out_dir: &Path,
         ) -> ::anyhow::Result<Vec<GeneratedSchema>> {
             let mut schemas = Vec::new();
-            paste! {
-                $(schemas.push(crate::export::write_json_schema::<[<$variant Params>]>(out_dir, stringify!([<$variant Params>]))?);)*
-            }
+            $(schemas.push(crate::export::write_json_schema::<$params>(out_dir, stringify!($params))?);)*
             Ok(schemas)
         }
     };
@@ -426,10 +482,42 @@ impl TryFrom<JSONRPCRequest> for ServerRequest {
 }
 
 server_request_definitions! {
+    /// NEW APIs
+    /// Request to approve a command before it runs.
+    CommandExecutionRequestApproval => "item/commandExecution/requestApproval" {
+        params: v2::CommandExecutionRequestApprovalParams,
+        response: v2::CommandExecutionRequestApprovalResponse,
+    },
+    /// Request to approve a file change before it is applied.
+    FileChangeRequestApproval => "item/fileChange/requestApproval" {
+        params: v2::FileChangeRequestApprovalParams,
+        response: v2::FileChangeRequestApprovalResponse,
+    },
+
+    /// Request to answer an MCP server's elicitation.
+    McpElicitation {
+        params: McpElicitationParams,
+        response: McpElicitationResponse,
+    },
+    /// Request to approve an MCP server's sampling request.
+    McpSamplingApproval {
+        params: McpSamplingApprovalParams,
+        response: McpSamplingApprovalResponse,
+    },
+
+    /// DEPRECATED APIs below
     /// Request to approve a patch.
-    ApplyPatchApproval,
+    /// DEPRECATED in favor of FileChangeRequestApproval
+    ApplyPatchApproval {
+        params: ApplyPatchApprovalParams,
+        response: ApplyPatchApprovalResponse,
+    },
     /// Request to exec a command.
-    ExecCommandApproval,
+    /// DEPRECATED in favor of CommandExecutionRequestApproval
+    ExecCommandApproval {
+        params: ExecCommandApprovalParams,
+        response: ExecCommandApprovalResponse,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -464,6 +552,11 @@ pub struct ExecCommandApprovalParams {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 pub struct ExecCommandApprovalResponse {
     pub decision: ReviewDecision,
+    /// When set alongside an approving decision, the rule is saved for the
+    /// project so that matching commands are approved without prompting in
+    /// future sessions.
+    #[serde(default, rename = "persistRule")]
+    pub persist_rule: Option<ApprovalRule>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -471,6 +564,46 @@ pub struct ApplyPatchApprovalResponse {
     pub decision: ReviewDecision,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct McpElicitationParams {
+    pub conversation_id: ConversationId,
+    /// Name of the MCP server that issued the request.
+    pub server_name: String,
+    /// Id the MCP server assigned to the request.
+    pub request_id: McpRequestId,
+    /// Human-readable message explaining what is being requested.
+    pub message: String,
+    /// Flat JSON schema describing the values the server wants back.
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct McpElicitationResponse {
+    pub action: ElicitationAction,
+    /// Values for the requested form when `action` is `accept`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub content: Option<JsonValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSamplingApprovalParams {
+    pub conversation_id: ConversationId,
+    /// Name of the MCP server that issued the request.
+    pub server_name: String,
+    /// Id the MCP server assigned to the request.
+    pub request_id: McpRequestId,
+    /// The messages, system prompt and limits the server wants sampled.
+    pub request: CreateMessageRequestParams,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct McpSamplingApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(rename_all = "camelCase")]
@@ -513,6 +646,8 @@ server_notification_definitions! {
     #[ts(rename = "account/login/completed")]
     #[strum(serialize = "account/login/completed")]
     AccountLoginCompleted(v2::AccountLoginCompletedNotification),
+    McpServerLoginAuthorizationUrl => "mcpServer/login/authorizationUrl" (v2::McpServerLoginAuthorizationUrlNotification),
+    McpServerLoginCompleted => "mcpServer/login/completed" (v2::McpServerLoginCompletedNotification),
 
     /// DEPRECATED NOTIFICATIONS below
     AuthStatusChange(v1::AuthStatusChangeNotification),
@@ -531,9 +666,13 @@ mod tests {
     use super::*;
     use anyhow::Result;
     use codex_protocol::account::PlanType;
+    use codex_protocol::parse_command::ParsedCommand;
     use codex_protocol::protocol::AskForApproval;
+    use codex_protocol::protocol::FileChange;
+    use codex_protocol::protocol::ReviewDecision;
     use pretty_assertions::assert_eq;
     use serde_json::json;
+    use std::path::Path;
 
     #[test]
     fn serialize_new_conversation() -> Result<()> {
@@ -656,6 +795,175 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialize_v2_approval_requests() -> Result<()> {
+        let request = ServerRequest::CommandExecutionRequestApproval {
+            request_id: RequestId::Integer(10),
+            params: v2::CommandExecutionRequestApprovalParams {
+                thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+                turn_id: "1".to_string(),
+                item_id: "call-42".to_string(),
+                command: vec!["echo".to_string(), "hello".to_string()],
+                cwd: PathBuf::from("/tmp"),
+                reason: None,
+                risk: None,
+                parsed_cmd: vec![ParsedCommand::Unknown {
+                    cmd: "echo hello".to_string(),
+                }],
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "item/commandExecution/requestApproval",
+                "id": 10,
+                "params": {
+                    "threadId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "turnId": "1",
+                    "itemId": "call-42",
+                    "command": ["echo", "hello"],
+                    "cwd": "/tmp",
+                    "reason": null,
+                    "risk": null,
+                    "parsedCmd": [
+                        {
+                            "type": "unknown",
+                            "cmd": "echo hello"
+                        }
+                    ]
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+
+        let request = ServerRequest::FileChangeRequestApproval {
+            request_id: RequestId::Integer(11),
+            params: v2::FileChangeRequestApprovalParams {
+                thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+                turn_id: "1".to_string(),
+                item_id: "call-43".to_string(),
+                changes: vec![v2::FileUpdateChange::from_core(
+                    Path::new("/tmp/a.txt"),
+                    FileChange::Update {
+                        unified_diff: "@@ -1 +1 @@\n-a\n+b\n".to_string(),
+                        move_path: Some(PathBuf::from("/tmp/b.txt")),
+                        mode: Some(0o755),
+                    },
+                )],
+                reason: None,
+                grant_root: None,
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "item/fileChange/requestApproval",
+                "id": 11,
+                "params": {
+                    "threadId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "turnId": "1",
+                    "itemId": "call-43",
+                    "changes": [
+                        {
+                            "path": "/tmp/a.txt",
+                            "movePath": "/tmp/b.txt",
+                            "kind": "update",
+                            "diff": "@@ -1 +1 @@\n-a\n+b\n",
+                            "mode": 0o755
+                        }
+                    ],
+                    "reason": null,
+                    "grantRoot": null
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+
+        let response: v2::CommandExecutionRequestApprovalResponse =
+            serde_json::from_value(json!({ "decision": "approvedForSession" }))?;
+        assert_eq!(
+            response.decision.to_core(),
+            ReviewDecision::ApprovedForSession
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_mcp_elicitation_request() -> Result<()> {
+        let conversation_id = ConversationId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+        let request = ServerRequest::McpElicitation {
+            request_id: RequestId::Integer(8),
+            params: McpElicitationParams {
+                conversation_id,
+                server_name: "deploy".to_string(),
+                request_id: McpRequestId::Integer(3),
+                message: "Deploy now?".to_string(),
+                requested_schema: ElicitRequestParamsRequestedSchema {
+                    properties: json!({ "approved": { "type": "boolean" } }),
+                    required: Some(vec!["approved".to_string()]),
+                    r#type: "object".to_string(),
+                },
+            },
+        };
+
+        assert_eq!(
+            json!({
+                "method": "mcpElicitation",
+                "id": 8,
+                "params": {
+                    "conversationId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "serverName": "deploy",
+                    "requestId": 3,
+                    "message": "Deploy now?",
+                    "requestedSchema": {
+                        "properties": { "approved": { "type": "boolean" } },
+                        "required": ["approved"],
+                        "type": "object"
+                    }
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_mcp_sampling_approval_request() -> Result<()> {
+        let conversation_id = ConversationId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+        let request = ServerRequest::McpSamplingApproval {
+            request_id: RequestId::Integer(9),
+            params: McpSamplingApprovalParams {
+                conversation_id,
+                server_name: "notes".to_string(),
+                request_id: McpRequestId::Integer(4),
+                request: serde_json::from_value(json!({
+                    "messages": [
+                        { "role": "user", "content": { "type": "text", "text": "Summarize" } }
+                    ],
+                    "maxTokens": 64
+                }))?,
+            },
+        };
+
+        assert_eq!(
+            json!({
+                "method": "mcpSamplingApproval",
+                "id": 9,
+                "params": {
+                    "conversationId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "serverName": "notes",
+                    "requestId": 4,
+                    "request": {
+                        "messages": [
+                            { "role": "user", "content": { "type": "text", "text": "Summarize" } }
+                        ],
+                        "maxTokens": 64
+                    }
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_get_account_rate_limits() -> Result<()> {
         let request = ClientRequest::GetAccountRateLimits {
@@ -795,4 +1103,89 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn serialize_review_start() -> Result<()> {
+        let request = ClientRequest::ReviewStart {
+            request_id: RequestId::Integer(7),
+            params: v2::ReviewStartParams {
+                thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+                target: v2::ReviewTarget::BaseBranch {
+                    branch: "main".to_string(),
+                },
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "review/start",
+                "id": 7,
+                "params": {
+                    "threadId": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+                    "target": {
+                        "type": "baseBranch",
+                        "branch": "main"
+                    }
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_config_write() -> Result<()> {
+        let request = ClientRequest::ConfigWrite {
+            request_id: RequestId::Integer(8),
+            params: v2::ConfigWriteParams {
+                edits: vec![v2::ConfigValueEdit {
+                    key_path: "profiles.fast.model".to_string(),
+                    value: json!("gpt-5-codex"),
+                }],
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "config/write",
+                "id": 8,
+                "params": {
+                    "edits": [{
+                        "keyPath": "profiles.fast.model",
+                        "value": "gpt-5-codex"
+                    }]
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_mcp_server_add() -> Result<()> {
+        let request = ClientRequest::McpServerAdd {
+            request_id: RequestId::Integer(9),
+            params: v2::McpServerAddParams {
+                name: "docs".to_string(),
+                transport: v2::McpServerTransport::StreamableHttp {
+                    url: "https://example.com/mcp".to_string(),
+                    bearer_token_env_var: Some("DOCS_TOKEN".to_string()),
+                },
+            },
+        };
+        assert_eq!(
+            json!({
+                "method": "mcpServer/add",
+                "id": 9,
+                "params": {
+                    "name": "docs",
+                    "transport": {
+                        "type": "streamableHttp",
+                        "url": "https://example.com/mcp",
+                        "bearerTokenEnvVar": "DOCS_TOKEN"
+                    }
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
 }
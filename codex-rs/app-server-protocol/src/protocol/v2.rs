@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::protocol::common::AuthMode;
@@ -6,8 +7,12 @@ use codex_protocol::ConversationId;
 use codex_protocol::account::PlanType;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::FileChange as CoreFileChange;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
+use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::SandboxCommandAssessment;
 use codex_protocol::user_input::UserInput as CoreUserInput;
 use mcp_types::ContentBlock as McpContentBlock;
 use schemars::JsonSchema;
@@ -53,13 +58,28 @@ v2_enum_from_core!(
     }
 );
 
+v2_enum_from_core!(
+    pub enum ApprovalRuleKind from codex_protocol::protocol::ApprovalRuleKind {
+        Exact, Prefix, Program
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ApprovalDecision from codex_protocol::protocol::ReviewDecision {
+        Approved, ApprovedForSession, Denied, Abort
+    }
+);
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(tag = "mode", rename_all = "camelCase")]
 #[ts(tag = "mode")]
 #[ts(export_to = "v2/")]
 pub enum SandboxPolicy {
     DangerFullAccess,
-    ReadOnly,
+    ReadOnly {
+        #[serde(default)]
+        deny_read: Vec<PathBuf>,
+    },
     WorkspaceWrite {
         #[serde(default)]
         writable_roots: Vec<PathBuf>,
@@ -69,6 +89,10 @@ pub enum SandboxPolicy {
         exclude_tmpdir_env_var: bool,
         #[serde(default)]
         exclude_slash_tmp: bool,
+        #[serde(default)]
+        deny_read: Vec<PathBuf>,
+        #[serde(default)]
+        network_allowlist: Vec<String>,
     },
 }
 
@@ -78,17 +102,25 @@ impl SandboxPolicy {
             SandboxPolicy::DangerFullAccess => {
                 codex_protocol::protocol::SandboxPolicy::DangerFullAccess
             }
-            SandboxPolicy::ReadOnly => codex_protocol::protocol::SandboxPolicy::ReadOnly,
+            SandboxPolicy::ReadOnly { deny_read } => {
+                codex_protocol::protocol::SandboxPolicy::ReadOnly {
+                    deny_read: deny_read.clone(),
+                }
+            }
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                deny_read,
+                network_allowlist,
             } => codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots: writable_roots.clone(),
                 network_access: *network_access,
                 exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                 exclude_slash_tmp: *exclude_slash_tmp,
+                deny_read: deny_read.clone(),
+                network_allowlist: network_allowlist.clone(),
             },
         }
     }
@@ -100,17 +132,23 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
             codex_protocol::protocol::SandboxPolicy::DangerFullAccess => {
                 SandboxPolicy::DangerFullAccess
             }
-            codex_protocol::protocol::SandboxPolicy::ReadOnly => SandboxPolicy::ReadOnly,
+            codex_protocol::protocol::SandboxPolicy::ReadOnly { deny_read } => {
+                SandboxPolicy::ReadOnly { deny_read }
+            }
             codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                deny_read,
+                network_allowlist,
             } => SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
+                deny_read,
+                network_allowlist,
             },
         }
     }
@@ -265,6 +303,365 @@ pub struct FeedbackUploadResponse {
     pub thread_id: String,
 }
 
+// === Config ===
+/// Configuration layer a value was read from. Later layers take precedence,
+/// except that `managed` settings always win.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ConfigLayerSource {
+    /// Built-in default.
+    Default,
+    /// Default implied by the `[projects]` trust entry for the working directory.
+    Project,
+    /// `$CODEX_HOME/config.toml`.
+    User,
+    /// The active `[profiles.<name>]` table.
+    Profile,
+    /// `-c key=value` passed to the app server.
+    CliOverride,
+    /// Administrator-managed configuration.
+    Managed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigReadParams {
+    /// Working directory used to resolve the project layer. Defaults to the
+    /// server's working directory.
+    pub cwd: Option<PathBuf>,
+    /// Profile to resolve instead of the configured `profile`.
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigReadResponse {
+    pub config: EffectiveConfig,
+    /// Layer each effective value came from, keyed by its `config.toml` key
+    /// path, e.g. `model`, `features.web_search_request` or `mcp_servers.docs`.
+    pub origins: HashMap<String, ConfigLayerSource>,
+}
+
+/// Effective configuration after all layers are applied.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct EffectiveConfig {
+    pub model: String,
+    pub model_provider: String,
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+    pub approval_policy: AskForApproval,
+    pub sandbox_mode: SandboxMode,
+    /// Name of the active profile, if any.
+    pub profile: Option<String>,
+    /// Every defined profile, as written in `config.toml`.
+    pub profiles: HashMap<String, JsonValue>,
+    /// Configured MCP servers, as written in `config.toml`.
+    pub mcp_servers: HashMap<String, JsonValue>,
+    /// Whether each known feature flag is enabled.
+    pub features: HashMap<String, bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigWriteParams {
+    /// Changes to `config.toml`, applied together or not at all.
+    pub edits: Vec<ConfigValueEdit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigValueEdit {
+    /// Dotted `config.toml` key path, e.g. `model`, `profiles.fast.model`,
+    /// `sandbox_workspace_write.network_access`, `mcp_servers.docs` or
+    /// `features.web_search_request`. Segments containing dots are quoted as
+    /// in TOML: `mcp_servers."docs.internal".enabled`.
+    pub key_path: String,
+    /// New value; `null` removes the key.
+    pub value: JsonValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigWriteResponse {}
+
+/// `data` of the error returned when `config/write` rejects its edits.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigWriteErrorData {
+    pub errors: Vec<ConfigFieldError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigFieldError {
+    pub key_path: String,
+    pub message: String,
+}
+
+// === MCP servers ===
+v2_enum_from_core!(
+    pub enum McpAuthStatus from codex_protocol::protocol::McpAuthStatus {
+        Unsupported, NotLoggedIn, BearerToken, OAuth
+    }
+);
+
+/// How Codex connects to an MCP server.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum McpServerTransport {
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Stdio {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        env: Option<HashMap<String, String>>,
+        #[serde(default)]
+        cwd: Option<PathBuf>,
+    },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    StreamableHttp {
+        url: String,
+        /// Environment variable holding a bearer token sent with each request.
+        #[serde(default)]
+        bearer_token_env_var: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum McpServerStatus {
+    /// Started and listed its tools.
+    Ready,
+    /// Failed to start; see `error`.
+    Failed,
+    /// Disabled in `config.toml`, so not started.
+    Disabled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServer {
+    pub name: String,
+    pub transport: McpServerTransport,
+    pub enabled: bool,
+    pub status: McpServerStatus,
+    /// Tools exposed after `enabled_tools`/`disabled_tools` filtering; 0
+    /// unless `status` is `ready`.
+    pub tool_count: u32,
+    pub error: Option<String>,
+    pub auth_status: McpAuthStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerListParams {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerListResponse {
+    pub data: Vec<McpServer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerAddParams {
+    pub name: String,
+    pub transport: McpServerTransport,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerAddResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerRemoveParams {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerRemoveResponse {
+    pub removed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerSetEnabledParams {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerSetEnabledResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerLoginParams {
+    pub name: String,
+    /// OAuth scopes to request; the server's defaults when empty.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// Sent once the login flow has started; the URL arrives in
+/// `mcpServer/login/authorizationUrl` and the outcome in
+/// `mcpServer/login/completed`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerLoginResponse {}
+
+// === Approval rules ===
+/// A saved "always allow" rule that approves matching commands in a project
+/// without prompting.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRule {
+    pub kind: ApprovalRuleKind,
+    pub command: Vec<String>,
+}
+
+impl ApprovalRule {
+    pub fn into_core(self) -> codex_protocol::protocol::ApprovalRule {
+        codex_protocol::protocol::ApprovalRule {
+            kind: self.kind.to_core(),
+            command: self.command,
+        }
+    }
+}
+
+impl From<codex_protocol::protocol::ApprovalRule> for ApprovalRule {
+    fn from(value: codex_protocol::protocol::ApprovalRule) -> Self {
+        Self {
+            kind: value.kind.into(),
+            command: value.command,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRuleListParams {
+    /// Any path inside the project whose rules should be listed. When
+    /// omitted, the rules for every project are returned.
+    pub project: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProjectApprovalRules {
+    /// Root of the project the rules apply to.
+    pub project: PathBuf,
+    pub rules: Vec<ApprovalRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRuleListResponse {
+    pub data: Vec<ProjectApprovalRules>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRuleRemoveParams {
+    /// Any path inside the project the rule was saved for.
+    pub project: PathBuf,
+    pub rule: ApprovalRule,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ApprovalRuleRemoveResponse {
+    /// `false` when no matching rule was saved for the project.
+    pub removed: bool,
+}
+
+// === Approval requests (server -> client) ===
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandExecutionRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Id of the `commandExecution` item the command runs as.
+    pub item_id: String,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    /// Optional explanation from the agent, e.g. why it needs to leave the sandbox.
+    pub reason: Option<String>,
+    /// Assessment of what the command does and how risky it is, when available.
+    pub risk: Option<SandboxCommandAssessment>,
+    pub parsed_cmd: Vec<ParsedCommand>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandExecutionRequestApprovalResponse {
+    pub decision: ApprovalDecision,
+    /// When set alongside an approving decision, the rule is saved for the
+    /// project so that matching commands are approved without prompting in
+    /// future sessions.
+    #[serde(default)]
+    pub persist_rule: Option<ApprovalRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileChangeRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Id of the `fileChange` item the patch is applied as.
+    pub item_id: String,
+    pub changes: Vec<FileUpdateChange>,
+    /// Optional explanation from the agent, e.g. why it needs extra write access.
+    pub reason: Option<String>,
+    /// When set, the agent is asking to allow writes under this root for the
+    /// rest of the session.
+    pub grant_root: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileChangeRequestApprovalResponse {
+    pub decision: ApprovalDecision,
+}
+
 // === Threads, Turns, and Items ===
 // Thread APIs
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -337,6 +734,55 @@ pub struct ThreadListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Whitespace-separated terms; every term must appear somewhere in a
+    /// thread's messages, commands or touched files for it to match.
+    pub query: String,
+    /// Optional maximum number of results; defaults to a reasonable server-side value.
+    pub limit: Option<u32>,
+    /// Optional provider filter; when set, only sessions recorded under these
+    /// providers are returned. When present but empty, includes all providers.
+    pub model_providers: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, best match first.
+    pub data: Vec<ThreadSearchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResult {
+    pub thread: Thread,
+    /// A few of the records that matched the query.
+    pub matches: Vec<ThreadSearchMatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchMatch {
+    pub kind: ThreadSearchMatchKind,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ThreadSearchMatchKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    File,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -349,6 +795,47 @@ pub struct ThreadCompactParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadCompactResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkParams {
+    pub thread_id: String,
+    /// The new thread keeps every turn before this one, so the client can
+    /// send an edited version of it. The original thread is left unchanged.
+    pub turn_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkResponse {
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRollbackParams {
+    pub thread_id: String,
+    /// Number of turns to drop from the end of the thread.
+    pub num_turns: u32,
+    /// Also restore the working tree to the ghost snapshot taken before the
+    /// oldest dropped turn. Requires the `ghost_commit` feature.
+    #[serde(default)]
+    pub restore_files: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRollbackResponse {
+    /// The rolled back thread. Like a fork, it gets a new id and the original
+    /// thread is left unchanged.
+    pub thread: Thread,
+    /// Id of the ghost commit the working tree was restored to, if any.
+    pub restored_snapshot: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -436,6 +923,40 @@ pub struct TurnInterruptParams {
 #[ts(export_to = "v2/")]
 pub struct TurnInterruptResponse {}
 
+// Review APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewStartParams {
+    pub thread_id: String,
+    pub target: ReviewTarget,
+}
+
+/// What to review; mirrors the TUI's `/review` presets.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum ReviewTarget {
+    /// Staged, unstaged and untracked changes in the working tree.
+    UncommittedChanges,
+    /// Changes that would be merged into `branch`.
+    BaseBranch { branch: String },
+    /// Changes introduced by a single commit.
+    Commit { sha: String, title: Option<String> },
+    /// Free-form review instructions.
+    Custom { instructions: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ReviewStartResponse {
+    /// The review turn. Its result arrives as a `codeReview` item in
+    /// `item/completed`.
+    pub turn: Turn,
+}
+
 // User input types
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -510,7 +1031,7 @@ pub enum ThreadItem {
     },
     CodeReview {
         id: String,
-        review: String,
+        review: ReviewOutputEvent,
     },
 }
 
@@ -528,8 +1049,48 @@ pub enum CommandExecutionStatus {
 #[ts(export_to = "v2/")]
 pub struct FileUpdateChange {
     pub path: String,
+    /// Destination of the file when the change also renames it.
+    pub move_path: Option<String>,
     pub kind: PatchChangeKind,
+    /// A unified diff for updates, and the whole contents for added and
+    /// deleted files (the target for symlinks). Empty for binary files and
+    /// mode changes.
     pub diff: String,
+    /// Permission bits the change sets on the file, e.g. `0o755`.
+    pub mode: Option<u32>,
+}
+
+impl FileUpdateChange {
+    pub fn from_core(path: &Path, change: CoreFileChange) -> Self {
+        let (kind, diff, move_path, mode) = match change {
+            CoreFileChange::Add { content, mode } => (PatchChangeKind::Add, content, None, mode),
+            CoreFileChange::AddBinary { mode, .. } => {
+                (PatchChangeKind::Add, String::new(), None, mode)
+            }
+            CoreFileChange::AddSymlink { target } => (
+                PatchChangeKind::Add,
+                target.to_string_lossy().into_owned(),
+                None,
+                None,
+            ),
+            CoreFileChange::Delete { content } => (PatchChangeKind::Delete, content, None, None),
+            CoreFileChange::Update {
+                unified_diff,
+                move_path,
+                mode,
+            } => (PatchChangeKind::Update, unified_diff, move_path, mode),
+            CoreFileChange::SetMode { mode } => {
+                (PatchChangeKind::Update, String::new(), None, Some(mode))
+            }
+        };
+        Self {
+            path: path.to_string_lossy().into_owned(),
+            move_path: move_path.map(|path| path.to_string_lossy().into_owned()),
+            kind,
+            diff,
+            mode,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -708,3 +1269,21 @@ pub struct AccountLoginCompletedNotification {
     pub success: bool,
     pub error: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerLoginAuthorizationUrlNotification {
+    pub name: String,
+    /// URL the client should open in a browser to authorize Codex.
+    pub authorization_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerLoginCompletedNotification {
+    pub name: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
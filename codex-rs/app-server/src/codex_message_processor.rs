@@ -1,6 +1,9 @@
+use crate::config_api;
+use crate::conversation_events::ConversationEvents;
 use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::fuzzy_file_search::run_fuzzy_file_search;
+use crate::mcp_server_api;
 use crate::models::supported_models;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
@@ -14,6 +17,12 @@ use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
 use codex_app_server_protocol::ApplyPatchApprovalResponse;
+use codex_app_server_protocol::ApprovalDecision;
+use codex_app_server_protocol::ApprovalRule;
+use codex_app_server_protocol::ApprovalRuleListParams;
+use codex_app_server_protocol::ApprovalRuleListResponse;
+use codex_app_server_protocol::ApprovalRuleRemoveParams;
+use codex_app_server_protocol::ApprovalRuleRemoveResponse;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::ArchiveConversationResponse;
 use codex_app_server_protocol::AskForApproval;
@@ -23,6 +32,10 @@ use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginChatGptResponse;
 use codex_app_server_protocol::ClientRequest;
+use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
+use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
+use codex_app_server_protocol::ConfigReadParams;
+use codex_app_server_protocol::ConfigWriteParams;
 use codex_app_server_protocol::ConversationGitInfo;
 use codex_app_server_protocol::ConversationSummary;
 use codex_app_server_protocol::ExecCommandApprovalParams;
@@ -31,6 +44,9 @@ use codex_app_server_protocol::ExecOneOffCommandParams;
 use codex_app_server_protocol::ExecOneOffCommandResponse;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::FeedbackUploadResponse;
+use codex_app_server_protocol::FileChangeRequestApprovalParams;
+use codex_app_server_protocol::FileChangeRequestApprovalResponse;
+use codex_app_server_protocol::FileUpdateChange;
 use codex_app_server_protocol::FuzzyFileSearchParams;
 use codex_app_server_protocol::FuzzyFileSearchResponse;
 use codex_app_server_protocol::GetAccountParams;
@@ -46,6 +62,7 @@ use codex_app_server_protocol::GitDiffToRemoteResponse;
 use codex_app_server_protocol::InputItem as WireInputItem;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::InterruptConversationResponse;
+use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::ListConversationsResponse;
@@ -56,16 +73,28 @@ use codex_app_server_protocol::LoginChatGptCompleteNotification;
 use codex_app_server_protocol::LoginChatGptResponse;
 use codex_app_server_protocol::LogoutAccountResponse;
 use codex_app_server_protocol::LogoutChatGptResponse;
+use codex_app_server_protocol::McpElicitationParams;
+use codex_app_server_protocol::McpElicitationResponse;
+use codex_app_server_protocol::McpSamplingApprovalParams;
+use codex_app_server_protocol::McpSamplingApprovalResponse;
+use codex_app_server_protocol::McpServerLoginParams;
+use codex_app_server_protocol::McpServerLoginResponse;
+use codex_app_server_protocol::McpServerRemoveParams;
+use codex_app_server_protocol::McpServerSetEnabledParams;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::ModelListResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
+use codex_app_server_protocol::ProjectApprovalRules;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::Result as JsonRpcResult;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::ResumeConversationResponse;
+use codex_app_server_protocol::ReviewStartParams;
+use codex_app_server_protocol::ReviewStartResponse;
+use codex_app_server_protocol::ReviewTarget;
 use codex_app_server_protocol::SandboxMode;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserMessageResponse;
@@ -79,11 +108,20 @@ use codex_app_server_protocol::SetDefaultModelResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadForkParams;
+use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadRollbackResponse;
+use codex_app_server_protocol::ThreadSearchMatch;
+use codex_app_server_protocol::ThreadSearchMatchKind;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadSearchResult;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
@@ -107,6 +145,9 @@ use codex_core::InitialHistory;
 use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
 use codex_core::SessionMeta;
+use codex_core::SessionSearchHit;
+use codex_core::SessionSearchMatchKind;
+use codex_core::approval_rules;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
 use codex_core::config::Config;
@@ -120,15 +161,24 @@ use codex_core::exec_env::create_env;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::git_info::git_diff_to_remote;
+use codex_core::mcp::manage::McpProbeCache;
 use codex_core::parse_cursor;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ElicitationAction;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_core::read_head_for_summary;
+use codex_core::review_prompts;
+use codex_core::user_message_positions;
 use codex_feedback::CodexFeedback;
+use codex_git::GhostCommit;
+use codex_git::restore_ghost_commit;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
 use codex_login::run_login_server;
@@ -143,7 +193,10 @@ use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::user_input::UserInput as CoreInputItem;
 use codex_utils_json_to_toml::json_to_toml;
+use mcp_types::RequestId as McpRequestId;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::io::Error as IoError;
 use std::path::Path;
@@ -154,7 +207,10 @@ use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::select;
 use tokio::sync::Mutex;
+use tokio::sync::broadcast;
 use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use toml::Value as TomlValue;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
@@ -183,14 +239,59 @@ pub(crate) struct CodexMessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_linux_sandbox_exe: Option<PathBuf>,
     config: Arc<Config>,
-    conversation_listeners: HashMap<Uuid, oneshot::Sender<()>>,
+    cli_overrides: Vec<(String, TomlValue)>,
+    conversation_listeners: HashMap<Uuid, ConversationListener>,
+    /// Fan-out of conversation events, shared with every other connection.
+    conversation_events: ConversationEvents,
+    /// Conversations this client started or resumed; shut down when it
+    /// disconnects unless another client is still listening.
+    owned_conversations: HashSet<ConversationId>,
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: PendingInterrupts,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    // Turn ids of each v2 thread, used to resolve `thread/fork` requests.
+    // Shared with every other connection.
+    thread_turns: SharedThreadTurns,
+    // Status of configured MCP servers, shared with every other connection.
+    mcp_probes: Arc<McpProbeCache>,
     feedback: CodexFeedback,
 }
 
+/// Task forwarding a conversation's events to this client.
+struct ConversationListener {
+    cancel: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Turns of every v2 thread that a client started, forked or resumed.
+pub(crate) type SharedThreadTurns = Arc<Mutex<HashMap<ConversationId, ThreadTurns>>>;
+
+/// Turns of a v2 thread, oldest first. Turn ids are submission ids and are not
+/// persisted, so turns loaded from an existing rollout are only counted.
+pub(crate) struct ThreadTurns {
+    loaded: usize,
+    ids: Vec<String>,
+}
+
+impl ThreadTurns {
+    fn loaded(loaded: usize) -> Self {
+        Self {
+            loaded,
+            ids: Vec::new(),
+        }
+    }
+
+    /// Index of `turn_id` among all turns of the thread, which is the
+    /// `nth_user_message` that `ConversationManager::fork_conversation` expects.
+    fn position(&self, turn_id: &str) -> Option<usize> {
+        self.ids
+            .iter()
+            .position(|id| id == turn_id)
+            .map(|idx| self.loaded + idx)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum ApiVersion {
     V1,
@@ -222,12 +323,17 @@ impl CodexMessageProcessor {
 
         Ok((conversation_id, conversation))
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         auth_manager: Arc<AuthManager>,
         conversation_manager: Arc<ConversationManager>,
+        conversation_events: ConversationEvents,
+        thread_turns: SharedThreadTurns,
         outgoing: Arc<OutgoingMessageSender>,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        cli_overrides: Vec<(String, TomlValue)>,
+        mcp_probes: Arc<McpProbeCache>,
         feedback: CodexFeedback,
     ) -> Self {
         Self {
@@ -236,10 +342,15 @@ impl CodexMessageProcessor {
             outgoing,
             codex_linux_sandbox_exe,
             config,
+            cli_overrides,
             conversation_listeners: HashMap::new(),
+            conversation_events,
+            owned_conversations: HashSet::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
+            thread_turns,
+            mcp_probes,
             feedback,
         }
     }
@@ -262,6 +373,9 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
+            ClientRequest::ThreadSearch { request_id, params } => {
+                self.thread_search(request_id, params).await;
+            }
             ClientRequest::ThreadCompact {
                 request_id,
                 params: _,
@@ -269,12 +383,21 @@ impl CodexMessageProcessor {
                 self.send_unimplemented_error(request_id, "thread/compact")
                     .await;
             }
+            ClientRequest::ThreadFork { request_id, params } => {
+                self.thread_fork(request_id, params).await;
+            }
+            ClientRequest::ThreadRollback { request_id, params } => {
+                self.thread_rollback(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
             ClientRequest::TurnInterrupt { request_id, params } => {
                 self.turn_interrupt(request_id, params).await;
             }
+            ClientRequest::ReviewStart { request_id, params } => {
+                self.review_start(request_id, params).await;
+            }
             ClientRequest::NewConversation { request_id, params } => {
                 // Do not tokio::spawn() to process new_conversation()
                 // asynchronously because we need to ensure the conversation is
@@ -305,6 +428,12 @@ impl CodexMessageProcessor {
             ClientRequest::GetAccount { request_id, params } => {
                 self.get_account(request_id, params).await;
             }
+            ClientRequest::ApprovalRuleList { request_id, params } => {
+                self.list_approval_rules(request_id, params).await;
+            }
+            ClientRequest::ApprovalRuleRemove { request_id, params } => {
+                self.remove_approval_rule(request_id, params).await;
+            }
             ClientRequest::ResumeConversation { request_id, params } => {
                 self.handle_resume_conversation(request_id, params).await;
             }
@@ -383,6 +512,37 @@ impl CodexMessageProcessor {
             } => {
                 self.get_account_rate_limits(request_id).await;
             }
+            ClientRequest::ConfigRead { request_id, params } => {
+                self.config_read(request_id, params).await;
+            }
+            ClientRequest::ConfigWrite { request_id, params } => {
+                self.config_write(request_id, params).await;
+            }
+            ClientRequest::McpServerList {
+                request_id,
+                params: _,
+            } => {
+                self.mcp_server_list(request_id).await;
+            }
+            ClientRequest::McpServerAdd { request_id, params } => {
+                let result = mcp_server_api::add_server(&self.config.codex_home, params).await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::McpServerRemove { request_id, params } => {
+                let McpServerRemoveParams { name } = params;
+                let result = mcp_server_api::remove_server(&self.config.codex_home, &name).await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::McpServerSetEnabled { request_id, params } => {
+                let McpServerSetEnabledParams { name, enabled } = params;
+                let result =
+                    mcp_server_api::set_server_enabled(&self.config.codex_home, &name, enabled)
+                        .await;
+                self.send_result(request_id, result).await;
+            }
+            ClientRequest::McpServerLogin { request_id, params } => {
+                self.mcp_server_login(request_id, params).await;
+            }
             ClientRequest::FeedbackUpload { request_id, params } => {
                 self.upload_feedback(request_id, params).await;
             }
@@ -1129,7 +1289,11 @@ impl CodexMessageProcessor {
         });
     }
 
-    async fn process_new_conversation(&self, request_id: RequestId, params: NewConversationParams) {
+    async fn process_new_conversation(
+        &mut self,
+        request_id: RequestId,
+        params: NewConversationParams,
+    ) {
         let NewConversationParams {
             model,
             model_provider,
@@ -1179,6 +1343,7 @@ impl CodexMessageProcessor {
                     session_configured,
                     ..
                 } = conversation_id;
+                self.owned_conversations.insert(conversation_id);
                 let response = NewConversationResponse {
                     conversation_id,
                     model: session_configured.model,
@@ -1228,50 +1393,131 @@ impl CodexMessageProcessor {
 
         match self.conversation_manager.new_conversation(config).await {
             Ok(new_conv) => {
-                let conversation_id = new_conv.conversation_id;
-                let rollout_path = new_conv.session_configured.rollout_path.clone();
-                let fallback_provider = self.config.model_provider_id.as_str();
-
-                // A bit hacky, but the summary contains a lot of useful information for the thread
-                // that unfortunately does not get returned from conversation_manager.new_conversation().
-                let thread = match read_summary_from_rollout(
-                    rollout_path.as_path(),
-                    fallback_provider,
-                )
-                .await
-                {
-                    Ok(summary) => summary_to_thread(summary),
-                    Err(err) => {
-                        warn!(
-                            "failed to load summary for new thread {}: {}",
-                            conversation_id, err
-                        );
-                        Thread {
-                            id: conversation_id.to_string(),
-                            preview: String::new(),
-                            model_provider: self.config.model_provider_id.clone(),
-                            created_at: chrono::Utc::now().timestamp(),
-                        }
-                    }
-                };
-
+                let thread = self.install_new_thread(new_conv, 0).await;
                 let response = ThreadStartResponse {
                     thread: thread.clone(),
                 };
+                self.outgoing.send_response(request_id, response).await;
 
-                // Auto-attach a conversation listener when starting a thread.
-                // Use the same behavior as the v1 API with experimental_raw_events=false.
-                if let Err(err) = self
-                    .attach_conversation_listener(conversation_id, false)
-                    .await
-                {
-                    tracing::warn!(
-                        "failed to attach listener for conversation {}: {}",
-                        conversation_id,
-                        err.message
+                let notif = ThreadStartedNotification { thread };
+                self.outgoing
+                    .send_server_notification(ServerNotification::ThreadStarted(notif))
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("error creating thread: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    /// Build the [`Thread`] for a conversation that was just spawned, start
+    /// tracking its turns and attach a v2 listener. `loaded_turns` is the
+    /// number of turns carried over from an existing rollout.
+    async fn install_new_thread(
+        &mut self,
+        new_conv: NewConversation,
+        loaded_turns: usize,
+    ) -> Thread {
+        let conversation_id = new_conv.conversation_id;
+        let rollout_path = new_conv.session_configured.rollout_path.clone();
+        let fallback_provider = self.config.model_provider_id.as_str();
+
+        // A bit hacky, but the summary contains a lot of useful information for the thread
+        // that unfortunately does not get returned from conversation_manager.new_conversation().
+        let thread =
+            match read_summary_from_rollout(rollout_path.as_path(), fallback_provider).await {
+                Ok(summary) => summary_to_thread(summary),
+                Err(err) => {
+                    warn!(
+                        "failed to load summary for new thread {}: {}",
+                        conversation_id, err
                     );
+                    Thread {
+                        id: conversation_id.to_string(),
+                        preview: String::new(),
+                        model_provider: self.config.model_provider_id.clone(),
+                        created_at: chrono::Utc::now().timestamp(),
+                    }
+                }
+            };
+
+        self.thread_turns
+            .lock()
+            .await
+            .insert(conversation_id, ThreadTurns::loaded(loaded_turns));
+        self.owned_conversations.insert(conversation_id);
+
+        // Auto-attach a conversation listener when starting a thread.
+        // Use the same behavior as the v1 API with experimental_raw_events=false.
+        if let Err(err) = self
+            .attach_conversation_listener(conversation_id, false, ApiVersion::V2)
+            .await
+        {
+            tracing::warn!(
+                "failed to attach listener for conversation {}: {}",
+                conversation_id,
+                err.message
+            );
+        }
+
+        thread
+    }
+
+    async fn thread_fork(&mut self, request_id: RequestId, params: ThreadForkParams) {
+        let ThreadForkParams { thread_id, turn_id } = params;
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
                 }
+            };
+
+        let nth_user_message = self
+            .thread_turns
+            .lock()
+            .await
+            .get(&conversation_id)
+            .and_then(|turns| turns.position(&turn_id));
+        let Some(nth_user_message) = nth_user_message else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("turn not found in thread {conversation_id}: {turn_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        let rollout_path = conversation.rollout_path();
+        let config = match load_rollout_items(&rollout_path).await {
+            Ok(items) => self.fork_config(&items).await,
+            Err(error) => Err(error),
+        };
+        let config = match config {
+            Ok(config) => config,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
 
+        match self
+            .conversation_manager
+            .fork_conversation(nth_user_message, config, rollout_path)
+            .await
+        {
+            Ok(new_conv) => {
+                let thread = self.install_new_thread(new_conv, nth_user_message).await;
+                let response = ThreadForkResponse {
+                    thread: thread.clone(),
+                };
                 self.outgoing.send_response(request_id, response).await;
 
                 let notif = ThreadStartedNotification { thread };
@@ -1282,7 +1528,112 @@ impl CodexMessageProcessor {
             Err(err) => {
                 let error = JSONRPCErrorError {
                     code: INTERNAL_ERROR_CODE,
-                    message: format!("error creating thread: {err}"),
+                    message: format!("error forking thread: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_rollback(&mut self, request_id: RequestId, params: ThreadRollbackParams) {
+        let ThreadRollbackParams {
+            thread_id,
+            num_turns,
+            restore_files,
+        } = params;
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        let rollout_path = conversation.rollout_path();
+        let items = match load_rollout_items(&rollout_path).await {
+            Ok(items) => items,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // Rolling back is a fork before the oldest dropped turn, the same way
+        // the TUI backtracks.
+        let user_positions = user_message_positions(&items);
+        let num_turns = num_turns as usize;
+        if num_turns == 0 || num_turns > user_positions.len() {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!(
+                    "cannot roll back {num_turns} turns: thread {conversation_id} has {} turns",
+                    user_positions.len()
+                ),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+        let kept_turns = user_positions.len() - num_turns;
+
+        let snapshot = if restore_files {
+            match ghost_snapshot_after(&items, user_positions[kept_turns]) {
+                Ok(snapshot) => Some(snapshot),
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let config = match self.fork_config(&items).await {
+            Ok(config) => config,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match self
+            .conversation_manager
+            .fork_conversation(kept_turns, config, rollout_path)
+            .await
+        {
+            Ok(new_conv) => {
+                // Only touch the working tree once the fork exists, and drop
+                // the fork again if the files cannot be restored.
+                let restored_snapshot = match snapshot {
+                    Some((cwd, ghost_commit)) => {
+                        match restore_ghost_snapshot(cwd, ghost_commit).await {
+                            Ok(commit_id) => Some(commit_id),
+                            Err(error) => {
+                                self.discard_conversation(new_conv).await;
+                                self.outgoing.send_error(request_id, error).await;
+                                return;
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                let thread = self.install_new_thread(new_conv, kept_turns).await;
+                let response = ThreadRollbackResponse {
+                    thread: thread.clone(),
+                    restored_snapshot,
+                };
+                self.outgoing.send_response(request_id, response).await;
+
+                let notif = ThreadStartedNotification { thread };
+                self.outgoing
+                    .send_server_notification(ServerNotification::ThreadStarted(notif))
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("error rolling back thread: {err}"),
                     data: None,
                 };
                 self.outgoing.send_error(request_id, error).await;
@@ -1290,6 +1641,53 @@ impl CodexMessageProcessor {
         }
     }
 
+    /// Config for a thread forked from the rollout `items`: the server config
+    /// with the provider the source thread was started with and the model,
+    /// working directory and policies of its latest turn.
+    async fn fork_config(&self, items: &[RolloutItem]) -> Result<Config, JSONRPCErrorError> {
+        let model_provider = items.iter().find_map(|item| match item {
+            RolloutItem::SessionMeta(meta_line) => meta_line.meta.model_provider.clone(),
+            _ => None,
+        });
+        let turn_context = items.iter().rev().find_map(|item| match item {
+            RolloutItem::TurnContext(ctx) => Some(ctx.clone()),
+            _ => None,
+        });
+        let overrides = ConfigOverrides {
+            model: turn_context.as_ref().map(|ctx| ctx.model.clone()),
+            cwd: turn_context.as_ref().map(|ctx| ctx.cwd.clone()),
+            approval_policy: turn_context.as_ref().map(|ctx| ctx.approval_policy),
+            model_provider,
+            codex_linux_sandbox_exe: self.codex_linux_sandbox_exe.clone(),
+            ..Default::default()
+        };
+
+        let mut config = Config::load_with_cli_overrides(self.cli_overrides.clone(), overrides)
+            .await
+            .map_err(|err| JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("error deriving config: {err}"),
+                data: None,
+            })?;
+        if let Some(ctx) = turn_context {
+            config.sandbox_policy = ctx.sandbox_policy;
+            config.model_reasoning_effort = ctx.effort;
+            config.model_reasoning_summary = ctx.summary;
+        }
+        Ok(config)
+    }
+
+    /// Shut down a conversation that was spawned but never handed to a client.
+    async fn discard_conversation(&self, new_conv: NewConversation) {
+        let conversation_id = new_conv.conversation_id;
+        self.conversation_manager
+            .remove_conversation(&conversation_id)
+            .await;
+        if let Err(err) = new_conv.conversation.submit(Op::Shutdown).await {
+            warn!("failed to submit Shutdown to conversation {conversation_id}: {err}");
+        }
+    }
+
     async fn thread_archive(&mut self, request_id: RequestId, params: ThreadArchiveParams) {
         let conversation_id = match ConversationId::from_string(&params.thread_id) {
             Ok(id) => id,
@@ -1336,6 +1734,7 @@ impl CodexMessageProcessor {
             .await
         {
             Ok(()) => {
+                self.thread_turns.lock().await.remove(&conversation_id);
                 let response = ThreadArchiveResponse {};
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -1371,6 +1770,51 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn thread_search(&self, request_id: RequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams {
+            query,
+            limit,
+            model_providers,
+        } = params;
+
+        let limit = limit.unwrap_or(25).max(1) as usize;
+        let model_provider_filter = match model_providers {
+            Some(providers) if providers.is_empty() => None,
+            Some(providers) => Some(providers),
+            None => Some(vec![self.config.model_provider_id.clone()]),
+        };
+        let fallback_provider = self.config.model_provider_id.as_str();
+
+        let hits = match RolloutRecorder::search_conversations(
+            &self.config.codex_home,
+            &query,
+            limit,
+            INTERACTIVE_SESSION_SOURCES,
+            model_provider_filter.as_deref(),
+            fallback_provider,
+        )
+        .await
+        {
+            Ok(hits) => hits,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to search conversations: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let data = hits
+            .into_iter()
+            .map(|hit| search_hit_to_result(hit, fallback_provider))
+            .collect();
+        let response = ThreadSearchResponse { data };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
     async fn thread_resume(&mut self, request_id: RequestId, params: ThreadResumeParams) {
         let conversation_id = match ConversationId::from_string(&params.thread_id) {
             Ok(id) => id,
@@ -1442,6 +1886,8 @@ impl CodexMessageProcessor {
             }
         };
 
+        let loaded_turns = user_message_positions(&initial_history.get_rollout_items()).len();
+
         match self
             .conversation_manager
             .resume_conversation_with_history(
@@ -1453,10 +1899,15 @@ impl CodexMessageProcessor {
         {
             Ok(_) => {
                 let thread = summary_to_thread(summary);
+                self.thread_turns
+                    .lock()
+                    .await
+                    .insert(conversation_id, ThreadTurns::loaded(loaded_turns));
+                self.owned_conversations.insert(conversation_id);
 
                 // Auto-attach a conversation listener when resuming a thread.
                 if let Err(err) = self
-                    .attach_conversation_listener(conversation_id, false)
+                    .attach_conversation_listener(conversation_id, false, ApiVersion::V2)
                     .await
                 {
                     tracing::warn!(
@@ -1689,7 +2140,7 @@ impl CodexMessageProcessor {
     }
 
     async fn handle_resume_conversation(
-        &self,
+        &mut self,
         request_id: RequestId,
         params: ResumeConversationParams,
     ) {
@@ -1830,6 +2281,7 @@ impl CodexMessageProcessor {
                 session_configured,
                 ..
             }) => {
+                self.owned_conversations.insert(conversation_id);
                 self.outgoing
                     .send_server_notification(ServerNotification::SessionConfigured(
                         SessionConfiguredNotification {
@@ -2169,13 +2621,14 @@ impl CodexMessageProcessor {
     }
 
     async fn turn_start(&self, request_id: RequestId, params: TurnStartParams) {
-        let (_, conversation) = match self.conversation_from_thread_id(&params.thread_id).await {
-            Ok(v) => v,
-            Err(error) => {
-                self.outgoing.send_error(request_id, error).await;
-                return;
-            }
-        };
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&params.thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
 
         // Keep a copy of v2 inputs for the notification payload.
         let v2_inputs_for_notif = params.input.clone();
@@ -2217,6 +2670,7 @@ impl CodexMessageProcessor {
 
         match turn_id {
             Ok(turn_id) => {
+                self.record_turn(conversation_id, &turn_id).await;
                 let turn = Turn {
                     id: turn_id.clone(),
                     items: vec![ThreadItem::UserMessage {
@@ -2271,8 +2725,72 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
-    async fn add_conversation_listener(
-        &mut self,
+    async fn review_start(&self, request_id: RequestId, params: ReviewStartParams) {
+        let ReviewStartParams { thread_id, target } = params;
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        let review_request = match target {
+            ReviewTarget::UncommittedChanges => review_prompts::uncommitted_changes(),
+            ReviewTarget::BaseBranch { branch } => review_prompts::base_branch(&branch),
+            ReviewTarget::Commit { sha, title } => review_prompts::commit(&sha, title.as_deref()),
+            ReviewTarget::Custom { instructions } => {
+                if instructions.trim().is_empty() {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message: "review instructions must not be empty".to_string(),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+                review_prompts::custom(&instructions)
+            }
+        };
+
+        match conversation.submit(Op::Review { review_request }).await {
+            Ok(turn_id) => {
+                self.record_turn(conversation_id, &turn_id).await;
+                let turn = Turn {
+                    id: turn_id,
+                    items: Vec::new(),
+                    status: TurnStatus::InProgress,
+                    error: None,
+                };
+
+                let response = ReviewStartResponse { turn: turn.clone() };
+                self.outgoing.send_response(request_id, response).await;
+
+                let notif = TurnStartedNotification { turn };
+                self.outgoing
+                    .send_server_notification(ServerNotification::TurnStarted(notif))
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to start review: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn record_turn(&self, conversation_id: ConversationId, turn_id: &str) {
+        if let Some(turns) = self.thread_turns.lock().await.get_mut(&conversation_id) {
+            turns.ids.push(turn_id.to_string());
+        }
+    }
+
+    async fn add_conversation_listener(
+        &mut self,
         request_id: RequestId,
         params: AddConversationListenerParams,
     ) {
@@ -2281,7 +2799,7 @@ impl CodexMessageProcessor {
             experimental_raw_events,
         } = params;
         match self
-            .attach_conversation_listener(conversation_id, experimental_raw_events)
+            .attach_conversation_listener(conversation_id, experimental_raw_events, ApiVersion::V1)
             .await
         {
             Ok(subscription_id) => {
@@ -2301,9 +2819,9 @@ impl CodexMessageProcessor {
     ) {
         let RemoveConversationListenerParams { subscription_id } = params;
         match self.conversation_listeners.remove(&subscription_id) {
-            Some(sender) => {
+            Some(listener) => {
                 // Signal the spawned task to exit and acknowledge.
-                let _ = sender.send(());
+                let _ = listener.cancel.send(());
                 let response = RemoveConversationSubscriptionResponse {};
                 self.outgoing.send_response(request_id, response).await;
             }
@@ -2322,6 +2840,7 @@ impl CodexMessageProcessor {
         &mut self,
         conversation_id: ConversationId,
         experimental_raw_events: bool,
+        api_version: ApiVersion,
     ) -> Result<Uuid, JSONRPCErrorError> {
         let conversation = match self
             .conversation_manager
@@ -2340,25 +2859,28 @@ impl CodexMessageProcessor {
 
         let subscription_id = Uuid::new_v4();
         let (cancel_tx, mut cancel_rx) = oneshot::channel();
-        self.conversation_listeners
-            .insert(subscription_id, cancel_tx);
+        let mut events = self
+            .conversation_events
+            .subscribe(conversation_id, conversation.clone())
+            .await;
 
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             loop {
                 tokio::select! {
                     _ = &mut cancel_rx => {
                         // User has unsubscribed, so exit this task.
                         break;
                     }
-                    event = conversation.next_event() => {
+                    event = events.recv() => {
                         let event = match event {
                             Ok(event) => event,
-                            Err(err) => {
-                                tracing::warn!("conversation.next_event() failed with: {err}");
-                                break;
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                warn!("listener on conversation {conversation_id} skipped {skipped} events");
+                                continue;
                             }
+                            Err(broadcast::error::RecvError::Closed) => break,
                         };
 
                         if let EventMsg::RawResponseItem(_) = &event.msg
@@ -2400,15 +2922,55 @@ impl CodexMessageProcessor {
                             conversation.clone(),
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
+                            api_version,
                         )
                         .await;
                     }
                 }
             }
         });
+        self.conversation_listeners.insert(
+            subscription_id,
+            ConversationListener {
+                cancel: cancel_tx,
+                task,
+            },
+        );
         Ok(subscription_id)
     }
 
+    /// Called once the client has disconnected: stop its listeners and shut
+    /// down the conversations it owns that no other client listens to.
+    pub(crate) async fn on_disconnect(&mut self) {
+        for (_, listener) in self.conversation_listeners.drain() {
+            let _ = listener.cancel.send(());
+            // Wait for the task so its subscription no longer counts below.
+            let _ = listener.task.await;
+        }
+
+        for conversation_id in std::mem::take(&mut self.owned_conversations) {
+            if self
+                .conversation_events
+                .listener_count(conversation_id)
+                .await
+                > 0
+            {
+                continue;
+            }
+            if let Some(conversation) = self
+                .conversation_manager
+                .remove_conversation(&conversation_id)
+                .await
+            {
+                info!("client disconnected; shutting down conversation {conversation_id}");
+                self.thread_turns.lock().await.remove(&conversation_id);
+                if let Err(err) = conversation.submit(Op::Shutdown).await {
+                    warn!("failed to submit Shutdown to conversation {conversation_id}: {err}");
+                }
+            }
+        }
+    }
+
     async fn git_diff_to_origin(&self, request_id: RequestId, cwd: PathBuf) {
         let diff = git_diff_to_remote(&cwd).await;
         match diff {
@@ -2470,6 +3032,121 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn list_approval_rules(&self, request_id: RequestId, params: ApprovalRuleListParams) {
+        let codex_home = self.config.codex_home.clone();
+        let result = match params.project {
+            Some(project) => {
+                let project = approval_rules::project_root(&project);
+                approval_rules::load_project_rules(&codex_home, &project)
+                    .map(|rules| vec![approval_rules::ProjectApprovalRules { project, rules }])
+            }
+            None => approval_rules::list_all_rules(&codex_home),
+        };
+
+        match result {
+            Ok(projects) => {
+                let data = projects
+                    .into_iter()
+                    .map(|entry| ProjectApprovalRules {
+                        project: entry.project,
+                        rules: entry.rules.into_iter().map(Into::into).collect(),
+                    })
+                    .collect();
+                self.outgoing
+                    .send_response(request_id, ApprovalRuleListResponse { data })
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to load approval rules: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn remove_approval_rule(&self, request_id: RequestId, params: ApprovalRuleRemoveParams) {
+        let ApprovalRuleRemoveParams { project, rule } = params;
+        let project = approval_rules::project_root(&project);
+        match approval_rules::remove_rule(&self.config.codex_home, &project, &rule.into_core()) {
+            Ok(removed) => {
+                self.outgoing
+                    .send_response(request_id, ApprovalRuleRemoveResponse { removed })
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to remove approval rule: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn send_result<T: Serialize>(
+        &self,
+        request_id: RequestId,
+        result: Result<T, JSONRPCErrorError>,
+    ) {
+        match result {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn mcp_server_list(&self, request_id: RequestId) {
+        let result = match mcp_server_api::load_config(&self.cli_overrides).await {
+            Ok(config) => Ok(mcp_server_api::list_servers(&config, &self.mcp_probes).await),
+            Err(error) => Err(error),
+        };
+        self.send_result(request_id, result).await;
+    }
+
+    async fn mcp_server_login(&self, request_id: RequestId, params: McpServerLoginParams) {
+        let McpServerLoginParams { name, scopes } = params;
+        let config = match mcp_server_api::load_config(&self.cli_overrides).await {
+            Ok(config) => config,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        if let Err(error) = mcp_server_api::check_login(&config, &name) {
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, McpServerLoginResponse {})
+            .await;
+        tokio::spawn(mcp_server_api::run_login(
+            config,
+            name,
+            scopes,
+            self.mcp_probes.clone(),
+            self.outgoing.clone(),
+        ));
+    }
+
+    async fn config_read(&self, request_id: RequestId, params: ConfigReadParams) {
+        match config_api::read_config(&self.cli_overrides, params).await {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn config_write(&self, request_id: RequestId, params: ConfigWriteParams) {
+        let ConfigWriteParams { edits } = params;
+        match config_api::write_config(&self.config.codex_home, edits).await {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
     async fn upload_feedback(&self, request_id: RequestId, params: FeedbackUploadParams) {
         let FeedbackUploadParams {
             classification,
@@ -2548,9 +3225,38 @@ async fn apply_bespoke_event_handling(
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
+    api_version: ApiVersion,
 ) {
     let Event { id: event_id, msg } = event;
     match msg {
+        EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+            call_id,
+            changes,
+            reason,
+            grant_root,
+        }) if matches!(api_version, ApiVersion::V2) => {
+            let mut changes: Vec<FileUpdateChange> = changes
+                .into_iter()
+                .map(|(path, change)| FileUpdateChange::from_core(&path, change))
+                .collect();
+            changes.sort_by(|a, b| a.path.cmp(&b.path));
+            let params = FileChangeRequestApprovalParams {
+                thread_id: conversation_id.to_string(),
+                // Events are tagged with the id of the submission that started
+                // the turn, which is what `turn/start` returns as the turn id.
+                turn_id: event_id.clone(),
+                item_id: call_id,
+                changes,
+                reason,
+                grant_root,
+            };
+            let rx = outgoing
+                .send_request(ServerRequestPayload::FileChangeRequestApproval(params))
+                .await;
+            tokio::spawn(async move {
+                on_file_change_request_approval_response(event_id, rx, conversation).await;
+            });
+        }
         EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
             call_id,
             changes,
@@ -2572,6 +3278,33 @@ async fn apply_bespoke_event_handling(
                 on_patch_approval_response(event_id, rx, conversation).await;
             });
         }
+        EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id,
+            command,
+            cwd,
+            reason,
+            risk,
+            parsed_cmd,
+        }) if matches!(api_version, ApiVersion::V2) => {
+            let params = CommandExecutionRequestApprovalParams {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_id.clone(),
+                item_id: call_id,
+                command,
+                cwd,
+                reason,
+                risk,
+                parsed_cmd,
+            };
+            let rx = outgoing
+                .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
+                    params,
+                ))
+                .await;
+            tokio::spawn(async move {
+                on_command_execution_request_approval_response(event_id, rx, conversation).await;
+            });
+        }
         EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
             call_id,
             command,
@@ -2598,6 +3331,46 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        EventMsg::ElicitationRequest(ElicitationRequestEvent {
+            server_name,
+            id,
+            message,
+            requested_schema,
+        }) => {
+            let params = McpElicitationParams {
+                conversation_id,
+                server_name: server_name.clone(),
+                request_id: id.clone(),
+                message,
+                requested_schema,
+            };
+            let rx = outgoing
+                .send_request(ServerRequestPayload::McpElicitation(params))
+                .await;
+
+            tokio::spawn(async move {
+                on_elicitation_response(server_name, id, rx, conversation).await;
+            });
+        }
+        EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+            server_name,
+            id,
+            request,
+        }) => {
+            let params = McpSamplingApprovalParams {
+                conversation_id,
+                server_name: server_name.clone(),
+                request_id: id.clone(),
+                request,
+            };
+            let rx = outgoing
+                .send_request(ServerRequestPayload::McpSamplingApproval(params))
+                .await;
+
+            tokio::spawn(async move {
+                on_sampling_approval_response(server_name, id, rx, conversation).await;
+            });
+        }
         EventMsg::TokenCount(token_count_event) => {
             if let Some(rate_limits) = token_count_event.rate_limits {
                 outgoing
@@ -2632,11 +3405,100 @@ async fn apply_bespoke_event_handling(
                 }
             }
         }
+        EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(review),
+        }) if matches!(api_version, ApiVersion::V2) => {
+            // The review runs as its own turn, so the turn id doubles as the item id.
+            let item = ThreadItem::CodeReview {
+                id: event_id,
+                review,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemCompleted(
+                    ItemCompletedNotification { item },
+                ))
+                .await;
+        }
 
         _ => {}
     }
 }
 
+async fn load_rollout_items(rollout_path: &Path) -> Result<Vec<RolloutItem>, JSONRPCErrorError> {
+    match RolloutRecorder::get_rollout_history(rollout_path).await {
+        Ok(history) => Ok(history.get_rollout_items()),
+        Err(err) => Err(JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("failed to load rollout `{}`: {err}", rollout_path.display()),
+            data: None,
+        }),
+    }
+}
+
+/// The first ghost snapshot recorded at or after `start` in `items`, i.e. the
+/// one taken before the turn starting there, with the directory it was taken in.
+fn ghost_snapshot_after(
+    items: &[RolloutItem],
+    start: usize,
+) -> Result<(PathBuf, GhostCommit), JSONRPCErrorError> {
+    let Some((idx, ghost_commit)) = items
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find_map(|(idx, item)| match item {
+            RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+                Some((idx, ghost_commit.clone()))
+            }
+            _ => None,
+        })
+    else {
+        return Err(JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: "no ghost snapshot recorded for the rolled back turns; enable the `ghost_commit` feature to restore files".to_string(),
+            data: None,
+        });
+    };
+
+    // The snapshot was taken in the working directory of the turn that recorded it.
+    let Some(cwd) = items[..idx].iter().rev().find_map(|item| match item {
+        RolloutItem::TurnContext(ctx) => Some(ctx.cwd.clone()),
+        RolloutItem::SessionMeta(meta_line) => Some(meta_line.meta.cwd.clone()),
+        _ => None,
+    }) else {
+        return Err(JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: "rollout does not record a working directory".to_string(),
+            data: None,
+        });
+    };
+
+    Ok((cwd, ghost_commit))
+}
+
+/// Restore the working tree in `cwd` to `ghost_commit`. Returns the restored
+/// commit id.
+async fn restore_ghost_snapshot(
+    cwd: PathBuf,
+    ghost_commit: GhostCommit,
+) -> Result<String, JSONRPCErrorError> {
+    let commit_id = ghost_commit.id().to_string();
+    let result =
+        tokio::task::spawn_blocking(move || restore_ghost_commit(&cwd, &ghost_commit)).await;
+    match result {
+        Ok(Ok(())) => Ok(commit_id),
+        Ok(Err(err)) => Err(JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("failed to restore snapshot {commit_id}: {err}"),
+            data: None,
+        }),
+        Err(err) => Err(JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("failed to restore snapshot {commit_id}: {err}"),
+            data: None,
+        }),
+    }
+}
+
 async fn derive_config_from_params(
     overrides: ConfigOverrides,
     cli_overrides: Option<std::collections::HashMap<String, serde_json::Value>>,
@@ -2714,6 +3576,7 @@ async fn on_exec_approval_response(
             // conservative.
             ExecCommandApprovalResponse {
                 decision: ReviewDecision::Denied,
+                persist_rule: None,
             }
         });
 
@@ -2721,6 +3584,72 @@ async fn on_exec_approval_response(
         .submit(Op::ExecApproval {
             id: event_id,
             decision: response.decision,
+            persist_rule: response.persist_rule,
+        })
+        .await
+    {
+        error!("failed to submit ExecApproval: {err}");
+    }
+}
+
+async fn on_file_change_request_approval_response(
+    event_id: String,
+    receiver: oneshot::Receiver<JsonRpcResult>,
+    conversation: Arc<CodexConversation>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<FileChangeRequestApprovalResponse>(value)
+            .map(|response| response.decision.to_core())
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize FileChangeRequestApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::PatchApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit PatchApproval: {err}");
+    }
+}
+
+async fn on_command_execution_request_approval_response(
+    event_id: String,
+    receiver: oneshot::Receiver<JsonRpcResult>,
+    conversation: Arc<CodexConversation>,
+) {
+    let response = match receiver.await {
+        Ok(value) => serde_json::from_value::<CommandExecutionRequestApprovalResponse>(value)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize CommandExecutionRequestApprovalResponse: {err}");
+                CommandExecutionRequestApprovalResponse {
+                    decision: ApprovalDecision::Denied,
+                    persist_rule: None,
+                }
+            }),
+        Err(err) => {
+            // Deny rather than leave the turn waiting forever.
+            error!("request failed: {err:?}");
+            CommandExecutionRequestApprovalResponse {
+                decision: ApprovalDecision::Denied,
+                persist_rule: None,
+            }
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::ExecApproval {
+            id: event_id,
+            decision: response.decision.to_core(),
+            persist_rule: response.persist_rule.map(ApprovalRule::into_core),
         })
         .await
     {
@@ -2728,6 +3657,76 @@ async fn on_exec_approval_response(
     }
 }
 
+async fn on_elicitation_response(
+    server_name: String,
+    request_id: McpRequestId,
+    receiver: oneshot::Receiver<JsonRpcResult>,
+    conversation: Arc<CodexConversation>,
+) {
+    let response = match receiver.await {
+        Ok(value) => {
+            serde_json::from_value::<McpElicitationResponse>(value).unwrap_or_else(|err| {
+                error!("failed to deserialize McpElicitationResponse: {err}");
+                McpElicitationResponse {
+                    action: ElicitationAction::Decline,
+                    content: None,
+                }
+            })
+        }
+        Err(err) => {
+            // Cancel rather than leave the MCP server waiting forever.
+            error!("request failed: {err:?}");
+            McpElicitationResponse {
+                action: ElicitationAction::Cancel,
+                content: None,
+            }
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::ResolveElicitation {
+            server_name,
+            request_id,
+            decision: response.action,
+            content: response.content,
+        })
+        .await
+    {
+        error!("failed to submit ResolveElicitation: {err}");
+    }
+}
+
+async fn on_sampling_approval_response(
+    server_name: String,
+    request_id: McpRequestId,
+    receiver: oneshot::Receiver<JsonRpcResult>,
+    conversation: Arc<CodexConversation>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpSamplingApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpSamplingApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::McpSamplingApproval {
+            server_name,
+            request_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpSamplingApproval: {err}");
+    }
+}
+
 async fn read_summary_from_rollout(
     path: &Path,
     fallback_provider: &str,
@@ -2867,6 +3866,39 @@ fn summary_to_thread(summary: ConversationSummary) -> Thread {
     }
 }
 
+fn search_hit_to_result(hit: SessionSearchHit, fallback_provider: &str) -> ThreadSearchResult {
+    let SessionSearchHit {
+        conversation_id,
+        preview,
+        model_provider,
+        created_at,
+        matches,
+        ..
+    } = hit;
+
+    let created_at = parse_datetime(Some(created_at.as_str()));
+    let thread = Thread {
+        id: conversation_id.to_string(),
+        preview,
+        model_provider: model_provider.unwrap_or_else(|| fallback_provider.to_string()),
+        created_at: created_at.map(|dt| dt.timestamp()).unwrap_or(0),
+    };
+    let matches = matches
+        .into_iter()
+        .map(|m| ThreadSearchMatch {
+            kind: match m.kind {
+                SessionSearchMatchKind::UserMessage => ThreadSearchMatchKind::UserMessage,
+                SessionSearchMatchKind::AgentMessage => ThreadSearchMatchKind::AgentMessage,
+                SessionSearchMatchKind::Command => ThreadSearchMatchKind::Command,
+                SessionSearchMatchKind::File => ThreadSearchMatchKind::File,
+            },
+            snippet: m.snippet,
+        })
+        .collect();
+
+    ThreadSearchResult { thread, matches }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,415 @@
+//! `config/read` and `config/write`: the effective configuration with the
+//! layer each value came from, and validated edits to `config.toml`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use codex_app_server_protocol::ConfigFieldError;
+use codex_app_server_protocol::ConfigReadParams;
+use codex_app_server_protocol::ConfigReadResponse;
+use codex_app_server_protocol::ConfigValueEdit;
+use codex_app_server_protocol::ConfigWriteErrorData;
+use codex_app_server_protocol::ConfigWriteResponse;
+use codex_app_server_protocol::EffectiveConfig;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::SandboxMode;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::ConfigToml;
+use codex_core::config::edit::ConfigChangedError;
+use codex_core::config::edit::ConfigEditsBuilder;
+use codex_core::config::provenance::ConfigOrigins;
+use codex_core::features::FEATURES;
+use codex_core::features::is_known_feature_key;
+use codex_core::protocol::SandboxPolicy;
+use codex_utils_json_to_toml::json_to_toml;
+use serde_json::Value as JsonValue;
+use toml::Value as TomlValue;
+use toml_edit::Key;
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+
+/// Top-level keys `config/write` may set directly.
+const SCALAR_KEYS: &[&str] = &[
+    "model",
+    "model_provider",
+    "model_reasoning_effort",
+    "approval_policy",
+    "sandbox_mode",
+    "profile",
+];
+
+/// Times `config/write` re-validates its edits when `config.toml` changes
+/// before they are written.
+const WRITE_ATTEMPTS: usize = 3;
+
+/// Tables `config/write` may edit at any depth; the first segment after the
+/// table name is the entry name.
+const NAMED_TABLES: &[&str] = &["profiles", "mcp_servers"];
+
+pub(crate) async fn read_config(
+    cli_overrides: &[(String, TomlValue)],
+    params: ConfigReadParams,
+) -> Result<ConfigReadResponse, JSONRPCErrorError> {
+    let ConfigReadParams { cwd, profile } = params;
+    let overrides = ConfigOverrides {
+        cwd,
+        config_profile: profile,
+        ..Default::default()
+    };
+    let config = Config::load_with_cli_overrides(cli_overrides.to_vec(), overrides)
+        .await
+        .map_err(|err| invalid_request(format!("failed to load config: {err}")))?;
+    let origins = ConfigOrigins::load(&config.codex_home, cli_overrides)
+        .await
+        .map_err(|err| internal_error(format!("failed to load config layers: {err}")))?;
+
+    let merged = origins.merged();
+    let sandbox_mode = match &config.sandbox_policy {
+        SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+        SandboxPolicy::ReadOnly { .. } => SandboxMode::ReadOnly,
+        SandboxPolicy::WorkspaceWrite { .. } => SandboxMode::WorkspaceWrite,
+    };
+    let effective = EffectiveConfig {
+        model: config.model.clone(),
+        model_provider: config.model_provider_id.clone(),
+        model_reasoning_effort: config.model_reasoning_effort,
+        approval_policy: config.approval_policy.into(),
+        sandbox_mode,
+        profile: config.active_profile.clone(),
+        profiles: table_as_json(&merged, "profiles"),
+        mcp_servers: table_as_json(&merged, "mcp_servers"),
+        features: FEATURES
+            .iter()
+            .map(|spec| (spec.key.to_string(), config.features.enabled(spec.id)))
+            .collect(),
+    };
+
+    Ok(ConfigReadResponse {
+        config: effective,
+        origins: origins.origins(&config),
+    })
+}
+
+/// Validate `edits` against the user's `config.toml` and persist them only if
+/// all of them are valid.
+pub(crate) async fn write_config(
+    codex_home: &Path,
+    edits: Vec<ConfigValueEdit>,
+) -> Result<ConfigWriteResponse, JSONRPCErrorError> {
+    // The edits are written only if config.toml still holds the contents they
+    // were validated against; if another writer got in between, validate again.
+    for _ in 0..WRITE_ATTEMPTS {
+        let (contents, document) = read_user_config(codex_home).await?;
+        let builder = validate_edits(codex_home, document, &edits)?;
+        match builder.if_unchanged(contents).apply().await {
+            Ok(()) => return Ok(ConfigWriteResponse {}),
+            Err(err) if err.is::<ConfigChangedError>() => continue,
+            Err(err) => {
+                return Err(internal_error(format!(
+                    "failed to write config.toml: {err}"
+                )));
+            }
+        }
+    }
+    Err(internal_error(
+        "config.toml kept changing while it was being written; try again".to_string(),
+    ))
+}
+
+/// Check `edits` against `document` and collect them into a builder, or
+/// report every invalid field.
+fn validate_edits(
+    codex_home: &Path,
+    mut document: TomlValue,
+    edits: &[ConfigValueEdit],
+) -> Result<ConfigEditsBuilder, JSONRPCErrorError> {
+    let mut errors = Vec::new();
+    let mut builder = ConfigEditsBuilder::new(codex_home);
+    let mut selected_profile = false;
+
+    for ConfigValueEdit { key_path, value } in edits {
+        let key_path = key_path.clone();
+        let segments = match parse_key_path(&key_path) {
+            Ok(segments) => segments,
+            Err(message) => {
+                errors.push(ConfigFieldError { key_path, message });
+                continue;
+            }
+        };
+        let value = (!value.is_null()).then(|| json_to_toml(value.clone()));
+        match apply_edit(&mut document, &segments, value.as_ref()) {
+            Ok(()) => {
+                selected_profile |= segments == ["profile"] && value.is_some();
+                builder = match &value {
+                    Some(value) => builder.set_path_value(&segments, value),
+                    None => builder.clear_path(&segments),
+                };
+            }
+            Err(message) => errors.push(ConfigFieldError { key_path, message }),
+        }
+    }
+
+    if selected_profile
+        && let Some(profile) = document.get("profile").and_then(TomlValue::as_str)
+        && document
+            .get("profiles")
+            .and_then(|profiles| profiles.get(profile))
+            .is_none()
+    {
+        errors.push(ConfigFieldError {
+            key_path: "profile".to_string(),
+            message: format!("profile `{profile}` is not defined under [profiles]"),
+        });
+    }
+
+    if !errors.is_empty() {
+        let data = serde_json::to_value(ConfigWriteErrorData { errors })
+            .map_err(|err| internal_error(format!("failed to serialize errors: {err}")))?;
+        return Err(JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: "invalid config edits".to_string(),
+            data: Some(data),
+        });
+    }
+
+    Ok(builder)
+}
+
+/// Split a key path written in TOML dotted-key syntax, so segments that
+/// contain dots can be quoted: `mcp_servers."docs.internal".enabled`.
+fn parse_key_path(key_path: &str) -> Result<Vec<String>, String> {
+    let keys = Key::parse(key_path).map_err(|err| format!("invalid key path: {err}"))?;
+    Ok(keys.iter().map(|key| key.get().to_string()).collect())
+}
+
+/// Apply one edit to `document` if the result still matches the config
+/// schema; otherwise leave `document` untouched and return a message for the
+/// field. Only the top-level key or named-table entry the edit falls under is
+/// re-checked, so the cost does not grow with the rest of the file.
+fn apply_edit(
+    document: &mut TomlValue,
+    segments: &[String],
+    value: Option<&TomlValue>,
+) -> Result<(), String> {
+    if segments.iter().any(String::is_empty) {
+        return Err("key path must not contain empty segments".to_string());
+    }
+    // Number of leading segments naming the unit that is validated as a whole.
+    let unit_len = match segments {
+        [key] if SCALAR_KEYS.contains(&key.as_str()) || key == "sandbox_workspace_write" => 1,
+        [table, ..] if table == "sandbox_workspace_write" => 1,
+        [table, _, ..] if NAMED_TABLES.contains(&table.as_str()) => 2,
+        [table, feature] if table == "features" => {
+            if !is_known_feature_key(feature) {
+                return Err(format!("unknown feature `{feature}`"));
+            }
+            2
+        }
+        _ => {
+            return Err(format!(
+                "unsupported key; expected one of {}, sandbox_workspace_write, profiles.<name>, mcp_servers.<name> or features.<name>",
+                SCALAR_KEYS.join(", ")
+            ));
+        }
+    };
+    let unit_path = &segments[..unit_len];
+
+    let mut unit = TomlValue::Table(Default::default());
+    if let Some(current) = get_path(document, unit_path) {
+        set_path(&mut unit, unit_path, current.clone())?;
+    }
+    match value {
+        Some(value) => set_path(&mut unit, segments, value.clone())?,
+        None => remove_path(&mut unit, segments),
+    }
+    let updated = get_path(&unit, unit_path).cloned();
+    unit.try_into::<ConfigToml>()
+        .map_err(|err| err.message().to_string())?;
+
+    match updated {
+        Some(updated) => set_path(document, unit_path, updated),
+        None => {
+            remove_path(document, unit_path);
+            Ok(())
+        }
+    }
+}
+
+fn get_path<'a>(root: &'a TomlValue, segments: &[String]) -> Option<&'a TomlValue> {
+    segments
+        .iter()
+        .try_fold(root, |current, segment| current.get(segment.as_str()))
+}
+
+fn set_path(root: &mut TomlValue, segments: &[String], value: TomlValue) -> Result<(), String> {
+    let Some((last, parents)) = segments.split_last() else {
+        return Err("key path must not be empty".to_string());
+    };
+    let mut current = root;
+    for segment in parents {
+        let TomlValue::Table(table) = current else {
+            return Err(format!("`{segment}` is not inside a table"));
+        };
+        current = table
+            .entry(segment.clone())
+            .or_insert_with(|| TomlValue::Table(Default::default()));
+    }
+    let TomlValue::Table(table) = current else {
+        return Err(format!("`{last}` is not inside a table"));
+    };
+    table.insert(last.clone(), value);
+    Ok(())
+}
+
+fn remove_path(root: &mut TomlValue, segments: &[String]) {
+    let Some((last, parents)) = segments.split_last() else {
+        return;
+    };
+    let parent = parents
+        .iter()
+        .try_fold(root, |current, segment| current.get_mut(segment.as_str()));
+    if let Some(TomlValue::Table(table)) = parent {
+        table.remove(last);
+    }
+}
+
+/// The raw contents of the user's `config.toml` (empty when it does not
+/// exist) and the document they parse to.
+async fn read_user_config(codex_home: &Path) -> Result<(String, TomlValue), JSONRPCErrorError> {
+    let path = codex_home.join(CONFIG_TOML_FILE);
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(internal_error(format!(
+                "failed to read {}: {err}",
+                path.display()
+            )));
+        }
+    };
+    let document = toml::from_str(&contents)
+        .map_err(|err| internal_error(format!("failed to parse {}: {err}", path.display())))?;
+    Ok((contents, document))
+}
+
+fn table_as_json(root: &TomlValue, key: &str) -> HashMap<String, JsonValue> {
+    root.get(key)
+        .and_then(TomlValue::as_table)
+        .map(|table| {
+            table
+                .iter()
+                .filter_map(|(name, value)| {
+                    serde_json::to_value(value)
+                        .ok()
+                        .map(|value| (name.clone(), value))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn invalid_request(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn document(contents: &str) -> TomlValue {
+        toml::from_str(contents).expect("valid toml")
+    }
+
+    fn segments(key_path: &str) -> Vec<String> {
+        parse_key_path(key_path).expect("valid key path")
+    }
+
+    #[test]
+    fn parse_key_path_accepts_quoted_segments() {
+        assert_eq!(
+            segments("mcp_servers.\"docs.internal\".enabled"),
+            vec!["mcp_servers", "docs.internal", "enabled"]
+        );
+        assert_eq!(
+            segments("profiles.fast.model"),
+            vec!["profiles", "fast", "model"]
+        );
+        assert!(parse_key_path("mcp_servers.\"docs").is_err());
+    }
+
+    #[test]
+    fn apply_edit_accepts_partial_mcp_server_update() {
+        let mut document = document(
+            "[mcp_servers.docs]\ncommand = \"docs\"\n\n[mcp_servers.\"docs.internal\"]\ncommand = \"internal\"\n",
+        );
+        apply_edit(
+            &mut document,
+            &segments("mcp_servers.\"docs.internal\".enabled"),
+            Some(&TomlValue::Boolean(false)),
+        )
+        .expect("valid edit");
+        assert_eq!(
+            document["mcp_servers"]["docs.internal"]["enabled"].as_bool(),
+            Some(false)
+        );
+        assert_eq!(document["mcp_servers"]["docs"].get("enabled"), None);
+    }
+
+    #[test]
+    fn apply_edit_reports_schema_errors() {
+        let mut base = document("");
+        let err = apply_edit(
+            &mut base,
+            &segments("approval_policy"),
+            Some(&TomlValue::String("sometimes".to_string())),
+        )
+        .expect_err("invalid variant");
+        assert!(err.contains("sometimes"), "unexpected message: {err}");
+
+        let err = apply_edit(
+            &mut base,
+            &segments("features.does_not_exist"),
+            Some(&TomlValue::Boolean(true)),
+        )
+        .expect_err("unknown feature");
+        assert_eq!(err, "unknown feature `does_not_exist`");
+
+        let err = apply_edit(
+            &mut base,
+            &segments("notify"),
+            Some(&TomlValue::Array(Vec::new())),
+        )
+        .expect_err("unsupported key");
+        assert!(
+            err.starts_with("unsupported key"),
+            "unexpected message: {err}"
+        );
+
+        // Rejected edits leave the document as it was.
+        assert_eq!(base, document(""));
+    }
+
+    #[test]
+    fn remove_path_ignores_missing_keys() {
+        let mut root = document("model = \"gpt-5\"\n");
+        remove_path(&mut root, &segments("profiles.fast.model"));
+        remove_path(&mut root, &segments("model"));
+        assert_eq!(root, document(""));
+    }
+}
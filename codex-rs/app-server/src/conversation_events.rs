@@ -0,0 +1,76 @@
+//! Fan-out of conversation events to every listener. `CodexConversation`
+//! hands each event to exactly one `next_event()` caller, so when several
+//! clients listen on the same conversation the server reads its events once
+//! and broadcasts them.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use codex_core::CodexConversation;
+use codex_core::protocol::Event;
+use codex_protocol::ConversationId;
+use tokio::sync::Mutex;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Events buffered per conversation for listeners that fall behind.
+const EVENT_BUFFER: usize = 1024;
+
+/// Broadcast channels of the conversations that currently have listeners,
+/// shared by every client connection.
+#[derive(Clone, Default)]
+pub(crate) struct ConversationEvents {
+    senders: Arc<Mutex<HashMap<ConversationId, broadcast::Sender<Event>>>>,
+}
+
+impl ConversationEvents {
+    /// Receive every event `conversation` emits from now on. The first
+    /// subscriber starts a task that drains the conversation; it exits once
+    /// the conversation ends or its last subscriber is gone.
+    pub(crate) async fn subscribe(
+        &self,
+        conversation_id: ConversationId,
+        conversation: Arc<CodexConversation>,
+    ) -> broadcast::Receiver<Event> {
+        let mut senders = self.senders.lock().await;
+        if let Some(sender) = senders.get(&conversation_id) {
+            return sender.subscribe();
+        }
+
+        let (sender, receiver) = broadcast::channel(EVENT_BUFFER);
+        senders.insert(conversation_id, sender.clone());
+        let senders = self.senders.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = conversation.next_event().await;
+                // Hold the lock while sending so a concurrent `subscribe`
+                // either joins this channel or finds it removed.
+                let mut senders = senders.lock().await;
+                match event {
+                    Ok(event) => {
+                        if sender.send(event).is_err() {
+                            debug!("no listeners left on conversation {conversation_id}");
+                            senders.remove(&conversation_id);
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        debug!("conversation {conversation_id} stopped emitting events: {err}");
+                        senders.remove(&conversation_id);
+                        break;
+                    }
+                }
+            }
+        });
+        receiver
+    }
+
+    /// Number of listeners currently subscribed to `conversation_id`.
+    pub(crate) async fn listener_count(&self, conversation_id: ConversationId) -> usize {
+        self.senders
+            .lock()
+            .await
+            .get(&conversation_id)
+            .map_or(0, broadcast::Sender::receiver_count)
+    }
+}
@@ -7,20 +7,23 @@ use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use std::io::ErrorKind;
 use std::io::Result as IoResult;
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::codex_message_processor::SharedThreadTurns;
+use crate::conversation_events::ConversationEvents;
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::JSONRPCMessage;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::mcp::manage::McpProbeCache;
 use codex_feedback::CodexFeedback;
-use tokio::io::AsyncBufReadExt;
-use tokio::io::AsyncWriteExt;
-use tokio::io::BufReader;
-use tokio::io::{self};
+use codex_protocol::protocol::SessionSource;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use toml::Value as TomlValue;
 use tracing::Level;
-use tracing::debug;
-use tracing::error;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::Layer;
@@ -29,48 +32,46 @@ use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 mod codex_message_processor;
+mod config_api;
+mod conversation_events;
 mod error_code;
 mod fuzzy_file_search;
+mod mcp_server_api;
 mod message_processor;
 mod models;
 mod outgoing_message;
+mod transport;
+
+pub use crate::transport::AppServerArgs;
+pub use crate::transport::AppServerTransport;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// State shared by every client connection, so that conversations started by
+/// one client can be driven by another.
+#[derive(Clone)]
+pub(crate) struct SharedState {
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
+    /// `-c key=value` overrides, kept so per-request config loads match the
+    /// process-wide one.
+    cli_overrides: Vec<(String, TomlValue)>,
+    auth_manager: Arc<AuthManager>,
+    conversation_manager: Arc<ConversationManager>,
+    conversation_events: ConversationEvents,
+    thread_turns: SharedThreadTurns,
+    mcp_probes: Arc<McpProbeCache>,
+    feedback: CodexFeedback,
+}
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
+    args: AppServerArgs,
 ) -> IoResult<()> {
-    // Set up channels.
-    let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
-    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
-
-    // Task: read from stdin, push to `incoming_tx`.
-    let stdin_reader_handle = tokio::spawn({
-        async move {
-            let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
-            let mut lines = reader.lines();
-
-            while let Some(line) = lines.next_line().await.unwrap_or_default() {
-                match serde_json::from_str::<JSONRPCMessage>(&line) {
-                    Ok(msg) => {
-                        if incoming_tx.send(msg).await.is_err() {
-                            // Receiver gone – nothing left to do.
-                            break;
-                        }
-                    }
-                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
-                }
-            }
-
-            debug!("stdin reader finished (EOF)");
-        }
-    });
-
     // Parse CLI overrides once and derive the base Config eagerly so later
     // components do not need to work with raw TOML values.
     let cli_kv_overrides = cli_config_overrides.parse_overrides().map_err(|e| {
@@ -79,11 +80,12 @@ pub async fn run_main(
             format!("error parsing -c overrides: {e}"),
         )
     })?;
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, ConfigOverrides::default())
-        .await
-        .map_err(|e| {
-            std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
-        })?;
+    let config =
+        Config::load_with_cli_overrides(cli_kv_overrides.clone(), ConfigOverrides::default())
+            .await
+            .map_err(|e| {
+                std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
+            })?;
 
     let feedback = CodexFeedback::new();
 
@@ -117,56 +119,77 @@ pub async fn run_main(
         }))
         .try_init();
 
-    // Task: process incoming messages.
-    let processor_handle = tokio::spawn({
-        let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
-        let mut processor = MessageProcessor::new(
-            outgoing_message_sender,
-            codex_linux_sandbox_exe,
-            std::sync::Arc::new(config),
-            feedback.clone(),
-        );
-        async move {
-            while let Some(msg) = incoming_rx.recv().await {
-                match msg {
-                    JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                    JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                    JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                    JSONRPCMessage::Error(e) => processor.process_error(e),
-                }
-            }
-
-            info!("processor task exited (channel closed)");
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let conversation_manager = Arc::new(ConversationManager::new(
+        auth_manager.clone(),
+        SessionSource::VSCode,
+    ));
+    let shared = SharedState {
+        codex_linux_sandbox_exe,
+        config: Arc::new(config),
+        cli_overrides: cli_kv_overrides,
+        auth_manager,
+        conversation_manager,
+        conversation_events: ConversationEvents::default(),
+        thread_turns: SharedThreadTurns::default(),
+        mcp_probes: Arc::new(McpProbeCache::default()),
+        feedback,
+    };
+
+    match args.listen {
+        AppServerTransport::Stdio => {
+            transport::serve_stdio(shared).await;
+            Ok(())
         }
-    });
+        AppServerTransport::WebSocket { addr } => {
+            transport::serve_websocket(addr, args.token_file, shared).await
+        }
+        AppServerTransport::Unix { path } => transport::serve_unix(&path, shared).await,
+    }
+}
 
-    // Task: write outgoing messages to stdout.
-    let stdout_writer_handle = tokio::spawn(async move {
-        let mut stdout = io::stdout();
-        while let Some(outgoing_message) = outgoing_rx.recv().await {
-            let Ok(value) = serde_json::to_value(outgoing_message) else {
-                error!("Failed to convert OutgoingMessage to JSON value");
-                continue;
-            };
-            match serde_json::to_string(&value) {
-                Ok(mut json) => {
-                    json.push('\n');
-                    if let Err(e) = stdout.write_all(json.as_bytes()).await {
-                        error!("Failed to write to stdout: {e}");
-                        break;
-                    }
-                }
-                Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
+/// Start a `MessageProcessor` for a new client connection. The transport
+/// feeds it through the returned sender and writes out whatever arrives on
+/// the returned receiver; the task exits once the sender is dropped.
+pub(crate) fn spawn_connection(
+    shared: SharedState,
+) -> (
+    mpsc::Sender<JSONRPCMessage>,
+    mpsc::UnboundedReceiver<OutgoingMessage>,
+    JoinHandle<()>,
+) {
+    let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+    let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
+
+    let mut processor = MessageProcessor::new(
+        OutgoingMessageSender::new(outgoing_tx),
+        shared.codex_linux_sandbox_exe,
+        shared.config,
+        shared.cli_overrides,
+        shared.auth_manager,
+        shared.conversation_manager,
+        shared.conversation_events,
+        shared.thread_turns,
+        shared.mcp_probes,
+        shared.feedback,
+    );
+    let handle = tokio::spawn(async move {
+        while let Some(msg) = incoming_rx.recv().await {
+            match msg {
+                JSONRPCMessage::Request(r) => processor.process_request(r).await,
+                JSONRPCMessage::Response(r) => processor.process_response(r).await,
+                JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
+                JSONRPCMessage::Error(e) => processor.process_error(e),
             }
         }
 
-        info!("stdout writer exited (channel closed)");
+        processor.on_disconnect().await;
+        info!("processor task exited (channel closed)");
     });
 
-    // Wait for all tasks to finish.  The typical exit path is the stdin reader
-    // hitting EOF which, once it drops `incoming_tx`, propagates shutdown to
-    // the processor and then to the stdout task.
-    let _ = tokio::join!(stdin_reader_handle, processor_handle, stdout_writer_handle);
-
-    Ok(())
+    (incoming_tx, outgoing_rx, handle)
 }
@@ -1,10 +1,27 @@
+use clap::Parser;
+use codex_app_server::AppServerArgs;
 use codex_app_server::run_main;
 use codex_arg0::arg0_dispatch_or_else;
 use codex_common::CliConfigOverrides;
 
+#[derive(Parser, Debug)]
+struct TopCli {
+    #[clap(flatten)]
+    config_overrides: CliConfigOverrides,
+
+    #[clap(flatten)]
+    args: AppServerArgs,
+}
+
 fn main() -> anyhow::Result<()> {
     arg0_dispatch_or_else(|codex_linux_sandbox_exe| async move {
-        run_main(codex_linux_sandbox_exe, CliConfigOverrides::default()).await?;
+        let top_cli = TopCli::parse();
+        run_main(
+            codex_linux_sandbox_exe,
+            top_cli.config_overrides,
+            top_cli.args,
+        )
+        .await?;
         Ok(())
     })
 }
@@ -0,0 +1,256 @@
+//! `mcpServer/*`: manage the `[mcp_servers]` table in `config.toml` through
+//! the same helpers `codex mcp` uses.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::McpAuthStatus;
+use codex_app_server_protocol::McpServer;
+use codex_app_server_protocol::McpServerAddParams;
+use codex_app_server_protocol::McpServerAddResponse;
+use codex_app_server_protocol::McpServerListResponse;
+use codex_app_server_protocol::McpServerLoginAuthorizationUrlNotification;
+use codex_app_server_protocol::McpServerLoginCompletedNotification;
+use codex_app_server_protocol::McpServerRemoveResponse;
+use codex_app_server_protocol::McpServerSetEnabledResponse;
+use codex_app_server_protocol::McpServerStatus;
+use codex_app_server_protocol::McpServerTransport;
+use codex_app_server_protocol::ServerNotification;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::types::McpServerConfig;
+use codex_core::config::types::McpServerTransportConfig;
+use codex_core::mcp::auth::compute_auth_statuses;
+use codex_core::mcp::manage;
+use codex_core::mcp::manage::McpProbeCache;
+use codex_core::mcp::manage::McpServerProbe;
+use toml::Value as TomlValue;
+
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::outgoing_message::OutgoingMessageSender;
+
+/// Load the configuration as it is on disk now, since servers may have been
+/// added or removed since the app server started.
+pub(crate) async fn load_config(
+    cli_overrides: &[(String, TomlValue)],
+) -> Result<Config, JSONRPCErrorError> {
+    Config::load_with_cli_overrides(cli_overrides.to_vec(), ConfigOverrides::default())
+        .await
+        .map_err(|err| internal_error(format!("failed to load config: {err}")))
+}
+
+/// List every configured server with the status and tool count of the
+/// enabled ones, starting only those `probes` has no current result for.
+pub(crate) async fn list_servers(config: &Config, probes: &McpProbeCache) -> McpServerListResponse {
+    let (auth_statuses, probes) = tokio::join!(
+        compute_auth_statuses(
+            config.mcp_servers.iter(),
+            config.mcp_oauth_credentials_store_mode,
+        ),
+        probes.probe(config),
+    );
+
+    let mut names: Vec<&String> = config.mcp_servers.keys().collect();
+    names.sort();
+    let data = names
+        .into_iter()
+        .map(|name| {
+            let server = &config.mcp_servers[name];
+            let (status, tool_count, error) = match probes.get(name) {
+                _ if !server.enabled => (McpServerStatus::Disabled, 0, None),
+                Some(McpServerProbe::Ready { tool_count }) => (
+                    McpServerStatus::Ready,
+                    u32::try_from(*tool_count).unwrap_or(u32::MAX),
+                    None,
+                ),
+                Some(McpServerProbe::Failed { error }) => {
+                    (McpServerStatus::Failed, 0, Some(error.clone()))
+                }
+                None => (McpServerStatus::Failed, 0, None),
+            };
+            let auth_status = auth_statuses
+                .get(name.as_str())
+                .map(|entry| entry.auth_status.into())
+                .unwrap_or(McpAuthStatus::Unsupported);
+            McpServer {
+                name: name.clone(),
+                transport: transport_to_v2(&server.transport),
+                enabled: server.enabled,
+                status,
+                tool_count,
+                error,
+                auth_status,
+            }
+        })
+        .collect();
+
+    McpServerListResponse { data }
+}
+
+pub(crate) async fn add_server(
+    codex_home: &Path,
+    params: McpServerAddParams,
+) -> Result<McpServerAddResponse, JSONRPCErrorError> {
+    let McpServerAddParams { name, transport } = params;
+    manage::validate_server_name(&name).map_err(|err| invalid_request(err.to_string()))?;
+    if let McpServerTransport::Stdio { command, .. } = &transport
+        && command.is_empty()
+    {
+        return Err(invalid_request("command is required".to_string()));
+    }
+
+    let server = McpServerConfig {
+        transport: transport_from_v2(transport),
+        enabled: true,
+        startup_timeout_sec: None,
+        tool_timeout_sec: None,
+        enabled_tools: None,
+        disabled_tools: None,
+        sampling: None,
+    };
+    manage::add_global_server(codex_home, &name, server)
+        .await
+        .map_err(|err| internal_error(format!("{err:#}")))?;
+    Ok(McpServerAddResponse {})
+}
+
+pub(crate) async fn remove_server(
+    codex_home: &Path,
+    name: &str,
+) -> Result<McpServerRemoveResponse, JSONRPCErrorError> {
+    manage::validate_server_name(name).map_err(|err| invalid_request(err.to_string()))?;
+    let removed = manage::remove_global_server(codex_home, name)
+        .await
+        .map_err(|err| internal_error(format!("{err:#}")))?;
+    Ok(McpServerRemoveResponse { removed })
+}
+
+pub(crate) async fn set_server_enabled(
+    codex_home: &Path,
+    name: &str,
+    enabled: bool,
+) -> Result<McpServerSetEnabledResponse, JSONRPCErrorError> {
+    manage::validate_server_name(name).map_err(|err| invalid_request(err.to_string()))?;
+    let found = manage::set_global_server_enabled(codex_home, name, enabled)
+        .await
+        .map_err(|err| internal_error(format!("{err:#}")))?;
+    if !found {
+        return Err(invalid_request(format!(
+            "No MCP server named '{name}' found."
+        )));
+    }
+    Ok(McpServerSetEnabledResponse {})
+}
+
+/// Check that `name` can log in with OAuth before the flow is started.
+pub(crate) fn check_login(config: &Config, name: &str) -> Result<(), JSONRPCErrorError> {
+    manage::oauth_login_target(config, name)
+        .map(|_| ())
+        .map_err(|err| invalid_request(err.to_string()))
+}
+
+/// Run the OAuth flow for `name`, sending the authorization URL and then the
+/// outcome as notifications. A successful login clears the server's cached
+/// status so the next list starts it with the new credentials.
+pub(crate) async fn run_login(
+    config: Config,
+    name: String,
+    scopes: Vec<String>,
+    probes: Arc<McpProbeCache>,
+    outgoing: Arc<OutgoingMessageSender>,
+) {
+    let result = async {
+        let login = manage::start_oauth_login(&config, &name, &scopes).await?;
+        outgoing
+            .send_server_notification(ServerNotification::McpServerLoginAuthorizationUrl(
+                McpServerLoginAuthorizationUrlNotification {
+                    name: name.clone(),
+                    authorization_url: login.authorization_url().to_string(),
+                },
+            ))
+            .await;
+        login.finish().await
+    }
+    .await;
+    if result.is_ok() {
+        probes.invalidate(&name).await;
+    }
+
+    let notification = McpServerLoginCompletedNotification {
+        name,
+        success: result.is_ok(),
+        error: result.err().map(|err| format!("{err:#}")),
+    };
+    outgoing
+        .send_server_notification(ServerNotification::McpServerLoginCompleted(notification))
+        .await;
+}
+
+fn transport_to_v2(transport: &McpServerTransportConfig) -> McpServerTransport {
+    match transport {
+        McpServerTransportConfig::Stdio {
+            command,
+            args,
+            env,
+            cwd,
+            ..
+        } => McpServerTransport::Stdio {
+            command: command.clone(),
+            args: args.clone(),
+            env: env.clone(),
+            cwd: cwd.clone(),
+        },
+        McpServerTransportConfig::StreamableHttp {
+            url,
+            bearer_token_env_var,
+            ..
+        } => McpServerTransport::StreamableHttp {
+            url: url.clone(),
+            bearer_token_env_var: bearer_token_env_var.clone(),
+        },
+    }
+}
+
+fn transport_from_v2(transport: McpServerTransport) -> McpServerTransportConfig {
+    match transport {
+        McpServerTransport::Stdio {
+            command,
+            args,
+            env,
+            cwd,
+        } => McpServerTransportConfig::Stdio {
+            command,
+            args,
+            env: env.filter(|env| !env.is_empty()),
+            env_vars: Vec::new(),
+            cwd,
+        },
+        McpServerTransport::StreamableHttp {
+            url,
+            bearer_token_env_var,
+        } => McpServerTransportConfig::StreamableHttp {
+            url,
+            bearer_token_env_var,
+            http_headers: None,
+            env_http_headers: None,
+        },
+    }
+}
+
+fn invalid_request(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INVALID_REQUEST_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
+
+fn internal_error(message: String) -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message,
+        data: None,
+    }
+}
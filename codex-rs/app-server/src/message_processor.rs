@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
 use crate::codex_message_processor::CodexMessageProcessor;
+use crate::codex_message_processor::SharedThreadTurns;
+use crate::conversation_events::ConversationEvents;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::ClientInfo;
@@ -17,9 +19,10 @@ use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
+use codex_core::mcp::manage::McpProbeCache;
 use codex_feedback::CodexFeedback;
-use codex_protocol::protocol::SessionSource;
 use std::sync::Arc;
+use toml::Value as TomlValue;
 
 pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
@@ -28,30 +31,35 @@ pub(crate) struct MessageProcessor {
 }
 
 impl MessageProcessor {
-    /// Create a new `MessageProcessor`, retaining a handle to the outgoing
-    /// `Sender` so handlers can enqueue messages to be written to stdout.
+    /// Create a new `MessageProcessor` for one client connection, retaining a
+    /// handle to the outgoing `Sender` so handlers can enqueue messages to be
+    /// written back to that client. `auth_manager`, `conversation_manager`,
+    /// `conversation_events`, `thread_turns` and `mcp_probes` are shared with
+    /// every other connection.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         outgoing: OutgoingMessageSender,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        cli_overrides: Vec<(String, TomlValue)>,
+        auth_manager: Arc<AuthManager>,
+        conversation_manager: Arc<ConversationManager>,
+        conversation_events: ConversationEvents,
+        thread_turns: SharedThreadTurns,
+        mcp_probes: Arc<McpProbeCache>,
         feedback: CodexFeedback,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
-            false,
-            config.cli_auth_credentials_store_mode,
-        );
-        let conversation_manager = Arc::new(ConversationManager::new(
-            auth_manager.clone(),
-            SessionSource::VSCode,
-        ));
         let codex_message_processor = CodexMessageProcessor::new(
             auth_manager,
             conversation_manager,
+            conversation_events,
+            thread_turns,
             outgoing.clone(),
             codex_linux_sandbox_exe,
             config,
+            cli_overrides,
+            mcp_probes,
             feedback,
         );
 
@@ -152,6 +160,11 @@ impl MessageProcessor {
         self.outgoing.notify_client_response(id, result).await
     }
 
+    /// Release what the client held once its connection is closed.
+    pub(crate) async fn on_disconnect(&mut self) {
+        self.codex_message_processor.on_disconnect().await;
+    }
+
     /// Handle an error object received from the peer.
     pub(crate) fn process_error(&mut self, err: JSONRPCError) {
         tracing::error!("<- error: {:?}", err);
@@ -0,0 +1,477 @@
+//! Transports the app server can accept JSON-RPC clients on. Stdio and Unix
+//! sockets carry newline-delimited JSON; WebSocket connections carry one
+//! JSON-RPC message per text frame.
+
+use std::fmt;
+use std::io::ErrorKind;
+use std::io::Result as IoResult;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use codex_app_server_protocol::JSONRPCMessage;
+use futures::SinkExt;
+use futures::StreamExt;
+use rand::RngCore;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::ErrorResponse;
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use tokio_tungstenite::tungstenite::handshake::server::Response;
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::SharedState;
+use crate::outgoing_message::OutgoingMessage;
+use crate::spawn_connection;
+
+/// Name of the file under `$CODEX_HOME` holding the WebSocket auth token when
+/// `--token-file` is not given.
+const DEFAULT_TOKEN_FILE: &str = "app-server-token";
+
+/// Where the app server accepts clients.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AppServerTransport {
+    /// A single client on stdin/stdout.
+    #[default]
+    Stdio,
+    /// Any number of WebSocket clients on a loopback TCP address; each must
+    /// present the auth token.
+    WebSocket { addr: SocketAddr },
+    /// Any number of clients on a Unix domain socket, protected by its file
+    /// permissions.
+    Unix { path: PathBuf },
+}
+
+impl FromStr for AppServerTransport {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "stdio" || value == "stdio://" {
+            Ok(Self::Stdio)
+        } else if let Some(addr) = value.strip_prefix("ws://") {
+            let addr = addr.trim_end_matches('/');
+            let addr: SocketAddr = addr
+                .parse()
+                .map_err(|err| format!("invalid WebSocket address `{addr}`: {err}"))?;
+            // Plain ws:// would send the auth token and every message in
+            // cleartext; remote clients should come in through a TLS proxy
+            // or an SSH tunnel instead.
+            if !addr.ip().is_loopback() {
+                return Err(format!(
+                    "refusing to listen on non-loopback address `{addr}`: ws:// is unencrypted; bind to 127.0.0.1 or [::1] and reach it through a TLS proxy or SSH tunnel"
+                ));
+            }
+            Ok(Self::WebSocket { addr })
+        } else if let Some(path) = value.strip_prefix("unix://") {
+            if path.is_empty() {
+                Err("unix:// requires a socket path".to_string())
+            } else {
+                Ok(Self::Unix {
+                    path: PathBuf::from(path),
+                })
+            }
+        } else {
+            Err(format!(
+                "unsupported listen address `{value}`; expected stdio://, ws://IP:PORT or unix:///PATH"
+            ))
+        }
+    }
+}
+
+impl fmt::Display for AppServerTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdio => write!(f, "stdio://"),
+            Self::WebSocket { addr } => write!(f, "ws://{addr}"),
+            Self::Unix { path } => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+/// Command-line options for `codex app-server`.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct AppServerArgs {
+    /// Where to accept clients: `stdio://` (default), `ws://IP:PORT` on a
+    /// loopback address or `unix:///path/to/socket`.
+    #[arg(long = "listen", value_name = "URL", default_value = "stdio://")]
+    pub listen: AppServerTransport,
+
+    /// File holding the token WebSocket clients must send, either as an
+    /// `Authorization: Bearer` header or a `token` query parameter. Created
+    /// with a random token if missing. Defaults to
+    /// `$CODEX_HOME/app-server-token`.
+    #[arg(long = "token-file", value_name = "FILE")]
+    pub token_file: Option<PathBuf>,
+}
+
+/// Serve the single stdio client until stdin is closed.
+pub(crate) async fn serve_stdio(shared: SharedState) {
+    serve_lines(tokio::io::stdin(), tokio::io::stdout(), shared).await;
+}
+
+/// Accept clients on a Unix domain socket until interrupted.
+#[cfg(unix)]
+pub(crate) async fn serve_unix(path: &Path, shared: SharedState) -> IoResult<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+    use tokio::net::UnixStream;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            if UnixStream::connect(path).await.is_ok() {
+                return Err(std::io::Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("{} is already in use", path.display()),
+                ));
+            }
+            // Left behind by a server that did not shut down cleanly.
+            std::fs::remove_file(path)?;
+        }
+        Ok(_) => {
+            return Err(std::io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    // Bind inside a private (0700) directory and move the socket into place
+    // once its mode is restricted, so other users can never connect to it.
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let staging = tempfile::Builder::new()
+        .prefix(".codex-app-server-")
+        .tempdir_in(parent)?;
+    let staged_path = staging.path().join("socket");
+    let listener = UnixListener::bind(&staged_path)?;
+    std::fs::set_permissions(&staged_path, std::fs::Permissions::from_mode(0o600))?;
+    std::fs::rename(&staged_path, path)?;
+    drop(staging);
+    info!("app server listening on unix://{}", path.display());
+
+    let result = loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let (reader, writer) = stream.into_split();
+                    tokio::spawn(serve_lines(reader, writer, shared.clone()));
+                }
+                Err(err) => break Err(err),
+            },
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        }
+    };
+    let _ = std::fs::remove_file(path);
+    result
+}
+
+#[cfg(not(unix))]
+pub(crate) async fn serve_unix(_path: &Path, _shared: SharedState) -> IoResult<()> {
+    Err(std::io::Error::new(
+        ErrorKind::Unsupported,
+        "unix:// listeners are only supported on Unix",
+    ))
+}
+
+/// Accept WebSocket clients on `addr` until interrupted.
+pub(crate) async fn serve_websocket(
+    addr: SocketAddr,
+    token_file: Option<PathBuf>,
+    shared: SharedState,
+) -> IoResult<()> {
+    let token_file =
+        token_file.unwrap_or_else(|| shared.config.codex_home.join(DEFAULT_TOKEN_FILE));
+    let token = load_or_create_token(&token_file)?;
+    let listener = TcpListener::bind(addr).await?;
+    info!(
+        "app server listening on ws://{}; token in {}",
+        listener.local_addr()?,
+        token_file.display()
+    );
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let token = token.clone();
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = serve_websocket_connection(stream, &token, shared).await {
+                        debug!("WebSocket connection from {peer} ended: {err}");
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Serve one client speaking newline-delimited JSON.
+async fn serve_lines<R, W>(reader: R, mut writer: W, shared: SharedState)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (incoming_tx, mut outgoing_rx, processor_handle) = spawn_connection(shared);
+
+    // Task: read lines, push to `incoming_tx`.
+    let reader_handle = tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await.unwrap_or_default() {
+            match serde_json::from_str::<JSONRPCMessage>(&line) {
+                Ok(msg) => {
+                    if incoming_tx.send(msg).await.is_err() {
+                        // Receiver gone – nothing left to do.
+                        break;
+                    }
+                }
+                Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
+            }
+        }
+
+        debug!("connection reader finished (EOF)");
+    });
+
+    // Task: write outgoing messages, one per line.
+    let writer_handle = tokio::spawn(async move {
+        while let Some(outgoing_message) = outgoing_rx.recv().await {
+            let Some(mut json) = serialize_outgoing(outgoing_message) else {
+                continue;
+            };
+            json.push('\n');
+            if let Err(e) = writer.write_all(json.as_bytes()).await {
+                error!("Failed to write outgoing message: {e}");
+                break;
+            }
+        }
+
+        info!("connection writer exited (channel closed)");
+    });
+
+    // The typical exit path is the reader hitting EOF which, once it drops
+    // `incoming_tx`, propagates shutdown to the processor and then to the
+    // writer.
+    let _ = tokio::join!(reader_handle, processor_handle, writer_handle);
+}
+
+async fn serve_websocket_connection(
+    stream: TcpStream,
+    token: &str,
+    shared: SharedState,
+) -> Result<(), tungstenite::Error> {
+    let authorize = |request: &Request, response: Response| {
+        let presented = presented_token(request).unwrap_or_default();
+        if constant_time_eq(presented.as_bytes(), token.as_bytes()) {
+            Ok(response)
+        } else {
+            warn!("rejecting WebSocket client without a valid token");
+            let mut response = ErrorResponse::new(None);
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(response)
+        }
+    };
+    let (mut sink, mut stream) = tokio_tungstenite::accept_hdr_async(stream, authorize)
+        .await?
+        .split();
+
+    let (incoming_tx, mut outgoing_rx, processor_handle) = spawn_connection(shared);
+
+    // Pings are answered by tungstenite itself while the stream is polled.
+    let reader_handle = tokio::spawn(async move {
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(Message::Text(text)) => match serde_json::from_str::<JSONRPCMessage>(&text) {
+                    Ok(msg) => {
+                        if incoming_tx.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
+                },
+                Ok(Message::Close(_)) => break,
+                Ok(_) => {}
+                Err(err) => {
+                    debug!("WebSocket read failed: {err}");
+                    break;
+                }
+            }
+        }
+    });
+
+    let writer_handle = tokio::spawn(async move {
+        while let Some(outgoing_message) = outgoing_rx.recv().await {
+            let Some(json) = serialize_outgoing(outgoing_message) else {
+                continue;
+            };
+            if let Err(e) = sink.send(Message::text(json)).await {
+                error!("Failed to write WebSocket message: {e}");
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let _ = tokio::join!(reader_handle, processor_handle, writer_handle);
+    Ok(())
+}
+
+/// Token sent in an `Authorization: Bearer` header or, for clients that
+/// cannot set headers, a `token` query parameter.
+fn presented_token(request: &Request) -> Option<&str> {
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let (scheme, token) = value.split_once(' ')?;
+            scheme
+                .eq_ignore_ascii_case("bearer")
+                .then_some(token.trim())
+        });
+    bearer.or_else(|| {
+        request
+            .uri()
+            .query()?
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(key, value)| (key == "token").then_some(value))
+    })
+}
+
+fn serialize_outgoing(outgoing_message: OutgoingMessage) -> Option<String> {
+    let Ok(value) = serde_json::to_value(outgoing_message) else {
+        error!("Failed to convert OutgoingMessage to JSON value");
+        return None;
+    };
+    match serde_json::to_string(&value) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            error!("Failed to serialize JSONRPCMessage: {e}");
+            None
+        }
+    }
+}
+
+/// Read the auth token from `path`, writing a fresh random one (readable only
+/// by the current user) if the file does not exist yet.
+fn load_or_create_token(path: &Path) -> IoResult<String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let token = contents.trim();
+            if token.is_empty() {
+                return Err(std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("token file {} is empty", path.display()),
+                ));
+            }
+            return Ok(token.to_string());
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    let token = URL_SAFE_NO_PAD.encode(bytes);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    std::io::Write::write_all(&mut file, format!("{token}\n").as_bytes())?;
+    Ok(token)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_listen_urls() {
+        assert_eq!("stdio://".parse(), Ok(AppServerTransport::Stdio));
+        assert_eq!(
+            "ws://127.0.0.1:4500".parse(),
+            Ok(AppServerTransport::WebSocket {
+                addr: "127.0.0.1:4500".parse().expect("socket addr"),
+            })
+        );
+        assert_eq!(
+            "unix:///tmp/codex.sock".parse(),
+            Ok(AppServerTransport::Unix {
+                path: PathBuf::from("/tmp/codex.sock"),
+            })
+        );
+        assert!("ws://localhost".parse::<AppServerTransport>().is_err());
+        assert!("ws://0.0.0.0:4500".parse::<AppServerTransport>().is_err());
+        assert!("ws://[::]:4500".parse::<AppServerTransport>().is_err());
+        assert!("http://127.0.0.1:80".parse::<AppServerTransport>().is_err());
+    }
+
+    #[test]
+    fn creates_token_once() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("token");
+        let token = load_or_create_token(&path).expect("create token");
+        assert_eq!(token.len(), 43);
+        assert_eq!(load_or_create_token(&path).expect("read token"), token);
+    }
+
+    #[test]
+    fn reads_token_from_header_or_query() {
+        let request = |uri: &str, authorization: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(authorization) = authorization {
+                builder = builder.header(AUTHORIZATION, authorization);
+            }
+            builder.body(()).expect("request")
+        };
+        assert_eq!(
+            presented_token(&request("/", Some("Bearer secret"))),
+            Some("secret")
+        );
+        assert_eq!(
+            presented_token(&request("/?a=b&token=secret", None)),
+            Some("secret")
+        );
+        assert_eq!(presented_token(&request("/", Some("Basic secret"))), None);
+        assert_eq!(presented_token(&request("/", None)), None);
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
@@ -13,11 +13,15 @@ use tokio::process::ChildStdout;
 use anyhow::Context;
 use assert_cmd::prelude::*;
 use codex_app_server_protocol::AddConversationListenerParams;
+use codex_app_server_protocol::ApprovalRuleListParams;
+use codex_app_server_protocol::ApprovalRuleRemoveParams;
 use codex_app_server_protocol::ArchiveConversationParams;
 use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginChatGptParams;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
+use codex_app_server_protocol::ConfigReadParams;
+use codex_app_server_protocol::ConfigWriteParams;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::GetAccountParams;
 use codex_app_server_protocol::GetAuthStatusParams;
@@ -30,18 +34,27 @@ use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::LoginApiKeyParams;
+use codex_app_server_protocol::McpServerAddParams;
+use codex_app_server_protocol::McpServerListParams;
+use codex_app_server_protocol::McpServerLoginParams;
+use codex_app_server_protocol::McpServerRemoveParams;
+use codex_app_server_protocol::McpServerSetEnabledParams;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::ResumeConversationParams;
+use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
+use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadResumeParams;
+use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadSearchParams;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
@@ -327,6 +340,15 @@ impl McpProcess {
         self.send_request("thread/list", params).await
     }
 
+    /// Send a `thread/search` JSON-RPC request.
+    pub async fn send_thread_search_request(
+        &mut self,
+        params: ThreadSearchParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/search", params).await
+    }
+
     /// Send a `model/list` JSON-RPC request.
     pub async fn send_list_models_request(
         &mut self,
@@ -336,6 +358,87 @@ impl McpProcess {
         self.send_request("model/list", params).await
     }
 
+    /// Send a `config/read` JSON-RPC request.
+    pub async fn send_config_read_request(
+        &mut self,
+        params: ConfigReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("config/read", params).await
+    }
+
+    /// Send a `config/write` JSON-RPC request.
+    pub async fn send_config_write_request(
+        &mut self,
+        params: ConfigWriteParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("config/write", params).await
+    }
+
+    /// Send an `mcpServer/list` JSON-RPC request.
+    pub async fn send_mcp_server_list_request(
+        &mut self,
+        params: McpServerListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/list", params).await
+    }
+
+    /// Send an `mcpServer/add` JSON-RPC request.
+    pub async fn send_mcp_server_add_request(
+        &mut self,
+        params: McpServerAddParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/add", params).await
+    }
+
+    /// Send an `mcpServer/remove` JSON-RPC request.
+    pub async fn send_mcp_server_remove_request(
+        &mut self,
+        params: McpServerRemoveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/remove", params).await
+    }
+
+    /// Send an `mcpServer/setEnabled` JSON-RPC request.
+    pub async fn send_mcp_server_set_enabled_request(
+        &mut self,
+        params: McpServerSetEnabledParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/setEnabled", params).await
+    }
+
+    /// Send an `mcpServer/login` JSON-RPC request.
+    pub async fn send_mcp_server_login_request(
+        &mut self,
+        params: McpServerLoginParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/login", params).await
+    }
+
+    /// Send an `approvalRule/list` JSON-RPC request.
+    pub async fn send_approval_rule_list_request(
+        &mut self,
+        params: ApprovalRuleListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("approvalRule/list", params).await
+    }
+
+    /// Send an `approvalRule/remove` JSON-RPC request.
+    pub async fn send_approval_rule_remove_request(
+        &mut self,
+        params: ApprovalRuleRemoveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("approvalRule/remove", params).await
+    }
+
     /// Send a `resumeConversation` JSON-RPC request.
     pub async fn send_resume_conversation_request(
         &mut self,
@@ -377,6 +480,33 @@ impl McpProcess {
         self.send_request("turn/interrupt", params).await
     }
 
+    /// Send a `thread/fork` JSON-RPC request (v2).
+    pub async fn send_thread_fork_request(
+        &mut self,
+        params: ThreadForkParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/fork", params).await
+    }
+
+    /// Send a `thread/rollback` JSON-RPC request (v2).
+    pub async fn send_thread_rollback_request(
+        &mut self,
+        params: ThreadRollbackParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/rollback", params).await
+    }
+
+    /// Send a `review/start` JSON-RPC request (v2).
+    pub async fn send_review_start_request(
+        &mut self,
+        params: ReviewStartParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("review/start", params).await
+    }
+
     /// Send a `cancelLoginChatGpt` JSON-RPC request.
     pub async fn send_cancel_login_chat_gpt_request(
         &mut self,
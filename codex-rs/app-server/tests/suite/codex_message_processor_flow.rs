@@ -426,6 +426,8 @@ async fn test_send_user_turn_updates_sandbox_and_cwd_between_turns() -> Result<(
                 network_access: false,
                 exclude_tmpdir_env_var: false,
                 exclude_slash_tmp: false,
+                deny_read: Vec::new(),
+                network_allowlist: Vec::new(),
             },
             model: model.clone(),
             effort: Some(ReasoningEffort::Medium),
@@ -0,0 +1,297 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_chat_completions_server;
+use assert_cmd::prelude::*;
+use codex_app_server_protocol::JSONRPCMessage;
+use futures::SinkExt;
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::process::Child;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_listener_serves_independent_clients_with_shared_threads() -> Result<()> {
+    use tokio::net::UnixStream;
+
+    let server =
+        create_mock_chat_completions_server(vec![create_final_assistant_message_sse_response(
+            "Done",
+        )?])
+        .await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let socket = codex_home.path().join("app-server.sock");
+    let _child = spawn_app_server(codex_home.path(), &format!("unix://{}", socket.display()))?;
+
+    let connect = || async {
+        loop {
+            if let Ok(stream) = UnixStream::connect(&socket).await {
+                return stream;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    let first = timeout(DEFAULT_READ_TIMEOUT, connect()).await?;
+    let second = timeout(DEFAULT_READ_TIMEOUT, connect()).await?;
+    let mut first = LineClient::new(first);
+    let mut second = LineClient::new(second);
+
+    first.request(1, "initialize", initialize_params()).await?;
+    let response = first
+        .request(2, "thread/start", json!({ "model": "gpt-5" }))
+        .await?;
+    let thread_id = response["result"]["thread"]["id"]
+        .as_str()
+        .context("thread id")?
+        .to_string();
+
+    // Initialization is tracked per connection.
+    let response = second
+        .request(
+            1,
+            "addConversationListener",
+            json!({ "conversationId": thread_id }),
+        )
+        .await?;
+    assert_eq!(response["error"]["message"], "Not initialized");
+
+    // The conversation started by the first client is visible to the second.
+    second.request(2, "initialize", initialize_params()).await?;
+    let response = second
+        .request(
+            3,
+            "addConversationListener",
+            json!({ "conversationId": thread_id }),
+        )
+        .await?;
+    assert!(
+        response["result"]["subscriptionId"].is_string(),
+        "unexpected response: {response}"
+    );
+
+    // Both clients see every event of the shared thread.
+    let response = first
+        .request(
+            3,
+            "turn/start",
+            json!({ "threadId": thread_id, "input": [{ "type": "text", "text": "Hello" }] }),
+        )
+        .await?;
+    let turn_id = response["result"]["turn"]["id"]
+        .as_str()
+        .context("turn id")?
+        .to_string();
+    first.notification("codex/event/task_complete").await?;
+    second.notification("codex/event/task_complete").await?;
+
+    // Turns started by one client can be forked by another.
+    let response = second
+        .request(
+            4,
+            "thread/fork",
+            json!({ "threadId": thread_id, "turnId": turn_id }),
+        )
+        .await?;
+    assert!(
+        response["result"]["thread"]["id"].is_string(),
+        "unexpected response: {response}"
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_listener_refuses_to_replace_other_files() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let path = codex_home.path().join("app-server.sock");
+    std::fs::write(&path, "not a socket")?;
+
+    let mut child = spawn_app_server(codex_home.path(), &format!("unix://{}", path.display()))?;
+    let status = timeout(DEFAULT_READ_TIMEOUT, child.wait()).await??;
+    assert!(!status.success());
+    assert_eq!(std::fs::read_to_string(&path)?, "not a socket");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn websocket_listener_requires_token() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    let _child = spawn_app_server(codex_home.path(), &format!("ws://127.0.0.1:{port}"))?;
+
+    let connect = || async {
+        loop {
+            if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)).await {
+                return stream;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+
+    for token in [None, Some("wrong")] {
+        let stream = timeout(DEFAULT_READ_TIMEOUT, connect()).await?;
+        match timeout(DEFAULT_READ_TIMEOUT, handshake(stream, port, token)).await? {
+            Err(tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            }
+            other => panic!("unexpected handshake result: {other:?}"),
+        }
+    }
+
+    // The server created the token file on startup.
+    let token = std::fs::read_to_string(codex_home.path().join("app-server-token"))?;
+    let stream = timeout(DEFAULT_READ_TIMEOUT, connect()).await?;
+    let mut socket = timeout(
+        DEFAULT_READ_TIMEOUT,
+        handshake(stream, port, Some(token.trim())),
+    )
+    .await??;
+
+    let request = json!({ "id": 1, "method": "initialize", "params": initialize_params() });
+    socket.send(Message::text(request.to_string())).await?;
+    let message = timeout(DEFAULT_READ_TIMEOUT, socket.next())
+        .await?
+        .context("connection closed")??;
+    let response: Value = serde_json::from_str(message.to_text()?)?;
+    assert_eq!(response["id"], 1);
+    assert!(
+        response["result"]["userAgent"].is_string(),
+        "unexpected response: {response}"
+    );
+
+    Ok(())
+}
+
+fn spawn_app_server(codex_home: &Path, listen: &str) -> Result<Child> {
+    let program = std::process::Command::cargo_bin("codex-app-server")?
+        .get_program()
+        .to_owned();
+    let child = Command::new(program)
+        .args(["--listen", listen])
+        .env("CODEX_HOME", codex_home)
+        .env("RUST_LOG", "debug")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()?;
+    Ok(child)
+}
+
+fn initialize_params() -> Value {
+    json!({ "clientInfo": { "name": "codex-app-server-tests", "version": "0.1.0" } })
+}
+
+/// Client for the newline-delimited JSON transports.
+struct LineClient<S> {
+    stream: BufReader<S>,
+}
+
+impl<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin> LineClient<S> {
+    fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    /// Send a request and wait for the response or error with the same id.
+    async fn request(&mut self, id: i64, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "id": id, "method": method, "params": params });
+        let mut line = request.to_string();
+        line.push('\n');
+        self.stream.get_mut().write_all(line.as_bytes()).await?;
+
+        loop {
+            let mut line = String::new();
+            let read = timeout(DEFAULT_READ_TIMEOUT, self.stream.read_line(&mut line)).await??;
+            anyhow::ensure!(read > 0, "connection closed");
+            let message: JSONRPCMessage = serde_json::from_str(&line)?;
+            if matches!(
+                &message,
+                JSONRPCMessage::Response(_) | JSONRPCMessage::Error(_)
+            ) {
+                let value: Value = serde_json::from_str(&line)?;
+                if value["id"] == id {
+                    return Ok(value);
+                }
+            }
+        }
+    }
+
+    /// Wait for a notification with the given method.
+    async fn notification(&mut self, method: &str) -> Result<Value> {
+        loop {
+            let mut line = String::new();
+            let read = timeout(DEFAULT_READ_TIMEOUT, self.stream.read_line(&mut line)).await??;
+            anyhow::ensure!(read > 0, "connection closed");
+            let value: Value = serde_json::from_str(&line)?;
+            if value["method"] == method && value.get("id").is_none() {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+/// Open a WebSocket session over `stream`, presenting `token` if given.
+async fn handshake(
+    stream: TcpStream,
+    port: u16,
+    token: Option<&str>,
+) -> Result<tokio_tungstenite::WebSocketStream<TcpStream>, tungstenite::Error> {
+    let mut request = format!("ws://127.0.0.1:{port}/").into_client_request()?;
+    if let Some(token) = token {
+        let value = format!("Bearer {token}")
+            .parse()
+            .map_err(|err| tungstenite::Error::HttpFormat(tungstenite::http::Error::from(err)))?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+    let (socket, _) = tokio_tungstenite::client_async(request, stream).await?;
+    Ok(socket)
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -6,6 +6,7 @@ mod create_conversation;
 mod fuzzy_file_search;
 mod interrupt;
 mod list_resume;
+mod listen;
 mod login;
 mod send_message;
 mod set_default_model;
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::ApprovalRule;
+use codex_app_server_protocol::ApprovalRuleKind;
+use codex_app_server_protocol::ApprovalRuleListParams;
+use codex_app_server_protocol::ApprovalRuleListResponse;
+use codex_app_server_protocol::ApprovalRuleRemoveParams;
+use codex_app_server_protocol::ApprovalRuleRemoveResponse;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::ProjectApprovalRules;
+use codex_app_server_protocol::RequestId;
+use codex_core::approval_rules::add_rule;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn approval_rules_can_be_listed_and_removed() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    let cargo_test = ApprovalRule {
+        kind: ApprovalRuleKind::Prefix,
+        command: vec!["cargo".to_string(), "test".to_string()],
+    };
+    add_rule(
+        codex_home.path(),
+        project.path(),
+        cargo_test.clone().into_core(),
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_approval_rule_list_request(ApprovalRuleListParams { project: None })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let ApprovalRuleListResponse { data } = to_response(response)?;
+    assert_eq!(
+        data,
+        vec![ProjectApprovalRules {
+            project: project.path().to_path_buf(),
+            rules: vec![cargo_test.clone()],
+        }]
+    );
+
+    let request_id = mcp
+        .send_approval_rule_remove_request(ApprovalRuleRemoveParams {
+            project: project.path().to_path_buf(),
+            rule: cargo_test,
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let ApprovalRuleRemoveResponse { removed } = to_response(response)?;
+    assert!(removed);
+
+    let request_id = mcp
+        .send_approval_rule_list_request(ApprovalRuleListParams {
+            project: Some(project.path().to_path_buf()),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let ApprovalRuleListResponse { data } = to_response(response)?;
+    assert_eq!(
+        data,
+        vec![ProjectApprovalRules {
+            project: project.path().to_path_buf(),
+            rules: Vec::new(),
+        }]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::AskForApproval;
+use codex_app_server_protocol::ConfigFieldError;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_app_server_protocol::ConfigReadParams;
+use codex_app_server_protocol::ConfigReadResponse;
+use codex_app_server_protocol::ConfigValueEdit;
+use codex_app_server_protocol::ConfigWriteErrorData;
+use codex_app_server_protocol::ConfigWriteParams;
+use codex_app_server_protocol::ConfigWriteResponse;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxMode;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const INVALID_REQUEST_ERROR_CODE: i64 = -32600;
+
+#[tokio::test]
+async fn config_read_reports_value_origins() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_config_read_request(ConfigReadParams {
+            cwd: None,
+            profile: Some("fast".to_string()),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let ConfigReadResponse { config, origins } = to_response::<ConfigReadResponse>(response)?;
+
+    assert_eq!(config.model, "gpt-5-codex");
+    assert_eq!(config.approval_policy, AskForApproval::Never);
+    assert_eq!(config.sandbox_mode, SandboxMode::ReadOnly);
+    assert_eq!(config.profile.as_deref(), Some("fast"));
+    assert_eq!(
+        config.mcp_servers["docs"],
+        json!({ "command": "docs-server" })
+    );
+    assert_eq!(config.features.get("web_search_request"), Some(&true));
+
+    assert_eq!(origins["model"], ConfigLayerSource::Profile);
+    assert_eq!(origins["approval_policy"], ConfigLayerSource::User);
+    assert_eq!(
+        origins["model_reasoning_effort"],
+        ConfigLayerSource::Default
+    );
+    assert_eq!(origins["profiles.fast"], ConfigLayerSource::User);
+    assert_eq!(origins["mcp_servers.docs"], ConfigLayerSource::User);
+    assert_eq!(
+        origins["features.web_search_request"],
+        ConfigLayerSource::User
+    );
+    assert_eq!(origins["features.unified_exec"], ConfigLayerSource::Default);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn config_write_persists_edits() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_config_write_request(ConfigWriteParams {
+            edits: vec![
+                edit("model", json!("o3")),
+                edit("profiles.slow.model_reasoning_effort", json!("high")),
+                edit("profile", json!("slow")),
+                edit(
+                    "mcp_servers.search",
+                    json!({ "command": "search-server", "args": ["--stdio"] }),
+                ),
+                edit("mcp_servers.docs", json!(null)),
+                edit("features.unified_exec", json!(true)),
+            ],
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let _: ConfigWriteResponse = to_response(response)?;
+
+    let request_id = mcp
+        .send_config_read_request(ConfigReadParams::default())
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let ConfigReadResponse { config, origins } = to_response::<ConfigReadResponse>(response)?;
+
+    assert_eq!(config.model, "o3");
+    assert_eq!(config.profile.as_deref(), Some("slow"));
+    assert_eq!(
+        config.mcp_servers["search"],
+        json!({ "command": "search-server", "args": ["--stdio"] })
+    );
+    assert!(!config.mcp_servers.contains_key("docs"));
+    assert_eq!(config.features.get("unified_exec"), Some(&true));
+    assert_eq!(
+        origins["model_reasoning_effort"],
+        ConfigLayerSource::Profile
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn config_write_rejects_invalid_fields() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let before = std::fs::read_to_string(codex_home.path().join("config.toml"))?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_config_write_request(ConfigWriteParams {
+            edits: vec![
+                edit("model", json!("gpt-5")),
+                edit("approval_policy", json!("sometimes")),
+                edit("features.does_not_exist", json!(true)),
+                edit("profile", json!("missing")),
+            ],
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    let ConfigWriteErrorData { errors } =
+        serde_json::from_value(error.error.data.expect("error data"))?;
+    let key_paths: Vec<&str> = errors
+        .iter()
+        .map(|ConfigFieldError { key_path, .. }| key_path.as_str())
+        .collect();
+    assert_eq!(
+        key_paths,
+        vec!["approval_policy", "features.does_not_exist", "profile"]
+    );
+    assert!(
+        errors[0].message.contains("sometimes"),
+        "unexpected message: {}",
+        errors[0].message
+    );
+
+    // Nothing is written when any edit is invalid.
+    let after = std::fs::read_to_string(codex_home.path().join("config.toml"))?;
+    assert_eq!(after, before);
+
+    Ok(())
+}
+
+fn edit(key_path: &str, value: serde_json::Value) -> ConfigValueEdit {
+    ConfigValueEdit {
+        key_path: key_path.to_string(),
+        value,
+    }
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "gpt-5"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+[profiles.fast]
+model = "gpt-5-codex"
+
+[mcp_servers.docs]
+command = "docs-server"
+
+[features]
+web_search_request = true
+"#,
+    )
+}
@@ -0,0 +1,195 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::McpAuthStatus;
+use codex_app_server_protocol::McpServerAddParams;
+use codex_app_server_protocol::McpServerAddResponse;
+use codex_app_server_protocol::McpServerListParams;
+use codex_app_server_protocol::McpServerListResponse;
+use codex_app_server_protocol::McpServerLoginParams;
+use codex_app_server_protocol::McpServerRemoveParams;
+use codex_app_server_protocol::McpServerRemoveResponse;
+use codex_app_server_protocol::McpServerSetEnabledParams;
+use codex_app_server_protocol::McpServerSetEnabledResponse;
+use codex_app_server_protocol::McpServerStatus;
+use codex_app_server_protocol::McpServerTransport;
+use codex_app_server_protocol::RequestId;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const INVALID_REQUEST_ERROR_CODE: i64 = -32600;
+
+#[tokio::test]
+async fn add_list_disable_and_remove_servers() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let transport = McpServerTransport::Stdio {
+        command: "/nonexistent/docs-mcp-server".to_string(),
+        args: vec!["--stdio".to_string()],
+        env: None,
+        cwd: None,
+    };
+    let request_id = mcp
+        .send_mcp_server_add_request(McpServerAddParams {
+            name: "docs".to_string(),
+            transport: transport.clone(),
+        })
+        .await?;
+    let response = read_response(&mut mcp, request_id).await?;
+    let _: McpServerAddResponse = to_response(response)?;
+
+    let config = std::fs::read_to_string(codex_home.path().join("config.toml"))?;
+    assert!(
+        config.contains("[mcp_servers.docs]"),
+        "server not written: {config}"
+    );
+
+    // The command does not exist, so the server fails to start.
+    let McpServerListResponse { data } = list_servers(&mut mcp).await?;
+    assert_eq!(data.len(), 1);
+    let server = &data[0];
+    assert_eq!(server.name, "docs");
+    assert_eq!(server.transport, transport);
+    assert!(server.enabled);
+    assert_eq!(server.status, McpServerStatus::Failed);
+    assert_eq!(server.tool_count, 0);
+    assert!(server.error.is_some());
+    assert_eq!(server.auth_status, McpAuthStatus::Unsupported);
+
+    let request_id = mcp
+        .send_mcp_server_set_enabled_request(McpServerSetEnabledParams {
+            name: "docs".to_string(),
+            enabled: false,
+        })
+        .await?;
+    let response = read_response(&mut mcp, request_id).await?;
+    let _: McpServerSetEnabledResponse = to_response(response)?;
+
+    let McpServerListResponse { data } = list_servers(&mut mcp).await?;
+    assert!(!data[0].enabled);
+    assert_eq!(data[0].status, McpServerStatus::Disabled);
+    assert_eq!(data[0].error, None);
+
+    for expected in [true, false] {
+        let request_id = mcp
+            .send_mcp_server_remove_request(McpServerRemoveParams {
+                name: "docs".to_string(),
+            })
+            .await?;
+        let response = read_response(&mut mcp, request_id).await?;
+        let McpServerRemoveResponse { removed } = to_response(response)?;
+        assert_eq!(removed, expected);
+    }
+
+    let McpServerListResponse { data } = list_servers(&mut mcp).await?;
+    assert_eq!(data, Vec::new());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_requests_are_rejected() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_mcp_server_add_request(McpServerAddParams {
+            name: "bad name".to_string(),
+            transport: McpServerTransport::StreamableHttp {
+                url: "https://example.com/mcp".to_string(),
+                bearer_token_env_var: None,
+            },
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(
+        error.error.message,
+        "invalid server name 'bad name' (use letters, numbers, '-', '_')"
+    );
+
+    let request_id = mcp
+        .send_mcp_server_set_enabled_request(McpServerSetEnabledParams {
+            name: "missing".to_string(),
+            enabled: true,
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(error.error.message, "No MCP server named 'missing' found.");
+
+    // OAuth login needs a streamable HTTP server.
+    let request_id = mcp
+        .send_mcp_server_add_request(McpServerAddParams {
+            name: "local".to_string(),
+            transport: McpServerTransport::Stdio {
+                command: "local-mcp-server".to_string(),
+                args: Vec::new(),
+                env: None,
+                cwd: None,
+            },
+        })
+        .await?;
+    read_response(&mut mcp, request_id).await?;
+    let request_id = mcp
+        .send_mcp_server_login_request(McpServerLoginParams {
+            name: "local".to_string(),
+            scopes: Vec::new(),
+        })
+        .await?;
+    let error = read_error(&mut mcp, request_id).await?;
+    assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+    assert_eq!(
+        error.error.message,
+        "OAuth login is only supported for streamable HTTP servers."
+    );
+
+    Ok(())
+}
+
+async fn list_servers(mcp: &mut McpProcess) -> Result<McpServerListResponse> {
+    let request_id = mcp
+        .send_mcp_server_list_request(McpServerListParams::default())
+        .await?;
+    let response = read_response(mcp, request_id).await?;
+    to_response(response)
+}
+
+async fn read_response(mcp: &mut McpProcess, request_id: i64) -> Result<JSONRPCResponse> {
+    timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await?
+}
+
+async fn read_error(mcp: &mut McpProcess, request_id: i64) -> Result<JSONRPCError> {
+    timeout(
+        DEFAULT_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await?
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#,
+    )
+}
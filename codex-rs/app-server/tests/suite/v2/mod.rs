@@ -1,9 +1,16 @@
 mod account;
+mod approval_rules;
+mod config;
+mod mcp_server;
 mod model_list;
 mod rate_limits;
+mod review_start;
 mod thread_archive;
+mod thread_fork;
 mod thread_list;
 mod thread_resume;
+mod thread_rollback;
+mod thread_search;
 mod thread_start;
 mod turn_interrupt;
 mod turn_start;
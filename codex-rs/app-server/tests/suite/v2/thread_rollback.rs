@@ -0,0 +1,228 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use anyhow::bail;
+use app_test_support::McpProcess;
+use app_test_support::create_final_assistant_message_sse_response;
+use app_test_support::create_mock_chat_completions_server;
+use app_test_support::create_shell_sse_response;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxMode;
+use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadRollbackResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_rollback_drops_last_turns() -> Result<()> {
+    let responses = vec![
+        create_final_assistant_message_sse_response("Done")?,
+        create_final_assistant_message_sse_response("Done")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_thread(&mut mcp, ThreadStartParams::default()).await?;
+    run_turn(&mut mcp, &thread_id, "first message").await?;
+    run_turn(&mut mcp, &thread_id, "second message").await?;
+
+    let rollback_id = mcp
+        .send_thread_rollback_request(ThreadRollbackParams {
+            thread_id: thread_id.clone(),
+            num_turns: 1,
+            restore_files: false,
+        })
+        .await?;
+    let rollback_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(rollback_id)),
+    )
+    .await??;
+    let ThreadRollbackResponse {
+        thread,
+        restored_snapshot,
+    } = to_response::<ThreadRollbackResponse>(rollback_resp)?;
+    assert_ne!(thread.id, thread_id);
+    assert_eq!(thread.preview, "first message");
+    assert_eq!(restored_snapshot, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_rollback_rejects_more_turns_than_recorded() -> Result<()> {
+    let server = create_mock_chat_completions_server(vec![]).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_thread(&mut mcp, ThreadStartParams::default()).await?;
+    let rollback_id = mcp
+        .send_thread_rollback_request(ThreadRollbackParams {
+            thread_id: thread_id.clone(),
+            num_turns: 1,
+            restore_files: false,
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(rollback_id)),
+    )
+    .await??;
+    assert_eq!(
+        err.error.message,
+        format!("cannot roll back 1 turns: thread {thread_id} has 0 turns")
+    );
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+#[tokio::test]
+async fn thread_rollback_restores_ghost_snapshot() -> Result<()> {
+    let repo = TempDir::new()?;
+    init_git_repo(repo.path())?;
+
+    let responses = vec![
+        create_shell_sse_response(
+            vec!["touch".to_string(), "created.txt".to_string()],
+            Some(repo.path()),
+            Some(5000),
+            "call1",
+        )?,
+        create_final_assistant_message_sse_response("Created")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let thread_id = start_thread(
+        &mut mcp,
+        ThreadStartParams {
+            cwd: Some(repo.path().to_string_lossy().into_owned()),
+            sandbox: Some(SandboxMode::DangerFullAccess),
+            ..Default::default()
+        },
+    )
+    .await?;
+    run_turn(&mut mcp, &thread_id, "create a file").await?;
+    assert!(repo.path().join("created.txt").exists());
+
+    let rollback_id = mcp
+        .send_thread_rollback_request(ThreadRollbackParams {
+            thread_id,
+            num_turns: 1,
+            restore_files: true,
+        })
+        .await?;
+    let rollback_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(rollback_id)),
+    )
+    .await??;
+    let ThreadRollbackResponse {
+        restored_snapshot, ..
+    } = to_response::<ThreadRollbackResponse>(rollback_resp)?;
+    assert!(restored_snapshot.is_some());
+    assert!(!repo.path().join("created.txt").exists());
+    assert!(repo.path().join("README.txt").exists());
+
+    Ok(())
+}
+
+async fn start_thread(mcp: &mut McpProcess, params: ThreadStartParams) -> Result<String> {
+    let start_id = mcp.send_thread_start_request(params).await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread } = to_response::<ThreadStartResponse>(start_resp)?;
+    Ok(thread.id)
+}
+
+// Start a turn and wait for it to complete.
+async fn run_turn(mcp: &mut McpProcess, thread_id: &str, text: &str) -> Result<()> {
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread_id.to_string(),
+            input: vec![V2UserInput::Text {
+                text: text.to_string(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("codex/event/task_complete"),
+    )
+    .await??;
+    Ok(())
+}
+
+fn git(path: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git").args(args).current_dir(path).status()?;
+    if !status.success() {
+        bail!("git {args:?} exited with {status}");
+    }
+    Ok(())
+}
+
+fn init_git_repo(path: &Path) -> Result<()> {
+    git(path, &["init", "--initial-branch=main"])?;
+    git(path, &["config", "user.name", "Codex Tests"])?;
+    git(path, &["config", "user.email", "codex-tests@example.com"])?;
+    std::fs::write(path.join("README.txt"), "hello\n")?;
+    git(path, &["add", "README.txt"])?;
+    git(path, &["commit", "-m", "Add README.txt"])?;
+    Ok(())
+}
+
+// Helper to create a config.toml pointing at the mock model server.
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
@@ -1,8 +1,10 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod unified_diff;
 
 use std::collections::HashMap;
+use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::Utf8Error;
@@ -15,6 +17,10 @@ pub use parser::ParseError;
 use parser::ParseError::*;
 use parser::UpdateFileChunk;
 pub use parser::parse_patch;
+pub use seek_sequence::MatchKind;
+use seek_sequence::SIMILARITY_THRESHOLD;
+use similar::Algorithm;
+use similar::DiffOp;
 use similar::TextDiff;
 use thiserror::Error;
 use tree_sitter::LanguageError;
@@ -31,15 +37,20 @@ pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_too
 
 const APPLY_PATCH_COMMANDS: [&str; 2] = ["apply_patch", "applypatch"];
 
+/// Regions less similar than this to a rejected chunk are not worth showing
+/// as its nearest candidate.
+const MIN_CANDIDATE_SIMILARITY: f32 = 0.5;
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ApplyPatchError {
     #[error(transparent)]
     ParseError(#[from] ParseError),
     #[error(transparent)]
     IoError(#[from] IoError),
-    /// Error that occurs while computing replacements when applying patch chunks
+    /// Some chunks of the `*** Update File:` hunks could not be located in the
+    /// files they update. The chunks that could be located are still applied.
     #[error("{0}")]
-    ComputeReplacements(String),
+    Conflicts(ConflictReport),
     /// A raw patch body was provided without an explicit `apply_patch` invocation.
     #[error(
         "patch detected without explicit call to apply_patch. Rerun as [\"apply_patch\", \"<patch>\"]"
@@ -79,6 +90,95 @@ impl PartialEq for IoError {
     }
 }
 
+/// How each chunk (`@@` section) of the `*** Update File:` hunks in a patch
+/// was applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictReport {
+    pub chunks: Vec<ChunkReport>,
+}
+
+impl ConflictReport {
+    /// Number of chunks that could not be applied.
+    pub fn rejected(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.is_rejected())
+            .count()
+    }
+}
+
+impl fmt::Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} chunks could not be applied:",
+            self.rejected(),
+            self.chunks.len()
+        )?;
+        for chunk in &self.chunks {
+            write!(f, "\n{} chunk {}: ", chunk.path.display(), chunk.index)?;
+            match &chunk.status {
+                ChunkStatus::Applied { line } => write!(f, "applied at line {line}")?,
+                ChunkStatus::Fuzzed { line, match_kind } => {
+                    write!(f, "fuzzed at line {line} ({match_kind})")?
+                }
+                ChunkStatus::Rejected { reason, nearest } => {
+                    write!(f, "rejected. {reason}")?;
+                    if let Some(nearest) = nearest {
+                        write!(
+                            f,
+                            "\nNearest candidate at line {} ({:.0}% similar):\n{}",
+                            nearest.line,
+                            nearest.similarity * 100.0,
+                            nearest.lines.join("\n")
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkReport {
+    /// File the chunk updates.
+    pub path: PathBuf,
+    /// 1-based position of the chunk within its `*** Update File:` hunk.
+    pub index: usize,
+    pub status: ChunkStatus,
+}
+
+impl ChunkReport {
+    pub fn is_rejected(&self) -> bool {
+        matches!(self.status, ChunkStatus::Rejected { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChunkStatus {
+    /// The chunk matched exactly and was applied at 1-based `line`.
+    Applied { line: usize },
+    /// The chunk only matched loosely, as described by `match_kind`, and was
+    /// applied at 1-based `line`.
+    Fuzzed { line: usize, match_kind: MatchKind },
+    /// The chunk could not be located and was not applied.
+    Rejected {
+        reason: String,
+        nearest: Option<NearestCandidate>,
+    },
+}
+
+/// The region of a file that came closest to a rejected chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearestCandidate {
+    /// 1-based line the region starts at.
+    pub line: usize,
+    /// Between 0 and 1.
+    pub similarity: f32,
+    pub lines: Vec<String>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum MaybeApplyPatch {
     Body(ApplyPatchArgs),
@@ -127,6 +227,16 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 pub enum ApplyPatchFileChange {
     Add {
         content: String,
+        /// Permission bits set by a later hunk of the same patch.
+        mode: Option<u32>,
+    },
+    AddBinary {
+        content: Vec<u8>,
+        /// Permission bits set by a later hunk of the same patch.
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -136,6 +246,12 @@ pub enum ApplyPatchFileChange {
         move_path: Option<PathBuf>,
         /// new_content that will result after the unified_diff is applied.
         new_content: String,
+        /// Permission bits set by a later hunk of the same patch.
+        mode: Option<u32>,
+    },
+    SetMode {
+        /// Permission bits, e.g. `0o755`.
+        mode: u32,
     },
 }
 
@@ -198,7 +314,13 @@ impl ApplyPatchAction {
 + {content}
 *** End Patch"#,
         );
-        let changes = HashMap::from([(path.to_path_buf(), ApplyPatchFileChange::Add { content })]);
+        let changes = HashMap::from([(
+            path.to_path_buf(),
+            ApplyPatchFileChange::Add {
+                content,
+                mode: None,
+            },
+        )]);
         #[expect(clippy::expect_used)]
         Self {
             changes,
@@ -252,11 +374,71 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                 })
                 .unwrap_or_else(|| cwd.to_path_buf());
             let mut changes = HashMap::new();
+            let mut report = ConflictReport { chunks: Vec::new() };
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
                 match hunk {
                     Hunk::AddFile { contents, .. } => {
-                        changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::Add {
+                                content: contents,
+                                mode: None,
+                            },
+                        );
+                    }
+                    Hunk::AddBinaryFile { contents, .. } => {
+                        changes.insert(
+                            path,
+                            ApplyPatchFileChange::AddBinary {
+                                content: contents,
+                                mode: None,
+                            },
+                        );
+                    }
+                    Hunk::AddSymlink { target, .. } => {
+                        changes.insert(path, ApplyPatchFileChange::AddSymlink { target });
+                    }
+                    Hunk::SetMode { mode, .. } => {
+                        // A file added, updated or moved earlier in the same
+                        // patch is described by that change, which also
+                        // carries the new mode.
+                        let earlier = if changes.contains_key(&path) {
+                            changes.get_mut(&path)
+                        } else {
+                            changes.values_mut().find(|change| {
+                                matches!(
+                                    change,
+                                    ApplyPatchFileChange::Update {
+                                        move_path: Some(dest),
+                                        ..
+                                    } if dest == &path
+                                )
+                            })
+                        };
+                        if let Some(earlier) = earlier {
+                            match earlier {
+                                ApplyPatchFileChange::Add { mode: slot, .. }
+                                | ApplyPatchFileChange::AddBinary { mode: slot, .. }
+                                | ApplyPatchFileChange::Update { mode: slot, .. } => {
+                                    *slot = Some(mode);
+                                }
+                                ApplyPatchFileChange::SetMode { mode: slot } => *slot = mode,
+                                // Applying the patch fails on these.
+                                ApplyPatchFileChange::AddSymlink { .. }
+                                | ApplyPatchFileChange::Delete { .. } => {}
+                            }
+                            continue;
+                        }
+                        if let Err(e) = std::fs::symlink_metadata(&path) {
+                            return MaybeApplyPatchVerified::CorrectnessError(
+                                ApplyPatchError::IoError(IoError {
+                                    context: format!("Failed to read {}", path.display()),
+                                    source: e,
+                                }),
+                            );
+                        }
+                        changes.insert(path, ApplyPatchFileChange::SetMode { mode });
                     }
                     Hunk::DeleteFile { .. } => {
                         let content = match std::fs::read_to_string(&path) {
@@ -275,26 +457,42 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                     Hunk::UpdateFile {
                         move_path, chunks, ..
                     } => {
-                        let ApplyPatchFileUpdate {
-                            unified_diff,
-                            content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
-                            Ok(diff) => diff,
+                        let (
+                            ApplyPatchFileUpdate {
+                                unified_diff,
+                                content: contents,
+                            },
+                            chunk_reports,
+                        ) = match partial_unified_diff_from_chunks(&path, &chunks, 1) {
+                            Ok(update) => update,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
                             }
                         };
+                        let applied_any = chunk_reports.iter().any(|chunk| !chunk.is_rejected());
+                        report.chunks.extend(chunk_reports);
+                        if !applied_any {
+                            continue;
+                        }
                         changes.insert(
                             path,
                             ApplyPatchFileChange::Update {
                                 unified_diff,
                                 move_path: move_path.map(|p| effective_cwd.join(p)),
                                 new_content: contents,
+                                mode: None,
                             },
                         );
                     }
                 }
             }
+            // Apply what can be applied; the rejected chunks are reported when
+            // the patch is executed. Only fail if nothing applies at all.
+            if changes.is_empty() && report.rejected() > 0 {
+                return MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::Conflicts(
+                    report,
+                ));
+            }
             MaybeApplyPatchVerified::Body(ApplyPatchAction {
                 changes,
                 patch,
@@ -510,11 +708,11 @@ pub fn apply_hunks(
     let _existing_paths: Vec<&Path> = hunks
         .iter()
         .filter_map(|hunk| match hunk {
-            Hunk::AddFile { .. } => {
+            Hunk::AddFile { .. } | Hunk::AddBinaryFile { .. } | Hunk::AddSymlink { .. } => {
                 // The file is being added, so it doesn't exist yet.
                 None
             }
-            Hunk::DeleteFile { path } => Some(path.as_path()),
+            Hunk::DeleteFile { path } | Hunk::SetMode { path, .. } => Some(path.as_path()),
             Hunk::UpdateFile {
                 path, move_path, ..
             } => match move_path {
@@ -535,10 +733,22 @@ pub fn apply_hunks(
 
     // Delegate to a helper that applies each hunk to the filesystem.
     match apply_hunks_to_files(hunks) {
-        Ok(affected) => {
+        Ok((affected, report)) if report.rejected() == 0 => {
             print_summary(&affected, stdout).map_err(ApplyPatchError::from)?;
             Ok(())
         }
+        Ok((affected, report)) => {
+            if !affected.is_empty() {
+                writeln!(
+                    stdout,
+                    "Applied part of the patch. Updated the following files:"
+                )
+                .map_err(ApplyPatchError::from)?;
+                write_affected_paths(&affected, stdout).map_err(ApplyPatchError::from)?;
+            }
+            writeln!(stderr, "{report}").map_err(ApplyPatchError::from)?;
+            Err(ApplyPatchError::Conflicts(report))
+        }
         Err(err) => {
             let msg = err.to_string();
             writeln!(stderr, "{msg}").map_err(ApplyPatchError::from)?;
@@ -563,9 +773,16 @@ pub struct AffectedPaths {
     pub deleted: Vec<PathBuf>,
 }
 
-/// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
-fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
+impl AffectedPaths {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// Apply the hunks to the filesystem, returning which files were added, modified, or deleted,
+/// and how each update chunk was applied. Files none of whose chunks could be located are left
+/// untouched. Returns an error if the patch could not be applied.
+fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<(AffectedPaths, ConflictReport)> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
@@ -573,20 +790,37 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
+    let mut report = ConflictReport { chunks: Vec::new() };
     for hunk in hunks {
         match hunk {
             Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
+                create_parent_dirs(path)?;
                 std::fs::write(path, contents)
                     .with_context(|| format!("Failed to write file {}", path.display()))?;
                 added.push(path.clone());
             }
+            Hunk::AddBinaryFile { path, contents } => {
+                create_parent_dirs(path)?;
+                std::fs::write(path, contents)
+                    .with_context(|| format!("Failed to write file {}", path.display()))?;
+                added.push(path.clone());
+            }
+            Hunk::AddSymlink { path, target } => {
+                create_parent_dirs(path)?;
+                // Like `*** Add File:`, replace whatever file is already there.
+                if std::fs::symlink_metadata(path).is_ok_and(|meta| !meta.is_dir()) {
+                    std::fs::remove_file(path)
+                        .with_context(|| format!("Failed to replace {}", path.display()))?;
+                }
+                create_symlink(target, path)
+                    .with_context(|| format!("Failed to create symlink {}", path.display()))?;
+                added.push(path.clone());
+            }
+            Hunk::SetMode { path, mode } => {
+                set_mode(path, *mode)
+                    .with_context(|| format!("Failed to set mode of {}", path.display()))?;
+                modified.push(path.clone());
+            }
             Hunk::DeleteFile { path } => {
                 std::fs::remove_file(path)
                     .with_context(|| format!("Failed to delete file {}", path.display()))?;
@@ -597,16 +831,18 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
                 move_path,
                 chunks,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                let AppliedPatch {
+                    new_contents,
+                    chunks: chunk_reports,
+                    ..
+                } = derive_new_contents_from_chunks(path, chunks)?;
+                let applied_any = chunk_reports.iter().any(|chunk| !chunk.is_rejected());
+                report.chunks.extend(chunk_reports);
+                if !applied_any {
+                    continue;
+                }
                 if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
+                    create_parent_dirs(dest)?;
                     std::fs::write(dest, new_contents)
                         .with_context(|| format!("Failed to write file {}", dest.display()))?;
                     std::fs::remove_file(path)
@@ -620,20 +856,68 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
             }
         }
     }
-    Ok(AffectedPaths {
-        added,
-        modified,
-        deleted,
-    })
+    Ok((
+        AffectedPaths {
+            added,
+            modified,
+            deleted,
+        },
+        report,
+    ))
+}
+
+fn create_parent_dirs(path: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create parent directories for {}", path.display())
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    // chmod follows symlinks, which would let a patch change the mode of a
+    // file the link points to anywhere on disk.
+    if std::fs::symlink_metadata(path)?.file_type().is_symlink() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "refusing to change the mode of a symlink",
+        ));
+    }
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "file modes can only be set on Unix",
+    ))
 }
 
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    chunks: Vec<ChunkReport>,
 }
 
-/// Return *only* the new file contents (joined into a single `String`) after
-/// applying the chunks to the file at `path`.
+/// Return the new file contents (joined into a single `String`) after
+/// applying the chunks to the file at `path`, along with how each chunk was
+/// applied. Chunks that cannot be located are skipped.
 fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
@@ -656,7 +940,7 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let (replacements, chunks) = compute_replacements(&original_lines, path, chunks);
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
     if !new_lines.last().is_some_and(String::is_empty) {
@@ -666,100 +950,250 @@ fn derive_new_contents_from_chunks(
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        chunks,
     })
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
-/// new lines, given the patch `chunks`. Each replacement is returned as
+/// new lines, given the patch `chunks`, along with a report of how each chunk
+/// was located. Chunks that cannot be located are reported as rejected and
+/// produce no replacement. Each replacement is returned as
 /// `(start_index, old_len, new_lines)`.
 fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
-) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
-    let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
+) -> (Vec<Replacement>, Vec<ChunkReport>) {
+    let mut replacements: Vec<Replacement> = Vec::new();
+    let mut reports: Vec<ChunkReport> = Vec::new();
     let mut line_index: usize = 0;
 
-    for chunk in chunks {
-        // If a chunk has a `change_context`, we use seek_sequence to find it, then
-        // adjust our `line_index` to continue from there.
-        if let Some(ctx_line) = &chunk.change_context {
-            if let Some(idx) = seek_sequence::seek_sequence(
-                original_lines,
-                std::slice::from_ref(ctx_line),
-                line_index,
-                false,
-            ) {
-                line_index = idx + 1;
-            } else {
-                return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
-                    ctx_line,
-                    path.display()
-                )));
+    for (index, chunk) in chunks.iter().enumerate() {
+        let status = match locate_chunk(original_lines, path, chunk, line_index) {
+            Ok(LocatedChunk {
+                replacement,
+                next_line_index,
+                match_kind,
+            }) => {
+                let line = replacement.0 + 1;
+                replacements.push(replacement);
+                line_index = next_line_index;
+                if match_kind == MatchKind::Exact {
+                    ChunkStatus::Applied { line }
+                } else {
+                    ChunkStatus::Fuzzed { line, match_kind }
+                }
             }
-        }
+            Err(status) => status,
+        };
+        reports.push(ChunkReport {
+            path: path.to_path_buf(),
+            index: index + 1,
+            status,
+        });
+    }
 
-        if chunk.old_lines.is_empty() {
-            // Pure addition (no old lines). We'll add them at the end or just
-            // before the final empty line if one exists.
-            let insertion_idx = if original_lines.last().is_some_and(String::is_empty) {
-                original_lines.len() - 1
-            } else {
-                original_lines.len()
-            };
-            replacements.push((insertion_idx, 0, chunk.new_lines.clone()));
-            continue;
-        }
+    replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
 
-        // Otherwise, try to match the existing lines in the file with the old lines
-        // from the chunk. If found, schedule that region for replacement.
-        // Attempt to locate the `old_lines` verbatim within the file.  In many
-        // real‑world diffs the last element of `old_lines` is an *empty* string
-        // representing the terminating newline of the region being replaced.
-        // This sentinel is not present in `original_lines` because we strip the
-        // trailing empty slice emitted by `split('\n')`.  If a direct search
-        // fails and the pattern ends with an empty string, retry without that
-        // final element so that modifications touching the end‑of‑file can be
-        // located reliably.
-
-        let mut pattern: &[String] = &chunk.old_lines;
-        let mut found =
-            seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
-
-        let mut new_slice: &[String] = &chunk.new_lines;
-
-        if found.is_none() && pattern.last().is_some_and(String::is_empty) {
-            // Retry without the trailing empty line which represents the final
-            // newline in the file.
-            pattern = &pattern[..pattern.len() - 1];
-            if new_slice.last().is_some_and(String::is_empty) {
-                new_slice = &new_slice[..new_slice.len() - 1];
-            }
+    (replacements, reports)
+}
+
+/// `(start_index, old_len, new_lines)`
+type Replacement = (usize, usize, Vec<String>);
+
+struct LocatedChunk {
+    replacement: Replacement,
+    /// Where to continue searching for the next chunk.
+    next_line_index: usize,
+    /// The loosest match needed to locate the chunk.
+    match_kind: MatchKind,
+}
 
-            found = seek_sequence::seek_sequence(
-                original_lines,
-                pattern,
-                line_index,
-                chunk.is_end_of_file,
-            );
+/// Locate `chunk` in `original_lines` at or after `line_index`. If the chunk
+/// cannot be located, returns its [`ChunkStatus::Rejected`] status instead.
+fn locate_chunk(
+    original_lines: &[String],
+    path: &Path,
+    chunk: &UpdateFileChunk,
+    mut line_index: usize,
+) -> std::result::Result<LocatedChunk, ChunkStatus> {
+    let mut context_match = MatchKind::Exact;
+
+    // If a chunk has a `change_context`, we use seek_sequence to find it, then
+    // adjust our `line_index` to continue from there.
+    if let Some(ctx_line) = &chunk.change_context {
+        match find_lines(
+            original_lines,
+            std::slice::from_ref(ctx_line),
+            &[false],
+            line_index,
+            false,
+        ) {
+            Ok((idx, match_kind)) => {
+                line_index = idx + 1;
+                context_match = match_kind;
+            }
+            Err(nearest) => {
+                return Err(ChunkStatus::Rejected {
+                    reason: format!(
+                        "Failed to find context '{}' in {}",
+                        ctx_line,
+                        path.display()
+                    ),
+                    nearest,
+                });
+            }
         }
+    }
 
-        if let Some(start_idx) = found {
-            replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
-            line_index = start_idx + pattern.len();
+    if chunk.old_lines.is_empty() {
+        // Pure addition (no old lines). We'll add them at the end or just
+        // before the final empty line if one exists.
+        let insertion_idx = if original_lines.last().is_some_and(String::is_empty) {
+            original_lines.len() - 1
         } else {
-            return Err(ApplyPatchError::ComputeReplacements(format!(
+            original_lines.len()
+        };
+        return Ok(LocatedChunk {
+            replacement: (insertion_idx, 0, chunk.new_lines.clone()),
+            next_line_index: line_index,
+            match_kind: context_match,
+        });
+    }
+
+    // Otherwise, try to match the existing lines in the file with the old lines
+    // from the chunk. If found, schedule that region for replacement.
+    // Attempt to locate the `old_lines` verbatim within the file.  In many
+    // real‑world diffs the last element of `old_lines` is an *empty* string
+    // representing the terminating newline of the region being replaced.
+    // This sentinel is not present in `original_lines` because we strip the
+    // trailing empty slice emitted by `split('\n')`.  If a direct search
+    // fails and the pattern ends with an empty string, retry without that
+    // final element so that modifications touching the end‑of‑file can be
+    // located reliably.
+
+    let mut pattern: &[String] = &chunk.old_lines;
+    let found =
+        seek_sequence::seek_sequence(original_lines, pattern, line_index, chunk.is_end_of_file);
+
+    let mut new_slice: &[String] = &chunk.new_lines;
+
+    if found.is_none() && pattern.last().is_some_and(String::is_empty) {
+        // Retry without the trailing empty line which represents the final
+        // newline in the file.
+        pattern = &pattern[..pattern.len() - 1];
+        if new_slice.last().is_some_and(String::is_empty) {
+            new_slice = &new_slice[..new_slice.len() - 1];
+        }
+    }
+
+    let found = match found {
+        Some(found) => Ok(found),
+        // Only context lines may be fuzzed: a line the chunk removes must be
+        // in the file, or the wrong code would be deleted.
+        None => find_lines(
+            original_lines,
+            pattern,
+            &removed_lines(pattern, new_slice),
+            line_index,
+            chunk.is_end_of_file,
+        ),
+    };
+    match found {
+        Ok((start_idx, match_kind)) => Ok(LocatedChunk {
+            replacement: (
+                start_idx,
+                pattern.len(),
+                if match_kind == MatchKind::Exact {
+                    new_slice.to_vec()
+                } else {
+                    keep_matched_context(
+                        &original_lines[start_idx..start_idx + pattern.len()],
+                        pattern,
+                        new_slice,
+                    )
+                },
+            ),
+            next_line_index: start_idx + pattern.len(),
+            match_kind: if match_kind == MatchKind::Exact {
+                context_match
+            } else {
+                match_kind
+            },
+        }),
+        Err(nearest) => Err(ChunkStatus::Rejected {
+            reason: format!(
                 "Failed to find expected lines in {}:\n{}",
                 path.display(),
                 chunk.old_lines.join("\n"),
-            )));
+            ),
+            nearest,
+        }),
+    }
+}
+
+/// When `pattern` only matched the `matched` lines of the file loosely, keep
+/// the file's own version of the lines the chunk leaves unchanged rather than
+/// the chunk's (possibly misremembered) copy of them.
+fn keep_matched_context(
+    matched: &[String],
+    pattern: &[String],
+    new_lines: &[String],
+) -> Vec<String> {
+    let mut new_lines = new_lines.to_vec();
+    for op in similar::capture_diff_slices(Algorithm::Myers, pattern, &new_lines) {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = op
+        {
+            new_lines[new_index..new_index + len]
+                .clone_from_slice(&matched[old_index..old_index + len]);
         }
     }
+    new_lines
+}
 
-    replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
+/// [`seek_sequence::seek_sequence`], falling back to the closest region of
+/// `lines` if it is at least [`SIMILARITY_THRESHOLD`] similar to `pattern`
+/// and every line flagged in `fixed` matched apart from whitespace. When
+/// nothing is close enough, returns the nearest candidate worth showing.
+fn find_lines(
+    lines: &[String],
+    pattern: &[String],
+    fixed: &[bool],
+    start: usize,
+    eof: bool,
+) -> std::result::Result<(usize, MatchKind), Option<NearestCandidate>> {
+    if let Some(found) = seek_sequence::seek_sequence(lines, pattern, start, eof) {
+        return Ok(found);
+    }
+    match seek_sequence::closest_sequence(lines, pattern, fixed, start) {
+        Some(closest) if closest.applicable && closest.similarity >= SIMILARITY_THRESHOLD => {
+            Ok((closest.index, MatchKind::Similar(closest.similarity)))
+        }
+        Some(closest) if closest.similarity >= MIN_CANDIDATE_SIMILARITY => {
+            Err(Some(NearestCandidate {
+                line: closest.index + 1,
+                similarity: closest.similarity,
+                lines: lines[closest.index..closest.index + pattern.len()].to_vec(),
+            }))
+        }
+        _ => Err(None),
+    }
+}
 
-    Ok(replacements)
+/// Flag the lines of `old_lines` that `new_lines` removes or replaces, as
+/// opposed to the context lines it keeps.
+fn removed_lines(old_lines: &[String], new_lines: &[String]) -> Vec<bool> {
+    let mut removed = vec![true; old_lines.len()];
+    for op in similar::capture_diff_slices(Algorithm::Myers, old_lines, new_lines) {
+        if let DiffOp::Equal { old_index, len, .. } = op {
+            removed[old_index..old_index + len].fill(false);
+        }
+    }
+    removed
 }
 
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
@@ -804,21 +1238,42 @@ pub fn unified_diff_from_chunks(
     unified_diff_from_chunks_with_context(path, chunks, 1)
 }
 
+/// Fails with [`ApplyPatchError::Conflicts`] if any of the chunks cannot be
+/// located in the file.
 pub fn unified_diff_from_chunks_with_context(
     path: &Path,
     chunks: &[UpdateFileChunk],
     context: usize,
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
+    let (update, chunks) = partial_unified_diff_from_chunks(path, chunks, context)?;
+    let report = ConflictReport { chunks };
+    if report.rejected() > 0 {
+        return Err(ApplyPatchError::Conflicts(report));
+    }
+    Ok(update)
+}
+
+/// Like [`unified_diff_from_chunks_with_context`], but skips the chunks that
+/// cannot be located and reports how each chunk was applied instead.
+fn partial_unified_diff_from_chunks(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    context: usize,
+) -> std::result::Result<(ApplyPatchFileUpdate, Vec<ChunkReport>), ApplyPatchError> {
     let AppliedPatch {
         original_contents,
         new_contents,
+        chunks,
     } = derive_new_contents_from_chunks(path, chunks)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
-    Ok(ApplyPatchFileUpdate {
-        unified_diff,
-        content: new_contents,
-    })
+    Ok((
+        ApplyPatchFileUpdate {
+            unified_diff,
+            content: new_contents,
+        },
+        chunks,
+    ))
 }
 
 /// Print the summary of changes in git-style format.
@@ -828,6 +1283,13 @@ pub fn print_summary(
     out: &mut impl std::io::Write,
 ) -> std::io::Result<()> {
     writeln!(out, "Success. Updated the following files:")?;
+    write_affected_paths(affected, out)
+}
+
+fn write_affected_paths(
+    affected: &AffectedPaths,
+    out: &mut impl std::io::Write,
+) -> std::io::Result<()> {
     for path in &affected.added {
         writeln!(out, "A {}", path.display())?;
     }
@@ -1115,6 +1577,155 @@ PATCH"#,
         assert!(!path.exists());
     }
 
+    #[test]
+    fn test_add_binary_file_hunk_writes_decoded_bytes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fixtures/blob.bin");
+        let patch = wrap_patch(&format!(
+            "*** Add Binary File: {}\n+AAEC/w==",
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), vec![0, 1, 2, 255]);
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nA {}\n",
+                path.display()
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_symlink_and_set_mode_hunks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        let link = dir.path().join("bin/run");
+        let patch = wrap_patch(&format!(
+            "*** Add File: {}\n+#!/bin/sh\n*** Set Mode: 755 {}\n*** Add Symlink: {}\n*** Target: ../run.sh",
+            script.display(),
+            script.display(),
+            link.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        let mode = fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("../run.sh"));
+        assert_eq!(fs::read_to_string(&link).unwrap(), "#!/bin/sh\n");
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nA {}\nA {}\nM {}\n",
+                script.display(),
+                link.display(),
+                script.display()
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_set_mode_refuses_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("target.sh");
+        fs::write(&target, "echo hi\n").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o644)).unwrap();
+        let link = dir.path().join("link.sh");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let patch = wrap_patch(&format!("*** Set Mode: 755 {}", link.display()));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        assert!(apply_patch(&patch, &mut stdout, &mut stderr).is_err());
+
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o644);
+    }
+
+    #[test]
+    fn test_verified_set_mode_of_added_file_is_folded_into_add() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("existing.sh"), "echo hi\n").unwrap();
+        let argv = vec![
+            "apply_patch".to_string(),
+            wrap_patch(
+                "*** Add File: new.sh\n+echo new\n*** Set Mode: 755 new.sh\n*** Set Mode: 700 existing.sh",
+            ),
+        ];
+
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected a verified patch");
+        };
+        assert_eq!(
+            action.changes(),
+            &HashMap::from([
+                (
+                    dir.path().join("new.sh"),
+                    ApplyPatchFileChange::Add {
+                        content: "echo new\n".to_string(),
+                        mode: Some(0o755),
+                    }
+                ),
+                (
+                    dir.path().join("existing.sh"),
+                    ApplyPatchFileChange::SetMode { mode: 0o700 }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_verified_unified_diff_with_rename_and_mode_change() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("run"), "echo hi\n").unwrap();
+        let argv = vec![
+            "apply_patch".to_string(),
+            "diff --git a/run b/bin/run.sh
+old mode 100644
+new mode 100755
+similarity index 80%
+rename from run
+rename to bin/run.sh
+--- a/run
++++ b/bin/run.sh
+@@ -1 +1 @@
+-echo hi
++echo hello
+"
+            .to_string(),
+        ];
+
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected a verified patch");
+        };
+        assert_eq!(
+            action.changes(),
+            &HashMap::from([(
+                dir.path().join("run"),
+                ApplyPatchFileChange::Update {
+                    unified_diff: "@@ -1 +1 @@\n-echo hi\n+echo hello\n".to_string(),
+                    move_path: Some(dir.path().join("bin/run.sh")),
+                    new_content: "echo hello\n".to_string(),
+                    mode: Some(0o755),
+                }
+            )])
+        );
+    }
+
     #[test]
     fn test_update_file_hunk_modifies_content() {
         let dir = tempdir().unwrap();
@@ -1338,6 +1949,161 @@ PATCH"#,
         assert_eq!(String::from_utf8(stderr).unwrap(), "");
     }
 
+    #[test]
+    fn test_update_applies_closest_match_above_threshold() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("greet.rs");
+        fs::write(
+            &path,
+            "fn greet() {\n    let greeting = \"hello\";\n    println!(\"{greeting}, world\");\n}\n",
+        )
+        .unwrap();
+
+        // The context line misspells `greeting`.
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+     let greting = "hello";
+-    println!("{{greeting}}, world");
++    println!("{{greeting}}, there");"#,
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "fn greet() {\n    let greeting = \"hello\";\n    println!(\"{greeting}, there\");\n}\n"
+        );
+        assert_eq!(String::from_utf8(stderr).unwrap(), "");
+    }
+
+    #[test]
+    fn test_update_does_not_fuzz_removed_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("limits.rs");
+        let original = "fn limits() {\n    let retries = 3;\n    let timeout = 30;\n}\n";
+        fs::write(&path, original).unwrap();
+
+        // The removed line names a value the file does not contain.
+        let argv = vec![
+            "apply_patch".to_string(),
+            r#"*** Begin Patch
+*** Update File: limits.rs
+@@
+ fn limits() {
+     let retries = 3;
+-    let timeout = 31;
++    let timeout = 60;
+*** End Patch"#
+                .to_string(),
+        ];
+
+        let MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::Conflicts(report)) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected a conflict report");
+        };
+        let [chunk] = report.chunks.as_slice() else {
+            panic!("expected a single chunk: {report:?}");
+        };
+        let ChunkStatus::Rejected {
+            nearest: Some(nearest),
+            ..
+        } = &chunk.status
+        else {
+            panic!("expected a rejection with a nearest candidate: {chunk:?}");
+        };
+        assert_eq!(nearest.line, 1);
+        assert!(nearest.similarity >= SIMILARITY_THRESHOLD);
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_update_reports_rejected_chunk_with_nearest_candidate() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[server]\nport = 8080\nhost = \"localhost\"\n").unwrap();
+
+        let argv = vec![
+            "apply_patch".to_string(),
+            r#"*** Begin Patch
+*** Update File: config.toml
+@@
+-listen_port = 9090
++listen_port = 9191
+*** End Patch"#
+                .to_string(),
+        ];
+
+        let MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::Conflicts(report)) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected a conflict report");
+        };
+        assert_eq!(report.rejected(), 1);
+        let [chunk] = report.chunks.as_slice() else {
+            panic!("expected a single chunk: {report:?}");
+        };
+        assert_eq!((chunk.path.as_path(), chunk.index), (path.as_path(), 1));
+        let ChunkStatus::Rejected {
+            reason,
+            nearest: Some(nearest),
+        } = &chunk.status
+        else {
+            panic!("expected a rejection with a nearest candidate: {chunk:?}");
+        };
+        assert_eq!(
+            reason,
+            &format!(
+                "Failed to find expected lines in {}:\nlisten_port = 9090",
+                path.display()
+            )
+        );
+        assert_eq!(
+            (nearest.line, nearest.lines.as_slice()),
+            (2, ["port = 8080".to_string()].as_slice())
+        );
+        assert!(nearest.similarity < SIMILARITY_THRESHOLD);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[server]\nport = 8080\nhost = \"localhost\"\n"
+        );
+    }
+
+    #[test]
+    fn test_verified_patch_keeps_chunks_that_can_be_located() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("list.txt");
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        let argv = vec![
+            "apply_patch".to_string(),
+            r#"*** Begin Patch
+*** Update File: list.txt
+@@
+-one
++uno
+@@
+-four
++cuatro
+*** End Patch"#
+                .to_string(),
+        ];
+
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected the located chunk to be kept");
+        };
+        assert_matches!(
+            action.changes().get(&path),
+            Some(ApplyPatchFileChange::Update { new_content, .. }) if new_content == "uno\ntwo\nthree\n"
+        );
+    }
+
     #[test]
     fn test_unified_diff() {
         // Start with a file containing four lines.
@@ -1595,6 +2361,7 @@ g
                         .to_string(),
                         move_path: None,
                         new_content: "updated session directory content\n".to_string(),
+                        mode: None,
                     },
                 )]),
                 patch: argv[1].clone(),
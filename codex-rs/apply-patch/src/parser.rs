@@ -7,12 +7,17 @@
 //! begin_patch: "*** Begin Patch" LF
 //! end_patch: "*** End Patch" LF?
 //!
-//! hunk: add_hunk | delete_hunk | update_hunk
+//! hunk: add_hunk | add_binary_hunk | add_symlink_hunk | delete_hunk | update_hunk | set_mode_hunk
 //! add_hunk: "*** Add File: " filename LF add_line+
+//! add_binary_hunk: "*** Add Binary File: " filename LF base64_line+
+//! add_symlink_hunk: "*** Add Symlink: " filename LF "*** Target: " filename LF
 //! delete_hunk: "*** Delete File: " filename LF
 //! update_hunk: "*** Update File: " filename LF change_move? change?
+//! set_mode_hunk: "*** Set Mode: " mode " " filename LF
 //! filename: /(.+)/
 //! add_line: "+" /(.+)/ LF -> line
+//! base64_line: "+" /[A-Za-z0-9+\/=]+/ LF
+//! mode: /[0-7]{3,4}/
 //!
 //! change_move: "*** Move to: " filename LF
 //! change: (change_context | change_line)+ eof_line?
@@ -22,16 +27,26 @@
 //!
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
+//!
+//! Standard unified diffs (e.g. the output of `git diff`) are also accepted and
+//! converted into the same hunks; see [`crate::unified_diff`].
 use crate::ApplyPatchArgs;
+use crate::unified_diff;
 use std::path::Path;
 use std::path::PathBuf;
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use thiserror::Error;
 
 const BEGIN_PATCH_MARKER: &str = "*** Begin Patch";
 const END_PATCH_MARKER: &str = "*** End Patch";
 const ADD_FILE_MARKER: &str = "*** Add File: ";
+const ADD_BINARY_FILE_MARKER: &str = "*** Add Binary File: ";
+const ADD_SYMLINK_MARKER: &str = "*** Add Symlink: ";
+const SYMLINK_TARGET_MARKER: &str = "*** Target: ";
 const DELETE_FILE_MARKER: &str = "*** Delete File: ";
+const SET_MODE_MARKER: &str = "*** Set Mode: ";
 const UPDATE_FILE_MARKER: &str = "*** Update File: ";
 const MOVE_TO_MARKER: &str = "*** Move to: ";
 const EOF_MARKER: &str = "*** End of File";
@@ -62,6 +77,17 @@ pub enum Hunk {
         path: PathBuf,
         contents: String,
     },
+    /// A file whose contents were given base64-encoded.
+    AddBinaryFile {
+        path: PathBuf,
+        contents: Vec<u8>,
+    },
+    AddSymlink {
+        path: PathBuf,
+        /// Written to the link as is, so relative targets are resolved against
+        /// the directory containing the link.
+        target: PathBuf,
+    },
     DeleteFile {
         path: PathBuf,
     },
@@ -73,14 +99,22 @@ pub enum Hunk {
         /// should occur later in the file than the previous chunk.
         chunks: Vec<UpdateFileChunk>,
     },
+    SetMode {
+        path: PathBuf,
+        /// Permission bits, e.g. `0o755`.
+        mode: u32,
+    },
 }
 
 impl Hunk {
     pub fn resolve_path(&self, cwd: &Path) -> PathBuf {
         match self {
             Hunk::AddFile { path, .. } => cwd.join(path),
+            Hunk::AddBinaryFile { path, .. } => cwd.join(path),
+            Hunk::AddSymlink { path, .. } => cwd.join(path),
             Hunk::DeleteFile { path } => cwd.join(path),
             Hunk::UpdateFile { path, .. } => cwd.join(path),
+            Hunk::SetMode { path, .. } => cwd.join(path),
         }
     }
 }
@@ -153,6 +187,13 @@ enum ParseMode {
 
 fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, ParseError> {
     let lines: Vec<&str> = patch.trim().lines().collect();
+    if let Some(diff_lines) = unified_diff_lines(&lines, &mode) {
+        return Ok(ApplyPatchArgs {
+            hunks: unified_diff::parse_unified_diff(diff_lines)?,
+            patch: diff_lines.join("\n"),
+            workdir: None,
+        });
+    }
     let lines: &[&str] = match check_patch_boundaries_strict(&lines) {
         Ok(()) => &lines,
         Err(e) => match mode {
@@ -182,6 +223,29 @@ fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, Pars
     })
 }
 
+/// Returns the lines that make up a unified diff, if `lines` are one. In
+/// lenient mode the diff may be wrapped in heredoc markers, as in
+/// [`check_patch_boundaries_lenient`].
+fn unified_diff_lines<'a>(lines: &'a [&'a str], mode: &ParseMode) -> Option<&'a [&'a str]> {
+    if unified_diff::is_unified_diff(lines) {
+        return Some(lines);
+    }
+    match (mode, lines) {
+        (ParseMode::Lenient, [first, inner @ .., last])
+            if is_heredoc_start(first)
+                && last.ends_with("EOF")
+                && unified_diff::is_unified_diff(inner) =>
+        {
+            Some(inner)
+        }
+        _ => None,
+    }
+}
+
+fn is_heredoc_start(line: &str) -> bool {
+    line == "<<EOF" || line == "<<'EOF'" || line == "<<\"EOF\""
+}
+
 /// Checks the start and end lines of the patch text for `apply_patch`,
 /// returning an error if they do not match the expected markers.
 fn check_patch_boundaries_strict(lines: &[&str]) -> Result<(), ParseError> {
@@ -206,10 +270,7 @@ fn check_patch_boundaries_lenient<'a>(
 ) -> Result<&'a [&'a str], ParseError> {
     match original_lines {
         [first, .., last] => {
-            if (first == &"<<EOF" || first == &"<<'EOF'" || first == &"<<\"EOF\"")
-                && last.ends_with("EOF")
-                && original_lines.len() >= 4
-            {
+            if is_heredoc_start(first) && last.ends_with("EOF") && original_lines.len() >= 4 {
                 let inner_lines = &original_lines[1..original_lines.len() - 1];
                 match check_patch_boundaries_strict(inner_lines) {
                     Ok(()) => Ok(inner_lines),
@@ -265,6 +326,79 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
             },
             parsed_lines,
         ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_BINARY_FILE_MARKER) {
+        // Add Binary File
+        let mut encoded = String::new();
+        let mut parsed_lines = 1;
+        for add_line in &lines[1..] {
+            if let Some(line_to_add) = add_line.strip_prefix('+') {
+                encoded.push_str(line_to_add.trim());
+                parsed_lines += 1;
+            } else {
+                break;
+            }
+        }
+        let contents = BASE64_STANDARD
+            .decode(&encoded)
+            .map_err(|err| InvalidHunkError {
+                message: format!(
+                    "Add Binary File hunk for path '{path}' is not valid base64: {err}"
+                ),
+                line_number,
+            })?;
+        return Ok((
+            AddBinaryFile {
+                path: PathBuf::from(path),
+                contents,
+            },
+            parsed_lines,
+        ));
+    } else if let Some(path) = first_line.strip_prefix(ADD_SYMLINK_MARKER) {
+        // Add Symlink
+        let Some(target) = lines
+            .get(1)
+            .and_then(|line| line.trim().strip_prefix(SYMLINK_TARGET_MARKER))
+        else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Add Symlink hunk for path '{path}' must be followed by '{SYMLINK_TARGET_MARKER}{{target}}'"
+                ),
+                line_number: line_number + 1,
+            });
+        };
+        return Ok((
+            AddSymlink {
+                path: PathBuf::from(path),
+                target: PathBuf::from(target),
+            },
+            2,
+        ));
+    } else if let Some(rest) = first_line.strip_prefix(SET_MODE_MARKER) {
+        // Set Mode
+        let mode = rest.split_once(' ').and_then(|(digits, path)| {
+            if !(3..=4).contains(&digits.len()) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let mode = u32::from_str_radix(digits, 8)
+                .ok()
+                .filter(|mode| *mode <= 0o777)?;
+            Some((mode, path.trim()))
+        });
+        let Some((mode, path)) = mode.filter(|(_, path)| !path.is_empty()) else {
+            return Err(InvalidHunkError {
+                message: format!(
+                    "Set Mode hunk must look like '{SET_MODE_MARKER}{{mode}} {{path}}' with an octal mode such as 755, got: '{first_line}'"
+                ),
+                line_number,
+            });
+        };
+        return Ok((
+            SetMode {
+                path: PathBuf::from(path),
+                mode,
+            },
+            1,
+        ));
     } else if let Some(path) = first_line.strip_prefix(DELETE_FILE_MARKER) {
         // Delete File
         return Ok((
@@ -331,7 +465,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
     Err(InvalidHunkError {
         message: format!(
-            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}'"
+            "'{first_line}' is not a valid hunk header. Valid hunk headers: '*** Add File: {{path}}', '*** Add Binary File: {{path}}', '*** Add Symlink: {{path}}', '*** Delete File: {{path}}', '*** Update File: {{path}}', '*** Set Mode: {{mode}} {{path}}'"
         ),
         line_number,
     })
@@ -653,13 +787,69 @@ fn test_parse_one_hunk() {
         parse_one_hunk(&["bad"], 234),
         Err(InvalidHunkError {
             message: "'bad' is not a valid hunk header. \
-            Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'".to_string(),
+            Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', '*** Add Symlink: {path}', '*** Delete File: {path}', '*** Update File: {path}', '*** Set Mode: {mode} {path}'".to_string(),
             line_number: 234
         })
     );
     // Other edge cases are already covered by tests above/below.
 }
 
+#[test]
+fn test_parse_binary_symlink_and_mode_hunks() {
+    assert_eq!(
+        parse_one_hunk(&["*** Add Binary File: logo.png", "+iVBORw0K", "+Ggo="], 1),
+        Ok((
+            AddBinaryFile {
+                path: PathBuf::from("logo.png"),
+                contents: b"\x89PNG\r\n\x1a\n".to_vec(),
+            },
+            3
+        ))
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Add Symlink: current", "*** Target: releases/v2"], 1),
+        Ok((
+            AddSymlink {
+                path: PathBuf::from("current"),
+                target: PathBuf::from("releases/v2"),
+            },
+            2
+        ))
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Set Mode: 755 scripts/run tests.sh"], 1),
+        Ok((
+            SetMode {
+                path: PathBuf::from("scripts/run tests.sh"),
+                mode: 0o755,
+            },
+            1
+        ))
+    );
+
+    assert_eq!(
+        parse_one_hunk(&["*** Add Symlink: current", "+releases/v2"], 5),
+        Err(InvalidHunkError {
+            message:
+                "Add Symlink hunk for path 'current' must be followed by '*** Target: {target}'"
+                    .to_string(),
+            line_number: 6,
+        })
+    );
+    assert_eq!(
+        parse_one_hunk(&["*** Set Mode: 4755 run.sh"], 5),
+        Err(InvalidHunkError {
+            message: "Set Mode hunk must look like '*** Set Mode: {mode} {path}' with an octal mode such as 755, got: '*** Set Mode: 4755 run.sh'"
+                .to_string(),
+            line_number: 5,
+        })
+    );
+    assert!(matches!(
+        parse_one_hunk(&["*** Add Binary File: blob.bin", "+not base64!"], 5),
+        Err(InvalidHunkError { line_number: 5, .. })
+    ));
+}
+
 #[test]
 fn test_update_file_chunk() {
     assert_eq!(
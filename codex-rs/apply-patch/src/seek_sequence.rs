@@ -1,22 +1,55 @@
+use std::fmt;
+
+use similar::TextDiff;
+
+/// How loosely a pattern had to be matched before it was found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatchKind {
+    /// The lines matched byte for byte.
+    Exact,
+    /// The lines matched once whitespace differences were ignored.
+    Whitespace,
+    /// The lines matched once typographic punctuation was normalised to ASCII.
+    Unicode,
+    /// No region matched, but the closest one was at least
+    /// [`SIMILARITY_THRESHOLD`] similar (the value, between 0 and 1).
+    Similar(f32),
+}
+
+impl fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchKind::Exact => write!(f, "exact match"),
+            MatchKind::Whitespace => write!(f, "ignoring whitespace"),
+            MatchKind::Unicode => write!(f, "ignoring Unicode punctuation"),
+            MatchKind::Similar(similarity) => write!(f, "{:.0}% similar", similarity * 100.0),
+        }
+    }
+}
+
+/// Minimum similarity for [`closest_sequence`] matches to be applied.
+pub(crate) const SIMILARITY_THRESHOLD: f32 = 0.9;
+
 /// Attempt to find the sequence of `pattern` lines within `lines` beginning at or after `start`.
-/// Returns the starting index of the match or `None` if not found. Matches are attempted with
-/// decreasing strictness: exact match, then ignoring trailing whitespace, then ignoring leading
-/// and trailing whitespace. When `eof` is true, we first try starting at the end-of-file (so that
-/// patterns intended to match file endings are applied at the end), and fall back to searching
-/// from `start` if needed.
+/// Returns the starting index of the match and how strictly it matched, or `None` if not found.
+/// Matches are attempted with decreasing strictness: exact match, then ignoring trailing
+/// whitespace, then ignoring leading and trailing whitespace, then ignoring differences in
+/// whitespace altogether, and finally after normalising Unicode punctuation. When `eof` is true,
+/// we first try starting at the end-of-file (so that patterns intended to match file endings are
+/// applied at the end), and fall back to searching from `start` if needed.
 ///
 /// Special cases handled defensively:
-///  • Empty `pattern` → returns `Some(start)` (no-op match)
-///  • `pattern.len() > lines.len()` → returns `None` (cannot match, avoids
+///  • Empty `pattern` → returns `Some(start)` (no-op match)
+///  • `pattern.len() > lines.len()` → returns `None` (cannot match, avoids
 ///    out‑of‑bounds panic that occurred pre‑2025‑04‑12)
 pub(crate) fn seek_sequence(
     lines: &[String],
     pattern: &[String],
     start: usize,
     eof: bool,
-) -> Option<usize> {
+) -> Option<(usize, MatchKind)> {
     if pattern.is_empty() {
-        return Some(start);
+        return Some((start, MatchKind::Exact));
     }
 
     // When the pattern is longer than the available input there is no possible
@@ -31,37 +64,26 @@ pub(crate) fn seek_sequence(
     } else {
         start
     };
+    let find = |matches: &dyn Fn(&str, &str) -> bool| {
+        (search_start..=lines.len().saturating_sub(pattern.len())).find(|&i| {
+            pattern
+                .iter()
+                .enumerate()
+                .all(|(p_idx, pat)| matches(&lines[i + p_idx], pat))
+        })
+    };
+
     // Exact match first.
-    for i in search_start..=lines.len().saturating_sub(pattern.len()) {
-        if lines[i..i + pattern.len()] == *pattern {
-            return Some(i);
-        }
-    }
-    // Then rstrip match.
-    for i in search_start..=lines.len().saturating_sub(pattern.len()) {
-        let mut ok = true;
-        for (p_idx, pat) in pattern.iter().enumerate() {
-            if lines[i + p_idx].trim_end() != pat.trim_end() {
-                ok = false;
-                break;
-            }
-        }
-        if ok {
-            return Some(i);
-        }
+    if let Some(i) = find(&|line, pat| line == pat) {
+        return Some((i, MatchKind::Exact));
     }
-    // Finally, trim both sides to allow more lenience.
-    for i in search_start..=lines.len().saturating_sub(pattern.len()) {
-        let mut ok = true;
-        for (p_idx, pat) in pattern.iter().enumerate() {
-            if lines[i + p_idx].trim() != pat.trim() {
-                ok = false;
-                break;
-            }
-        }
-        if ok {
-            return Some(i);
-        }
+    // Then rstrip match, then trim both sides to allow more lenience, and
+    // finally ignore how runs of whitespace inside the line are spelled.
+    if let Some(i) = find(&|line, pat| line.trim_end() == pat.trim_end())
+        .or_else(|| find(&|line, pat| line.trim() == pat.trim()))
+        .or_else(|| find(&|line, pat| line.split_whitespace().eq(pat.split_whitespace())))
+    {
+        return Some((i, MatchKind::Whitespace));
     }
 
     // ------------------------------------------------------------------
@@ -72,45 +94,132 @@ pub(crate) fn seek_sequence(
     // fuzzy behaviour of `git apply` which ignores minor byte-level
     // differences when locating context lines.
     // ------------------------------------------------------------------
+    find(&|line, pat| normalise(line) == normalise(pat)).map(|i| (i, MatchKind::Unicode))
+}
+
+/// Lines longer than this are only compared for equality by
+/// [`closest_sequence`], since character diffs grow quadratically.
+const MAX_SIMILARITY_LINE_CHARS: usize = 200;
+
+/// Upper bound on the characters [`closest_sequence`] would compare across
+/// all candidate regions, so a large file cannot stall the search.
+const MAX_CLOSEST_COMPARED_CHARS: usize = 10_000_000;
+
+/// A region found by [`closest_sequence`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ClosestSequence {
+    /// Index of the region's first line.
+    pub(crate) index: usize,
+    /// Share of characters the region has in common with the pattern,
+    /// between 0 and 1.
+    pub(crate) similarity: f32,
+    /// Whether every pattern line that must not be fuzzed matched, ignoring
+    /// whitespace and Unicode punctuation.
+    pub(crate) applicable: bool,
+}
+
+/// Find the region of `lines` at or after `start` that is most similar to
+/// `pattern`, for when [`seek_sequence`] found no match.
+///
+/// Lines are compared pairwise after [`normalise`], and the similarity is the
+/// share of characters the region has in common with the pattern, so a typo
+/// in one line of a long hunk barely lowers it while a hunk written against a
+/// different part of the file scores low. Pattern lines flagged in `fixed`
+/// (the lines a hunk removes) must match after normalisation for the region to
+/// be applicable; applicable regions are preferred over closer ones that are
+/// not.
+///
+/// Returns `None` when there is nothing to compare, or when doing so would
+/// exceed [`MAX_CLOSEST_COMPARED_CHARS`].
+pub(crate) fn closest_sequence(
+    lines: &[String],
+    pattern: &[String],
+    fixed: &[bool],
+    start: usize,
+) -> Option<ClosestSequence> {
+    if pattern.is_empty() || pattern.len() > lines.len() {
+        return None;
+    }
+    let last_start = lines.len() - pattern.len();
+    // A region may be found before `start` when nothing after it is left.
+    let start = start.min(last_start);
+
+    // Each region compares the whole pattern against as many file lines, so a
+    // file line takes part in at most one comparison per pattern line.
+    let chars = |lines: &[String]| lines.iter().map(|line| line.chars().count()).sum::<usize>();
+    let compared = (last_start - start + 1)
+        .saturating_mul(chars(pattern))
+        .saturating_add(pattern.len().saturating_mul(chars(&lines[start..])));
+    if compared > MAX_CLOSEST_COMPARED_CHARS {
+        return None;
+    }
 
-    fn normalise(s: &str) -> String {
-        s.trim()
-            .chars()
-            .map(|c| match c {
-                // Various dash / hyphen code-points → ASCII '-'
-                '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
-                | '\u{2212}' => '-',
-                // Fancy single quotes → '\''
-                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
-                // Fancy double quotes → '"'
-                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
-                // Non-breaking space and other odd spaces → normal space
-                '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
-                | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
-                | '\u{3000}' => ' ',
-                other => other,
-            })
-            .collect::<String>()
-    }
-
-    for i in search_start..=lines.len().saturating_sub(pattern.len()) {
-        let mut ok = true;
-        for (p_idx, pat) in pattern.iter().enumerate() {
-            if normalise(&lines[i + p_idx]) != normalise(pat) {
-                ok = false;
-                break;
+    let pattern: Vec<String> = pattern.iter().map(|line| normalise(line)).collect();
+    let lines: Vec<String> = lines[start..].iter().map(|line| normalise(line)).collect();
+
+    let mut best: Option<ClosestSequence> = None;
+    for i in 0..=last_start - start {
+        let mut common = 0.0;
+        let mut total = 0;
+        let mut applicable = true;
+        for ((line, pat), &fixed) in lines[i..].iter().zip(&pattern).zip(fixed) {
+            let line_chars = line.chars().count();
+            let pat_chars = pat.chars().count();
+            let len = line_chars + pat_chars;
+            if line == pat {
+                common += len as f32;
+            } else {
+                applicable &= !fixed;
+                if line_chars <= MAX_SIMILARITY_LINE_CHARS && pat_chars <= MAX_SIMILARITY_LINE_CHARS
+                {
+                    common +=
+                        TextDiff::from_chars(line.as_str(), pat.as_str()).ratio() * len as f32;
+                }
             }
+            total += len;
         }
-        if ok {
-            return Some(i);
+        let similarity = if total == 0 {
+            1.0
+        } else {
+            common / total as f32
+        };
+        if best.is_none_or(|best| (applicable, similarity) > (best.applicable, best.similarity)) {
+            best = Some(ClosestSequence {
+                index: start + i,
+                similarity,
+                applicable,
+            });
         }
     }
+    best
+}
 
-    None
+fn normalise(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .map(|c| match c {
+            // Various dash / hyphen code-points → ASCII '-'
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+            | '\u{2212}' => '-',
+            // Fancy single quotes → '\''
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            // Fancy double quotes → '"'
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            // Non-breaking space and other odd spaces → normal space
+            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
+            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
+            | '\u{3000}' => ' ',
+            other => other,
+        })
+        .collect::<String>()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::MatchKind;
+    use super::closest_sequence;
     use super::seek_sequence;
     use std::string::ToString;
 
@@ -122,7 +231,10 @@ mod tests {
     fn test_exact_match_finds_sequence() {
         let lines = to_vec(&["foo", "bar", "baz"]);
         let pattern = to_vec(&["bar", "baz"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(1));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((1, MatchKind::Exact))
+        );
     }
 
     #[test]
@@ -130,7 +242,10 @@ mod tests {
         let lines = to_vec(&["foo   ", "bar\t\t"]);
         // Pattern omits trailing whitespace.
         let pattern = to_vec(&["foo", "bar"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(0));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchKind::Whitespace))
+        );
     }
 
     #[test]
@@ -138,7 +253,20 @@ mod tests {
         let lines = to_vec(&["    foo   ", "   bar\t"]);
         // Pattern omits any additional whitespace.
         let pattern = to_vec(&["foo", "bar"]);
-        assert_eq!(seek_sequence(&lines, &pattern, 0, false), Some(0));
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchKind::Whitespace))
+        );
+    }
+
+    #[test]
+    fn test_whitespace_insensitive_match_ignores_inner_whitespace() {
+        let lines = to_vec(&["let  x =\t1;", "foo(a,  b)"]);
+        let pattern = to_vec(&["let x = 1;", "foo(a, b)"]);
+        assert_eq!(
+            seek_sequence(&lines, &pattern, 0, false),
+            Some((0, MatchKind::Whitespace))
+        );
     }
 
     #[test]
@@ -148,4 +276,58 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_closest_sequence_finds_region_with_typo() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "    let greeting = \"hello\";",
+            "    println!(\"{greeting}\");",
+            "}",
+        ]);
+        let pattern = to_vec(&[
+            "    let greting = \"hello\";",
+            "    println!(\"{greeting}\");",
+        ]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
+
+        let closest = closest_sequence(&lines, &pattern, &[false, true], 0).unwrap();
+        assert_eq!((closest.index, closest.applicable), (1, true));
+        assert!(
+            closest.similarity > super::SIMILARITY_THRESHOLD,
+            "{closest:?}"
+        );
+    }
+
+    #[test]
+    fn test_closest_sequence_requires_removed_lines_to_match() {
+        let lines = to_vec(&["let retries = 3;", "let timeout = 30;"]);
+        let pattern = to_vec(&["let retries = 3;", "let timeout = 31;"]);
+
+        let fuzzed_context = closest_sequence(&lines, &pattern, &[false, false], 0).unwrap();
+        assert!(fuzzed_context.applicable);
+
+        let fuzzed_removal = closest_sequence(&lines, &pattern, &[false, true], 0).unwrap();
+        assert_eq!(fuzzed_removal.index, 0);
+        assert!(!fuzzed_removal.applicable);
+        assert!(
+            fuzzed_removal.similarity > super::SIMILARITY_THRESHOLD,
+            "{fuzzed_removal:?}"
+        );
+    }
+
+    #[test]
+    fn test_closest_sequence_scores_unrelated_lines_low() {
+        let lines = to_vec(&["alpha", "beta", "gamma"]);
+        let pattern = to_vec(&["completely different"]);
+        let closest = closest_sequence(&lines, &pattern, &[true], 0).unwrap();
+        assert!(closest.similarity < 0.5, "{closest:?}");
+    }
+
+    #[test]
+    fn test_closest_sequence_gives_up_on_huge_inputs() {
+        let lines = vec!["x".repeat(100); 100_001];
+        let pattern = to_vec(&["y"]);
+        assert_eq!(closest_sequence(&lines, &pattern, &[false], 0), None);
+    }
 }
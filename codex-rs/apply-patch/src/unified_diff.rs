@@ -0,0 +1,661 @@
+//! Support for standard unified diffs, as produced by `git diff` or `diff -u`.
+//!
+//! A unified diff is converted into the same [`Hunk`]s as the `*** Begin Patch`
+//! format so that it goes through the same verification and application path.
+//! Chunks are located by their context lines rather than by the line numbers in
+//! the `@@` headers, so diffs with miscounted ranges still apply. The header
+//! line counts do bound each chunk, so a removed `-- foo` or added `++ bar`
+//! line is not mistaken for the next file's header.
+//!
+//! Supported:
+//! - `a/` and `b/` path prefixes (always stripped after a `diff --git` header,
+//!   otherwise only when both sides use them)
+//! - `/dev/null` for added and deleted files
+//! - `rename from` / `rename to`, with or without content changes
+//! - `new file mode`, `old mode` / `new mode` (including symlinks)
+//!
+//! Binary diffs and copies are rejected.
+use std::path::PathBuf;
+
+use crate::parser::Hunk;
+use crate::parser::ParseError;
+use crate::parser::ParseError::*;
+use crate::parser::UpdateFileChunk;
+
+const GIT_DIFF_HEADER: &str = "diff --git ";
+const OLD_FILE_HEADER: &str = "--- ";
+const NEW_FILE_HEADER: &str = "+++ ";
+const CHUNK_HEADER: &str = "@@";
+const NO_NEWLINE_MARKER: &str = "\\";
+const DEV_NULL: &str = "/dev/null";
+const SYMLINK_MODE: u32 = 0o120000;
+const FILE_TYPE_MASK: u32 = 0o170000;
+
+/// Returns true if `lines` look like a unified diff: they start with a
+/// `diff --git` header, or with a `---` / `+++` pair, optionally preceded by
+/// `diff <options>` or `Index:` lines.
+pub(crate) fn is_unified_diff(lines: &[&str]) -> bool {
+    let mut lines = lines.iter().skip_while(|line| {
+        (line.starts_with("diff ") && !line.starts_with(GIT_DIFF_HEADER))
+            || line.starts_with("Index: ")
+            || line.starts_with("====")
+    });
+    match (lines.next(), lines.next()) {
+        (Some(first), _) if first.starts_with(GIT_DIFF_HEADER) => true,
+        (Some(first), Some(second)) => {
+            first.starts_with(OLD_FILE_HEADER) && second.starts_with(NEW_FILE_HEADER)
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn parse_unified_diff(lines: &[&str]) -> Result<Vec<Hunk>, ParseError> {
+    let mut hunks = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        if is_file_diff_start(&lines[index..]) {
+            let (file_hunks, consumed) = parse_file_diff(&lines[index..], index + 1)?;
+            hunks.extend(file_hunks);
+            index += consumed;
+        } else {
+            // Skip anything between file diffs, such as `diff -u a b` or
+            // `Index:` lines and commit messages.
+            index += 1;
+        }
+    }
+    if hunks.is_empty() {
+        return Err(InvalidPatchError(
+            "The unified diff does not change any files".to_string(),
+        ));
+    }
+    Ok(hunks)
+}
+
+fn is_file_diff_start(lines: &[&str]) -> bool {
+    match lines {
+        [first, ..] if first.starts_with(GIT_DIFF_HEADER) => true,
+        [first, second, ..] => {
+            first.starts_with(OLD_FILE_HEADER) && second.starts_with(NEW_FILE_HEADER)
+        }
+        _ => false,
+    }
+}
+
+/// Everything known about one file from its headers.
+#[derive(Default)]
+struct FileHeader {
+    is_git: bool,
+    /// Paths from the `diff --git a/<old> b/<new>` line.
+    git_paths: Option<(String, String)>,
+    /// Paths from the `---` / `+++` lines; `None` stands for `/dev/null`.
+    old_path: Option<Option<String>>,
+    new_path: Option<Option<String>>,
+    rename_from: Option<String>,
+    rename_to: Option<String>,
+    new_file_mode: Option<u32>,
+    is_deleted: bool,
+    new_mode: Option<u32>,
+}
+
+/// Parses the diff of a single file from the start of `lines`. Returns the
+/// resulting hunks and the number of lines consumed.
+fn parse_file_diff(lines: &[&str], line_number: usize) -> Result<(Vec<Hunk>, usize), ParseError> {
+    let mut header = FileHeader::default();
+    let mut index = 0;
+
+    if let Some(rest) = lines[0].strip_prefix(GIT_DIFF_HEADER) {
+        header.is_git = true;
+        header.git_paths = parse_git_paths(rest);
+        index += 1;
+        while let Some(line) = lines.get(index) {
+            let line_number = line_number + index;
+            if let Some(mode) = line.strip_prefix("new file mode ") {
+                header.new_file_mode = Some(parse_mode(mode, line_number)?);
+            } else if line.starts_with("deleted file mode ") {
+                header.is_deleted = true;
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                header.new_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                header.rename_from = Some(unquote(path).to_string());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                header.rename_to = Some(unquote(path).to_string());
+            } else if line.starts_with("copy from ") || line.starts_with("copy to ") {
+                return Err(InvalidHunkError {
+                    message: "copies are not supported in unified diffs; add the new file instead"
+                        .to_string(),
+                    line_number,
+                });
+            } else if line.starts_with("GIT binary patch") || line.starts_with("Binary files ") {
+                return Err(InvalidHunkError {
+                    message: "binary diffs are not supported; use '*** Add Binary File:' instead"
+                        .to_string(),
+                    line_number,
+                });
+            } else if line.starts_with("old mode ")
+                || line.starts_with("index ")
+                || line.starts_with("similarity index ")
+                || line.starts_with("dissimilarity index ")
+            {
+                // Nothing to do: the old mode and blob ids are not verified.
+            } else {
+                break;
+            }
+            index += 1;
+        }
+    }
+
+    if let (Some(old), Some(new)) = (lines.get(index), lines.get(index + 1))
+        && let (Some(old), Some(new)) = (
+            old.strip_prefix(OLD_FILE_HEADER),
+            new.strip_prefix(NEW_FILE_HEADER),
+        )
+    {
+        header.old_path = Some(parse_header_path(old));
+        header.new_path = Some(parse_header_path(new));
+        index += 2;
+    }
+
+    let mut chunks = Vec::new();
+    let mut added_lines: Vec<String> = Vec::new();
+    let mut added_missing_newline = false;
+    while let Some(line) = lines.get(index) {
+        if !line.starts_with(CHUNK_HEADER) {
+            break;
+        }
+        let (chunk, consumed, missing_newline) = parse_chunk(&lines[index..], line_number + index)?;
+        index += consumed;
+        added_missing_newline = missing_newline;
+        added_lines.extend(chunk.new_lines.iter().cloned());
+        chunks.push(chunk);
+    }
+
+    let hunks = header_to_hunks(
+        header,
+        chunks,
+        added_lines,
+        added_missing_newline,
+        line_number,
+    )?;
+    Ok((hunks, index.max(1)))
+}
+
+/// Parses one `@@ ... @@` chunk. Returns the chunk, the number of lines
+/// consumed, and whether its last added line has no trailing newline.
+fn parse_chunk(
+    lines: &[&str],
+    line_number: usize,
+) -> Result<(UpdateFileChunk, usize, bool), ParseError> {
+    let mut chunk = UpdateFileChunk {
+        change_context: None,
+        old_lines: Vec::new(),
+        new_lines: Vec::new(),
+        is_end_of_file: false,
+    };
+    let mut missing_newline = false;
+    let mut last_was_addition = false;
+    let mut remaining = chunk_line_counts(lines[0]);
+    let mut index = 1;
+    while let Some(line) = lines.get(index) {
+        let counted = remaining.is_some_and(|(old, new)| old > 0 || new > 0);
+        if !counted && (line.starts_with(CHUNK_HEADER) || is_file_diff_start(&lines[index..])) {
+            break;
+        }
+        // Models often strip the single space from blank context lines.
+        if line.is_empty() {
+            chunk.old_lines.push(String::new());
+            chunk.new_lines.push(String::new());
+            last_was_addition = false;
+        } else if line.starts_with(NO_NEWLINE_MARKER) {
+            missing_newline = last_was_addition;
+        } else if let Some(text) = line.strip_prefix(' ') {
+            chunk.old_lines.push(text.to_string());
+            chunk.new_lines.push(text.to_string());
+            last_was_addition = false;
+        } else if let Some(text) = line.strip_prefix('-') {
+            chunk.old_lines.push(text.to_string());
+            last_was_addition = false;
+        } else if let Some(text) = line.strip_prefix('+') {
+            chunk.new_lines.push(text.to_string());
+            last_was_addition = true;
+        } else {
+            break;
+        }
+        if let Some((old, new)) = remaining.as_mut() {
+            let (in_old, in_new) = match line.chars().next() {
+                None | Some(' ') => (true, true),
+                Some('-') => (true, false),
+                Some('+') => (false, true),
+                _ => (false, false),
+            };
+            *old = old.saturating_sub(usize::from(in_old));
+            *new = new.saturating_sub(usize::from(in_new));
+        }
+        index += 1;
+    }
+
+    // Trailing blank lines may just separate this chunk from what follows.
+    while chunk.old_lines.last().is_some_and(String::is_empty)
+        && chunk.new_lines.last().is_some_and(String::is_empty)
+        && lines[index - 1].is_empty()
+    {
+        chunk.old_lines.pop();
+        chunk.new_lines.pop();
+        index -= 1;
+    }
+
+    if chunk.old_lines.is_empty() && !chunk.new_lines.is_empty() && !is_empty_old_range(lines[0]) {
+        return Err(InvalidHunkError {
+            message: format!(
+                "'{}' adds lines without any context lines to locate them; include unchanged lines around the change",
+                lines[0]
+            ),
+            line_number,
+        });
+    }
+
+    Ok((chunk, index, missing_newline))
+}
+
+/// The old and new line counts of a chunk header such as `@@ -1,3 +1,4 @@`, or
+/// `None` when the header carries no ranges (models often write a bare `@@`).
+fn chunk_line_counts(header: &str) -> Option<(usize, usize)> {
+    let mut ranges = header.trim_start_matches(CHUNK_HEADER).split_whitespace();
+    let old = range_count(ranges.next()?.strip_prefix('-')?)?;
+    let new = range_count(ranges.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+/// The line count of a `start,count` range; a bare `start` means one line.
+fn range_count(range: &str) -> Option<usize> {
+    match range.split_once(',') {
+        Some((start, count)) => {
+            start.parse::<usize>().ok()?;
+            count.parse().ok()
+        }
+        None => {
+            range.parse::<usize>().ok()?;
+            Some(1)
+        }
+    }
+}
+
+/// Whether a chunk header such as `@@ -0,0 +1,3 @@` describes an empty file.
+fn is_empty_old_range(header: &str) -> bool {
+    header
+        .trim_start_matches(CHUNK_HEADER)
+        .split_whitespace()
+        .next()
+        .is_some_and(|range| range == "-0,0")
+}
+
+fn header_to_hunks(
+    header: FileHeader,
+    chunks: Vec<UpdateFileChunk>,
+    added_lines: Vec<String>,
+    added_missing_newline: bool,
+    line_number: usize,
+) -> Result<Vec<Hunk>, ParseError> {
+    let strip_prefixes = header.is_git
+        || (header
+            .old_path
+            .as_ref()
+            .is_some_and(|path| path.as_deref().is_none_or(|p| p.starts_with("a/")))
+            && header
+                .new_path
+                .as_ref()
+                .is_some_and(|path| path.as_deref().is_none_or(|p| p.starts_with("b/"))));
+    let strip = |path: String, prefix: &str| -> PathBuf {
+        if strip_prefixes {
+            PathBuf::from(path.strip_prefix(prefix).unwrap_or(&path))
+        } else {
+            PathBuf::from(path)
+        }
+    };
+
+    let (git_old, git_new) = header.git_paths.unzip();
+    let is_add = header.new_file_mode.is_some() || matches!(header.old_path, Some(None));
+    let is_delete = header.is_deleted || matches!(header.new_path, Some(None));
+    let old_path = header
+        .old_path
+        .flatten()
+        .map(|path| strip(path, "a/"))
+        .or_else(|| header.rename_from.map(PathBuf::from))
+        .or_else(|| git_old.map(PathBuf::from));
+    let new_path = header
+        .new_path
+        .flatten()
+        .map(|path| strip(path, "b/"))
+        .or_else(|| header.rename_to.map(PathBuf::from))
+        .or_else(|| git_new.map(PathBuf::from));
+    let missing_path = || InvalidHunkError {
+        message: "could not determine which file this diff applies to".to_string(),
+        line_number,
+    };
+
+    if is_add && is_delete {
+        return Err(InvalidHunkError {
+            message: "a file diff cannot both add and delete a file".to_string(),
+            line_number,
+        });
+    }
+
+    if is_add {
+        let path = new_path.ok_or_else(missing_path)?;
+        let mut contents = added_lines.join("\n");
+        let mode = header.new_file_mode.or(header.new_mode);
+        if mode.is_some_and(|mode| mode & FILE_TYPE_MASK == SYMLINK_MODE) {
+            return Ok(vec![Hunk::AddSymlink {
+                path,
+                target: PathBuf::from(contents),
+            }]);
+        }
+        if !added_lines.is_empty() && !added_missing_newline {
+            contents.push('\n');
+        }
+        let mut hunks = vec![Hunk::AddFile {
+            path: path.clone(),
+            contents,
+        }];
+        if let Some(mode) = mode.map(|mode| mode & 0o777)
+            && mode & 0o111 != 0
+        {
+            hunks.push(Hunk::SetMode { path, mode });
+        }
+        return Ok(hunks);
+    }
+
+    if is_delete {
+        let path = old_path.ok_or_else(missing_path)?;
+        return Ok(vec![Hunk::DeleteFile { path }]);
+    }
+
+    let path = old_path.ok_or_else(missing_path)?;
+    let new_path = new_path.unwrap_or_else(|| path.clone());
+    let move_path = (new_path != path).then(|| new_path.clone());
+    let chunks: Vec<UpdateFileChunk> = chunks
+        .into_iter()
+        .filter(|chunk| chunk.old_lines != chunk.new_lines)
+        .collect();
+
+    let mut hunks = Vec::new();
+    if !chunks.is_empty() || move_path.is_some() {
+        hunks.push(Hunk::UpdateFile {
+            path,
+            move_path,
+            chunks,
+        });
+    }
+    if let Some(mode) = header.new_mode {
+        hunks.push(Hunk::SetMode {
+            path: new_path,
+            mode: mode & 0o777,
+        });
+    }
+    Ok(hunks)
+}
+
+/// Splits the `a/<old> b/<new>` part of a `diff --git` line.
+fn parse_git_paths(rest: &str) -> Option<(String, String)> {
+    let (old, new) = rest.split_once(" b/")?;
+    let old = old.strip_prefix("a/").unwrap_or(old);
+    Some((old.to_string(), new.to_string()))
+}
+
+/// Parses the path of a `---` / `+++` line, dropping any trailing timestamp.
+/// Returns `None` for `/dev/null`.
+fn parse_header_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or(raw).trim_end();
+    let path = unquote(path);
+    (path != DEV_NULL).then(|| path.to_string())
+}
+
+fn unquote(path: &str) -> &str {
+    path.strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path)
+}
+
+fn parse_mode(mode: &str, line_number: usize) -> Result<u32, ParseError> {
+    u32::from_str_radix(mode.trim(), 8).map_err(|_| InvalidHunkError {
+        message: format!("invalid file mode '{}'", mode.trim()),
+        line_number,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(diff: &str) -> Result<Vec<Hunk>, ParseError> {
+        let lines: Vec<&str> = diff.lines().collect();
+        assert!(is_unified_diff(&lines));
+        parse_unified_diff(&lines)
+    }
+
+    #[test]
+    fn test_parse_git_diff_update() {
+        let hunks = parse(
+            "diff --git a/src/lib.rs b/src/lib.rs
+index 3b18e51..a9c2f3e 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ fn main() {
+ fn main() {
+-    println!(\"hi\");
++    println!(\"hello\");
+ }
+@@ -10,2 +10,3 @@
+ fn other() {}
++fn added() {}
+
+",
+        )
+        .unwrap();
+        assert_eq!(
+            hunks,
+            vec![Hunk::UpdateFile {
+                path: PathBuf::from("src/lib.rs"),
+                move_path: None,
+                chunks: vec![
+                    UpdateFileChunk {
+                        change_context: None,
+                        old_lines: vec![
+                            "fn main() {".to_string(),
+                            "    println!(\"hi\");".to_string(),
+                            "}".to_string(),
+                        ],
+                        new_lines: vec![
+                            "fn main() {".to_string(),
+                            "    println!(\"hello\");".to_string(),
+                            "}".to_string(),
+                        ],
+                        is_end_of_file: false,
+                    },
+                    UpdateFileChunk {
+                        change_context: None,
+                        old_lines: vec!["fn other() {}".to_string()],
+                        new_lines: vec!["fn other() {}".to_string(), "fn added() {}".to_string()],
+                        is_end_of_file: false,
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_changed_lines_that_look_like_file_headers() {
+        let hunks = parse(
+            "--- a/notes.md
++++ b/notes.md
+@@ -1,3 +1,3 @@
+ intro
+--- foo
++++ bar
+ outro
+--- a/other.md
++++ b/other.md
+@@ -1 +1 @@
+-old
++new
+",
+        )
+        .unwrap();
+        assert_eq!(
+            hunks,
+            vec![
+                Hunk::UpdateFile {
+                    path: PathBuf::from("notes.md"),
+                    move_path: None,
+                    chunks: vec![UpdateFileChunk {
+                        change_context: None,
+                        old_lines: vec![
+                            "intro".to_string(),
+                            "-- foo".to_string(),
+                            "outro".to_string(),
+                        ],
+                        new_lines: vec![
+                            "intro".to_string(),
+                            "++ bar".to_string(),
+                            "outro".to_string(),
+                        ],
+                        is_end_of_file: false,
+                    }],
+                },
+                Hunk::UpdateFile {
+                    path: PathBuf::from("other.md"),
+                    move_path: None,
+                    chunks: vec![UpdateFileChunk {
+                        change_context: None,
+                        old_lines: vec!["old".to_string()],
+                        new_lines: vec!["new".to_string()],
+                        is_end_of_file: false,
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_add_delete_rename_and_mode() {
+        let hunks = parse(
+            "diff --git a/new.sh b/new.sh
+new file mode 100755
+index 0000000..e69de29
+--- /dev/null
++++ b/new.sh
+@@ -0,0 +1,2 @@
++#!/bin/sh
++echo hi
+\\ No newline at end of file
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index e69de29..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/from.txt b/to.txt
+similarity index 100%
+rename from from.txt
+rename to to.txt
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+diff --git a/link b/link
+new file mode 120000
+--- /dev/null
++++ b/link
+@@ -0,0 +1 @@
++target.txt
+\\ No newline at end of file
+",
+        )
+        .unwrap();
+        assert_eq!(
+            hunks,
+            vec![
+                Hunk::AddFile {
+                    path: PathBuf::from("new.sh"),
+                    contents: "#!/bin/sh\necho hi".to_string(),
+                },
+                Hunk::SetMode {
+                    path: PathBuf::from("new.sh"),
+                    mode: 0o755,
+                },
+                Hunk::DeleteFile {
+                    path: PathBuf::from("old.txt"),
+                },
+                Hunk::UpdateFile {
+                    path: PathBuf::from("from.txt"),
+                    move_path: Some(PathBuf::from("to.txt")),
+                    chunks: Vec::new(),
+                },
+                Hunk::SetMode {
+                    path: PathBuf::from("run.sh"),
+                    mode: 0o755,
+                },
+                Hunk::AddSymlink {
+                    path: PathBuf::from("link"),
+                    target: PathBuf::from("target.txt"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_diff_u_keeps_paths() {
+        let hunks = parse(
+            "--- notes.txt\t2024-01-01 00:00:00.000000000 +0000
++++ notes.txt\t2024-01-02 00:00:00.000000000 +0000
+@@ -1 +1 @@
+-old
++new
+",
+        )
+        .unwrap();
+        assert_eq!(
+            hunks,
+            vec![Hunk::UpdateFile {
+                path: PathBuf::from("notes.txt"),
+                move_path: None,
+                chunks: vec![UpdateFileChunk {
+                    change_context: None,
+                    old_lines: vec!["old".to_string()],
+                    new_lines: vec!["new".to_string()],
+                    is_end_of_file: false,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_binary_and_contextless_additions() {
+        assert_eq!(
+            parse(
+                "diff --git a/logo.png b/logo.png
+index 1111111..2222222 100644
+Binary files a/logo.png and b/logo.png differ
+"
+            ),
+            Err(InvalidHunkError {
+                message: "binary diffs are not supported; use '*** Add Binary File:' instead"
+                    .to_string(),
+                line_number: 3,
+            })
+        );
+        assert_eq!(
+            parse(
+                "--- a/notes.txt
++++ b/notes.txt
+@@ -4,0 +5 @@
++inserted
+"
+            ),
+            Err(InvalidHunkError {
+                message: "'@@ -4,0 +5 @@' adds lines without any context lines to locate them; include unchanged lines around the change".to_string(),
+                line_number: 3,
+            })
+        );
+    }
+}
@@ -41,6 +41,71 @@ fn test_apply_patch_cli_applies_multiple_operations() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_apply_patch_cli_applies_git_diff() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    fs::write(tmp.path().join("modify.txt"), "line1\nline2\nline3\n")?;
+    fs::write(tmp.path().join("delete.txt"), "obsolete\n")?;
+    fs::write(tmp.path().join("old_name.txt"), "keep\nrename me\n")?;
+
+    let patch = "diff --git a/modify.txt b/modify.txt
+index 83db48f..bf269f4 100644
+--- a/modify.txt
++++ b/modify.txt
+@@ -1,3 +1,3 @@
+ line1
+-line2
++changed
+ line3
+diff --git a/nested/new.txt b/nested/new.txt
+new file mode 100644
+index 0000000..3e75765
+--- /dev/null
++++ b/nested/new.txt
+@@ -0,0 +1 @@
++created
+diff --git a/delete.txt b/delete.txt
+deleted file mode 100644
+index 2b3ba1c..0000000
+--- a/delete.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-obsolete
+diff --git a/old_name.txt b/new_name.txt
+similarity index 50%
+rename from old_name.txt
+rename to new_name.txt
+index 1c5a2b8..7e0c1a4 100644
+--- a/old_name.txt
++++ b/new_name.txt
+@@ -1,2 +1,2 @@
+ keep
+-rename me
++renamed
+";
+
+    run_apply_patch_in_dir(tmp.path(), patch)?.success().stdout(
+        "Success. Updated the following files:\nA nested/new.txt\nM modify.txt\nM new_name.txt\nD delete.txt\n",
+    );
+
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("modify.txt"))?,
+        "line1\nchanged\nline3\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("nested/new.txt"))?,
+        "created\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("new_name.txt"))?,
+        "keep\nrenamed\n"
+    );
+    assert!(!tmp.path().join("delete.txt").exists());
+    assert!(!tmp.path().join("old_name.txt").exists());
+
+    Ok(())
+}
+
 #[test]
 fn test_apply_patch_cli_applies_multiple_chunks() -> anyhow::Result<()> {
     let tmp = tempdir()?;
@@ -104,12 +169,39 @@ fn test_apply_patch_cli_reports_missing_context() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Update File: modify.txt\n@@\n-missing\n+changed\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Failed to find expected lines in modify.txt:\nmissing\n");
+        .stderr(
+            "1 of 1 chunks could not be applied:\nmodify.txt chunk 1: rejected. Failed to find expected lines in modify.txt:\nmissing\n",
+        );
     assert_eq!(fs::read_to_string(&target_path)?, "line1\nline2\n");
 
     Ok(())
 }
 
+#[test]
+fn test_apply_patch_cli_applies_located_chunks_and_reports_rejected() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let target_path = tmp.path().join("modify.txt");
+    fs::write(
+        &target_path,
+        "fn alpha() {\n    let value = 1;\n}\n\nfn beta() {\n    let other = 2;\n}\n",
+    )?;
+
+    apply_patch_command(tmp.path())?
+        .arg("*** Begin Patch\n*** Update File: modify.txt\n@@\n-    let value  =  1;\n+    let value = 10;\n@@\n-    let another = 3;\n+    let another = 30;\n*** End Patch")
+        .assert()
+        .failure()
+        .stdout("Applied part of the patch. Updated the following files:\nM modify.txt\n")
+        .stderr(
+            "1 of 2 chunks could not be applied:\nmodify.txt chunk 1: fuzzed at line 2 (ignoring whitespace)\nmodify.txt chunk 2: rejected. Failed to find expected lines in modify.txt:\n    let another = 3;\nNearest candidate at line 6 (87% similar):\n    let other = 2;\n",
+        );
+    assert_eq!(
+        fs::read_to_string(&target_path)?,
+        "fn alpha() {\n    let value = 10;\n}\n\nfn beta() {\n    let other = 2;\n}\n"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_apply_patch_cli_rejects_missing_file_delete() -> anyhow::Result<()> {
     let tmp = tempdir()?;
@@ -214,7 +306,7 @@ fn test_apply_patch_cli_rejects_invalid_hunk_header() -> anyhow::Result<()> {
         .arg("*** Begin Patch\n*** Frobnicate File: foo\n*** End Patch")
         .assert()
         .failure()
-        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Delete File: {path}', '*** Update File: {path}'\n");
+        .stderr("Invalid patch hunk on line 2: '*** Frobnicate File: foo' is not a valid hunk header. Valid hunk headers: '*** Add File: {path}', '*** Add Binary File: {path}', '*** Add Symlink: {path}', '*** Delete File: {path}', '*** Update File: {path}', '*** Set Mode: {mode} {path}'\n");
 
     Ok(())
 }
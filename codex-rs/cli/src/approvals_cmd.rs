@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::approval_rules::ProjectApprovalRules;
+use codex_core::approval_rules::describe_rule;
+use codex_core::approval_rules::list_all_rules;
+use codex_core::approval_rules::load_project_rules;
+use codex_core::approval_rules::project_root;
+use codex_core::approval_rules::remove_rule;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Audit and revoke the "always allow" approval rules saved for projects.
+///
+/// Subcommands:
+/// - `list`   — show the rules saved for the current project (or all projects)
+/// - `remove` — delete saved rules
+#[derive(Debug, clap::Parser)]
+pub struct ApprovalsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ApprovalsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ApprovalsSubcommand {
+    /// List saved approval rules.
+    List(ListArgs),
+
+    /// Remove saved approval rules from a project.
+    Remove(RemoveArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// List the rules saved for every project instead of the current one.
+    #[arg(long, conflicts_with = "project")]
+    pub all: bool,
+
+    /// Project to list rules for. Defaults to the project containing the
+    /// current directory.
+    #[arg(long, value_name = "PATH")]
+    pub project: Option<PathBuf>,
+
+    /// Output the rules as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RemoveArgs {
+    /// Number of the rule to remove, as shown by `codex approvals list`.
+    #[arg(value_name = "NUMBER", required_unless_present = "all")]
+    pub number: Option<usize>,
+
+    /// Remove every rule saved for the project.
+    #[arg(long, conflicts_with = "number")]
+    pub all: bool,
+
+    /// Project to remove rules from. Defaults to the project containing the
+    /// current directory.
+    #[arg(long, value_name = "PATH")]
+    pub project: Option<PathBuf>,
+}
+
+impl ApprovalsCli {
+    pub async fn run(self) -> Result<()> {
+        let ApprovalsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+
+        match subcommand {
+            ApprovalsSubcommand::List(args) => run_list(&config, args)?,
+            ApprovalsSubcommand::Remove(args) => run_remove(&config, args)?,
+        }
+
+        Ok(())
+    }
+}
+
+fn resolve_project(config: &Config, project: Option<PathBuf>) -> PathBuf {
+    match project {
+        Some(project) => project_root(&config.cwd.join(project)),
+        None => project_root(&config.cwd),
+    }
+}
+
+fn run_list(config: &Config, list_args: ListArgs) -> Result<()> {
+    let ListArgs { all, project, json } = list_args;
+
+    let projects = if all {
+        list_all_rules(&config.codex_home)?
+    } else {
+        let project = resolve_project(config, project);
+        let rules = load_project_rules(&config.codex_home, &project)?;
+        vec![ProjectApprovalRules { project, rules }]
+    };
+
+    if json {
+        let output: Vec<_> = projects
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "project": entry.project,
+                    "rules": entry.rules,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if projects.iter().all(|entry| entry.rules.is_empty()) {
+        match projects.first() {
+            Some(entry) if !all => {
+                println!("No approval rules saved for {}.", entry.project.display())
+            }
+            _ => println!("No approval rules saved."),
+        }
+        return Ok(());
+    }
+
+    for (index, entry) in projects.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("{}:", entry.project.display());
+        for (number, rule) in entry.rules.iter().enumerate() {
+            println!("  {}. {}", number + 1, describe_rule(rule));
+        }
+    }
+
+    Ok(())
+}
+
+fn run_remove(config: &Config, remove_args: RemoveArgs) -> Result<()> {
+    let RemoveArgs {
+        number,
+        all,
+        project,
+    } = remove_args;
+
+    let project = resolve_project(config, project);
+    let rules = load_project_rules(&config.codex_home, &project)?;
+    let to_remove = if all {
+        rules
+    } else {
+        let Some(number) = number else {
+            bail!("specify a rule number or --all");
+        };
+        match number.checked_sub(1).and_then(|index| rules.get(index)) {
+            Some(rule) => vec![rule.clone()],
+            None => bail!(
+                "no approval rule numbered {number} for {}; run `codex approvals list` to see saved rules",
+                project.display()
+            ),
+        }
+    };
+
+    for rule in &to_remove {
+        remove_rule(&config.codex_home, &project, rule)?;
+        println!(
+            "Removed approval rule for {}: {}",
+            project.display(),
+            describe_rule(rule)
+        );
+    }
+    if to_remove.is_empty() {
+        println!("No approval rules saved for {}.", project.display());
+    }
+
+    Ok(())
+}
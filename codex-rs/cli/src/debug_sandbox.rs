@@ -140,7 +140,7 @@ async fn run_command_under_sandbox(
 
             let policy_str = match &config.sandbox_policy {
                 codex_core::protocol::SandboxPolicy::DangerFullAccess => "workspace-write",
-                codex_core::protocol::SandboxPolicy::ReadOnly => "read-only",
+                codex_core::protocol::SandboxPolicy::ReadOnly { .. } => "read-only",
                 codex_core::protocol::SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
             };
 
@@ -0,0 +1,140 @@
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::exec_policy::ExecPolicyDecision;
+use codex_core::exec_policy::evaluate_command;
+use codex_core::exec_policy::load_exec_policy;
+use serde_json::json;
+
+/// Inspect the execpolicy rules Codex consults before running commands.
+///
+/// Subcommands:
+/// - `check` — explain the decision for a command
+#[derive(Debug, clap::Parser)]
+pub struct ExecpolicyCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ExecpolicySubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ExecpolicySubcommand {
+    /// Explain whether a command would be allowed, require approval, or be
+    /// forbidden by the configured execpolicy files.
+    Check(CheckArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct CheckArgs {
+    /// Output the decision as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// The command to check, as it would be passed to execv(3).
+    #[arg(trailing_var_arg = true, required = true)]
+    pub command: Vec<String>,
+}
+
+impl ExecpolicyCli {
+    pub async fn run(self) -> Result<()> {
+        let ExecpolicyCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            ExecpolicySubcommand::Check(args) => {
+                run_check(&config_overrides, args).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_check(config_overrides: &CliConfigOverrides, check_args: CheckArgs) -> Result<()> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")?;
+
+    let CheckArgs { json, command } = check_args;
+    if command.is_empty() {
+        bail!("no command provided");
+    }
+
+    let checker = load_exec_policy(&config.execpolicy_files)?;
+    let evaluation = evaluate_command(
+        &checker,
+        &command,
+        &config.cwd,
+        &config.sandbox_policy,
+        config.approval_policy,
+    );
+
+    if json {
+        let commands: Vec<_> = evaluation
+            .commands
+            .iter()
+            .map(|command| {
+                let (decision, reason) = decision_fields(command.decision.as_ref());
+                json!({
+                    "command": command.command,
+                    "decision": decision,
+                    "reason": reason,
+                    "explanation": command.explanation,
+                })
+            })
+            .collect();
+        let (decision, reason) = decision_fields(evaluation.decision.as_ref());
+        let output = json!({
+            "decision": decision,
+            "reason": reason,
+            "policy_files": config.execpolicy_files,
+            "commands": commands,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let summary = match &evaluation.decision {
+        Some(ExecPolicyDecision::Allow) => "allowed without approval".to_string(),
+        Some(ExecPolicyDecision::RequireApproval { reason }) => {
+            format!("requires approval: {reason}")
+        }
+        Some(ExecPolicyDecision::Forbid { reason }) => format!("forbidden: {reason}"),
+        None => "no matching rule; Codex falls back to its built-in approval rules".to_string(),
+    };
+    println!("Decision: {summary}");
+
+    println!("Policy files:");
+    println!("  (built-in default)");
+    for path in &config.execpolicy_files {
+        println!("  {}", path.display());
+    }
+
+    println!("Commands:");
+    for command in &evaluation.commands {
+        println!("  {}: {}", command.command.join(" "), command.explanation);
+    }
+
+    Ok(())
+}
+
+fn decision_fields(decision: Option<&ExecPolicyDecision>) -> (Option<&'static str>, Option<&str>) {
+    match decision {
+        Some(ExecPolicyDecision::Allow) => (Some("allow"), None),
+        Some(ExecPolicyDecision::RequireApproval { reason }) => {
+            (Some("require_approval"), Some(reason.as_str()))
+        }
+        Some(ExecPolicyDecision::Forbid { reason }) => (Some("forbid"), Some(reason.as_str())),
+        None => (None, None),
+    }
+}
@@ -26,11 +26,17 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod approvals_cmd;
+mod execpolicy_cmd;
 mod mcp_cmd;
+mod sessions_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::approvals_cmd::ApprovalsCli;
+use crate::execpolicy_cmd::ExecpolicyCli;
 use crate::mcp_cmd::McpCli;
+use crate::sessions_cmd::SessionsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -93,6 +99,12 @@ enum Subcommand {
     #[clap(visible_alias = "debug")]
     Sandbox(SandboxArgs),
 
+    /// Inspect the execpolicy rules consulted before running commands.
+    Execpolicy(ExecpolicyCli),
+
+    /// List or remove the "always allow" approval rules saved for projects.
+    Approvals(ApprovalsCli),
+
     /// Apply the latest diff produced by Codex agent as a `git apply` to your local working tree.
     #[clap(visible_alias = "a")]
     Apply(ApplyCommand),
@@ -100,6 +112,9 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Search recorded sessions.
+    Sessions(SessionsCli),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -210,6 +225,9 @@ struct AppServerCommand {
     /// Omit to run the app server; specify a subcommand for tooling.
     #[command(subcommand)]
     subcommand: Option<AppServerSubcommand>,
+
+    #[clap(flatten)]
+    args: codex_app_server::AppServerArgs,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -431,9 +449,35 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Execpolicy(mut execpolicy_cli)) => {
+            prepend_config_flags(
+                &mut execpolicy_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            execpolicy_cli.run().await?;
+        }
+        Some(Subcommand::Approvals(mut approvals_cli)) => {
+            prepend_config_flags(
+                &mut approvals_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            approvals_cli.run().await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
-                codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
+                codex_app_server::run_main(
+                    codex_linux_sandbox_exe,
+                    root_config_overrides,
+                    app_server_cli.args,
+                )
+                .await?;
             }
             Some(AppServerSubcommand::GenerateTs(gen_cli)) => {
                 codex_app_server_protocol::generate_ts(
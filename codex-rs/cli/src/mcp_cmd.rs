@@ -9,13 +9,17 @@ use codex_common::CliConfigOverrides;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
-use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config::find_codex_home;
-use codex_core::config::load_global_mcp_servers;
 use codex_core::config::types::McpServerConfig;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
 use codex_core::mcp::auth::compute_auth_statuses;
+use codex_core::mcp::auth::describe_auth_status;
+use codex_core::mcp::manage::OAuthLoginTarget;
+use codex_core::mcp::manage::add_global_server;
+use codex_core::mcp::manage::oauth_login_target;
+use codex_core::mcp::manage::remove_global_server;
+use codex_core::mcp::manage::validate_server_name;
 use codex_core::protocol::McpAuthStatus;
 use codex_rmcp_client::delete_oauth_tokens;
 use codex_rmcp_client::perform_oauth_login;
@@ -53,11 +57,9 @@ pub enum McpSubcommand {
     Remove(RemoveArgs),
 
     /// [experimental] Authenticate with a configured MCP server via OAuth.
-    /// Requires experimental_use_rmcp_client = true in config.toml.
     Login(LoginArgs),
 
     /// [experimental] Remove stored OAuth credentials for a server.
-    /// Requires experimental_use_rmcp_client = true in config.toml.
     Logout(LogoutArgs),
 }
 
@@ -211,9 +213,6 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
     validate_server_name(&name)?;
 
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let mut servers = load_global_mcp_servers(&codex_home)
-        .await
-        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
 
     let transport = match transport_args {
         AddMcpTransportArgs {
@@ -261,15 +260,10 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        sampling: None,
     };
 
-    servers.insert(name.clone(), new_entry);
-
-    ConfigEditsBuilder::new(&codex_home)
-        .replace_mcp_servers(&servers)
-        .apply()
-        .await
-        .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
+    add_global_server(&codex_home, &name, new_entry).await?;
 
     println!("Added global MCP server '{name}'.");
 
@@ -284,8 +278,8 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
             Ok(true) => {
                 if !config.features.enabled(Feature::RmcpClient) {
                     println!(
-                        "MCP server supports login. Add `experimental_use_rmcp_client = true` \
-                         to your config.toml and run `codex mcp login {name}` to login."
+                        "MCP server supports login. Remove `rmcp_client = false` from [features] \
+                         in your config.toml and run `codex mcp login {name}` to login."
                     );
                 } else {
                     println!("Detected OAuth support. Starting OAuth flow…");
@@ -318,22 +312,8 @@ async fn run_remove(config_overrides: &CliConfigOverrides, remove_args: RemoveAr
 
     let RemoveArgs { name } = remove_args;
 
-    validate_server_name(&name)?;
-
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let mut servers = load_global_mcp_servers(&codex_home)
-        .await
-        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
-
-    let removed = servers.remove(&name).is_some();
-
-    if removed {
-        ConfigEditsBuilder::new(&codex_home)
-            .replace_mcp_servers(&servers)
-            .apply()
-            .await
-            .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
-    }
+    let removed = remove_global_server(&codex_home, &name).await?;
 
     if removed {
         println!("Removed global MCP server '{name}'.");
@@ -352,27 +332,13 @@ async fn run_login(config_overrides: &CliConfigOverrides, login_args: LoginArgs)
         .await
         .context("failed to load configuration")?;
 
-    if !config.features.enabled(Feature::RmcpClient) {
-        bail!(
-            "OAuth login is only supported when [features].rmcp_client is true in config.toml. See https://github.com/openai/codex/blob/main/docs/config.md#feature-flags for details."
-        );
-    }
-
     let LoginArgs { name, scopes } = login_args;
 
-    let Some(server) = config.mcp_servers.get(&name) else {
-        bail!("No MCP server named '{name}' found.");
-    };
-
-    let (url, http_headers, env_http_headers) = match &server.transport {
-        McpServerTransportConfig::StreamableHttp {
-            url,
-            http_headers,
-            env_http_headers,
-            ..
-        } => (url.clone(), http_headers.clone(), env_http_headers.clone()),
-        _ => bail!("OAuth login is only supported for streamable HTTP servers."),
-    };
+    let OAuthLoginTarget {
+        url,
+        http_headers,
+        env_http_headers,
+    } = oauth_login_target(&config, &name)?;
 
     perform_oauth_login(
         &name,
@@ -436,10 +402,10 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
         let json_entries: Vec<_> = entries
             .into_iter()
             .map(|(name, cfg)| {
-                let auth_status = auth_statuses
+                let (auth_status, auth_expires_at) = auth_statuses
                     .get(name.as_str())
-                    .map(|entry| entry.auth_status)
-                    .unwrap_or(McpAuthStatus::Unsupported);
+                    .map(|entry| (entry.auth_status, entry.expires_at))
+                    .unwrap_or((McpAuthStatus::Unsupported, None));
                 let transport = match &cfg.transport {
                     McpServerTransportConfig::Stdio {
                         command,
@@ -482,6 +448,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                         .tool_timeout_sec
                         .map(|timeout| timeout.as_secs_f64()),
                     "auth_status": auth_status,
+                    "auth_expires_at": auth_expires_at,
                 })
             })
             .collect();
@@ -525,9 +492,8 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                 };
                 let auth_status = auth_statuses
                     .get(name.as_str())
-                    .map(|entry| entry.auth_status)
-                    .unwrap_or(McpAuthStatus::Unsupported)
-                    .to_string();
+                    .map(|entry| describe_auth_status(entry.auth_status, entry.expires_at))
+                    .unwrap_or_else(|| McpAuthStatus::Unsupported.to_string());
                 stdio_rows.push([
                     name.clone(),
                     command.clone(),
@@ -550,9 +516,8 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                 };
                 let auth_status = auth_statuses
                     .get(name.as_str())
-                    .map(|entry| entry.auth_status)
-                    .unwrap_or(McpAuthStatus::Unsupported)
-                    .to_string();
+                    .map(|entry| describe_auth_status(entry.auth_status, entry.expires_at))
+                    .unwrap_or_else(|| McpAuthStatus::Unsupported.to_string());
                 let bearer_token_display =
                     bearer_token_env_var.as_deref().unwrap_or("-").to_string();
                 http_rows.push([
@@ -720,6 +685,7 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
             "transport": transport,
             "enabled_tools": server.enabled_tools.clone(),
             "disabled_tools": server.disabled_tools.clone(),
+            "sampling": server.sampling,
             "startup_timeout_sec": server
                 .startup_timeout_sec
                 .map(|timeout| timeout.as_secs_f64()),
@@ -753,6 +719,9 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
         let disabled_tools_display = format_tool_list(&server.disabled_tools);
         println!("  disabled_tools: {disabled_tools_display}");
     }
+    if let Some(sampling) = server.sampling {
+        println!("  sampling: {}", sampling.as_str());
+    }
     match &server.transport {
         McpServerTransportConfig::Stdio {
             command,
@@ -841,16 +810,3 @@ fn parse_env_pair(raw: &str) -> Result<(String, String), String> {
 
     Ok((key.to_string(), value))
 }
-
-fn validate_server_name(name: &str) -> Result<()> {
-    let is_valid = !name.is_empty()
-        && name
-            .chars()
-            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
-
-    if is_valid {
-        Ok(())
-    } else {
-        bail!("invalid server name '{name}' (use letters, numbers, '-', '_')");
-    }
-}
@@ -0,0 +1,133 @@
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::SessionSearchHit;
+use codex_core::SessionSearchMatchKind;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Inspect recorded sessions.
+///
+/// Subcommands:
+/// - `search` — full-text search over past sessions
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Search the messages, commands and touched files of recorded sessions.
+    Search(SearchArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Terms to search for; every term must appear in a session for it to match.
+    #[arg(value_name = "QUERY", required = true, num_args = 1..)]
+    pub query: Vec<String>,
+
+    /// Maximum number of sessions to show.
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: usize,
+
+    /// Output the results as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        let overrides = config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+
+        match subcommand {
+            SessionsSubcommand::Search(args) => run_search(&config, args).await?,
+        }
+
+        Ok(())
+    }
+}
+
+async fn run_search(config: &Config, search_args: SearchArgs) -> Result<()> {
+    let SearchArgs { query, limit, json } = search_args;
+    let query = query.join(" ");
+
+    let hits = RolloutRecorder::search_conversations(
+        &config.codex_home,
+        &query,
+        limit,
+        &[],
+        None,
+        &config.model_provider_id,
+    )
+    .await
+    .context("failed to search sessions")?;
+
+    if json {
+        let output: Vec<_> = hits
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "id": hit.conversation_id.to_string(),
+                    "path": hit.path,
+                    "preview": hit.preview,
+                    "model_provider": hit.model_provider,
+                    "created_at": hit.created_at,
+                    "updated_at": hit.updated_at,
+                    "matches": hit
+                        .matches
+                        .iter()
+                        .map(|m| serde_json::json!({ "kind": m.kind, "snippet": m.snippet }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        println!("No sessions match \"{query}\".");
+        return Ok(());
+    }
+
+    for (index, hit) in hits.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        print_hit(hit);
+    }
+    println!();
+    println!("Resume a session with `codex resume <id>`.");
+
+    Ok(())
+}
+
+fn print_hit(hit: &SessionSearchHit) {
+    let preview = hit.preview.lines().next().unwrap_or_default();
+    println!("{}  {}  {preview}", hit.updated_at, hit.conversation_id);
+    for m in &hit.matches {
+        let label = match m.kind {
+            SessionSearchMatchKind::UserMessage => "user",
+            SessionSearchMatchKind::AgentMessage => "assistant",
+            SessionSearchMatchKind::Command => "command",
+            SessionSearchMatchKind::File => "file",
+        };
+        println!("    {label}: {}", m.snippet);
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use anyhow::Result;
+use codex_core::approval_rules::add_rule;
+use codex_core::approval_rules::load_project_rules;
+use codex_core::protocol::ApprovalRule;
+use codex_core::protocol::ApprovalRuleKind;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn rule(kind: ApprovalRuleKind, command: &[&str]) -> ApprovalRule {
+    ApprovalRule {
+        kind,
+        command: command.iter().map(|arg| (*arg).to_string()).collect(),
+    }
+}
+
+#[test]
+fn list_and_remove_project_rules() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    let project_path = project.path().to_string_lossy().to_string();
+    add_rule(
+        codex_home.path(),
+        project.path(),
+        rule(ApprovalRuleKind::Prefix, &["cargo", "test"]),
+    )?;
+    add_rule(
+        codex_home.path(),
+        project.path(),
+        rule(ApprovalRuleKind::Program, &["pnpm"]),
+    )?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["approvals", "list", "--project", &project_path])
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("1. commands starting with `cargo test`"));
+    assert!(stdout.contains("2. any `pnpm` command"));
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["approvals", "remove", "1", "--project", &project_path])
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        load_project_rules(codex_home.path(), project.path())?,
+        vec![rule(ApprovalRuleKind::Program, &["pnpm"])]
+    );
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["approvals", "list", "--all", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let parsed: JsonValue = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        parsed,
+        json!([{
+            "project": project_path,
+            "rules": [{ "kind": "program", "command": ["pnpm"] }],
+        }])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn remove_rejects_unknown_rule_number() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args([
+            "approvals",
+            "remove",
+            "3",
+            "--project",
+            &project.path().to_string_lossy(),
+        ])
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("no approval rule numbered 3"));
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use anyhow::Result;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+#[test]
+fn check_explains_default_policy_decision() -> Result<()> {
+    let codex_home = TempDir::new()?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd.args(["execpolicy", "check", "ls", "-l"]).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains("Decision: allowed without approval"));
+    assert!(stdout.contains("ls -l: allowed: matches a `ls` rule"));
+
+    Ok(())
+}
+
+#[test]
+fn check_applies_configured_policy_files() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("team.policy"),
+        r#"
+define_program(
+    program="terraform",
+    args=["destroy"],
+    forbidden="never destroy infrastructure from an agent",
+)
+"#,
+    )?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        "execpolicy_files = [\"team.policy\"]\n",
+    )?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["execpolicy", "check", "--json", "terraform", "destroy"])
+        .output()?;
+    assert!(output.status.success());
+    let parsed: JsonValue = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(parsed["decision"], json!("forbid"));
+    assert_eq!(
+        parsed["reason"],
+        json!("never destroy infrastructure from an agent")
+    );
+    assert_eq!(
+        parsed["policy_files"],
+        json!([codex_home.path().join("team.policy")])
+    );
+
+    Ok(())
+}
@@ -107,7 +107,8 @@ async fn list_and_get_render_expected_output() -> Result<()> {
             },
             "startup_timeout_sec": null,
             "tool_timeout_sec": null,
-            "auth_status": "unsupported"
+            "auth_status": "unsupported",
+            "auth_expires_at": null
           }
         ]
         )
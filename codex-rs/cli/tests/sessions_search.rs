@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use anyhow::Result;
+use pretty_assertions::assert_eq;
+use serde_json::Value as JsonValue;
+use serde_json::json;
+use tempfile::TempDir;
+
+const CONVERSATION_ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::cargo_bin("codex")?;
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+fn write_rollout(codex_home: &Path) -> Result<()> {
+    let dir = codex_home.join("sessions/2025/01/03");
+    std::fs::create_dir_all(&dir)?;
+    let ts = "2025-01-03T12:00:00.000Z";
+    let lines = [
+        json!({
+            "timestamp": ts,
+            "type": "session_meta",
+            "payload": {
+                "id": CONVERSATION_ID,
+                "timestamp": ts,
+                "cwd": "/",
+                "originator": "codex",
+                "cli_version": "0.0.0",
+                "instructions": null,
+                "source": "cli",
+                "model_provider": "openai",
+            },
+        }),
+        json!({
+            "timestamp": ts,
+            "type": "event_msg",
+            "payload": {"type": "user_message", "message": "speed up the file watcher"},
+        }),
+        json!({
+            "timestamp": ts,
+            "type": "response_item",
+            "payload": {
+                "type": "function_call",
+                "name": "shell",
+                "arguments": json!({"command": ["bash", "-lc", "rg notify src/watcher.rs"]}).to_string(),
+                "call_id": "call-1",
+            },
+        }),
+    ];
+    let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    std::fs::write(
+        dir.join(format!(
+            "rollout-2025-01-03T12-00-00-{CONVERSATION_ID}.jsonl"
+        )),
+        contents,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn search_finds_sessions_by_command() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    write_rollout(codex_home.path())?;
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd.args(["sessions", "search", "notify"]).output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(stdout.contains(CONVERSATION_ID));
+    assert!(stdout.contains("speed up the file watcher"));
+    assert!(stdout.contains("command: rg notify src/watcher.rs"));
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd
+        .args(["sessions", "search", "watcher", "--json"])
+        .output()?;
+    assert!(output.status.success());
+    let results: JsonValue = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(results[0]["id"], json!(CONVERSATION_ID));
+    assert_eq!(
+        results[0]["matches"],
+        json!([
+            {"kind": "user_message", "snippet": "speed up the file watcher"},
+            {"kind": "command", "snippet": "rg notify src/watcher.rs"},
+        ])
+    );
+
+    let mut cmd = codex_command(codex_home.path())?;
+    let output = cmd.args(["sessions", "search", "nothing-here"]).output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "No sessions match \"nothing-here\".\n"
+    );
+
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use codex_core::protocol::SandboxPolicy;
 
 pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
-    match sandbox_policy {
+    let mut summary = match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-        SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
             exclude_tmpdir_env_var,
             exclude_slash_tmp,
+            deny_read: _,
+            network_allowlist,
         } => {
             let mut summary = "workspace-write".to_string();
 
@@ -29,8 +31,23 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
             summary.push_str(&format!(" [{}]", writable_entries.join(", ")));
             if *network_access {
                 summary.push_str(" (network access enabled)");
+            } else if !network_allowlist.is_empty() {
+                summary.push_str(&format!(
+                    " (network allowlist: {})",
+                    network_allowlist.join(", ")
+                ));
             }
             summary
         }
+    };
+
+    let deny_read = sandbox_policy.get_deny_read_paths();
+    if !deny_read.is_empty() {
+        let denied_entries: Vec<String> = deny_read
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        summary.push_str(&format!(" (read denied: {})", denied_entries.join(", ")));
     }
+    summary
 }
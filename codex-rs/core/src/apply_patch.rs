@@ -96,8 +96,16 @@ pub(crate) fn convert_apply_patch_to_protocol(
     let mut result = HashMap::with_capacity(changes.len());
     for (path, change) in changes {
         let protocol_change = match change {
-            ApplyPatchFileChange::Add { content } => FileChange::Add {
+            ApplyPatchFileChange::Add { content, mode } => FileChange::Add {
                 content: content.clone(),
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddBinary { content, mode } => FileChange::AddBinary {
+                size: content.len() as u64,
+                mode: *mode,
+            },
+            ApplyPatchFileChange::AddSymlink { target } => FileChange::AddSymlink {
+                target: target.clone(),
             },
             ApplyPatchFileChange::Delete { content } => FileChange::Delete {
                 content: content.clone(),
@@ -106,10 +114,13 @@ pub(crate) fn convert_apply_patch_to_protocol(
                 unified_diff,
                 move_path,
                 new_content: _new_content,
+                mode,
             } => FileChange::Update {
                 unified_diff: unified_diff.clone(),
                 move_path: move_path.clone(),
+                mode: *mode,
             },
+            ApplyPatchFileChange::SetMode { mode } => FileChange::SetMode { mode: *mode },
         };
         result.insert(path.clone(), protocol_change);
     }
@@ -135,7 +146,8 @@ mod tests {
         assert_eq!(
             got.get(&p),
             Some(&FileChange::Add {
-                content: "hello".to_string()
+                content: "hello".to_string(),
+                mode: None,
             })
         );
     }
@@ -0,0 +1,340 @@
+/*
+Module: approval_rules
+
+Persistent, project-scoped "always allow" rules for command approvals. Each
+project (the root of the git repository containing the working directory, or
+the working directory itself) gets its own TOML file under
+`$CODEX_HOME/approval_rules/`, so approvals granted in one session carry over
+to the next without leaking into unrelated projects.
+*/
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::ApprovalRule;
+use codex_protocol::protocol::ApprovalRuleKind;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tempfile::NamedTempFile;
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+/// Directory under `CODEX_HOME` holding one rules file per project.
+pub const APPROVAL_RULES_DIR: &str = "approval_rules";
+
+/// The contents of a single project's rules file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectApprovalRules {
+    pub project: PathBuf,
+    #[serde(default)]
+    pub rules: Vec<ApprovalRule>,
+}
+
+/// The project a working directory belongs to: the root of its git
+/// repository (the main worktree for linked worktrees), or `cwd` itself.
+pub fn project_root(cwd: &Path) -> PathBuf {
+    resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// The rules a session has loaded so far, keyed by project, so approving a
+/// command does not read the rules file or run git discovery every time.
+/// Rules the session saves itself are added as it saves them; edits made
+/// elsewhere take effect in the next session.
+#[derive(Debug, Default)]
+pub(crate) struct ApprovalRulesCache {
+    project_roots: HashMap<PathBuf, PathBuf>,
+    rules: HashMap<PathBuf, Vec<ApprovalRule>>,
+}
+
+impl ApprovalRulesCache {
+    /// The cached rules for the project containing `cwd`, or `None` when
+    /// they have not been loaded yet.
+    pub(crate) fn rules_for(&self, cwd: &Path) -> Option<&[ApprovalRule]> {
+        let project = self.project_roots.get(cwd)?;
+        self.rules.get(project).map(Vec::as_slice)
+    }
+
+    /// Records the rules loaded for `project`, which contains `cwd`. Rules
+    /// already cached for the project are kept.
+    pub(crate) fn insert(&mut self, cwd: PathBuf, project: PathBuf, rules: Vec<ApprovalRule>) {
+        self.rules.entry(project.clone()).or_insert(rules);
+        self.project_roots.insert(cwd, project);
+    }
+
+    /// Records that `rule` was saved for `project`.
+    pub(crate) fn add(&mut self, project: &Path, rule: ApprovalRule) {
+        if let Some(rules) = self.rules.get_mut(project)
+            && !rules.contains(&rule)
+        {
+            rules.push(rule);
+        }
+    }
+}
+
+/// Path of the rules file for `project`.
+pub fn rules_file_path(codex_home: &Path, project: &Path) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(project.to_string_lossy().as_bytes());
+    let digest = hasher.finalize();
+    let hex = format!("{digest:x}");
+    let truncated = hex.get(..16).unwrap_or(&hex);
+    codex_home
+        .join(APPROVAL_RULES_DIR)
+        .join(format!("{truncated}.toml"))
+}
+
+/// Loads the rules stored for `project`; a missing file means no rules.
+pub fn load_project_rules(codex_home: &Path, project: &Path) -> std::io::Result<Vec<ApprovalRule>> {
+    let path = rules_file_path(codex_home, project);
+    Ok(read_rules_file(&path)?
+        .map(|file| file.rules)
+        .unwrap_or_default())
+}
+
+/// Loads the rules for every project, sorted by project path.
+pub fn list_all_rules(codex_home: &Path) -> std::io::Result<Vec<ProjectApprovalRules>> {
+    let dir = codex_home.join(APPROVAL_RULES_DIR);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut projects = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        if let Some(file) = read_rules_file(&path)?
+            && !file.rules.is_empty()
+        {
+            projects.push(file);
+        }
+    }
+    projects.sort_by(|a, b| a.project.cmp(&b.project));
+    Ok(projects)
+}
+
+/// Stores `rule` for `project`. Returns `false` if an identical rule was
+/// already stored.
+pub fn add_rule(codex_home: &Path, project: &Path, rule: ApprovalRule) -> std::io::Result<bool> {
+    let mut rules = load_project_rules(codex_home, project)?;
+    if rules.contains(&rule) {
+        return Ok(false);
+    }
+    rules.push(rule);
+    write_rules_file(codex_home, project, rules)?;
+    Ok(true)
+}
+
+/// Removes `rule` from `project`. Returns `false` if no such rule was stored.
+pub fn remove_rule(
+    codex_home: &Path,
+    project: &Path,
+    rule: &ApprovalRule,
+) -> std::io::Result<bool> {
+    let mut rules = load_project_rules(codex_home, project)?;
+    let before = rules.len();
+    rules.retain(|existing| existing != rule);
+    if rules.len() == before {
+        return Ok(false);
+    }
+    write_rules_file(codex_home, project, rules)?;
+    Ok(true)
+}
+
+/// The rules worth offering for `command`, narrowest first. Commands are
+/// reduced to the plain command inside `bash -lc "..."` when the script is a
+/// single word-only command; scripts that cannot be reduced only get an exact
+/// rule so that approving one script never approves the shell itself.
+pub fn suggested_rules(command: &[String]) -> Vec<ApprovalRule> {
+    let plain = match parse_shell_lc_plain_commands(command) {
+        Some(mut commands) if commands.len() == 1 => commands.remove(0),
+        Some(_) => return exact_rule(command).into_iter().collect(),
+        None if extract_bash_command(command).is_some() => {
+            return exact_rule(command).into_iter().collect();
+        }
+        None => command.to_vec(),
+    };
+
+    let mut suggestions: Vec<ApprovalRule> = exact_rule(&plain).into_iter().collect();
+    if let [program, subcommand, _, ..] = plain.as_slice()
+        && !subcommand.starts_with('-')
+    {
+        suggestions.push(ApprovalRule {
+            kind: ApprovalRuleKind::Prefix,
+            command: vec![program.clone(), subcommand.clone()],
+        });
+    }
+    if let [program, _, ..] = plain.as_slice() {
+        suggestions.push(ApprovalRule {
+            kind: ApprovalRuleKind::Program,
+            command: vec![program.clone()],
+        });
+    }
+    suggestions
+}
+
+/// Short human-readable description of what `rule` approves.
+pub fn describe_rule(rule: &ApprovalRule) -> String {
+    let command = rule.command.join(" ");
+    match rule.kind {
+        ApprovalRuleKind::Exact => format!("`{command}`"),
+        ApprovalRuleKind::Prefix => format!("commands starting with `{command}`"),
+        ApprovalRuleKind::Program => format!("any `{command}` command"),
+    }
+}
+
+fn exact_rule(command: &[String]) -> Option<ApprovalRule> {
+    (!command.is_empty()).then(|| ApprovalRule {
+        kind: ApprovalRuleKind::Exact,
+        command: command.to_vec(),
+    })
+}
+
+fn read_rules_file(path: &Path) -> std::io::Result<Option<ProjectApprovalRules>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    toml::from_str(&contents).map(Some).map_err(|err| {
+        std::io::Error::new(ErrorKind::InvalidData, format!("{}: {err}", path.display()))
+    })
+}
+
+fn write_rules_file(
+    codex_home: &Path,
+    project: &Path,
+    rules: Vec<ApprovalRule>,
+) -> std::io::Result<()> {
+    let path = rules_file_path(codex_home, project);
+    let dir = codex_home.join(APPROVAL_RULES_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let file = ProjectApprovalRules {
+        project: project.to_path_buf(),
+        rules,
+    };
+    let contents = toml::to_string_pretty(&file)
+        .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?;
+    let tmp = NamedTempFile::new_in(&dir)?;
+    std::fs::write(tmp.path(), contents)?;
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    fn rule(kind: ApprovalRuleKind, command: &[&str]) -> ApprovalRule {
+        ApprovalRule {
+            kind,
+            command: vec_str(command),
+        }
+    }
+
+    #[test]
+    fn rules_round_trip_per_project() {
+        let codex_home = TempDir::new().expect("codex home");
+        let project_a = PathBuf::from("/work/a");
+        let project_b = PathBuf::from("/work/b");
+        let cargo_test = rule(ApprovalRuleKind::Prefix, &["cargo", "test"]);
+        let pnpm = rule(ApprovalRuleKind::Program, &["pnpm"]);
+
+        assert!(add_rule(codex_home.path(), &project_a, cargo_test.clone()).expect("add"));
+        assert!(!add_rule(codex_home.path(), &project_a, cargo_test.clone()).expect("add"));
+        assert!(add_rule(codex_home.path(), &project_b, pnpm.clone()).expect("add"));
+
+        assert_eq!(
+            load_project_rules(codex_home.path(), &project_a).expect("load"),
+            vec![cargo_test.clone()]
+        );
+        assert_eq!(
+            list_all_rules(codex_home.path()).expect("list"),
+            vec![
+                ProjectApprovalRules {
+                    project: project_a.clone(),
+                    rules: vec![cargo_test.clone()],
+                },
+                ProjectApprovalRules {
+                    project: project_b,
+                    rules: vec![pnpm],
+                },
+            ]
+        );
+
+        assert!(remove_rule(codex_home.path(), &project_a, &cargo_test).expect("remove"));
+        assert!(!remove_rule(codex_home.path(), &project_a, &cargo_test).expect("remove"));
+        assert_eq!(
+            load_project_rules(codex_home.path(), &project_a).expect("load"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn suggestions_unwrap_single_command_scripts() {
+        assert_eq!(
+            suggested_rules(&vec_str(&["bash", "-lc", "cargo test -p codex-core"])),
+            vec![
+                rule(
+                    ApprovalRuleKind::Exact,
+                    &["cargo", "test", "-p", "codex-core"]
+                ),
+                rule(ApprovalRuleKind::Prefix, &["cargo", "test"]),
+                rule(ApprovalRuleKind::Program, &["cargo"]),
+            ]
+        );
+        assert_eq!(
+            suggested_rules(&vec_str(&["pnpm", "lint"])),
+            vec![
+                rule(ApprovalRuleKind::Exact, &["pnpm", "lint"]),
+                rule(ApprovalRuleKind::Program, &["pnpm"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn compound_scripts_only_get_exact_rules() {
+        let command = vec_str(&["bash", "-lc", "cargo build && ./target/debug/app"]);
+        assert_eq!(
+            suggested_rules(&command),
+            vec![ApprovalRule {
+                kind: ApprovalRuleKind::Exact,
+                command,
+            }]
+        );
+    }
+
+    #[test]
+    fn cache_shares_rules_between_directories_of_one_project() {
+        let project = PathBuf::from("/work/a");
+        let rule = ApprovalRule {
+            kind: ApprovalRuleKind::Prefix,
+            command: vec_str(&["cargo", "test"]),
+        };
+        let mut cache = ApprovalRulesCache::default();
+        assert_eq!(cache.rules_for(&project), None);
+
+        cache.insert(project.clone(), project.clone(), Vec::new());
+        cache.add(&project, rule.clone());
+        cache.insert(project.join("src"), project.clone(), Vec::new());
+
+        assert_eq!(
+            cache.rules_for(&project.join("src")),
+            Some(std::slice::from_ref(&rule))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::io::BufRead;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::OnceLock;
 use std::time::Duration;
 
@@ -41,6 +42,7 @@ use crate::client_common::ResponsesApiRequest;
 use crate::client_common::create_reasoning_param_for_request;
 use crate::client_common::create_text_param_for_request;
 use crate::config::Config;
+use crate::config::types::ModelFallback;
 use crate::default_client::CodexHttpClient;
 use crate::default_client::create_client;
 use crate::error::CodexErr;
@@ -51,7 +53,10 @@ use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::messages_api::stream_messages;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
@@ -81,6 +86,7 @@ struct Error {
 #[derive(Debug, Clone)]
 pub struct ModelClient {
     config: Arc<Config>,
+    failover: Arc<Mutex<FailoverState>>,
     auth_manager: Option<Arc<AuthManager>>,
     otel_event_manager: OtelEventManager,
     client: CodexHttpClient,
@@ -107,6 +113,7 @@ impl ModelClient {
 
         Self {
             config,
+            failover: Arc::new(Mutex::new(FailoverState::default())),
             auth_manager,
             otel_event_manager,
             client,
@@ -119,16 +126,18 @@ impl ModelClient {
     }
 
     pub fn get_model_context_window(&self) -> Option<i64> {
-        let pct = self.config.model_family.effective_context_window_percent;
-        self.config
+        let config = self.active_client().config;
+        let pct = config.model_family.effective_context_window_percent;
+        config
             .model_context_window
-            .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+            .or_else(|| get_model_info(&config.model_family).map(|info| info.context_window))
             .map(|w| w.saturating_mul(pct) / 100)
     }
 
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
-        self.config.model_auto_compact_token_limit.or_else(|| {
-            get_model_info(&self.config.model_family).and_then(|info| info.auto_compact_token_limit)
+        let config = self.active_client().config;
+        config.model_auto_compact_token_limit.or_else(|| {
+            get_model_info(&config.model_family).and_then(|info| info.auto_compact_token_limit)
         })
     }
 
@@ -140,7 +149,115 @@ impl ModelClient {
         &self.provider
     }
 
+    /// Streams a response for `prompt`. When the serving provider exhausts its
+    /// retries, the next entry of `model_fallbacks` takes over; see
+    /// [`ModelClient::take_provider_switch`]. This is the only place that
+    /// fails over, so callers retrying a turn keep the provider chosen here.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        loop {
+            let client = self.active_client();
+            match client.stream_with_active_provider(prompt).await {
+                Err(err) if is_failover_error(&err) => {
+                    let Some(switch) = self.fail_over() else {
+                        return Err(err);
+                    };
+                    warn!(
+                        "provider `{}` failed ({err}); switching to `{}`",
+                        client.config.model_provider_id, switch.provider_id
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Switches to the next configured fallback provider. Returns `None` when
+    /// the chain is exhausted. The switch lasts for the lifetime of this
+    /// client (one turn), so later turns try the primary provider again.
+    fn fail_over(&self) -> Option<ProviderSwitch> {
+        #[expect(clippy::expect_used)]
+        let mut state = self.failover.lock().expect("failover state poisoned");
+        let start = state.active.as_ref().map_or(0, |(index, _)| index + 1);
+        let (index, client, switch) = self
+            .config
+            .model_fallbacks
+            .iter()
+            .enumerate()
+            .skip(start)
+            .find_map(|(index, fallback)| {
+                let (client, switch) = self.fallback_client(fallback)?;
+                Some((index, client, switch))
+            })?;
+        state.active = Some((index, client));
+        state.pending_switch = Some(switch.clone());
+        Some(switch)
+    }
+
+    /// Returns the most recent provider switch that has not been reported yet.
+    pub fn take_provider_switch(&self) -> Option<ProviderSwitch> {
+        #[expect(clippy::expect_used)]
+        let mut state = self.failover.lock().expect("failover state poisoned");
+        state.pending_switch.take()
+    }
+
+    /// Key in `model_providers` of the provider currently serving requests.
+    pub fn get_provider_id(&self) -> String {
+        self.active_client().config.model_provider_id.clone()
+    }
+
+    fn active_client(&self) -> ModelClient {
+        #[expect(clippy::expect_used)]
+        let state = self.failover.lock().expect("failover state poisoned");
+        match &state.active {
+            Some((_, client)) => client.clone(),
+            None => self.clone(),
+        }
+    }
+
+    fn fallback_client(&self, fallback: &ModelFallback) -> Option<(ModelClient, ProviderSwitch)> {
+        let provider = self.config.model_providers.get(&fallback.provider)?.clone();
+        let mut config = (*self.config).clone();
+        if let Some(model) = &fallback.model {
+            let model_family =
+                find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+            config.model_context_window =
+                get_model_info(&model_family).map(|info| info.context_window);
+            config.model = model.clone();
+            config.model_family = model_family;
+        }
+        config.model_provider_id = fallback.provider.clone();
+        config.model_provider = provider.clone();
+        // Only providers that accept OpenAI credentials may see them; other
+        // providers authenticate with their own `env_key`.
+        let auth_manager = if provider.requires_openai_auth {
+            self.auth_manager.clone()
+        } else {
+            None
+        };
+
+        let switch = ProviderSwitch {
+            provider_id: fallback.provider.clone(),
+            provider_name: provider.name.clone(),
+            model: config.model.clone(),
+        };
+        let otel_event_manager = self
+            .otel_event_manager
+            .clone()
+            .with_model(config.model.as_str(), config.model_family.slug.as_str());
+        let client = ModelClient::new(
+            Arc::new(config),
+            auth_manager,
+            otel_event_manager,
+            provider,
+            self.effort,
+            self.summary,
+            self.conversation_id,
+            self.session_source.clone(),
+        );
+        Some((client, switch))
+    }
+
+    async fn stream_with_active_provider(&self, prompt: &Prompt) -> Result<ResponseStream> {
         match self.provider.wire_api {
             WireApi::Responses => self.stream_responses(prompt).await,
             WireApi::Chat => {
@@ -180,6 +297,17 @@ impl ModelClient {
 
                 Ok(ResponseStream { rx_event: rx })
             }
+            WireApi::Messages => {
+                stream_messages(
+                    prompt,
+                    &self.config,
+                    self.effort,
+                    &self.client,
+                    &self.provider,
+                    &self.otel_event_manager,
+                )
+                .await
+            }
         }
     }
 
@@ -251,6 +379,7 @@ impl ModelClient {
             include,
             prompt_cache_key: Some(self.conversation_id.to_string()),
             text,
+            max_output_tokens: prompt.max_output_tokens,
         };
 
         let mut payload_json = serde_json::to_value(&payload)?;
@@ -465,8 +594,9 @@ impl ModelClient {
         }
     }
 
+    /// Returns the provider currently serving requests.
     pub fn get_provider(&self) -> ModelProviderInfo {
-        self.provider.clone()
+        self.active_client().provider
     }
 
     pub fn get_otel_event_manager(&self) -> OtelEventManager {
@@ -477,14 +607,14 @@ impl ModelClient {
         self.session_source.clone()
     }
 
-    /// Returns the currently configured model slug.
+    /// Returns the model slug of the provider currently serving requests.
     pub fn get_model(&self) -> String {
-        self.config.model.clone()
+        self.active_client().config.model.clone()
     }
 
-    /// Returns the currently configured model family.
+    /// Returns the model family of the provider currently serving requests.
     pub fn get_model_family(&self) -> ModelFamily {
-        self.config.model_family.clone()
+        self.active_client().config.model_family.clone()
     }
 
     /// Returns the current reasoning effort setting.
@@ -502,6 +632,39 @@ impl ModelClient {
     }
 }
 
+/// Fallback state shared by the clones of a [`ModelClient`].
+#[derive(Debug, Default)]
+struct FailoverState {
+    /// Index into `model_fallbacks` and the client serving requests, or
+    /// `None` while the primary provider is in use.
+    active: Option<(usize, ModelClient)>,
+    pending_switch: Option<ProviderSwitch>,
+}
+
+/// Describes a switch to a fallback provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderSwitch {
+    /// Key in `model_providers`.
+    pub provider_id: String,
+    /// Display name of the provider.
+    pub provider_name: String,
+    pub model: String,
+}
+
+/// Errors that mean the provider is unavailable rather than that the request
+/// itself is bad, so another provider may succeed.
+fn is_failover_error(err: &CodexErr) -> bool {
+    match err {
+        CodexErr::RetryLimit(_) | CodexErr::ConnectionFailed(_) | CodexErr::InternalServerError => {
+            true
+        }
+        CodexErr::UnexpectedStatus(err) => {
+            err.status.is_server_error() || err.status == StatusCode::TOO_MANY_REQUESTS
+        }
+        _ => false,
+    }
+}
+
 enum StreamAttemptError {
     RetryableHttpError {
         status: StatusCode,
@@ -3,6 +3,7 @@ use crate::error::Result;
 use crate::model_family::ModelFamily;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
+use crate::sandboxing::network::BlockedConnection;
 use codex_apply_patch::APPLY_PATCH_TOOL_INSTRUCTIONS;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -46,6 +47,9 @@ pub struct Prompt {
 
     /// Optional the output schema for the model's response.
     pub output_schema: Option<Value>,
+
+    /// Optional cap on the number of tokens the model may generate.
+    pub(crate) max_output_tokens: Option<i64>,
 }
 
 impl Prompt {
@@ -149,6 +153,8 @@ struct ExecOutputJson {
 struct ExecOutputMetadataJson {
     exit_code: i32,
     duration_seconds: f32,
+    #[serde(default)]
+    blocked_network_connections: Vec<BlockedConnection>,
 }
 
 fn parse_structured_shell_output(raw: &str) -> Option<String> {
@@ -163,6 +169,18 @@ fn build_structured_output(parsed: &ExecOutputJson) -> String {
         "Wall time: {} seconds",
         parsed.metadata.duration_seconds
     ));
+    if !parsed.metadata.blocked_network_connections.is_empty() {
+        let blocked: Vec<String> = parsed
+            .metadata
+            .blocked_network_connections
+            .iter()
+            .map(|connection| format!("{}:{}", connection.host, connection.port))
+            .collect();
+        sections.push(format!(
+            "Blocked network connections: {}",
+            blocked.join(", ")
+        ));
+    }
 
     let mut output = parsed.output.clone();
     if let Some(total_lines) = extract_total_output_lines(&parsed.output) {
@@ -281,6 +299,8 @@ pub(crate) struct ResponsesApiRequest<'a> {
     pub(crate) prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) text: Option<TextControls>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_output_tokens: Option<i64>,
 }
 
 pub(crate) mod tools {
@@ -471,6 +491,7 @@ mod tests {
                 verbosity: Some(OpenAiVerbosity::Low),
                 format: None,
             }),
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -509,6 +530,7 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: Some(text_controls),
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -545,9 +567,30 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
         assert!(v.get("text").is_none());
     }
+
+    #[test]
+    fn structured_shell_output_lists_blocked_network_connections() {
+        let raw = serde_json::json!({
+            "output": "npm ERR! 403 Forbidden",
+            "metadata": {
+                "exit_code": 1,
+                "duration_seconds": 0.5,
+                "blocked_network_connections": [
+                    {"host": "registry.npmjs.org", "port": 443}
+                ],
+            },
+        })
+        .to_string();
+
+        assert_eq!(
+            parse_structured_shell_output(&raw).expect("structured output"),
+            "Exit code: 1\nWall time: 0.5 seconds\nBlocked network connections: registry.npmjs.org:443\nOutput:\nnpm ERR! 403 Forbidden"
+        );
+    }
 }
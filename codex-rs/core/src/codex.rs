@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
+use crate::approval_rules;
+use crate::approval_rules::ApprovalRulesCache;
 use crate::client_common::REVIEW_PROMPT;
 use crate::compact;
 use crate::features::Feature;
@@ -23,6 +26,7 @@ use codex_protocol::ConversationId;
 use codex_protocol::items::TurnItem;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
+use codex_protocol::protocol::HookTrigger;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
@@ -65,18 +69,29 @@ use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 #[cfg(test)]
 use crate::exec::StreamOutput;
+use crate::exec_policy::default_exec_policy;
+use crate::exec_policy::load_exec_policy;
+use crate::hooks;
+use crate::hooks::HookInput;
+use crate::hooks::HookResult;
+use crate::hooks::HookRunner;
+use crate::hooks::MAX_TURN_END_HOOK_CONTINUATIONS;
 // Removed: legacy executor wiring replaced by ToolOrchestrator flows.
 // legacy normalize_exec_result no longer used after orchestrator migration
 use crate::compact::build_compacted_history;
 use crate::compact::collect_user_messages;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::workspace_roots;
+use crate::mcp_sampling;
+use crate::mcp_sampling::SamplingApprovals;
 use crate::model_family::find_family_for_model;
 use crate::openai_model_info::get_model_info;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
+use crate::protocol::ApprovalRule;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::DeprecationNoticeEvent;
@@ -97,8 +112,11 @@ use crate::protocol::Submission;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
+use crate::safety::get_platform_sandbox;
+use crate::sandboxing::unenforced_policy_warnings;
 use crate::shell;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
@@ -291,6 +309,18 @@ impl TurnContext {
             .as_deref()
             .unwrap_or(compact::SUMMARIZATION_PROMPT)
     }
+
+    pub(crate) fn to_turn_context_item(&self) -> TurnContextItem {
+        TurnContextItem {
+            cwd: self.cwd.clone(),
+            approval_policy: self.approval_policy,
+            sandbox_policy: self.sandbox_policy.clone(),
+            model: self.client.get_model(),
+            model_provider: Some(self.client.get_provider_id()),
+            effort: self.client.get_reasoning_effort(),
+            summary: self.client.get_reasoning_summary(),
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -484,9 +514,16 @@ impl Session {
         // - load history metadata
         let rollout_fut = RolloutRecorder::new(&config, rollout_params);
 
+        let (tx_sampling, rx_sampling) = async_channel::unbounded();
         let mcp_fut = McpConnectionManager::new(
             config.mcp_servers.clone(),
             config.mcp_oauth_credentials_store_mode,
+            tx_event.clone(),
+            tx_sampling,
+            workspace_roots(
+                &session_configuration.cwd,
+                &session_configuration.sandbox_policy,
+            ),
         );
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
@@ -561,6 +598,14 @@ impl Session {
             });
         }
 
+        for message in unenforced_policy_warnings(&config.sandbox_policy, get_platform_sandbox()) {
+            warn!("{message}");
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent { message }),
+            });
+        }
+
         let otel_event_manager = OtelEventManager::new(
             conversation_id,
             config.model.as_str(),
@@ -585,19 +630,39 @@ impl Session {
             config.active_profile.clone(),
         );
 
+        let exec_policy = match load_exec_policy(&config.execpolicy_files) {
+            Ok(exec_policy) => exec_policy,
+            Err(err) => {
+                let message = format!(
+                    "{err}. Falling back to the built-in execpolicy; rules from execpolicy_files are not being enforced."
+                );
+                error!("{message}");
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Error(ErrorEvent { message }),
+                });
+                default_exec_policy()
+            }
+        };
+
         // Create the mutable state for the Session.
         let state = SessionState::new(session_configuration.clone());
 
         let services = SessionServices {
             mcp_connection_manager,
+            mcp_sampling_approvals: SamplingApprovals::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(config.notify.clone()),
+            hooks: HookRunner::new(config.hooks.clone()),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy,
+            codex_home: config.codex_home.clone(),
+            approval_rules: Mutex::new(ApprovalRulesCache::default()),
         };
 
         let sess = Arc::new(Session {
@@ -608,6 +673,10 @@ impl Session {
             services,
             next_internal_sub_id: AtomicU64::new(0),
         });
+        tokio::spawn(mcp_sampling::serve_sampling_requests(
+            Arc::downgrade(&sess),
+            rx_sampling,
+        ));
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
@@ -692,9 +761,24 @@ impl Session {
     }
 
     pub(crate) async fn update_settings(&self, updates: SessionSettingsUpdate) {
-        let mut state = self.state.lock().await;
+        let session_configuration = {
+            let mut state = self.state.lock().await;
+            state.session_configuration = state.session_configuration.apply(&updates);
+            state.session_configuration.clone()
+        };
+        self.sync_mcp_roots(&session_configuration).await;
+    }
 
-        state.session_configuration = state.session_configuration.apply(&updates);
+    /// Keep the roots reported to MCP servers in line with the session's cwd
+    /// and writable roots.
+    async fn sync_mcp_roots(&self, session_configuration: &SessionConfiguration) {
+        self.services
+            .mcp_connection_manager
+            .update_roots(workspace_roots(
+                &session_configuration.cwd,
+                &session_configuration.sandbox_policy,
+            ))
+            .await;
     }
 
     pub(crate) async fn new_turn(&self, updates: SessionSettingsUpdate) -> Arc<TurnContext> {
@@ -713,6 +797,8 @@ impl Session {
             state.session_configuration = session_configuration.clone();
             session_configuration
         };
+        self.sync_mcp_roots(&session_configuration).await;
+        self.services.mcp_connection_manager.refresh().await;
 
         let mut turn_context: TurnContext = Self::make_turn_context(
             Some(Arc::clone(&self.services.auth_manager)),
@@ -728,6 +814,21 @@ impl Session {
         Arc::new(turn_context)
     }
 
+    /// Build a turn context from the current settings without applying any
+    /// updates or refreshing MCP servers. Used for requests made on behalf of
+    /// an MCP server, which may be in the middle of a tool call.
+    pub(crate) async fn current_turn_context(&self, sub_id: String) -> Arc<TurnContext> {
+        let session_configuration = self.state.lock().await.session_configuration.clone();
+        Arc::new(Self::make_turn_context(
+            Some(Arc::clone(&self.services.auth_manager)),
+            &self.services.otel_event_manager,
+            session_configuration.provider.clone(),
+            &session_configuration,
+            self.conversation_id,
+            sub_id,
+        ))
+    }
+
     fn build_environment_update_item(
         &self,
         previous: Option<&Arc<TurnContext>>,
@@ -927,6 +1028,82 @@ impl Session {
         }
     }
 
+    /// Saves `rule` for the session's current project so matching commands
+    /// are approved without prompting from now on.
+    async fn persist_approval_rule(&self, rule: ApprovalRule) {
+        let cwd = {
+            let state = self.state.lock().await;
+            state.session_configuration.cwd.clone()
+        };
+        let codex_home = self.services.codex_home.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let project = approval_rules::project_root(&cwd);
+            approval_rules::add_rule(&codex_home, &project, rule.clone()).map(|_| (project, rule))
+        })
+        .await;
+        let err = match result {
+            Ok(Ok((project, rule))) => {
+                self.services
+                    .approval_rules
+                    .lock()
+                    .await
+                    .add(&project, rule);
+                return;
+            }
+            Ok(Err(err)) => err.to_string(),
+            Err(err) => err.to_string(),
+        };
+        let message = format!("Failed to save approval rule: {err}");
+        warn!("{message}");
+        self.send_event_raw(Event {
+            id: self.next_internal_sub_id(),
+            msg: EventMsg::Warning(WarningEvent { message }),
+        })
+        .await;
+    }
+
+    /// Returns `true` when the rules persisted for the project containing
+    /// `cwd` approve `command`. Rules are loaded once per project and then
+    /// served from the session's cache.
+    pub(crate) async fn is_approved_by_persisted_rule(
+        &self,
+        command: &[String],
+        cwd: &Path,
+    ) -> bool {
+        if let Some(rules) = self.services.approval_rules.lock().await.rules_for(cwd) {
+            return crate::safety::is_command_approved_by_rules(command, rules);
+        }
+
+        let codex_home = self.services.codex_home.clone();
+        let cwd = cwd.to_path_buf();
+        let loaded = tokio::task::spawn_blocking(move || {
+            let project = approval_rules::project_root(&cwd);
+            let rules = approval_rules::load_project_rules(&codex_home, &project);
+            (cwd, project, rules)
+        })
+        .await;
+        let (cwd, project, rules) = match loaded {
+            Ok((cwd, project, Ok(rules))) => (cwd, project, rules),
+            Ok((_, project, Err(err))) => {
+                warn!(
+                    "failed to load approval rules for {}: {err}",
+                    project.display()
+                );
+                return false;
+            }
+            Err(err) => {
+                warn!("failed to load approval rules: {err}");
+                return false;
+            }
+        };
+
+        let mut cache = self.services.approval_rules.lock().await;
+        cache.insert(cwd.clone(), project, rules);
+        cache
+            .rules_for(&cwd)
+            .is_some_and(|rules| crate::safety::is_command_approved_by_rules(command, rules))
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     pub(crate) async fn record_conversation_items(
@@ -1053,6 +1230,23 @@ impl Session {
         self.send_token_count_event(turn_context).await;
     }
 
+    /// Account tokens spent on a request that is not part of the
+    /// conversation, such as an MCP sampling request.
+    pub(crate) async fn add_out_of_band_token_usage(
+        &self,
+        turn_context: &TurnContext,
+        token_usage: &TokenUsage,
+    ) {
+        {
+            let mut state = self.state.lock().await;
+            state.add_out_of_band_token_usage(
+                token_usage,
+                turn_context.client.get_model_context_window(),
+            );
+        }
+        self.send_token_count_event(turn_context).await;
+    }
+
     pub(crate) async fn update_rate_limits(
         &self,
         turn_context: &TurnContext,
@@ -1117,6 +1311,22 @@ impl Session {
         self.send_event(turn_context, event).await;
     }
 
+    /// Surfaces a switch to a fallback provider made while starting the
+    /// turn's model request and records the new provider in the rollout.
+    pub(crate) async fn report_provider_switch(&self, turn_context: &TurnContext) {
+        let Some(switch) = turn_context.client.take_provider_switch() else {
+            return;
+        };
+        let message = format!(
+            "Model provider unavailable; switched to fallback provider {} (`{}`) using model `{}`.",
+            switch.provider_name, switch.provider_id, switch.model
+        );
+        self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+        let rollout_item = RolloutItem::TurnContext(turn_context.to_turn_context_item());
+        self.persist_rollout_items(&[rollout_item]).await;
+    }
+
     pub(crate) async fn notify_stream_error(
         &self,
         turn_context: &TurnContext,
@@ -1241,12 +1451,80 @@ impl Session {
     pub async fn interrupt_task(self: &Arc<Self>) {
         info!("interrupt received: abort current task, if any");
         self.abort_all_tasks(TurnAbortReason::Interrupted).await;
+        // MCP servers may still be waiting on a prompt from the aborted turn.
+        self.services
+            .mcp_connection_manager
+            .cancel_elicitations()
+            .await;
     }
 
     pub(crate) fn notifier(&self) -> &UserNotifier {
         &self.services.notifier
     }
 
+    pub(crate) fn has_hooks(&self, trigger: HookTrigger, tool_name: Option<&str>) -> bool {
+        self.services.hooks.has_hooks(trigger, tool_name)
+    }
+
+    pub(crate) fn hook_input(&self, turn_context: &TurnContext, trigger: HookTrigger) -> HookInput {
+        hooks::hook_input(
+            trigger,
+            self.conversation_id.to_string(),
+            turn_context.sub_id.clone(),
+            &turn_context.cwd,
+        )
+    }
+
+    /// Runs the hooks configured for `input.event` and reports each one as a
+    /// `HookCompleted` event so that the results land in the rollout.
+    pub(crate) async fn run_hooks(
+        &self,
+        turn_context: &TurnContext,
+        input: HookInput,
+    ) -> HookResult {
+        let result = self.services.hooks.run(input).await;
+        for event in &result.events {
+            self.send_event(turn_context, EventMsg::HookCompleted(event.clone()))
+                .await;
+        }
+        result
+    }
+
+    /// Queues hook messages to be sent with the next model request of the
+    /// running turn, or records them right away when no turn is running.
+    pub(crate) async fn queue_hook_messages(
+        &self,
+        turn_context: &TurnContext,
+        messages: &[String],
+    ) {
+        if messages.is_empty() {
+            return;
+        }
+        let input = messages
+            .iter()
+            .map(|message| hooks::hook_message_input(message))
+            .collect();
+        if self.inject_input(input).await.is_err() {
+            self.record_hook_messages(turn_context, messages).await;
+        }
+    }
+
+    /// Adds hook messages to the conversation history.
+    pub(crate) async fn record_hook_messages(
+        &self,
+        turn_context: &TurnContext,
+        messages: &[String],
+    ) {
+        if messages.is_empty() {
+            return;
+        }
+        let items: Vec<ResponseItem> = messages
+            .iter()
+            .map(|message| hooks::hook_message_item(message))
+            .collect();
+        self.record_conversation_items(turn_context, &items).await;
+    }
+
     pub(crate) fn user_shell(&self) -> &shell::Shell {
         &self.services.user_shell
     }
@@ -1291,12 +1569,32 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 handlers::user_input_or_turn(&sess, sub.id.clone(), sub.op, &mut previous_context)
                     .await;
             }
-            Op::ExecApproval { id, decision } => {
-                handlers::exec_approval(&sess, id, decision).await;
+            Op::ExecApproval {
+                id,
+                decision,
+                persist_rule,
+            } => {
+                handlers::exec_approval(&sess, id, decision, persist_rule).await;
             }
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::ResolveElicitation {
+                server_name,
+                request_id,
+                decision,
+                content,
+            } => {
+                handlers::resolve_elicitation(&sess, server_name, request_id, decision, content)
+                    .await;
+            }
+            Op::McpSamplingApproval {
+                server_name,
+                request_id,
+                decision,
+            } => {
+                handlers::mcp_sampling_approval(&sess, server_name, request_id, decision).await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1310,6 +1608,23 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListMcpPrompts => {
+                handlers::list_mcp_prompts(&sess, sub.id.clone()).await;
+            }
+            Op::GetMcpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                handlers::get_mcp_prompt(&sess, sub.id.clone(), server, name, arguments).await;
+            }
+            Op::RestartMcpServer { server } => {
+                handlers::restart_mcp_server(&sess, &config, sub.id.clone(), server).await;
+            }
+            Op::SetMcpServerEnabled { server, enabled } => {
+                handlers::set_mcp_server_enabled(&sess, &config, sub.id.clone(), server, enabled)
+                    .await;
+            }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
@@ -1347,21 +1662,32 @@ mod handlers {
 
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
+    use crate::config::load_global_mcp_servers;
+    use crate::config::types::McpServerConfig;
     use crate::mcp::auth::compute_auth_statuses;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::ApprovalRule;
+    use codex_protocol::protocol::ElicitationAction;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::McpGetPromptResponseEvent;
+    use codex_protocol::protocol::McpListPromptsResponseEvent;
+    use codex_protocol::protocol::McpServerUpdate;
+    use codex_protocol::protocol::McpServerUpdatedEvent;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::TurnAbortReason;
     use codex_protocol::user_input::UserInput;
+    use mcp_types::GetPromptRequestParams;
+    use mcp_types::RequestId;
+    use serde_json::Value;
     use std::sync::Arc;
     use tracing::info;
     use tracing::warn;
@@ -1445,12 +1771,27 @@ mod handlers {
         *previous_context = Some(turn_context);
     }
 
-    pub async fn exec_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+    pub async fn exec_approval(
+        sess: &Arc<Session>,
+        id: String,
+        decision: ReviewDecision,
+        persist_rule: Option<ApprovalRule>,
+    ) {
         match decision {
             ReviewDecision::Abort => {
                 sess.interrupt_task().await;
             }
-            other => sess.notify_approval(&id, other).await,
+            other => {
+                if let Some(rule) = persist_rule
+                    && matches!(
+                        other,
+                        ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+                    )
+                {
+                    sess.persist_approval_rule(rule).await;
+                }
+                sess.notify_approval(&id, other).await
+            }
         }
     }
 
@@ -1463,6 +1804,39 @@ mod handlers {
         }
     }
 
+    pub async fn resolve_elicitation(
+        sess: &Arc<Session>,
+        server_name: String,
+        request_id: RequestId,
+        decision: ElicitationAction,
+        content: Option<Value>,
+    ) {
+        if let Err(err) = sess
+            .services
+            .mcp_connection_manager
+            .resolve_elicitation(server_name, request_id, decision, content)
+            .await
+        {
+            warn!("failed to resolve MCP elicitation: {err:#}");
+        }
+    }
+
+    pub async fn mcp_sampling_approval(
+        sess: &Arc<Session>,
+        server_name: String,
+        request_id: RequestId,
+        decision: ReviewDecision,
+    ) {
+        if let Err(err) = sess
+            .services
+            .mcp_sampling_approvals
+            .resolve(server_name, request_id, decision)
+            .await
+        {
+            warn!("failed to resolve MCP sampling approval: {err:#}");
+        }
+    }
+
     pub async fn add_to_history(sess: &Arc<Session>, config: &Arc<Config>, text: String) {
         let id = sess.conversation_id;
         let config = Arc::clone(config);
@@ -1527,6 +1901,10 @@ mod handlers {
             .iter()
             .map(|(name, entry)| (name.clone(), entry.auth_status))
             .collect();
+        let auth_expirations = auth_status_entries
+            .iter()
+            .filter_map(|(name, entry)| Some((name.clone(), entry.expires_at?)))
+            .collect();
         let event = Event {
             id: sub_id,
             msg: EventMsg::McpListToolsResponse(crate::protocol::McpListToolsResponseEvent {
@@ -1534,6 +1912,7 @@ mod handlers {
                 resources,
                 resource_templates,
                 auth_statuses,
+                auth_expirations,
             }),
         };
         sess.send_event_raw(event).await;
@@ -1556,6 +1935,121 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn list_mcp_prompts(sess: &Session, sub_id: String) {
+        let prompts = sess
+            .services
+            .mcp_connection_manager
+            .list_all_prompts()
+            .await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpListPromptsResponse(McpListPromptsResponseEvent { prompts }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn get_mcp_prompt(
+        sess: &Session,
+        sub_id: String,
+        server: String,
+        name: String,
+        arguments: Option<Value>,
+    ) {
+        let params = GetPromptRequestParams {
+            arguments,
+            name: name.clone(),
+        };
+        let result = sess
+            .services
+            .mcp_connection_manager
+            .get_prompt(&server, params)
+            .await
+            .map_err(|err| format!("{err:#}"));
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpGetPromptResponse(McpGetPromptResponseEvent {
+                server,
+                name,
+                result,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn restart_mcp_server(
+        sess: &Session,
+        config: &Arc<Config>,
+        sub_id: String,
+        server: String,
+    ) {
+        let server_config = reload_mcp_server_config(config, &server).await;
+        let result = sess
+            .services
+            .mcp_connection_manager
+            .restart_server(&server, server_config)
+            .await;
+        send_mcp_server_updated(sess, sub_id, server, McpServerUpdate::Restarted, result).await;
+    }
+
+    pub async fn set_mcp_server_enabled(
+        sess: &Session,
+        config: &Arc<Config>,
+        sub_id: String,
+        server: String,
+        enabled: bool,
+    ) {
+        let server_config = if enabled {
+            reload_mcp_server_config(config, &server).await
+        } else {
+            None
+        };
+        let result = sess
+            .services
+            .mcp_connection_manager
+            .set_server_enabled(&server, enabled, server_config)
+            .await;
+        let update = if enabled {
+            McpServerUpdate::Enabled
+        } else {
+            McpServerUpdate::Disabled
+        };
+        send_mcp_server_updated(sess, sub_id, server, update, result).await;
+    }
+
+    /// Read the server's current entry from `config.toml` so that edits made
+    /// since the session started take effect. Falls back to the
+    /// configuration the session started with.
+    async fn reload_mcp_server_config(config: &Config, server: &str) -> Option<McpServerConfig> {
+        match load_global_mcp_servers(&config.codex_home).await {
+            Ok(mut servers) => servers.remove(server),
+            Err(err) => {
+                warn!("failed to reload MCP server config for `{server}`: {err}");
+                None
+            }
+        }
+    }
+
+    async fn send_mcp_server_updated(
+        sess: &Session,
+        sub_id: String,
+        server: String,
+        update: McpServerUpdate,
+        result: anyhow::Result<usize>,
+    ) {
+        if let Err(err) = &result {
+            warn!("MCP server `{server}` could not be {update}: {err:#}");
+        }
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpServerUpdated(McpServerUpdatedEvent {
+                server,
+                update,
+                result: result.map_err(|err| format!("{err:#}")),
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1745,9 +2239,32 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    let input_messages: Vec<String> = input
+        .iter()
+        .filter_map(|item| match item {
+            UserInput::Text { text } => Some(text.clone()),
+            _ => None,
+        })
+        .collect();
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
+
+    // Turn-start hooks run before the user message is recorded, so a blocked
+    // turn leaves no trace of its input in the conversation.
+    let mut turn_start_messages = Vec::new();
+    if sess.has_hooks(HookTrigger::TurnStart, None) {
+        let mut hook_input = sess.hook_input(&turn_context, HookTrigger::TurnStart);
+        hook_input.input_messages = input_messages;
+        let result = sess.run_hooks(&turn_context, hook_input).await;
+        if result.blocked.is_some() {
+            return None;
+        }
+        turn_start_messages = result.messages;
+    }
+
     sess.record_input_and_rollout_usermsg(turn_context.as_ref(), &initial_input_for_turn)
         .await;
+    sess.record_hook_messages(&turn_context, &turn_start_messages)
+        .await;
 
     sess.maybe_start_ghost_snapshot(Arc::clone(&turn_context), cancellation_token.child_token())
         .await;
@@ -1756,6 +2273,7 @@ pub(crate) async fn run_task(
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
     let mut auto_compact_recently_attempted = false;
+    let mut turn_end_hook_continuations = 0;
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -1835,6 +2353,37 @@ pub(crate) async fn run_task(
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
+                    if sess.has_hooks(HookTrigger::TurnEnd, None) {
+                        let mut hook_input = sess.hook_input(&turn_context, HookTrigger::TurnEnd);
+                        hook_input.input_messages = turn_input_messages.clone();
+                        hook_input.last_assistant_message = last_agent_message.clone();
+                        let result = sess.run_hooks(&turn_context, hook_input).await;
+                        let mut messages = result.messages;
+                        // A blocking turn-end hook sends the model back to
+                        // work with the reason as its next instruction, up to
+                        // a limit so a hook that always blocks cannot keep the
+                        // turn running forever.
+                        let continue_turn = result.blocked.is_some()
+                            && turn_end_hook_continuations < MAX_TURN_END_HOOK_CONTINUATIONS;
+                        let exhausted = result.blocked.is_some() && !continue_turn;
+                        if continue_turn {
+                            messages.extend(result.blocked);
+                        }
+                        sess.record_hook_messages(&turn_context, &messages).await;
+                        if continue_turn {
+                            turn_end_hook_continuations += 1;
+                            continue;
+                        }
+                        if exhausted {
+                            let event = EventMsg::Error(ErrorEvent {
+                                message: format!(
+                                    "Turn-end hooks kept blocking after {MAX_TURN_END_HOOK_CONTINUATIONS} continuations; ending the turn."
+                                ),
+                            });
+                            sess.send_event(&turn_context, event).await;
+                            break;
+                        }
+                    }
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             thread_id: sess.conversation_id.to_string(),
@@ -1893,6 +2442,7 @@ async fn run_turn(
         parallel_tool_calls,
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
+        max_output_tokens: None,
     };
 
     let mut retries = 0;
@@ -1988,14 +2538,7 @@ async fn try_run_turn(
     prompt: &Prompt,
     cancellation_token: CancellationToken,
 ) -> CodexResult<TurnRunResult> {
-    let rollout_item = RolloutItem::TurnContext(TurnContextItem {
-        cwd: turn_context.cwd.clone(),
-        approval_policy: turn_context.approval_policy,
-        sandbox_policy: turn_context.sandbox_policy.clone(),
-        model: turn_context.client.get_model(),
-        effort: turn_context.client.get_reasoning_effort(),
-        summary: turn_context.client.get_reasoning_summary(),
-    });
+    let rollout_item = RolloutItem::TurnContext(turn_context.to_turn_context_item());
 
     sess.persist_rollout_items(&[rollout_item]).await;
     let mut stream = turn_context
@@ -2004,6 +2547,7 @@ async fn try_run_turn(
         .stream(prompt)
         .or_cancel(&cancellation_token)
         .await??;
+    sess.report_provider_switch(&turn_context).await;
 
     let tool_runtime = ToolCallRuntime::new(
         Arc::clone(&router),
@@ -2419,6 +2963,7 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            blocked_network_connections: Vec::new(),
         };
 
         let out = format_exec_output_str(&exec);
@@ -2544,14 +3089,19 @@ mod tests {
 
         let services = SessionServices {
             mcp_connection_manager: McpConnectionManager::default(),
+            mcp_sampling_approvals: SamplingApprovals::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy: default_exec_policy(),
+            codex_home: config.codex_home.clone(),
+            approval_rules: Mutex::new(ApprovalRulesCache::default()),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2620,14 +3170,19 @@ mod tests {
 
         let services = SessionServices {
             mcp_connection_manager: McpConnectionManager::default(),
+            mcp_sampling_approvals: SamplingApprovals::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
             auth_manager: Arc::clone(&auth_manager),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            exec_policy: default_exec_policy(),
+            codex_home: config.codex_home.clone(),
+            approval_rules: Mutex::new(ApprovalRulesCache::default()),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -3121,8 +3676,10 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
+            expires_at: None,
         };
         let err = anyhow::anyhow!("OAuth is unsupported");
 
@@ -3165,8 +3722,10 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
+            expires_at: None,
         };
         let err = anyhow::anyhow!("boom");
 
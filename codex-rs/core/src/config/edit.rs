@@ -155,6 +155,9 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        if let Some(sampling) = config.sampling {
+            entry["sampling"] = value(sampling.as_str());
+        }
 
         TomlItem::Table(entry)
     }
@@ -176,6 +179,50 @@ mod document_helpers {
         table
     }
 
+    /// Convert a parsed TOML value into a document item. Tables become
+    /// explicit tables; tables nested inside arrays stay inline.
+    pub(super) fn item_from_toml(value: &toml::Value) -> TomlItem {
+        match value {
+            toml::Value::Table(entries) => {
+                let mut table = TomlTable::new();
+                table.set_implicit(false);
+                for (key, entry) in entries {
+                    table.insert(key, item_from_toml(entry));
+                }
+                TomlItem::Table(table)
+            }
+            other => TomlItem::Value(value_from_toml(other)),
+        }
+    }
+
+    fn value_from_toml(value: &toml::Value) -> toml_edit::Value {
+        match value {
+            toml::Value::String(text) => text.clone().into(),
+            toml::Value::Integer(number) => (*number).into(),
+            toml::Value::Float(number) => (*number).into(),
+            toml::Value::Boolean(flag) => (*flag).into(),
+            toml::Value::Datetime(datetime) => datetime
+                .to_string()
+                .parse::<toml_edit::Datetime>()
+                .map(Into::into)
+                .unwrap_or_else(|_| datetime.to_string().into()),
+            toml::Value::Array(values) => {
+                let mut array = TomlArray::new();
+                for entry in values {
+                    array.push(value_from_toml(entry));
+                }
+                array.into()
+            }
+            toml::Value::Table(entries) => {
+                let mut table = InlineTable::new();
+                for (key, entry) in entries {
+                    table.insert(key, value_from_toml(entry));
+                }
+                table.into()
+            }
+        }
+    }
+
     fn array_from_iter<I>(iter: I) -> TomlItem
     where
         I: Iterator<Item = String>,
@@ -379,11 +426,26 @@ impl ConfigDocument {
     }
 }
 
+/// Returned when `config.toml` no longer holds the contents passed to
+/// [`ConfigEditsBuilder::if_unchanged`].
+#[derive(Debug, thiserror::Error)]
+#[error("config.toml changed since it was read")]
+pub struct ConfigChangedError;
+
 /// Persist edits using a blocking strategy.
 pub fn apply_blocking(
     codex_home: &Path,
     profile: Option<&str>,
     edits: &[ConfigEdit],
+) -> anyhow::Result<()> {
+    apply_blocking_if_unchanged(codex_home, profile, edits, None)
+}
+
+fn apply_blocking_if_unchanged(
+    codex_home: &Path,
+    profile: Option<&str>,
+    edits: &[ConfigEdit],
+    expected_contents: Option<&str>,
 ) -> anyhow::Result<()> {
     if edits.is_empty() {
         return Ok(());
@@ -395,6 +457,9 @@ pub fn apply_blocking(
         Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
         Err(err) => return Err(err.into()),
     };
+    if expected_contents.is_some_and(|expected| expected != serialized) {
+        return Err(ConfigChangedError.into());
+    }
 
     let doc = if serialized.is_empty() {
         DocumentMut::new()
@@ -457,6 +522,7 @@ pub struct ConfigEditsBuilder {
     codex_home: PathBuf,
     profile: Option<String>,
     edits: Vec<ConfigEdit>,
+    expected_contents: Option<String>,
 }
 
 impl ConfigEditsBuilder {
@@ -465,6 +531,7 @@ impl ConfigEditsBuilder {
             codex_home: codex_home.to_path_buf(),
             profile: None,
             edits: Vec::new(),
+            expected_contents: None,
         }
     }
 
@@ -517,24 +584,54 @@ impl ConfigEditsBuilder {
         self
     }
 
+    /// Set the value at the exact dotted path; tables are written as
+    /// standard `[a.b]` tables.
+    pub fn set_path_value(mut self, segments: &[String], value: &toml::Value) -> Self {
+        self.edits.push(ConfigEdit::SetPath {
+            segments: segments.to_vec(),
+            value: document_helpers::item_from_toml(value),
+        });
+        self
+    }
+
+    /// Remove the value at the exact dotted path, if present.
+    pub fn clear_path(mut self, segments: &[String]) -> Self {
+        self.edits.push(ConfigEdit::ClearPath {
+            segments: segments.to_vec(),
+        });
+        self
+    }
+
+    /// Only apply the edits if `config.toml` still holds `contents` (empty
+    /// when the file is missing), e.g. the text they were validated against;
+    /// otherwise fail with [`ConfigChangedError`].
+    pub fn if_unchanged(mut self, contents: String) -> Self {
+        self.expected_contents = Some(contents);
+        self
+    }
+
     /// Apply edits on a blocking thread.
     pub fn apply_blocking(self) -> anyhow::Result<()> {
-        apply_blocking(&self.codex_home, self.profile.as_deref(), &self.edits)
+        apply_blocking_if_unchanged(
+            &self.codex_home,
+            self.profile.as_deref(),
+            &self.edits,
+            self.expected_contents.as_deref(),
+        )
     }
 
     /// Apply edits asynchronously via a blocking offload.
     pub async fn apply(self) -> anyhow::Result<()> {
-        task::spawn_blocking(move || {
-            apply_blocking(&self.codex_home, self.profile.as_deref(), &self.edits)
-        })
-        .await
-        .context("config persistence task panicked")?
+        task::spawn_blocking(move || self.apply_blocking())
+            .await
+            .context("config persistence task panicked")?
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::McpSamplingPolicy;
     use crate::config::types::McpServerTransportConfig;
     use codex_protocol::config_types::ReasoningEffort;
     use pretty_assertions::assert_eq;
@@ -802,6 +899,7 @@ hide_rate_limit_model_nudge = true
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -823,6 +921,7 @@ hide_rate_limit_model_nudge = true
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                sampling: Some(McpSamplingPolicy::Ask),
             },
         );
 
@@ -841,6 +940,7 @@ bearer_token_env_var = \"TOKEN\"
 enabled = false
 startup_timeout_sec = 5.0
 disabled_tools = [\"forbidden\"]
+sampling = \"ask\"
 
 [mcp_servers.http.http_headers]
 Z-Header = \"z\"
@@ -904,6 +1004,69 @@ B = \"2\"
         assert_eq!(notifications, Some(false));
     }
 
+    #[test]
+    fn blocking_builder_set_path_value_writes_tables() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            "# keep me\nmodel_provider = \"openai\"\nmodel = \"gpt-5\"\n",
+        )
+        .expect("seed");
+
+        let server: TomlValue = toml::from_str(
+            r#"
+command = "docs-server"
+args = ["--port", "4000"]
+env = { TOKEN = "abc" }
+"#,
+        )
+        .expect("server");
+        ConfigEditsBuilder::new(codex_home)
+            .set_path_value(&["mcp_servers".to_string(), "docs".to_string()], &server)
+            .clear_path(&["model".to_string()])
+            .apply_blocking()
+            .expect("apply");
+
+        let raw = std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let expected = r#"# keep me
+model_provider = "openai"
+
+[mcp_servers.docs]
+args = ["--port", "4000"]
+command = "docs-server"
+
+[mcp_servers.docs.env]
+TOKEN = "abc"
+"#;
+        assert_eq!(raw, expected);
+    }
+
+    #[test]
+    fn blocking_builder_if_unchanged_refuses_changed_file() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        let config_path = codex_home.join(CONFIG_TOML_FILE);
+        std::fs::write(&config_path, "model = \"gpt-5\"\n").expect("seed");
+
+        let err = ConfigEditsBuilder::new(codex_home)
+            .set_path_value(&["model_provider".to_string()], &"openai".into())
+            .if_unchanged("model = \"o3\"\n".to_string())
+            .apply_blocking()
+            .expect_err("contents changed");
+        assert!(err.is::<ConfigChangedError>());
+        let raw = std::fs::read_to_string(&config_path).expect("read config");
+        assert_eq!(raw, "model = \"gpt-5\"\n");
+
+        ConfigEditsBuilder::new(codex_home)
+            .set_path_value(&["model_provider".to_string()], &"openai".into())
+            .if_unchanged(raw)
+            .apply_blocking()
+            .expect("apply");
+        let raw = std::fs::read_to_string(&config_path).expect("read config");
+        assert_eq!(raw, "model = \"gpt-5\"\nmodel_provider = \"openai\"\n");
+    }
+
     #[tokio::test]
     async fn async_builder_set_model_persists() {
         let tmp = tempdir().expect("tmpdir");
@@ -1,13 +1,16 @@
 use crate::auth::AuthCredentialsStoreMode;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
 use crate::config::types::History;
+use crate::config::types::HookConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelFallback;
 use crate::config::types::Notice;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::SandboxReadOnly;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
@@ -56,6 +59,7 @@ use toml_edit::DocumentMut;
 
 pub mod edit;
 pub mod profile;
+pub mod provenance;
 pub mod types;
 
 #[cfg(target_os = "windows")]
@@ -70,7 +74,7 @@ pub const GPT_5_CODEX_MEDIUM_MODEL: &str = "gpt-5-codex";
 /// the context window.
 pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
-pub(crate) const CONFIG_TOML_FILE: &str = "config.toml";
+pub const CONFIG_TOML_FILE: &str = "config.toml";
 
 /// Application configuration loaded from disk and merged with overrides.
 #[derive(Debug, Clone, PartialEq)]
@@ -98,6 +102,10 @@ pub struct Config {
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Providers to switch to, in order, once `model_provider` has exhausted
+    /// its retries. Every entry refers to a key in `model_providers`.
+    pub model_fallbacks: Vec<ModelFallback>,
+
     /// Approval policy for executing commands.
     pub approval_policy: AskForApproval,
 
@@ -156,6 +164,11 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Commands run synchronously at points in the agent loop (before and
+    /// after tool calls, at the start and end of a turn). See
+    /// `docs/config.md#hooks` for the protocol they speak.
+    pub hooks: Vec<HookConfig>,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -258,6 +271,11 @@ pub struct Config {
     /// is (1) part of a git repo, (2) a git worktree, or (3) just using the cwd
     pub active_project: ProjectConfig,
 
+    /// Absolute paths of the `execpolicy` files merged on top of the built-in
+    /// default policy: user-level files first, then those of the active
+    /// project.
+    pub execpolicy_files: Vec<PathBuf>,
+
     /// Tracks whether the Windows onboarding screen has been acknowledged.
     pub windows_wsl_setup_acknowledged: bool,
 
@@ -514,6 +532,10 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Providers to fail over to, declared as `[[model_fallbacks]]` tables.
+    #[serde(default)]
+    pub model_fallbacks: Option<Vec<ModelFallback>>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
@@ -535,10 +557,17 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Sandbox configuration to apply if `sandbox` is `ReadOnly`.
+    pub sandbox_read_only: Option<SandboxReadOnly>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Lifecycle hooks, declared as `[[hooks]]` tables.
+    #[serde(default)]
+    pub hooks: Option<Vec<HookConfig>>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -628,6 +657,10 @@ pub struct ConfigToml {
 
     pub projects: Option<HashMap<String, ProjectConfig>>,
 
+    /// `execpolicy` files consulted before running commands. Relative paths
+    /// are resolved against `CODEX_HOME`.
+    pub execpolicy_files: Option<Vec<PathBuf>>,
+
     /// Nested tools section for feature toggles
     pub tools: Option<ToolsToml>,
 
@@ -687,6 +720,10 @@ impl From<ConfigToml> for UserSavedConfig {
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ProjectConfig {
     pub trust_level: Option<String>,
+
+    /// `execpolicy` files that apply only within this project. Relative paths
+    /// are resolved against the project root.
+    pub execpolicy_files: Option<Vec<PathBuf>>,
 }
 
 impl ProjectConfig {
@@ -746,18 +783,27 @@ impl ConfigToml {
             })
             .unwrap_or_default();
         let mut sandbox_policy = match resolved_sandbox_mode {
-            SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+            SandboxMode::ReadOnly => match self.sandbox_read_only.as_ref() {
+                Some(SandboxReadOnly { deny_read }) => SandboxPolicy::ReadOnly {
+                    deny_read: expand_home_dirs(deny_read),
+                },
+                None => SandboxPolicy::new_read_only_policy(),
+            },
             SandboxMode::WorkspaceWrite => match self.sandbox_workspace_write.as_ref() {
                 Some(SandboxWorkspaceWrite {
                     writable_roots,
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    deny_read,
+                    network_allowlist,
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
+                    deny_read: expand_home_dirs(deny_read),
+                    network_allowlist: network_allowlist.clone(),
                 },
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
@@ -769,7 +815,9 @@ impl ConfigToml {
             // If the experimental Windows sandbox is enabled, do not force a downgrade.
             && crate::safety::get_platform_sandbox().is_none()
         {
-            sandbox_policy = SandboxPolicy::new_read_only_policy();
+            sandbox_policy = SandboxPolicy::ReadOnly {
+                deny_read: sandbox_policy.get_deny_read_paths().to_vec(),
+            };
             forced_auto_mode_downgraded_on_windows = true;
         }
         SandboxPolicyResolution {
@@ -781,10 +829,20 @@ impl ConfigToml {
     /// Resolves the cwd to an existing project, or returns None if ConfigToml
     /// does not contain a project corresponding to cwd or a git repo for cwd
     pub fn get_active_project(&self, resolved_cwd: &Path) -> Option<ProjectConfig> {
+        self.get_active_project_with_root(resolved_cwd)
+            .map(|(_, project_config)| project_config)
+    }
+
+    /// Like [`ConfigToml::get_active_project`], but also returns the project
+    /// root the matching `[projects]` entry is keyed by.
+    fn get_active_project_with_root(
+        &self,
+        resolved_cwd: &Path,
+    ) -> Option<(PathBuf, ProjectConfig)> {
         let projects = self.projects.clone().unwrap_or_default();
 
         if let Some(project_config) = projects.get(&resolved_cwd.to_string_lossy().to_string()) {
-            return Some(project_config.clone());
+            return Some((resolved_cwd.to_path_buf(), project_config.clone()));
         }
 
         // If cwd lives inside a git repo/worktree, check whether the root git project
@@ -794,7 +852,7 @@ impl ConfigToml {
             && let Some(project_config_for_root) =
                 projects.get(&repo_root.to_string_lossy().to_string_lossy().to_string())
         {
-            return Some(project_config_for_root.clone());
+            return Some((repo_root, project_config_for_root.clone()));
         }
 
         None
@@ -936,9 +994,25 @@ impl Config {
                 }
             })
             .collect();
-        let active_project = cfg
-            .get_active_project(&resolved_cwd)
-            .unwrap_or(ProjectConfig { trust_level: None });
+        let (active_project_root, active_project) =
+            cfg.get_active_project_with_root(&resolved_cwd).map_or_else(
+                || {
+                    (
+                        None,
+                        ProjectConfig {
+                            trust_level: None,
+                            execpolicy_files: None,
+                        },
+                    )
+                },
+                |(root, project)| (Some(root), project),
+            );
+        let execpolicy_files = resolve_execpolicy_files(
+            cfg.execpolicy_files.as_deref(),
+            &codex_home,
+            active_project_root.as_deref(),
+            active_project.execpolicy_files.as_deref(),
+        );
 
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
@@ -990,6 +1064,23 @@ impl Config {
             })?
             .clone();
 
+        let model_fallbacks = config_profile
+            .model_fallbacks
+            .or(cfg.model_fallbacks)
+            .unwrap_or_default();
+        if let Some(fallback) = model_fallbacks
+            .iter()
+            .find(|fallback| !model_providers.contains_key(&fallback.provider))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "Model provider `{}` listed in model_fallbacks not found",
+                    fallback.provider
+                ),
+            ));
+        }
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let history = cfg.history.unwrap_or_default();
@@ -1092,6 +1183,7 @@ impl Config {
             model_auto_compact_token_limit,
             model_provider_id,
             model_provider,
+            model_fallbacks,
             cwd: resolved_cwd,
             approval_policy,
             sandbox_policy,
@@ -1099,6 +1191,7 @@ impl Config {
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            hooks: cfg.hooks.unwrap_or_default(),
             user_instructions,
             base_instructions,
             developer_instructions,
@@ -1157,6 +1250,7 @@ impl Config {
             features,
             active_profile: active_profile_name,
             active_project,
+            execpolicy_files,
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
             notices: cfg.notice.unwrap_or_default(),
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
@@ -1239,6 +1333,30 @@ fn default_review_model() -> String {
     OPENAI_DEFAULT_REVIEW_MODEL.to_string()
 }
 
+/// Resolves the user-level `execpolicy_files` against `codex_home` and the
+/// project-level ones against the project root, user-level files first so
+/// that project policies take precedence when merged.
+fn resolve_execpolicy_files(
+    user_files: Option<&[PathBuf]>,
+    codex_home: &Path,
+    project_root: Option<&Path>,
+    project_files: Option<&[PathBuf]>,
+) -> Vec<PathBuf> {
+    let user_files = user_files
+        .unwrap_or_default()
+        .iter()
+        .map(|path| codex_home.join(path));
+    let project_files: Vec<PathBuf> = match project_root {
+        Some(root) => project_files
+            .unwrap_or_default()
+            .iter()
+            .map(|path| root.join(path))
+            .collect(),
+        None => Vec::new(),
+    };
+    user_files.chain(project_files).collect()
+}
+
 /// Returns the path to the Codex configuration directory, which can be
 /// specified by the `CODEX_HOME` environment variable. If not set, defaults to
 /// `~/.codex`.
@@ -1266,6 +1384,19 @@ pub fn find_codex_home() -> std::io::Result<PathBuf> {
     Ok(p)
 }
 
+/// Expands a leading `~` in each of `paths` to the user's home directory so
+/// entries such as `~/.ssh` name the real location.
+fn expand_home_dirs(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let home = home_dir();
+    paths
+        .iter()
+        .map(|path| match (path.strip_prefix("~"), home.as_ref()) {
+            (Ok(rest), Some(home)) => home.join(rest),
+            _ => path.clone(),
+        })
+        .collect()
+}
+
 /// Returns the path to the folder where Codex logs are stored. Does not verify
 /// that the directory exists.
 pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
@@ -1377,7 +1508,7 @@ network_access = true  # This should be ignored.
         assert_eq!(
             resolution,
             SandboxPolicyResolution {
-                policy: SandboxPolicy::ReadOnly,
+                policy: SandboxPolicy::new_read_only_policy(),
                 forced_auto_mode_downgraded_on_windows: false,
             }
         );
@@ -1405,7 +1536,7 @@ exclude_slash_tmp = true
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1418,6 +1549,8 @@ exclude_slash_tmp = true
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        deny_read: Vec::new(),
+                        network_allowlist: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1450,7 +1583,7 @@ trust_level = "trusted"
             assert_eq!(
                 resolution,
                 SandboxPolicyResolution {
-                    policy: SandboxPolicy::ReadOnly,
+                    policy: SandboxPolicy::new_read_only_policy(),
                     forced_auto_mode_downgraded_on_windows: true,
                 }
             );
@@ -1463,6 +1596,8 @@ trust_level = "trusted"
                         network_access: false,
                         exclude_tmpdir_env_var: true,
                         exclude_slash_tmp: true,
+                        deny_read: Vec::new(),
+                        network_allowlist: Vec::new(),
                     },
                     forced_auto_mode_downgraded_on_windows: false,
                 }
@@ -1470,6 +1605,113 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn test_sandbox_deny_read_config_parsing() {
+        let sandbox_read_only = r#"
+sandbox_mode = "read-only"
+
+[sandbox_read_only]
+deny_read = ["/home/other", "/root/.ssh"]
+"#;
+
+        let sandbox_read_only_cfg = toml::from_str::<ConfigToml>(sandbox_read_only)
+            .expect("TOML deserialization should succeed");
+        let resolution =
+            sandbox_read_only_cfg.derive_sandbox_policy(None, None, &PathBuf::from("/tmp/test"));
+        assert_eq!(
+            resolution,
+            SandboxPolicyResolution {
+                policy: SandboxPolicy::ReadOnly {
+                    deny_read: vec![PathBuf::from("/home/other"), PathBuf::from("/root/.ssh")],
+                },
+                forced_auto_mode_downgraded_on_windows: false,
+            }
+        );
+        assert!(!resolution.policy.has_full_disk_read_access());
+
+        let sandbox_workspace_write = r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+deny_read = ["/root/.aws", "~/.ssh"]
+"#;
+
+        let sandbox_workspace_write_cfg = toml::from_str::<ConfigToml>(sandbox_workspace_write)
+            .expect("TOML deserialization should succeed");
+        let resolution = sandbox_workspace_write_cfg.derive_sandbox_policy(
+            None,
+            None,
+            &PathBuf::from("/tmp/test"),
+        );
+        let home = home_dir().expect("home dir should be known");
+        assert_eq!(
+            resolution.policy.get_deny_read_paths(),
+            &[PathBuf::from("/root/.aws"), home.join(".ssh")]
+        );
+    }
+
+    #[test]
+    fn test_sandbox_network_allowlist_config_parsing() {
+        let sandbox_workspace_write = r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+network_allowlist = ["registry.internal.example:443", "*.crates.io"]
+"#;
+
+        let sandbox_workspace_write_cfg = toml::from_str::<ConfigToml>(sandbox_workspace_write)
+            .expect("TOML deserialization should succeed");
+        let resolution = sandbox_workspace_write_cfg.derive_sandbox_policy(
+            None,
+            None,
+            &PathBuf::from("/tmp/test"),
+        );
+        assert_eq!(
+            resolution.policy.get_network_allowlist(),
+            &[
+                "registry.internal.example:443".to_string(),
+                "*.crates.io".to_string()
+            ]
+        );
+        assert!(!resolution.policy.has_full_network_access());
+    }
+
+    #[test]
+    fn execpolicy_files_resolve_user_then_project_paths() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let project = TempDir::new()?;
+        let project_path = project.path().to_path_buf();
+        let cfg = toml::from_str::<ConfigToml>(&format!(
+            r#"
+execpolicy_files = ["user.policy", "/etc/codex/org.policy"]
+
+[projects."{}"]
+execpolicy_files = ["tools/codex.policy"]
+"#,
+            project_path.display()
+        ))
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(project_path.clone()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.execpolicy_files,
+            vec![
+                codex_home.path().join("user.policy"),
+                PathBuf::from("/etc/codex/org.policy"),
+                project_path.join("tools/codex.policy"),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1498,7 +1740,7 @@ trust_level = "trusted"
                 "expected workspace-write request to be downgraded on Windows"
             );
             match config.sandbox_policy {
-                SandboxPolicy::ReadOnly => {}
+                SandboxPolicy::ReadOnly { .. } => {}
                 other => panic!("expected read-only policy on Windows, got {other:?}"),
             }
         } else {
@@ -1609,6 +1851,60 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_model_fallbacks_override_base() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "work"
+
+[[model_fallbacks]]
+provider = "oss"
+
+[profiles.work]
+model_fallbacks = [{ provider = "oss", model = "gpt-oss:20b" }]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.model_fallbacks,
+            vec![ModelFallback {
+                provider: "oss".to_string(),
+                model: Some("gpt-oss:20b".to_string()),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_model_fallback_provider_is_rejected() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg = ConfigToml {
+            model_fallbacks: Some(vec![ModelFallback {
+                provider: "missing".to_string(),
+                model: None,
+            }]),
+            ..Default::default()
+        };
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown provider should fail");
+
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1671,7 +1967,10 @@ trust_level = "trusted"
         )?;
 
         if cfg!(target_os = "windows") {
-            assert!(matches!(config.sandbox_policy, SandboxPolicy::ReadOnly));
+            assert!(matches!(
+                config.sandbox_policy,
+                SandboxPolicy::ReadOnly { .. }
+            ));
             assert!(config.forced_auto_mode_downgraded_on_windows);
         } else {
             assert!(matches!(
@@ -1824,6 +2123,7 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1970,6 +2270,7 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2038,6 +2339,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2086,6 +2388,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2132,6 +2435,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2194,6 +2498,7 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
         apply_blocking(
@@ -2268,6 +2573,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2295,6 +2601,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
         apply_blocking(
@@ -2360,6 +2667,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
             (
@@ -2377,6 +2685,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
         ]);
@@ -2457,6 +2766,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2499,6 +2809,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                sampling: None,
             },
         )]);
 
@@ -2863,6 +3174,7 @@ model_verbosity = "high"
                 model_auto_compact_token_limit: Some(180_000),
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                model_fallbacks: Vec::new(),
                 approval_policy: AskForApproval::Never,
                 sandbox_policy: SandboxPolicy::new_read_only_policy(),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -2870,6 +3182,7 @@ model_verbosity = "high"
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                hooks: Vec::new(),
                 cwd: fixture.cwd(),
                 cli_auth_credentials_store_mode: Default::default(),
                 mcp_servers: HashMap::new(),
@@ -2896,10 +3209,14 @@ model_verbosity = "high"
                 tools_web_search_request: false,
                 experimental_sandbox_command_assessment: false,
                 use_experimental_unified_exec_tool: false,
-                use_experimental_use_rmcp_client: false,
+                use_experimental_use_rmcp_client: true,
                 features: Features::with_defaults(),
                 active_profile: Some("o3".to_string()),
-                active_project: ProjectConfig { trust_level: None },
+                active_project: ProjectConfig {
+                    trust_level: None,
+                    execpolicy_files: None,
+                },
+                execpolicy_files: Vec::new(),
                 windows_wsl_setup_acknowledged: false,
                 notices: Default::default(),
                 disable_paste_burst: false,
@@ -2934,6 +3251,7 @@ model_verbosity = "high"
             model_auto_compact_token_limit: Some(14_746),
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::UnlessTrusted,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -2941,6 +3259,7 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Vec::new(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -2967,10 +3286,14 @@ model_verbosity = "high"
             tools_web_search_request: false,
             experimental_sandbox_command_assessment: false,
             use_experimental_unified_exec_tool: false,
-            use_experimental_use_rmcp_client: false,
+            use_experimental_use_rmcp_client: true,
             features: Features::with_defaults(),
             active_profile: Some("gpt3".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                execpolicy_files: None,
+            },
+            execpolicy_files: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
@@ -3020,6 +3343,7 @@ model_verbosity = "high"
             model_auto_compact_token_limit: Some(180_000),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3027,6 +3351,7 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Vec::new(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3053,10 +3378,14 @@ model_verbosity = "high"
             tools_web_search_request: false,
             experimental_sandbox_command_assessment: false,
             use_experimental_unified_exec_tool: false,
-            use_experimental_use_rmcp_client: false,
+            use_experimental_use_rmcp_client: true,
             features: Features::with_defaults(),
             active_profile: Some("zdr".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                execpolicy_files: None,
+            },
+            execpolicy_files: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
@@ -3092,6 +3421,7 @@ model_verbosity = "high"
             model_auto_compact_token_limit: Some(244_800),
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            model_fallbacks: Vec::new(),
             approval_policy: AskForApproval::OnFailure,
             sandbox_policy: SandboxPolicy::new_read_only_policy(),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
@@ -3099,6 +3429,7 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            hooks: Vec::new(),
             cwd: fixture.cwd(),
             cli_auth_credentials_store_mode: Default::default(),
             mcp_servers: HashMap::new(),
@@ -3125,10 +3456,14 @@ model_verbosity = "high"
             tools_web_search_request: false,
             experimental_sandbox_command_assessment: false,
             use_experimental_unified_exec_tool: false,
-            use_experimental_use_rmcp_client: false,
+            use_experimental_use_rmcp_client: true,
             features: Features::with_defaults(),
             active_profile: Some("gpt5".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig {
+                trust_level: None,
+                execpolicy_files: None,
+            },
+            execpolicy_files: Vec::new(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
@@ -0,0 +1,266 @@
+//! Tracks which configuration layer each effective setting comes from, so
+//! clients can show (and edit) the place a value is actually defined.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use codex_app_server_protocol::ConfigLayerSource;
+use toml::Value as TomlValue;
+
+use crate::config::Config;
+use crate::config::apply_toml_override;
+use crate::config_loader::LoadedConfigLayers;
+use crate::config_loader::LoaderOverrides;
+use crate::config_loader::load_config_layers_with_overrides;
+use crate::config_loader::merge_toml_values;
+use crate::features::FEATURES;
+use crate::protocol::AskForApproval;
+use crate::protocol::SandboxPolicy;
+
+/// Settings a `[profiles.<name>]` table can override.
+const PROFILE_SCOPED_KEYS: &[&str] = &[
+    "model",
+    "model_provider",
+    "model_reasoning_effort",
+    "approval_policy",
+    "sandbox_mode",
+];
+
+/// The raw layers that make up the effective configuration.
+#[derive(Debug)]
+pub struct ConfigOrigins {
+    user: TomlValue,
+    /// `-c` overrides in the order they were given.
+    cli_override_list: Vec<(String, TomlValue)>,
+    /// The same overrides applied to an empty table.
+    cli_overrides: TomlValue,
+    managed: Vec<TomlValue>,
+}
+
+impl ConfigOrigins {
+    /// Load `config.toml` and the managed layers from `codex_home` alongside
+    /// the `-c key=value` overrides the process was started with.
+    pub async fn load(
+        codex_home: &Path,
+        cli_overrides: &[(String, TomlValue)],
+    ) -> std::io::Result<Self> {
+        let LoadedConfigLayers {
+            base,
+            managed_config,
+            managed_preferences,
+        } = load_config_layers_with_overrides(codex_home, LoaderOverrides::default()).await?;
+
+        let managed = [managed_config, managed_preferences]
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok(Self::from_layers(base, cli_overrides, managed))
+    }
+
+    fn from_layers(
+        user: TomlValue,
+        cli_overrides: &[(String, TomlValue)],
+        managed: Vec<TomlValue>,
+    ) -> Self {
+        let mut overrides = TomlValue::Table(Default::default());
+        for (path, value) in cli_overrides {
+            apply_toml_override(&mut overrides, path, value.clone());
+        }
+        Self {
+            user,
+            cli_override_list: cli_overrides.to_vec(),
+            cli_overrides: overrides,
+            managed,
+        }
+    }
+
+    /// All layers merged the same way the config loader does.
+    pub fn merged(&self) -> TomlValue {
+        let mut merged = self.user.clone();
+        for (path, value) in &self.cli_override_list {
+            apply_toml_override(&mut merged, path, value.clone());
+        }
+        for layer in &self.managed {
+            merge_toml_values(&mut merged, layer);
+        }
+        merged
+    }
+
+    /// Highest-precedence layer that sets the value at `segments`. Managed
+    /// settings win over `-c` overrides, which win over `config.toml`.
+    pub fn layer_of(&self, segments: &[&str]) -> Option<ConfigLayerSource> {
+        if self
+            .managed
+            .iter()
+            .any(|layer| lookup(layer, segments).is_some())
+        {
+            Some(ConfigLayerSource::Managed)
+        } else if lookup(&self.cli_overrides, segments).is_some() {
+            Some(ConfigLayerSource::CliOverride)
+        } else {
+            lookup(&self.user, segments).map(|_| ConfigLayerSource::User)
+        }
+    }
+
+    /// Like [`ConfigOrigins::layer_of`] for settings that `profile` may
+    /// override; a value from the profile's table in `config.toml` is
+    /// reported as [`ConfigLayerSource::Profile`].
+    pub fn profile_layer_of(
+        &self,
+        profile: Option<&str>,
+        segments: &[&str],
+    ) -> Option<ConfigLayerSource> {
+        if let Some(profile) = profile {
+            let mut scoped = vec!["profiles", profile];
+            scoped.extend_from_slice(segments);
+            if let Some(layer) = self.layer_of(&scoped) {
+                return Some(match layer {
+                    ConfigLayerSource::User => ConfigLayerSource::Profile,
+                    other => other,
+                });
+            }
+        }
+        self.layer_of(segments)
+    }
+
+    /// Origin of every setting exposed by the config API, keyed by its dotted
+    /// `config.toml` key path. `config` must have been loaded from the same
+    /// layers.
+    pub fn origins(&self, config: &Config) -> HashMap<String, ConfigLayerSource> {
+        let profile = config.active_profile.as_deref();
+        let mut origins = HashMap::new();
+
+        for key in PROFILE_SCOPED_KEYS {
+            let origin = self
+                .profile_layer_of(profile, &[key])
+                .unwrap_or_else(|| match *key {
+                    "approval_policy"
+                        if config.active_project.is_trusted()
+                            && config.approval_policy == AskForApproval::OnRequest =>
+                    {
+                        ConfigLayerSource::Project
+                    }
+                    "sandbox_mode"
+                        if config.active_project.is_trusted()
+                            && matches!(
+                                config.sandbox_policy,
+                                SandboxPolicy::WorkspaceWrite { .. }
+                            ) =>
+                    {
+                        ConfigLayerSource::Project
+                    }
+                    _ => ConfigLayerSource::Default,
+                });
+            origins.insert((*key).to_string(), origin);
+        }
+
+        if let Some(origin) = self.layer_of(&["profile"]) {
+            origins.insert("profile".to_string(), origin);
+        }
+        if let Some(profiles) = self.merged().get("profiles").and_then(TomlValue::as_table) {
+            for name in profiles.keys() {
+                if let Some(origin) = self.layer_of(&["profiles", name]) {
+                    origins.insert(format!("profiles.{name}"), origin);
+                }
+            }
+        }
+        for name in config.mcp_servers.keys() {
+            if let Some(origin) = self.layer_of(&["mcp_servers", name]) {
+                origins.insert(format!("mcp_servers.{name}"), origin);
+            }
+        }
+        for spec in FEATURES {
+            let origin = self
+                .profile_layer_of(profile, &["features", spec.key])
+                .unwrap_or(ConfigLayerSource::Default);
+            origins.insert(format!("features.{}", spec.key), origin);
+        }
+
+        origins
+    }
+}
+
+fn lookup<'a>(value: &'a TomlValue, segments: &[&str]) -> Option<&'a TomlValue> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| current.get(*segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn origins(user: &str, cli_overrides: &[(&str, TomlValue)], managed: &[&str]) -> ConfigOrigins {
+        let cli_overrides: Vec<(String, TomlValue)> = cli_overrides
+            .iter()
+            .map(|(path, value)| ((*path).to_string(), value.clone()))
+            .collect();
+        ConfigOrigins::from_layers(
+            toml::from_str(user).expect("user layer"),
+            &cli_overrides,
+            managed
+                .iter()
+                .map(|layer| toml::from_str(layer).expect("managed layer"))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn layer_of_prefers_managed_then_cli_then_user() {
+        let origins = origins(
+            r#"
+model = "gpt-5"
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#,
+            &[("approval_policy", TomlValue::String("on-request".into()))],
+            &["sandbox_mode = \"workspace-write\""],
+        );
+
+        assert_eq!(origins.layer_of(&["model"]), Some(ConfigLayerSource::User));
+        assert_eq!(
+            origins.layer_of(&["approval_policy"]),
+            Some(ConfigLayerSource::CliOverride)
+        );
+        assert_eq!(
+            origins.layer_of(&["sandbox_mode"]),
+            Some(ConfigLayerSource::Managed)
+        );
+        assert_eq!(origins.layer_of(&["model_provider"]), None);
+
+        let merged = origins.merged();
+        assert_eq!(merged["approval_policy"].as_str(), Some("on-request"));
+        assert_eq!(merged["sandbox_mode"].as_str(), Some("workspace-write"));
+    }
+
+    #[test]
+    fn profile_values_are_reported_as_profile() {
+        let origins = origins(
+            r#"
+model = "gpt-5"
+
+[profiles.fast]
+model = "gpt-5-codex"
+"#,
+            &[(
+                "profiles.fast.approval_policy",
+                TomlValue::String("never".into()),
+            )],
+            &[],
+        );
+
+        assert_eq!(
+            origins.profile_layer_of(Some("fast"), &["model"]),
+            Some(ConfigLayerSource::Profile)
+        );
+        assert_eq!(
+            origins.profile_layer_of(Some("fast"), &["approval_policy"]),
+            Some(ConfigLayerSource::CliOverride)
+        );
+        assert_eq!(
+            origins.profile_layer_of(None, &["model"]),
+            Some(ConfigLayerSource::User)
+        );
+    }
+}
@@ -3,6 +3,7 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use codex_protocol::protocol::HookTrigger;
 use serde::Deserializer;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -43,6 +44,31 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// Whether this server may run completions against the session's model
+    /// via `sampling/createMessage`. Unset means sampling is not offered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<McpSamplingPolicy>,
+}
+
+/// How sampling requests from an MCP server are handled.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSamplingPolicy {
+    /// Ask the user to approve each request (or approve the server for the
+    /// rest of the session).
+    Ask,
+    /// Forward requests to the model without prompting.
+    Auto,
+}
+
+impl McpSamplingPolicy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            McpSamplingPolicy::Ask => "ask",
+            McpSamplingPolicy::Auto => "auto",
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -84,6 +110,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled_tools: Option<Vec<String>>,
             #[serde(default)]
             disabled_tools: Option<Vec<String>>,
+            #[serde(default)]
+            sampling: Option<McpSamplingPolicy>,
         }
 
         let mut raw = RawMcpServerConfig::deserialize(deserializer)?;
@@ -100,6 +128,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let sampling = raw.sampling;
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -153,6 +182,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            sampling,
         })
     }
 }
@@ -271,6 +301,54 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// A command run synchronously at a point in the agent loop. Declared in
+/// `config.toml` as:
+///
+/// ```toml
+/// [[hooks]]
+/// event = "before_tool_call"
+/// command = ["/path/to/guard-migrations.sh"]
+/// tools = ["apply_patch"]
+/// timeout_ms = 5000
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookConfig {
+    pub event: HookTrigger,
+
+    /// Program and arguments to run. The hook input is written to its stdin
+    /// as JSON.
+    pub command: Vec<String>,
+
+    /// Tool names the hook applies to (tool hooks only). When unset the hook
+    /// runs for every tool call.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+
+    /// How long to wait for the hook before ignoring it. Defaults to 60
+    /// seconds.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Provider to fail over to once the active provider has exhausted its
+/// retries. Declared in `config.toml` (or a profile) as:
+///
+/// ```toml
+/// [[model_fallbacks]]
+/// provider = "azure"
+/// model = "gpt-5"
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModelFallback {
+    /// Key into the `model_providers` map.
+    pub provider: String,
+
+    /// Model to request from `provider`. Defaults to the model in use when
+    /// the switch happens.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -379,6 +457,10 @@ pub struct SandboxWorkspaceWrite {
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
+    #[serde(default)]
+    pub deny_read: Vec<PathBuf>,
+    #[serde(default)]
+    pub network_allowlist: Vec<String>,
 }
 
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
@@ -388,10 +470,18 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
             network_access: Some(sandbox_workspace_write.network_access),
             exclude_tmpdir_env_var: Some(sandbox_workspace_write.exclude_tmpdir_env_var),
             exclude_slash_tmp: Some(sandbox_workspace_write.exclude_slash_tmp),
+            deny_read: sandbox_workspace_write.deny_read,
+            network_allowlist: sandbox_workspace_write.network_allowlist,
         }
     }
 }
 
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SandboxReadOnly {
+    #[serde(default)]
+    pub deny_read: Vec<PathBuf>,
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub enum ShellEnvironmentPolicyInherit {
@@ -523,6 +613,7 @@ mod tests {
         assert!(cfg.enabled);
         assert!(cfg.enabled_tools.is_none());
         assert!(cfg.disabled_tools.is_none());
+        assert!(cfg.sampling.is_none());
     }
 
     #[test]
@@ -712,6 +803,26 @@ mod tests {
         assert_eq!(cfg.disabled_tools, Some(vec!["blocked".to_string()]));
     }
 
+    #[test]
+    fn deserialize_server_config_with_sampling_policy() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            sampling = "ask"
+        "#,
+        )
+        .expect("should deserialize sampling policy");
+        assert_eq!(cfg.sampling, Some(McpSamplingPolicy::Ask));
+
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            sampling = "always"
+        "#,
+        )
+        .expect_err("should reject unknown sampling policy");
+    }
+
     #[test]
     fn deserialize_rejects_command_and_url() {
         toml::from_str::<McpServerConfig>(
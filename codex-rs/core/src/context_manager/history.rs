@@ -96,6 +96,26 @@ impl ContextManager {
         );
     }
 
+    /// Count `usage` from a request made outside the conversation (such as an
+    /// MCP sampling request) towards the session total. `last_token_usage` is
+    /// left alone because it tracks how full the context window is.
+    pub(crate) fn add_out_of_band_token_usage(
+        &mut self,
+        usage: &TokenUsage,
+        model_context_window: Option<i64>,
+    ) {
+        match &mut self.token_info {
+            Some(info) => info.total_token_usage.add_assign(usage),
+            None => {
+                self.token_info = Some(TokenUsageInfo {
+                    total_token_usage: usage.clone(),
+                    last_token_usage: TokenUsage::default(),
+                    model_context_window,
+                });
+            }
+        }
+    }
+
     /// This function enforces a couple of invariants on the in-memory history:
     /// 1. every call (function/custom) has a corresponding output entry
     /// 2. every output has a corresponding call entry
@@ -51,6 +51,31 @@ fn reasoning_msg(text: &str) -> ResponseItem {
     }
 }
 
+#[test]
+fn out_of_band_usage_only_adds_to_total() {
+    let mut h = ContextManager::new();
+    let turn = TokenUsage {
+        input_tokens: 100,
+        output_tokens: 20,
+        total_tokens: 120,
+        ..Default::default()
+    };
+    let sampling = TokenUsage {
+        input_tokens: 30,
+        output_tokens: 10,
+        total_tokens: 40,
+        ..Default::default()
+    };
+    h.update_token_info(&turn, Some(1_000));
+    h.add_out_of_band_token_usage(&sampling, Some(1_000));
+
+    let info = h.token_info().expect("token info");
+    assert_eq!(info.last_token_usage, turn);
+    assert_eq!(info.total_token_usage.input_tokens, 130);
+    assert_eq!(info.total_token_usage.output_tokens, 30);
+    assert_eq!(info.total_token_usage.total_tokens, 160);
+}
+
 #[test]
 fn filters_non_api_messages() {
     let mut h = ContextManager::default();
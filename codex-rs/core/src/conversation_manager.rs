@@ -191,24 +191,32 @@ impl ConversationManager {
     }
 }
 
+/// Indices of the user messages in `items`, in rollout order. Each one starts a
+/// turn, so `n` in [`ConversationManager::fork_conversation`] indexes into this.
+pub fn user_message_positions(items: &[RolloutItem]) -> Vec<usize> {
+    items
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, item)| match item {
+            RolloutItem::ResponseItem(item @ ResponseItem::Message { .. })
+                if matches!(
+                    crate::event_mapping::parse_turn_item(item),
+                    Some(TurnItem::UserMessage(_))
+                ) =>
+            {
+                Some(idx)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 /// Return a prefix of `items` obtained by cutting strictly before the nth user message
 /// (0-based) and all items that follow it.
 fn truncate_before_nth_user_message(history: InitialHistory, n: usize) -> InitialHistory {
     // Work directly on rollout items, and cut the vector at the nth user message input.
     let items: Vec<RolloutItem> = history.get_rollout_items();
-
-    // Find indices of user message inputs in rollout order.
-    let mut user_positions: Vec<usize> = Vec::new();
-    for (idx, item) in items.iter().enumerate() {
-        if let RolloutItem::ResponseItem(item @ ResponseItem::Message { .. }) = item
-            && matches!(
-                crate::event_mapping::parse_turn_item(item),
-                Some(TurnItem::UserMessage(_))
-            )
-        {
-            user_positions.push(idx);
-        }
-    }
+    let user_positions = user_message_positions(&items);
 
     // If fewer than or equal to n user messages exist, treat as empty (out of range).
     if user_positions.len() <= n {
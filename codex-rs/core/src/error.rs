@@ -529,6 +529,7 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -545,6 +546,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -561,6 +563,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -577,6 +580,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -26,6 +26,11 @@ use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
+use crate::sandboxing::network::BlockedConnection;
+#[cfg(target_os = "linux")]
+use crate::sandboxing::network::CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR;
+#[cfg(target_os = "linux")]
+use crate::sandboxing::network::NetworkReportPipe;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
@@ -184,7 +189,7 @@ async fn exec_windows_sandbox(
 
     let policy_str = match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "workspace-write",
-        SandboxPolicy::ReadOnly => "read-only",
+        SandboxPolicy::ReadOnly { .. } => "read-only",
         SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
     };
 
@@ -241,6 +246,7 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        blocked_network_connections: Vec::new(),
     })
 }
 
@@ -280,6 +286,7 @@ fn finalize_exec_result(
                 aggregated_output,
                 duration,
                 timed_out,
+                blocked_network_connections: raw_output.blocked_network_connections,
             };
 
             if timed_out {
@@ -335,6 +342,10 @@ pub(crate) fn is_likely_sandbox_denied(
         return false;
     }
 
+    if !exec_output.blocked_network_connections.is_empty() {
+        return true;
+    }
+
     // Quick rejects: well-known non-sandbox shell exit codes
     // 2: misuse of shell builtins
     // 126: permission denied
@@ -397,6 +408,7 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    pub blocked_network_connections: Vec<BlockedConnection>,
 }
 
 impl StreamOutput<String> {
@@ -430,6 +442,9 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    /// Connections the sandbox's filtering proxy refused, as reported over a
+    /// channel the command itself cannot write to.
+    pub blocked_network_connections: Vec<BlockedConnection>,
 }
 
 #[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
@@ -458,6 +473,34 @@ async fn exec(
             "command args are empty",
         ))
     })?;
+
+    // The Linux sandbox's filtering proxy reports refused connections over a
+    // dedicated pipe rather than the command's own output, which the command
+    // could forge.
+    #[cfg(target_os = "linux")]
+    let mut network_report = if sandbox == SandboxType::LinuxSeccomp
+        && !sandbox_policy.get_network_allowlist().is_empty()
+    {
+        Some(NetworkReportPipe::new()?)
+    } else {
+        None
+    };
+    #[cfg(target_os = "linux")]
+    let env = {
+        let mut env = env;
+        if let Some(report) = &network_report {
+            env.insert(
+                CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR.to_string(),
+                report.write_fd().to_string(),
+            );
+        }
+        env
+    };
+    #[cfg(target_os = "linux")]
+    let inherited_fd = network_report.as_ref().map(NetworkReportPipe::write_fd);
+    #[cfg(not(target_os = "linux"))]
+    let inherited_fd = None;
+
     let arg0_ref = arg0.as_deref();
     let child = spawn_child_async(
         PathBuf::from(program),
@@ -467,9 +510,22 @@ async fn exec(
         sandbox_policy,
         StdioPolicy::RedirectForShellTool,
         env,
+        inherited_fd,
     )
     .await?;
-    consume_truncated_output(child, timeout, stdout_stream).await
+
+    #[cfg(target_os = "linux")]
+    if let Some(report) = network_report.as_mut() {
+        report.close_write();
+    }
+
+    #[allow(unused_mut)]
+    let mut output = consume_truncated_output(child, timeout, stdout_stream).await?;
+    #[cfg(target_os = "linux")]
+    if let Some(report) = network_report {
+        output.blocked_network_connections = report.read_reports();
+    }
+    Ok(output)
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
@@ -552,6 +608,7 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        blocked_network_connections: Vec::new(),
     })
 }
 
@@ -673,6 +730,7 @@ mod tests {
             aggregated_output: StreamOutput::new(aggregated.to_string()),
             duration: Duration::from_millis(1),
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         }
     }
 
@@ -747,7 +805,13 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::new_read_only_policy(),
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
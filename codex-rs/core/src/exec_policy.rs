@@ -0,0 +1,455 @@
+/*
+Module: exec_policy
+
+Loads the `execpolicy` rules (the built-in default policy merged with any
+user- and project-level policy files) and evaluates proposed commands against
+them so the approval path can auto-approve, require approval for, or forbid a
+command before falling back to the hard-coded safety heuristics.
+*/
+
+use std::ffi::OsString;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_execpolicy::ArgType;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::ExecvChecker;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::ValidExec;
+use codex_execpolicy::get_default_policy;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SandboxPolicy;
+use thiserror::Error;
+
+use crate::bash::parse_shell_lc_plain_commands;
+
+#[derive(Debug, Error)]
+pub enum ExecPolicyError {
+    #[error("failed to load the default execpolicy: {message}")]
+    Default { message: String },
+
+    #[error("failed to read execpolicy file {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse execpolicy file {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+}
+
+/// What the configured policy says about a proposed command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecPolicyDecision {
+    /// Every command matched a rule and only touches files the sandbox
+    /// already permits (and, under `UnlessTrusted`, writes no files at all),
+    /// so it can run without asking the user.
+    Allow,
+    /// A rule requires the user to approve the command; `reason` is shown in
+    /// the approval prompt.
+    RequireApproval { reason: String },
+    /// A rule forbids the command outright.
+    Forbid { reason: String },
+}
+
+/// The policy verdict for one plain command within a (possibly compound)
+/// shell invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandEvaluation {
+    pub command: Vec<String>,
+    /// `None` when no rule vouches for the command.
+    pub decision: Option<ExecPolicyDecision>,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecPolicyEvaluation {
+    /// `None` means the policy has no opinion and the caller should fall back
+    /// to its usual approval heuristics.
+    pub decision: Option<ExecPolicyDecision>,
+    pub commands: Vec<CommandEvaluation>,
+}
+
+/// Loads the built-in default policy and merges `policy_files` on top of it,
+/// in order, so that later files can tighten rules from earlier ones.
+pub fn load_exec_policy(policy_files: &[PathBuf]) -> Result<ExecvChecker, ExecPolicyError> {
+    let mut policy = get_default_policy().map_err(|err| ExecPolicyError::Default {
+        message: err.to_string(),
+    })?;
+    for path in policy_files {
+        let contents = std::fs::read_to_string(path).map_err(|source| ExecPolicyError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        let parsed = PolicyParser::new(&path.to_string_lossy(), &contents)
+            .parse()
+            .map_err(|err| ExecPolicyError::Parse {
+                path: path.clone(),
+                message: err.to_string(),
+            })?;
+        policy = policy.merge(parsed).map_err(|err| ExecPolicyError::Parse {
+            path: path.clone(),
+            message: err.to_string(),
+        })?;
+    }
+    Ok(ExecvChecker::new(policy))
+}
+
+/// The checker used when no policy files could be loaded.
+pub(crate) fn default_exec_policy() -> ExecvChecker {
+    #[expect(clippy::expect_used)]
+    let policy = get_default_policy().expect("built-in execpolicy must parse");
+    ExecvChecker::new(policy)
+}
+
+/// Evaluates `command` (an argv, possibly a `bash -lc "..."` invocation)
+/// against the policy. A forbidden command anywhere in the script wins, then
+/// any command that requires approval; the script is only allowed when every
+/// command in it is.
+///
+/// Under [`AskForApproval::UnlessTrusted`] only commands that write no files
+/// can be allowed, so a policy never runs more without asking than the
+/// built-in list of known-safe commands does.
+pub fn evaluate_command(
+    checker: &ExecvChecker,
+    command: &[String],
+    cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+    approval_policy: AskForApproval,
+) -> ExecPolicyEvaluation {
+    let commands = parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+    let writable_folders = if approval_policy == AskForApproval::UnlessTrusted {
+        Vec::new()
+    } else {
+        writable_folders(sandbox_policy, cwd)
+    };
+    let deny_read = sandbox_policy.get_deny_read_paths();
+    let commands: Vec<CommandEvaluation> = commands
+        .into_iter()
+        .map(|command| evaluate_plain_command(checker, command, cwd, &writable_folders, deny_read))
+        .collect();
+
+    let forbid = commands
+        .iter()
+        .find_map(|evaluation| match &evaluation.decision {
+            Some(decision @ ExecPolicyDecision::Forbid { .. }) => Some(decision.clone()),
+            _ => None,
+        });
+    let require_approval = commands
+        .iter()
+        .find_map(|evaluation| match &evaluation.decision {
+            Some(decision @ ExecPolicyDecision::RequireApproval { .. }) => Some(decision.clone()),
+            _ => None,
+        });
+    let all_allowed = !commands.is_empty()
+        && commands
+            .iter()
+            .all(|evaluation| evaluation.decision == Some(ExecPolicyDecision::Allow));
+    let decision = forbid
+        .or(require_approval)
+        .or(all_allowed.then_some(ExecPolicyDecision::Allow));
+
+    ExecPolicyEvaluation { decision, commands }
+}
+
+fn evaluate_plain_command(
+    checker: &ExecvChecker,
+    command: Vec<String>,
+    cwd: &Path,
+    writable_folders: &[PathBuf],
+    deny_read: &[PathBuf],
+) -> CommandEvaluation {
+    let Some((program, args)) = command.split_first() else {
+        return CommandEvaluation {
+            command,
+            decision: None,
+            explanation: "empty command".to_string(),
+        };
+    };
+    let exec_call = ExecCall {
+        program: program.clone(),
+        args: args.to_vec(),
+    };
+    let (decision, explanation) = match checker.r#match(&exec_call) {
+        Ok(MatchedExec::Forbidden { reason, .. }) => (
+            Some(ExecPolicyDecision::Forbid {
+                reason: reason.clone(),
+            }),
+            format!("forbidden: {reason}"),
+        ),
+        Ok(MatchedExec::RequiresApproval { reason, .. }) => (
+            Some(ExecPolicyDecision::RequireApproval {
+                reason: reason.clone(),
+            }),
+            format!("requires approval: {reason}"),
+        ),
+        Ok(MatchedExec::Match { exec }) if touches_denied_path(&exec, cwd, deny_read) => (
+            None,
+            format!("matches a `{program}` rule but touches a path listed in `deny_read`"),
+        ),
+        Ok(MatchedExec::Match { exec }) => {
+            let cwd = Some(OsString::from(cwd.as_os_str()));
+            let readable_folders = [PathBuf::from("/")];
+            match checker.check(exec, &cwd, &readable_folders, writable_folders) {
+                Ok(_) => (
+                    Some(ExecPolicyDecision::Allow),
+                    format!("allowed: matches a `{program}` rule"),
+                ),
+                Err(err) => (
+                    None,
+                    format!("matches a `{program}` rule but could not be verified: {err:?}"),
+                ),
+            }
+        }
+        Err(err) => (None, format!("no rule matched: {err:?}")),
+    };
+    CommandEvaluation {
+        command,
+        decision,
+        explanation,
+    }
+}
+
+/// Returns `true` when a file argument of `exec` lies inside one of the
+/// `deny_read` paths. The execpolicy checker only understands allowed
+/// folders, so everything else counts as readable and exclusions are
+/// checked here.
+fn touches_denied_path(exec: &ValidExec, cwd: &Path, deny_read: &[PathBuf]) -> bool {
+    if deny_read.is_empty() {
+        return false;
+    }
+    exec.args
+        .iter()
+        .map(|arg| (&arg.r#type, &arg.value))
+        .chain(exec.opts.iter().map(|opt| (&opt.r#type, &opt.value)))
+        .filter(|(arg_type, _)| matches!(arg_type, ArgType::ReadableFile | ArgType::WriteableFile))
+        .any(|(_, value)| {
+            let path = normalize(&cwd.join(value));
+            deny_read
+                .iter()
+                .any(|denied| path.starts_with(normalize(denied)))
+        })
+}
+
+/// Removes `.` and resolves `..` without touching the file-system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+fn writable_folders(sandbox_policy: &SandboxPolicy, cwd: &Path) -> Vec<PathBuf> {
+    match sandbox_policy {
+        SandboxPolicy::DangerFullAccess => vec![PathBuf::from("/")],
+        SandboxPolicy::ReadOnly { .. } => Vec::new(),
+        SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy
+            .get_writable_roots_with_cwd(cwd)
+            .into_iter()
+            .map(|writable_root| writable_root.root)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    fn workspace_write(cwd: &Path) -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![cwd.to_path_buf()],
+            network_access: false,
+            network_allowlist: Vec::new(),
+            deny_read: Vec::new(),
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        }
+    }
+
+    fn load_with_policy(contents: &str) -> (TempDir, ExecvChecker) {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("project.policy");
+        std::fs::write(&path, contents).expect("write policy");
+        let checker = load_exec_policy(&[path]).expect("load policy");
+        (dir, checker)
+    }
+
+    #[test]
+    fn default_policy_allows_read_only_commands() {
+        let checker = default_exec_policy();
+        let cwd = PathBuf::from("/repo");
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["bash", "-lc", "ls -l && cat README.md"]),
+            &cwd,
+            &SandboxPolicy::new_read_only_policy(),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, Some(ExecPolicyDecision::Allow));
+        assert_eq!(evaluation.commands.len(), 2);
+    }
+
+    #[test]
+    fn writes_outside_writable_roots_are_not_allowed() {
+        let checker = default_exec_policy();
+        let cwd = PathBuf::from("/repo");
+        let command = vec_str(&["cp", "a.txt", "/etc/b.txt"]);
+
+        let evaluation = evaluate_command(
+            &checker,
+            &command,
+            &cwd,
+            &workspace_write(&cwd),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, None);
+
+        let command = vec_str(&["cp", "a.txt", "b.txt"]);
+        let evaluation = evaluate_command(
+            &checker,
+            &command,
+            &cwd,
+            &workspace_write(&cwd),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, Some(ExecPolicyDecision::Allow));
+    }
+
+    #[test]
+    fn unless_trusted_only_allows_commands_that_write_nothing() {
+        let checker = default_exec_policy();
+        let cwd = PathBuf::from("/repo");
+
+        let command = vec_str(&["cp", "a.txt", "b.txt"]);
+        let evaluation = evaluate_command(
+            &checker,
+            &command,
+            &cwd,
+            &workspace_write(&cwd),
+            AskForApproval::UnlessTrusted,
+        );
+        assert_eq!(evaluation.decision, None);
+
+        let command = vec_str(&["cat", "a.txt"]);
+        let evaluation = evaluate_command(
+            &checker,
+            &command,
+            &cwd,
+            &workspace_write(&cwd),
+            AskForApproval::UnlessTrusted,
+        );
+        assert_eq!(evaluation.decision, Some(ExecPolicyDecision::Allow));
+    }
+
+    #[test]
+    fn reads_of_denied_paths_are_not_allowed() {
+        let checker = default_exec_policy();
+        let cwd = PathBuf::from("/repo");
+        let sandbox_policy = SandboxPolicy::ReadOnly {
+            deny_read: vec![PathBuf::from("/repo/secrets")],
+        };
+
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["cat", "secrets/../secrets/key.pem"]),
+            &cwd,
+            &sandbox_policy,
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, None);
+
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["cat", "README.md"]),
+            &cwd,
+            &sandbox_policy,
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, Some(ExecPolicyDecision::Allow));
+    }
+
+    #[test]
+    fn unknown_commands_defer_to_heuristics() {
+        let checker = default_exec_policy();
+        let cwd = PathBuf::from("/repo");
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["bash", "-lc", "ls && cargo build"]),
+            &cwd,
+            &SandboxPolicy::new_read_only_policy(),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(evaluation.decision, None);
+    }
+
+    #[test]
+    fn project_policy_can_forbid_and_require_approval() {
+        let (_dir, checker) = load_with_policy(
+            r#"
+define_program(
+    program="terraform",
+    args=["apply"],
+    require_approval="terraform apply changes shared infrastructure",
+)
+
+define_program(
+    program="terraform",
+    args=["destroy"],
+    forbidden="never destroy infrastructure from an agent",
+)
+"#,
+        );
+        let cwd = PathBuf::from("/repo");
+
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["terraform", "apply"]),
+            &cwd,
+            &SandboxPolicy::new_read_only_policy(),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(
+            evaluation.decision,
+            Some(ExecPolicyDecision::RequireApproval {
+                reason: "terraform apply changes shared infrastructure".to_string()
+            })
+        );
+
+        let evaluation = evaluate_command(
+            &checker,
+            &vec_str(&["bash", "-lc", "terraform apply && terraform destroy"]),
+            &cwd,
+            &SandboxPolicy::new_read_only_policy(),
+            AskForApproval::OnRequest,
+        );
+        assert_eq!(
+            evaluation.decision,
+            Some(ExecPolicyDecision::Forbid {
+                reason: "never destroy infrastructure from an agent".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn missing_policy_file_is_an_error() {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("missing.policy");
+        let result = load_exec_policy(std::slice::from_ref(&path));
+        assert!(matches!(result, Err(ExecPolicyError::Read { path: p, .. }) if p == path));
+    }
+}
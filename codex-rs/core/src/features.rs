@@ -29,7 +29,7 @@ pub enum Stage {
 pub enum Feature {
     /// Use the single unified PTY-backed exec tool.
     UnifiedExec,
-    /// Enable experimental RMCP features such as OAuth login.
+    /// Allow OAuth login for streamable HTTP MCP servers.
     RmcpClient,
     /// Include the freeform apply_patch tool.
     ApplyPatchFreeform,
@@ -253,8 +253,8 @@ pub const FEATURES: &[FeatureSpec] = &[
     FeatureSpec {
         id: Feature::RmcpClient,
         key: "rmcp_client",
-        stage: Stage::Experimental,
-        default_enabled: false,
+        stage: Stage::Stable,
+        default_enabled: true,
     },
     FeatureSpec {
         id: Feature::ApplyPatchFreeform,
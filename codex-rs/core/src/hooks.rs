@@ -0,0 +1,516 @@
+/*
+Module: hooks
+
+User-configured commands that run synchronously at points in the agent loop:
+before and after each tool call, and at the start and end of a turn. A hook
+receives a JSON description of the call or turn on stdin and may answer with a
+JSON object on stdout to block the call, rewrite its arguments, or add a
+message to the conversation for the model. Exiting with status 2 blocks with
+stderr as the reason; any other failure (non-zero exit, timeout, malformed
+output) is reported and otherwise ignored.
+*/
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::HookCompletedEvent;
+use codex_protocol::protocol::HookOutcome;
+use codex_protocol::protocol::HookTrigger;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::types::HookConfig;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Exit status a hook uses to block without printing JSON.
+const BLOCK_EXIT_CODE: i32 = 2;
+
+/// How many times `turn_end` hooks may send the model back to work within one
+/// turn before the turn ends with an error instead.
+pub(crate) const MAX_TURN_END_HOOK_CONTINUATIONS: usize = 5;
+
+pub const HOOK_MESSAGE_OPEN: &str = "<hook_message>";
+pub const HOOK_MESSAGE_CLOSE: &str = "</hook_message>";
+
+pub fn is_hook_message_text(text: &str) -> bool {
+    text.trim_start()
+        .to_ascii_lowercase()
+        .starts_with(HOOK_MESSAGE_OPEN)
+}
+
+/// What a hook is told about the call or turn it runs for.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookInput {
+    pub event: HookTrigger,
+    pub thread_id: String,
+    pub turn_id: String,
+    pub cwd: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<HookToolCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<HookToolOutput>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub input_messages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_assistant_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookToolCall {
+    pub name: String,
+    pub call_id: String,
+    /// The call's arguments: the parsed JSON object for function and MCP
+    /// tools, or the raw input string for freeform tools such as
+    /// `apply_patch`.
+    pub arguments: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct HookToolOutput {
+    pub success: bool,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    #[default]
+    Allow,
+    Block,
+}
+
+/// What a hook may print on stdout. Every field is optional; empty output
+/// means "carry on".
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: HookDecision,
+    reason: Option<String>,
+    arguments: Option<Value>,
+    message: Option<String>,
+}
+
+/// The combined effect of every hook that ran for one trigger.
+#[derive(Debug, Default)]
+pub(crate) struct HookResult {
+    /// Set when a hook blocked the call or turn.
+    pub blocked: Option<String>,
+    /// Replacement arguments for the tool call, if any hook rewrote them.
+    pub arguments: Option<Value>,
+    /// Messages to add to the conversation for the model.
+    pub messages: Vec<String>,
+    pub events: Vec<HookCompletedEvent>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct HookRunner {
+    hooks: Vec<HookConfig>,
+}
+
+impl HookRunner {
+    pub(crate) fn new(hooks: Vec<HookConfig>) -> Self {
+        Self { hooks }
+    }
+
+    pub(crate) fn has_hooks(&self, trigger: HookTrigger, tool_name: Option<&str>) -> bool {
+        self.hooks
+            .iter()
+            .any(|hook| applies_to(hook, trigger, tool_name))
+    }
+
+    /// Runs the hooks configured for `input.event` in order. Arguments
+    /// rewritten by one hook are what the next hook sees; the first hook to
+    /// block stops the chain.
+    pub(crate) async fn run(&self, mut input: HookInput) -> HookResult {
+        let mut result = HookResult::default();
+        let tool_name = input.tool.as_ref().map(|tool| tool.name.clone());
+        for hook in self
+            .hooks
+            .iter()
+            .filter(|hook| applies_to(hook, input.event, tool_name.as_deref()))
+        {
+            let mut event = HookCompletedEvent {
+                trigger: input.event,
+                command: hook.command.clone(),
+                call_id: input.tool.as_ref().map(|tool| tool.call_id.clone()),
+                tool_name: tool_name.clone(),
+                outcome: HookOutcome::Continued,
+                reason: None,
+                rewrote_arguments: false,
+                message: None,
+            };
+            match execute_hook(hook, &input).await {
+                Ok(response) => {
+                    if let Some(arguments) = response.arguments
+                        && input.event == HookTrigger::BeforeToolCall
+                        && let Some(tool) = input.tool.as_mut()
+                    {
+                        tool.arguments = arguments.clone();
+                        result.arguments = Some(arguments);
+                        event.rewrote_arguments = true;
+                    }
+                    if let Some(message) = response.message.filter(|m| !m.trim().is_empty()) {
+                        result.messages.push(message.clone());
+                        event.message = Some(message);
+                    }
+                    if response.decision == HookDecision::Block {
+                        let reason = response
+                            .reason
+                            .filter(|reason| !reason.trim().is_empty())
+                            .unwrap_or_else(|| {
+                                format!("blocked by hook `{}`", hook.command.join(" "))
+                            });
+                        event.outcome = HookOutcome::Blocked;
+                        event.reason = Some(reason.clone());
+                        result.blocked = Some(reason);
+                    }
+                }
+                Err(err) => {
+                    warn!("hook `{}` failed: {err}", hook.command.join(" "));
+                    event.outcome = HookOutcome::Failed;
+                    event.reason = Some(err);
+                }
+            }
+            result.events.push(event);
+            if result.blocked.is_some() {
+                break;
+            }
+        }
+        result
+    }
+}
+
+fn applies_to(hook: &HookConfig, trigger: HookTrigger, tool_name: Option<&str>) -> bool {
+    if hook.event != trigger || hook.command.is_empty() {
+        return false;
+    }
+    match (&hook.tools, tool_name) {
+        (Some(tools), Some(tool_name)) => tools.iter().any(|tool| tool == tool_name),
+        _ => true,
+    }
+}
+
+async fn execute_hook(hook: &HookConfig, input: &HookInput) -> Result<HookResponse, String> {
+    let payload = serde_json::to_vec(input).map_err(|err| format!("invalid hook input: {err}"))?;
+    let timeout = hook
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_HOOK_TIMEOUT);
+
+    let mut command = Command::new(&hook.command[0]);
+    command
+        .args(&hook.command[1..])
+        .current_dir(&input.cwd)
+        .env("CODEX_HOOK_EVENT", input.event.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("failed to start hook: {err}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Write the input while the output is being read, so a hook that
+        // prints before it reads cannot fill its pipes and stall. A hook that
+        // does not care about its input may exit without reading it; that is
+        // not an error.
+        tokio::spawn(async move {
+            let _ = stdin.write_all(&payload).await;
+        });
+    }
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => return Err(format!("failed to wait for hook: {err}")),
+        Err(_) => return Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match output.status.code() {
+        Some(0) => parse_response(&stdout),
+        Some(BLOCK_EXIT_CODE) => Ok(HookResponse {
+            decision: HookDecision::Block,
+            reason: Some(stderr.trim().to_string()),
+            ..Default::default()
+        }),
+        _ => Err(format!("exited with {}: {}", output.status, stderr.trim())),
+    }
+}
+
+fn parse_response(stdout: &str) -> Result<HookResponse, String> {
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(HookResponse::default());
+    }
+    serde_json::from_str(stdout).map_err(|err| format!("printed invalid JSON: {err}"))
+}
+
+/// The JSON a hook sees as the arguments of a tool call.
+pub(crate) fn payload_arguments(payload: &ToolPayload) -> Value {
+    match payload {
+        ToolPayload::Function { arguments }
+        | ToolPayload::UnifiedExec { arguments }
+        | ToolPayload::Mcp {
+            raw_arguments: arguments,
+            ..
+        } => serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.clone())),
+        ToolPayload::Custom { input } => Value::String(input.clone()),
+        ToolPayload::LocalShell { params } => serde_json::to_value(params).unwrap_or(Value::Null),
+    }
+}
+
+/// Applies arguments rewritten by a hook to `payload`.
+pub(crate) fn rewrite_payload(
+    payload: &ToolPayload,
+    arguments: Value,
+) -> Result<ToolPayload, String> {
+    let encode = |arguments: Value| match arguments {
+        Value::String(arguments) => arguments,
+        other => other.to_string(),
+    };
+    match payload {
+        ToolPayload::Function { .. } => Ok(ToolPayload::Function {
+            arguments: encode(arguments),
+        }),
+        ToolPayload::UnifiedExec { .. } => Ok(ToolPayload::UnifiedExec {
+            arguments: encode(arguments),
+        }),
+        ToolPayload::Mcp { server, tool, .. } => Ok(ToolPayload::Mcp {
+            server: server.clone(),
+            tool: tool.clone(),
+            raw_arguments: encode(arguments),
+        }),
+        ToolPayload::Custom { .. } => match arguments {
+            Value::String(input) => Ok(ToolPayload::Custom { input }),
+            _ => Err("arguments for this tool must be a string".to_string()),
+        },
+        ToolPayload::LocalShell { .. } => serde_json::from_value::<ShellToolCallParams>(arguments)
+            .map(|params| ToolPayload::LocalShell { params })
+            .map_err(|err| format!("invalid shell arguments: {err}")),
+    }
+}
+
+pub(crate) fn tool_output_for_hook(output: &ToolOutput) -> HookToolOutput {
+    match output {
+        ToolOutput::Function {
+            content, success, ..
+        } => HookToolOutput {
+            success: success.unwrap_or(true),
+            content: content.clone(),
+        },
+        ToolOutput::Mcp { result } => HookToolOutput {
+            success: result
+                .as_ref()
+                .is_ok_and(|result| result.is_error != Some(true)),
+            content: match result {
+                Ok(result) => serde_json::to_string(result).unwrap_or_default(),
+                Err(err) => err.clone(),
+            },
+        },
+    }
+}
+
+pub(crate) fn hook_message_text(message: &str) -> String {
+    format!("{HOOK_MESSAGE_OPEN}\n{message}\n{HOOK_MESSAGE_CLOSE}")
+}
+
+pub(crate) fn hook_message_item(message: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: hook_message_text(message),
+        }],
+    }
+}
+
+pub(crate) fn hook_message_input(message: &str) -> UserInput {
+    UserInput::Text {
+        text: hook_message_text(message),
+    }
+}
+
+pub(crate) fn hook_input(
+    event: HookTrigger,
+    thread_id: String,
+    turn_id: String,
+    cwd: &Path,
+) -> HookInput {
+    HookInput {
+        event,
+        thread_id,
+        turn_id,
+        cwd: cwd.to_path_buf(),
+        tool: None,
+        output: None,
+        input_messages: Vec::new(),
+        last_assistant_message: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn shell_hook(event: HookTrigger, script: &str) -> HookConfig {
+        HookConfig {
+            event,
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            tools: None,
+            timeout_ms: Some(5_000),
+        }
+    }
+
+    fn tool_input(cwd: &Path, arguments: Value) -> HookInput {
+        HookInput {
+            tool: Some(HookToolCall {
+                name: "shell".to_string(),
+                call_id: "call-1".to_string(),
+                arguments,
+            }),
+            ..hook_input(
+                HookTrigger::BeforeToolCall,
+                "thread".to_string(),
+                "turn".to_string(),
+                cwd,
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn hooks_can_rewrite_arguments_and_add_messages() {
+        let cwd = TempDir::new().expect("tempdir");
+        let runner = HookRunner::new(vec![
+            shell_hook(
+                HookTrigger::BeforeToolCall,
+                r#"cat > input.json; echo '{"arguments": {"command": ["echo", "rewritten"]}}'"#,
+            ),
+            shell_hook(
+                HookTrigger::BeforeToolCall,
+                r#"grep -q rewritten && echo '{"message": "saw the rewrite"}'"#,
+            ),
+        ]);
+
+        let result = runner
+            .run(tool_input(
+                cwd.path(),
+                serde_json::json!({ "command": ["echo", "hi"] }),
+            ))
+            .await;
+
+        assert_eq!(result.blocked, None);
+        assert_eq!(
+            result.arguments,
+            Some(serde_json::json!({ "command": ["echo", "rewritten"] }))
+        );
+        assert_eq!(result.messages, vec!["saw the rewrite".to_string()]);
+        let input: Value = serde_json::from_str(
+            &std::fs::read_to_string(cwd.path().join("input.json")).expect("hook input"),
+        )
+        .expect("valid json");
+        assert_eq!(input["event"], "before_tool_call");
+        assert_eq!(input["tool"]["arguments"]["command"][1], "hi");
+    }
+
+    #[tokio::test]
+    async fn exit_code_two_blocks_and_stops_the_chain() {
+        let cwd = TempDir::new().expect("tempdir");
+        let runner = HookRunner::new(vec![
+            shell_hook(
+                HookTrigger::BeforeToolCall,
+                "echo 'migrations are read-only' >&2; exit 2",
+            ),
+            shell_hook(HookTrigger::BeforeToolCall, "touch ran"),
+        ]);
+
+        let result = runner
+            .run(tool_input(cwd.path(), serde_json::json!({})))
+            .await;
+
+        assert_eq!(result.blocked.as_deref(), Some("migrations are read-only"));
+        assert_eq!(result.events.len(), 1);
+        assert_eq!(result.events[0].outcome, HookOutcome::Blocked);
+        assert!(!cwd.path().join("ran").exists());
+    }
+
+    #[tokio::test]
+    async fn failing_hooks_are_reported_and_ignored() {
+        let cwd = TempDir::new().expect("tempdir");
+        let mut slow = shell_hook(HookTrigger::BeforeToolCall, "sleep 5");
+        slow.timeout_ms = Some(100);
+        let runner = HookRunner::new(vec![
+            shell_hook(HookTrigger::BeforeToolCall, "echo not-json"),
+            slow,
+            shell_hook(HookTrigger::BeforeToolCall, "exit 1"),
+        ]);
+
+        let result = runner
+            .run(tool_input(cwd.path(), serde_json::json!({})))
+            .await;
+
+        assert_eq!(result.blocked, None);
+        assert_eq!(
+            result
+                .events
+                .iter()
+                .map(|event| event.outcome)
+                .collect::<Vec<_>>(),
+            vec![HookOutcome::Failed; 3]
+        );
+        assert_eq!(
+            result.events[1].reason.as_deref(),
+            Some("timed out after 100ms")
+        );
+    }
+
+    #[test]
+    fn tool_filter_limits_tool_hooks() {
+        let mut hook = shell_hook(HookTrigger::BeforeToolCall, "true");
+        hook.tools = Some(vec!["apply_patch".to_string()]);
+        let runner = HookRunner::new(vec![hook]);
+
+        assert!(runner.has_hooks(HookTrigger::BeforeToolCall, Some("apply_patch")));
+        assert!(!runner.has_hooks(HookTrigger::BeforeToolCall, Some("shell")));
+        assert!(!runner.has_hooks(HookTrigger::AfterToolCall, Some("apply_patch")));
+    }
+
+    #[test]
+    fn rewritten_arguments_keep_the_payload_shape() {
+        let payload = ToolPayload::Custom {
+            input: "*** Begin Patch".to_string(),
+        };
+        assert!(rewrite_payload(&payload, serde_json::json!({})).is_err());
+        assert!(matches!(
+            rewrite_payload(&payload, Value::String("patched".to_string())),
+            Ok(ToolPayload::Custom { input }) if input == "patched"
+        ));
+
+        let payload = ToolPayload::Function {
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+        };
+        assert_eq!(
+            payload_arguments(&payload),
+            serde_json::json!({ "command": ["ls"] })
+        );
+        assert!(matches!(
+            rewrite_payload(&payload, serde_json::json!({ "command": ["pwd"] })),
+            Ok(ToolPayload::Function { arguments }) if arguments == r#"{"command":["pwd"]}"#
+        ));
+    }
+}
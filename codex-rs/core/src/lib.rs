@@ -6,6 +6,7 @@
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
 mod apply_patch;
+pub mod approval_rules;
 pub mod auth;
 pub mod bash;
 mod chat_completions;
@@ -24,14 +25,17 @@ mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
+pub mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
+mod mcp_sampling;
 mod mcp_tool_call;
 mod message_history;
+mod messages_api;
 mod model_provider_info;
 pub mod parse_command;
 mod response_processing;
@@ -48,9 +52,11 @@ pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
 pub mod review_format;
+pub mod review_prompts;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
+pub use conversation_manager::user_message_positions;
 // Re-export common auth types for workspace consumers
 pub use auth::AuthManager;
 pub use auth::CodexAuth;
@@ -77,7 +83,11 @@ pub use rollout::list::ConversationsPage;
 pub use rollout::list::Cursor;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::search::SessionSearchHit;
+pub use rollout::search::SessionSearchMatch;
+pub use rollout::search::SessionSearchMatchKind;
 mod function_tool;
+mod hooks;
 mod state;
 mod tasks;
 mod user_notification;
@@ -1,9 +1,15 @@
 use std::collections::HashMap;
 
+use std::time::SystemTime;
+
 use anyhow::Result;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
 use codex_protocol::protocol::McpAuthStatus;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::determine_streamable_http_auth_status;
+use codex_rmcp_client::oauth_token_expiry;
 use futures::future::join_all;
 use tracing::warn;
 
@@ -14,6 +20,27 @@ use crate::config::types::McpServerTransportConfig;
 pub struct McpAuthStatusEntry {
     pub config: McpServerConfig,
     pub auth_status: McpAuthStatus,
+    /// When the stored OAuth access token expires, as a Unix timestamp in
+    /// seconds. Only known for servers logged in with OAuth.
+    pub expires_at: Option<i64>,
+}
+
+/// Describe `status` for display, adding when the OAuth access token expires
+/// (in local time) if that is known.
+pub fn describe_auth_status(status: McpAuthStatus, expires_at: Option<i64>) -> String {
+    let expiry = expires_at.and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0));
+    match (status, expiry) {
+        (McpAuthStatus::OAuth, Some(expiry)) => {
+            let verb = if expiry <= Utc::now() {
+                "expired"
+            } else {
+                "expires"
+            };
+            let local = expiry.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+            format!("{status} ({verb} {local})")
+        }
+        _ => status.to_string(),
+    }
 }
 
 pub async fn compute_auth_statuses<'a, I>(
@@ -34,9 +61,14 @@ where
                     McpAuthStatus::Unsupported
                 }
             };
+            let expires_at = match auth_status {
+                McpAuthStatus::OAuth => oauth_expiry(&name, &config, store_mode),
+                _ => None,
+            };
             let entry = McpAuthStatusEntry {
                 config,
                 auth_status,
+                expires_at,
             };
             (name, entry)
         }
@@ -70,3 +102,49 @@ async fn compute_auth_status(
         }
     }
 }
+
+fn oauth_expiry(
+    server_name: &str,
+    config: &McpServerConfig,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Option<i64> {
+    let McpServerTransportConfig::StreamableHttp { url, .. } = &config.transport else {
+        return None;
+    };
+    let expiry = match oauth_token_expiry(server_name, url, store_mode) {
+        Ok(expiry) => expiry?,
+        Err(error) => {
+            warn!("failed to read OAuth token expiry for MCP server `{server_name}`: {error:?}");
+            return None;
+        }
+    };
+    let seconds = expiry
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    i64::try_from(seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn describe_auth_status_includes_oauth_expiry() {
+        let now = Utc::now().timestamp();
+        assert_eq!(describe_auth_status(McpAuthStatus::OAuth, None), "OAuth");
+        assert_eq!(
+            describe_auth_status(McpAuthStatus::BearerToken, Some(now + 3600)),
+            "Bearer token"
+        );
+        assert!(
+            describe_auth_status(McpAuthStatus::OAuth, Some(now + 3600))
+                .starts_with("OAuth (expires ")
+        );
+        assert!(
+            describe_auth_status(McpAuthStatus::OAuth, Some(now - 3600))
+                .starts_with("OAuth (expired ")
+        );
+    }
+}
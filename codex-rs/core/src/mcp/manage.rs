@@ -0,0 +1,395 @@
+//! Managing the global `[mcp_servers]` table in `config.toml`, shared by
+//! `codex mcp` and the app server.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_rmcp_client::OAuthLogin;
+use tokio::sync::Mutex;
+
+use crate::config::Config;
+use crate::config::edit::ConfigEditsBuilder;
+use crate::config::load_global_mcp_servers;
+use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerTransportConfig;
+use crate::features::Feature;
+use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_connection_manager::is_valid_mcp_server_name;
+use crate::mcp_connection_manager::workspace_roots;
+
+/// Reject names that cannot be used to qualify tool names.
+pub fn validate_server_name(name: &str) -> Result<()> {
+    if is_valid_mcp_server_name(name) {
+        Ok(())
+    } else {
+        bail!("invalid server name '{name}' (use letters, numbers, '-', '_')");
+    }
+}
+
+/// Add `server` to `config.toml` as `name`, replacing any existing entry.
+pub async fn add_global_server(
+    codex_home: &Path,
+    name: &str,
+    server: McpServerConfig,
+) -> Result<()> {
+    validate_server_name(name)?;
+    let mut servers = load_servers(codex_home).await?;
+    servers.insert(name.to_string(), server);
+    write_servers(codex_home, &servers).await
+}
+
+/// Remove `name` from `config.toml`. Returns whether the server existed.
+pub async fn remove_global_server(codex_home: &Path, name: &str) -> Result<bool> {
+    validate_server_name(name)?;
+    let mut servers = load_servers(codex_home).await?;
+    let removed = servers.remove(name).is_some();
+    if removed {
+        write_servers(codex_home, &servers).await?;
+    }
+    Ok(removed)
+}
+
+/// Set `enabled` for `name` in `config.toml`. Returns whether the server
+/// existed.
+pub async fn set_global_server_enabled(
+    codex_home: &Path,
+    name: &str,
+    enabled: bool,
+) -> Result<bool> {
+    validate_server_name(name)?;
+    let mut servers = load_servers(codex_home).await?;
+    let Some(server) = servers.get_mut(name) else {
+        return Ok(false);
+    };
+    if server.enabled != enabled {
+        server.enabled = enabled;
+        write_servers(codex_home, &servers).await?;
+    }
+    Ok(true)
+}
+
+/// Where to run the OAuth flow for a configured server.
+#[derive(Debug, Clone)]
+pub struct OAuthLoginTarget {
+    pub url: String,
+    pub http_headers: Option<HashMap<String, String>>,
+    pub env_http_headers: Option<HashMap<String, String>>,
+}
+
+/// Resolve the OAuth endpoint of `name`, failing if OAuth login is disabled
+/// or the server does not use the streamable HTTP transport.
+pub fn oauth_login_target(config: &Config, name: &str) -> Result<OAuthLoginTarget> {
+    if !config.features.enabled(Feature::RmcpClient) {
+        bail!(
+            "OAuth login is disabled by [features].rmcp_client = false in config.toml. See https://github.com/openai/codex/blob/main/docs/config.md#feature-flags for details."
+        );
+    }
+
+    let Some(server) = config.mcp_servers.get(name) else {
+        bail!("No MCP server named '{name}' found.");
+    };
+
+    match &server.transport {
+        McpServerTransportConfig::StreamableHttp {
+            url,
+            http_headers,
+            env_http_headers,
+            ..
+        } => Ok(OAuthLoginTarget {
+            url: url.clone(),
+            http_headers: http_headers.clone(),
+            env_http_headers: env_http_headers.clone(),
+        }),
+        _ => bail!("OAuth login is only supported for streamable HTTP servers."),
+    }
+}
+
+/// Start the OAuth flow for `name`. The caller shows
+/// [`OAuthLogin::authorization_url`] to the user and awaits
+/// [`OAuthLogin::finish`].
+pub async fn start_oauth_login(
+    config: &Config,
+    name: &str,
+    scopes: &[String],
+) -> Result<OAuthLogin> {
+    let OAuthLoginTarget {
+        url,
+        http_headers,
+        env_http_headers,
+    } = oauth_login_target(config, name)?;
+    OAuthLogin::start(
+        name,
+        &url,
+        config.mcp_oauth_credentials_store_mode,
+        http_headers,
+        env_http_headers,
+        scopes,
+    )
+    .await
+}
+
+/// Outcome of starting a configured server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpServerProbe {
+    /// The server started and exposes this many tools after filtering.
+    Ready {
+        tool_count: usize,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// How long a failed probe is reported before the server is tried again.
+const FAILED_PROBE_RETRY: Duration = Duration::from_secs(60);
+
+/// Probe results kept between calls, so that listing servers does not start
+/// every one of them each time. A result is reused while the server's
+/// configuration is unchanged; failures are retried after
+/// [`FAILED_PROBE_RETRY`].
+#[derive(Default)]
+pub struct McpProbeCache {
+    entries: Mutex<HashMap<String, CachedProbe>>,
+}
+
+struct CachedProbe {
+    server: McpServerConfig,
+    probe: McpServerProbe,
+    probed_at: Instant,
+}
+
+impl CachedProbe {
+    fn is_current(&self, server: &McpServerConfig) -> bool {
+        self.server == *server
+            && (matches!(self.probe, McpServerProbe::Ready { .. })
+                || self.probed_at.elapsed() < FAILED_PROBE_RETRY)
+    }
+}
+
+impl McpProbeCache {
+    /// Status of every enabled server in `config`, starting only those without
+    /// a current cached result. Disabled servers are not reported.
+    pub async fn probe(&self, config: &Config) -> HashMap<String, McpServerProbe> {
+        // Held across the probe so concurrent callers wait for its result
+        // instead of starting the same servers again.
+        let mut entries = self.entries.lock().await;
+        entries.retain(|name, _| {
+            config
+                .mcp_servers
+                .get(name)
+                .is_some_and(|server| server.enabled)
+        });
+
+        let stale: HashMap<String, McpServerConfig> = config
+            .mcp_servers
+            .iter()
+            .filter(|(name, server)| {
+                server.enabled
+                    && !entries
+                        .get(*name)
+                        .is_some_and(|cached| cached.is_current(server))
+            })
+            .map(|(name, server)| (name.clone(), server.clone()))
+            .collect();
+        if !stale.is_empty() {
+            let probed_at = Instant::now();
+            for (name, probe) in probe_servers(config, stale.clone()).await {
+                if let Some(server) = stale.get(&name) {
+                    let server = server.clone();
+                    entries.insert(
+                        name,
+                        CachedProbe {
+                            server,
+                            probe,
+                            probed_at,
+                        },
+                    );
+                }
+            }
+        }
+
+        entries
+            .iter()
+            .map(|(name, cached)| (name.clone(), cached.probe.clone()))
+            .collect()
+    }
+
+    /// Forget the result for `name`, e.g. after logging in to it.
+    pub async fn invalidate(&self, name: &str) {
+        self.entries.lock().await.remove(name);
+    }
+}
+
+/// Start `servers`, record how many tools each one exposes, and shut them
+/// down again.
+async fn probe_servers(
+    config: &Config,
+    servers: HashMap<String, McpServerConfig>,
+) -> HashMap<String, McpServerProbe> {
+    let (tx_event, _rx_event) = async_channel::unbounded();
+    let (tx_sampling, _rx_sampling) = async_channel::unbounded();
+    let names: Vec<String> = servers.keys().cloned().collect();
+
+    let (manager, errors) = match McpConnectionManager::new(
+        servers,
+        config.mcp_oauth_credentials_store_mode,
+        tx_event,
+        tx_sampling,
+        workspace_roots(&config.cwd, &config.sandbox_policy),
+    )
+    .await
+    {
+        Ok(started) => started,
+        Err(err) => {
+            let error = format!("{err:#}");
+            return names
+                .into_iter()
+                .map(|name| {
+                    let probe = McpServerProbe::Failed {
+                        error: error.clone(),
+                    };
+                    (name, probe)
+                })
+                .collect();
+        }
+    };
+
+    names
+        .into_iter()
+        .map(|name| {
+            let probe = match errors.get(&name) {
+                Some(err) => McpServerProbe::Failed {
+                    error: format!("{err:#}"),
+                },
+                None => McpServerProbe::Ready {
+                    tool_count: manager.tool_count(&name),
+                },
+            };
+            (name, probe)
+        })
+        .collect()
+}
+
+async fn load_servers(codex_home: &Path) -> Result<BTreeMap<String, McpServerConfig>> {
+    load_global_mcp_servers(codex_home)
+        .await
+        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))
+}
+
+async fn write_servers(
+    codex_home: &Path,
+    servers: &BTreeMap<String, McpServerConfig>,
+) -> Result<()> {
+    ConfigEditsBuilder::new(codex_home)
+        .replace_mcp_servers(servers)
+        .apply()
+        .await
+        .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn stdio_server(command: &str) -> McpServerConfig {
+        McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: command.to_string(),
+                args: Vec::new(),
+                env: None,
+                env_vars: Vec::new(),
+                cwd: None,
+            },
+            enabled: true,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            sampling: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn add_disable_and_remove_global_server() -> Result<()> {
+        let codex_home = tempdir()?;
+
+        add_global_server(codex_home.path(), "docs", stdio_server("docs-server")).await?;
+        assert!(set_global_server_enabled(codex_home.path(), "docs", false).await?);
+        assert!(!set_global_server_enabled(codex_home.path(), "missing", false).await?);
+
+        let servers = load_global_mcp_servers(codex_home.path()).await?;
+        let mut expected = stdio_server("docs-server");
+        expected.enabled = false;
+        assert_eq!(servers.get("docs"), Some(&expected));
+
+        assert!(remove_global_server(codex_home.path(), "docs").await?);
+        assert!(!remove_global_server(codex_home.path(), "docs").await?);
+        assert!(load_global_mcp_servers(codex_home.path()).await?.is_empty());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn probe_cache_reuses_results_until_invalidated() -> Result<()> {
+        use crate::config::ConfigOverrides;
+        use crate::config::ConfigToml;
+
+        let codex_home = tempdir()?;
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        // Not an MCP server: it records that it was started and exits.
+        let starts = codex_home.path().join("starts");
+        let mut server = stdio_server("sh");
+        if let McpServerTransportConfig::Stdio { args, .. } = &mut server.transport {
+            *args = vec![
+                "-c".to_string(),
+                format!("echo started >> '{}'", starts.display()),
+            ];
+        }
+        config.mcp_servers = HashMap::from([("broken".to_string(), server)]);
+        let start_count = || {
+            std::fs::read_to_string(&starts)
+                .map(|contents| contents.lines().count())
+                .unwrap_or_default()
+        };
+
+        let cache = McpProbeCache::default();
+        let probes = cache.probe(&config).await;
+        assert!(matches!(
+            probes.get("broken"),
+            Some(McpServerProbe::Failed { .. })
+        ));
+        assert_eq!(start_count(), 1);
+
+        assert_eq!(cache.probe(&config).await, probes);
+        assert_eq!(start_count(), 1);
+
+        cache.invalidate("broken").await;
+        cache.probe(&config).await;
+        assert_eq!(start_count(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_global_server_rejects_invalid_names() {
+        let codex_home = tempdir().expect("tempdir");
+        let err = add_global_server(codex_home.path(), "bad name", stdio_server("x"))
+            .await
+            .expect_err("invalid name");
+        assert_eq!(
+            err.to_string(),
+            "invalid server name 'bad name' (use letters, numbers, '-', '_')"
+        );
+    }
+}
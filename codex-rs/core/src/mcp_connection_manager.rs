@@ -5,40 +5,74 @@
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
 //! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//!
+//! Servers can be restarted, enabled and disabled while the session runs.
+//! Crashed stdio servers and servers that announce a new tool list are picked
+//! up by [`McpConnectionManager::refresh`], which runs before every turn.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::Path;
 use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
+use async_channel::Sender;
+use codex_protocol::protocol::ElicitationAction;
+use codex_protocol::protocol::ElicitationRequestEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpServerUpdate;
+use codex_protocol::protocol::McpServerUpdatedEvent;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_rmcp_client::ListRoots;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use futures::FutureExt;
 use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::ElicitResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
+use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::Root;
 use mcp_types::Tool;
 
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
 use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
+use url::Url;
 
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::mcp_sampling::SamplingRequest;
+use crate::mcp_sampling::make_sampling_sender;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -54,10 +88,57 @@ pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Event id used for elicitation requests, which arrive outside of any
+/// particular submission.
+const ELICITATION_REQUEST_EVENT_ID: &str = "mcp_elicitation_request";
+
+/// Event id used to report servers that were restarted after they crashed.
+const MCP_SERVER_UPDATE_EVENT_ID: &str = "mcp_server_update";
+
+/// Delay before the second attempt to restart a crashed stdio server; doubles
+/// after every failed attempt up to [`RECONNECT_MAX_BACKOFF`].
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Crashed servers are left stopped after this many failed restarts.
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
 /// Map that holds a startup error for every MCP server that could **not** be
 /// spawned successfully.
 pub type ClientStartErrors = HashMap<String, anyhow::Error>;
 
+/// Build the roots advertised to MCP servers: the session cwd followed by any
+/// extra writable roots granted by the sandbox policy.
+pub(crate) fn workspace_roots(cwd: &Path, sandbox_policy: &SandboxPolicy) -> Vec<Root> {
+    let mut paths = vec![cwd];
+    if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = sandbox_policy {
+        for root in writable_roots {
+            if !paths.contains(&root.as_path()) {
+                paths.push(root);
+            }
+        }
+    }
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let uri = match Url::from_file_path(path) {
+                Ok(uri) => uri,
+                Err(()) => {
+                    warn!("skipping MCP root that is not an absolute path: {path:?}");
+                    return None;
+                }
+            };
+            Some(Root {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                uri: uri.to_string(),
+            })
+        })
+        .collect()
+}
+
 fn qualify_tools(tools: Vec<ToolInfo>) -> HashMap<String, ToolInfo> {
     let mut used_names = HashSet::new();
     let mut qualified_tools = HashMap::new();
@@ -90,21 +171,329 @@ fn qualify_tools(tools: Vec<ToolInfo>) -> HashMap<String, ToolInfo> {
     qualified_tools
 }
 
+#[derive(Clone)]
 struct ToolInfo {
     server_name: String,
     tool_name: String,
     tool: Tool,
 }
 
+#[derive(Clone)]
 struct ManagedClient {
     client: Arc<RmcpClient>,
     startup_timeout: Duration,
     tool_timeout: Option<Duration>,
+    /// Whether the server advertised the `prompts` capability.
+    supports_prompts: bool,
+    /// Stdio servers are restarted automatically when their process exits.
+    is_stdio: bool,
+    /// Tools returned by the server's latest `tools/list`.
+    tools: Vec<Tool>,
 }
 
-/// A thin wrapper around a set of running [`RmcpClient`] instances.
+type PendingElicitations = HashMap<(String, RequestId), oneshot::Sender<ElicitResult>>;
+
+/// Tracks elicitation requests that are waiting for the user to answer.
+///
+/// Each request is surfaced as an [`EventMsg::ElicitationRequest`] and parked
+/// until a matching `Op::ResolveElicitation` arrives.
+#[derive(Clone)]
+struct ElicitationRequestManager {
+    pending: Arc<Mutex<PendingElicitations>>,
+    /// Signalled whenever a request is parked or answered so tool calls can
+    /// stop their timeout while the user is being asked.
+    changed: Arc<watch::Sender<()>>,
+}
+
+impl Default for ElicitationRequestManager {
+    fn default() -> Self {
+        Self {
+            pending: Arc::default(),
+            changed: Arc::new(watch::channel(()).0),
+        }
+    }
+}
+
+impl ElicitationRequestManager {
+    fn make_sender(&self, server_name: String, tx_event: Sender<Event>) -> SendElicitation {
+        let pending = Arc::clone(&self.pending);
+        let changed = Arc::clone(&self.changed);
+        Arc::new(move |id, params| {
+            let pending = Arc::clone(&pending);
+            let changed = Arc::clone(&changed);
+            let server_name = server_name.clone();
+            let tx_event = tx_event.clone();
+            async move {
+                let (tx, rx) = oneshot::channel();
+                pending
+                    .lock()
+                    .await
+                    .insert((server_name.clone(), id.clone()), tx);
+                changed.send_replace(());
+
+                let event = Event {
+                    id: ELICITATION_REQUEST_EVENT_ID.to_string(),
+                    msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                        server_name: server_name.clone(),
+                        id: id.clone(),
+                        message: params.message,
+                        requested_schema: params.requested_schema,
+                    }),
+                };
+                if tx_event.send(event).await.is_err() {
+                    pending.lock().await.remove(&(server_name, id));
+                    changed.send_replace(());
+                    return Err(anyhow!("session closed before elicitation could be shown"));
+                }
+
+                rx.await
+                    .map_err(|_| anyhow!("elicitation request was dropped before it was answered"))
+            }
+            .boxed()
+        })
+    }
+
+    async fn resolve(
+        &self,
+        server_name: String,
+        id: RequestId,
+        response: ElicitResult,
+    ) -> Result<()> {
+        let tx = self
+            .pending
+            .lock()
+            .await
+            .remove(&(server_name.clone(), id.clone()))
+            .ok_or_else(|| {
+                anyhow!("no pending elicitation request {id:?} for MCP server '{server_name}'")
+            })?;
+        self.changed.send_replace(());
+        tx.send(response)
+            .map_err(|_| anyhow!("MCP server '{server_name}' is no longer waiting for {id:?}"))
+    }
+
+    /// Answer every pending request with `cancel`, e.g. because the user
+    /// interrupted the turn that was waiting on them.
+    async fn cancel_all(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().await);
+        if pending.is_empty() {
+            return;
+        }
+        self.changed.send_replace(());
+        for ((server_name, id), tx) in pending {
+            let response = ElicitResult {
+                action: ElicitationAction::Cancel.to_string(),
+                content: None,
+            };
+            if tx.send(response).is_err() {
+                warn!("MCP server '{server_name}' is no longer waiting for {id:?}");
+            }
+        }
+    }
+
+    async fn has_pending(&self, server_name: &str) -> bool {
+        self.pending
+            .lock()
+            .await
+            .keys()
+            .any(|(server, _)| server == server_name)
+    }
+
+    /// Await `call`, failing once it has run for `timeout` without counting
+    /// the time `server_name` spends waiting for the user to answer an
+    /// elicitation.
+    async fn run_with_paused_timeout<T>(
+        &self,
+        server_name: &str,
+        call: impl Future<Output = Result<T>>,
+        timeout: Option<Duration>,
+    ) -> Result<T> {
+        let Some(timeout) = timeout else {
+            return call.await;
+        };
+        let mut changed = self.changed.subscribe();
+        let mut remaining = timeout;
+        tokio::pin!(call);
+        loop {
+            if self.has_pending(server_name).await {
+                tokio::select! {
+                    result = &mut call => return result,
+                    _ = changed.changed() => continue,
+                }
+            }
+            let started = Instant::now();
+            tokio::select! {
+                result = &mut call => return result,
+                _ = tokio::time::sleep(remaining) => {
+                    return Err(anyhow!("timed out awaiting tools/call after {timeout:?}"));
+                }
+                _ = changed.changed() => {
+                    remaining = remaining.saturating_sub(started.elapsed());
+                }
+            }
+        }
+    }
+}
+
+fn make_roots_lister(roots: Arc<RwLock<Vec<Root>>>) -> ListRoots {
+    Arc::new(move || {
+        #[expect(clippy::expect_used)]
+        roots.read().expect("MCP roots lock poisoned").clone()
+    })
+}
+
+/// Delay before the next automatic restart of a crashed stdio server after
+/// `failures` consecutive failed attempts.
+fn reconnect_delay(failures: u32) -> Duration {
+    if failures == 0 {
+        return Duration::ZERO;
+    }
+    RECONNECT_INITIAL_BACKOFF
+        .saturating_mul(1 << (failures - 1).min(16))
+        .min(RECONNECT_MAX_BACKOFF)
+}
+
+/// Failed automatic restarts of a crashed stdio server.
+#[derive(Clone, Copy)]
+struct ReconnectBackoff {
+    failures: u32,
+    not_before: Instant,
+}
+
+/// Everything needed to start a server, kept so that servers can be started
+/// again after the manager was created.
+#[derive(Clone)]
+struct ServerLauncher {
+    store_mode: OAuthCredentialsStoreMode,
+    tx_event: Sender<Event>,
+    tx_sampling: Sender<SamplingRequest>,
+    elicitation_requests: ElicitationRequestManager,
+    roots: Arc<RwLock<Vec<Root>>>,
+}
+
+impl ServerLauncher {
+    /// Spawn (or connect to) the server, perform the initialization
+    /// handshake and list its tools.
+    async fn start(&self, server_name: &str, cfg: McpServerConfig) -> Result<ManagedClient> {
+        let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+        let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
+        let send_elicitation = self
+            .elicitation_requests
+            .make_sender(server_name.to_string(), self.tx_event.clone());
+        let send_sampling = cfg.sampling.map(|policy| {
+            make_sampling_sender(server_name.to_string(), policy, self.tx_sampling.clone())
+        });
+        let params = mcp_types::InitializeRequestParams {
+            capabilities: ClientCapabilities {
+                experimental: None,
+                roots: Some(ClientCapabilitiesRoots {
+                    list_changed: Some(true),
+                }),
+                // Only offered to servers the user allowed to sample.
+                sampling: send_sampling.as_ref().map(|_| json!({})),
+                // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
+                // indicates this should be an empty object.
+                elicitation: Some(json!({})),
+            },
+            client_info: Implementation {
+                name: "codex-mcp-client".to_owned(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+                title: Some("Codex".into()),
+                // This field is used by Codex when it is an MCP server: it
+                // should not be used when Codex is an MCP client.
+                user_agent: None,
+            },
+            protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
+        };
+
+        let McpServerConfig { transport, .. } = cfg;
+        let is_stdio = matches!(transport, McpServerTransportConfig::Stdio { .. });
+        let client = match transport {
+            McpServerTransportConfig::Stdio {
+                command,
+                args,
+                env,
+                env_vars,
+                cwd,
+            } => {
+                let command_os: OsString = command.into();
+                let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
+                RmcpClient::new_stdio_client(command_os, args_os, env, &env_vars, cwd).await?
+            }
+            McpServerTransportConfig::StreamableHttp {
+                url,
+                bearer_token_env_var,
+                http_headers,
+                env_http_headers,
+            } => {
+                let bearer_token =
+                    resolve_bearer_token(server_name, bearer_token_env_var.as_deref())
+                        .unwrap_or_default();
+                RmcpClient::new_streamable_http_client(
+                    server_name,
+                    &url,
+                    bearer_token,
+                    http_headers,
+                    env_http_headers,
+                    self.store_mode,
+                )
+                .await?
+            }
+        };
+
+        let client = Arc::new(client);
+        let initialize_result = client
+            .initialize(
+                params,
+                Some(startup_timeout),
+                send_elicitation,
+                send_sampling,
+                Some(make_roots_lister(Arc::clone(&self.roots))),
+            )
+            .await?;
+        let tools = match list_server_tools(&client, startup_timeout).await {
+            Ok(tools) => tools,
+            Err(err) => {
+                warn!("Failed to list tools for MCP server '{server_name}': {err:#}");
+                Vec::new()
+            }
+        };
+
+        Ok(ManagedClient {
+            client,
+            startup_timeout,
+            tool_timeout: Some(tool_timeout),
+            supports_prompts: initialize_result.capabilities.prompts.is_some(),
+            is_stdio,
+            tools,
+        })
+    }
+
+    /// Report the outcome of a restart that the user did not ask for.
+    async fn notify_update(
+        &self,
+        server_name: &str,
+        update: McpServerUpdate,
+        result: Result<usize, String>,
+    ) {
+        let event = Event {
+            id: MCP_SERVER_UPDATE_EVENT_ID.to_string(),
+            msg: EventMsg::McpServerUpdated(McpServerUpdatedEvent {
+                server: server_name.to_string(),
+                update,
+                result,
+            }),
+        };
+        if let Err(err) = self.tx_event.send(event).await {
+            warn!("failed to send MCP server update event: {err}");
+        }
+    }
+}
+
+/// The parts of [`McpConnectionManager`] that change when servers are
+/// restarted or report new tools.
 #[derive(Default)]
-pub(crate) struct McpConnectionManager {
+struct ManagerState {
     /// Server-name -> client instance.
     ///
     /// The server name originates from the keys of the `mcp_servers` map in
@@ -116,6 +505,64 @@ pub(crate) struct McpConnectionManager {
 
     /// Server-name -> configured tool filters.
     tool_filters: HashMap<String, ToolFilter>,
+
+    /// Server-name -> configuration used to (re)start the server. `enabled`
+    /// reflects the current session rather than `config.toml`.
+    configs: HashMap<String, McpServerConfig>,
+
+    /// Server-name -> failed automatic restarts since the server crashed.
+    reconnects: HashMap<String, ReconnectBackoff>,
+}
+
+impl ManagerState {
+    /// Recompute `tools` from the tools every client reported last.
+    fn rebuild_tools(&mut self) {
+        let all_tools = self
+            .clients
+            .iter()
+            .flat_map(|(server_name, managed_client)| {
+                managed_client.tools.iter().map(move |tool| ToolInfo {
+                    server_name: server_name.clone(),
+                    tool_name: tool.name.clone(),
+                    tool: tool.clone(),
+                })
+            })
+            .collect();
+        let filtered_tools = filter_tools(all_tools, &self.tool_filters);
+        self.tools = qualify_tools(filtered_tools);
+        info!(
+            "aggregated {} tools from {} servers",
+            self.tools.len(),
+            self.clients.len()
+        );
+    }
+
+    fn tool_count(&self, server_name: &str) -> usize {
+        self.tools
+            .values()
+            .filter(|tool| tool.server_name == server_name)
+            .count()
+    }
+}
+
+/// A thin wrapper around a set of running [`RmcpClient`] instances.
+#[derive(Default)]
+pub(crate) struct McpConnectionManager {
+    state: RwLock<ManagerState>,
+
+    /// Starts servers after construction. `None` when the manager was not
+    /// created through [`Self::new`].
+    launcher: Option<ServerLauncher>,
+
+    /// Held while a server is being (re)started so that a crashed server is
+    /// only restarted once.
+    restart_lock: Mutex<()>,
+
+    /// Elicitation requests awaiting an answer from the user.
+    elicitation_requests: ElicitationRequestManager,
+
+    /// Roots returned to servers that send `roots/list`.
+    roots: Arc<RwLock<Vec<Root>>>,
 }
 
 impl McpConnectionManager {
@@ -127,182 +574,388 @@ impl McpConnectionManager {
     ///
     /// Servers that fail to start are reported in `ClientStartErrors`: the
     /// user should be informed about these errors.
+    ///
+    /// Elicitation requests from any server are forwarded on `tx_event`;
+    /// sampling requests from servers that allow it are queued on
+    /// `tx_sampling`. `roots` is what every server sees in `roots/list` until
+    /// [`Self::update_roots`] replaces it.
     pub async fn new(
         mcp_servers: HashMap<String, McpServerConfig>,
         store_mode: OAuthCredentialsStoreMode,
+        tx_event: Sender<Event>,
+        tx_sampling: Sender<SamplingRequest>,
+        roots: Vec<Root>,
     ) -> Result<(Self, ClientStartErrors)> {
-        // Early exit if no servers are configured.
-        if mcp_servers.is_empty() {
-            return Ok((Self::default(), ClientStartErrors::default()));
-        }
+        let roots = Arc::new(RwLock::new(roots));
+        let elicitation_requests = ElicitationRequestManager::default();
+        let launcher = ServerLauncher {
+            store_mode,
+            tx_event,
+            tx_sampling,
+            elicitation_requests: elicitation_requests.clone(),
+            roots: Arc::clone(&roots),
+        };
 
         // Launch all configured servers concurrently.
         let mut join_set = JoinSet::new();
         let mut errors = ClientStartErrors::new();
-        let mut tool_filters: HashMap<String, ToolFilter> = HashMap::new();
+        let mut state = ManagerState::default();
 
         for (server_name, cfg) in mcp_servers {
             // Validate server name before spawning
             if !is_valid_mcp_server_name(&server_name) {
-                let error = anyhow::anyhow!(
-                    "invalid server name '{server_name}': must match pattern ^[a-zA-Z0-9_-]+$"
-                );
+                let error = invalid_server_name_error(&server_name);
                 errors.insert(server_name, error);
                 continue;
             }
 
+            state
+                .tool_filters
+                .insert(server_name.clone(), ToolFilter::from_config(&cfg));
+            state.configs.insert(server_name.clone(), cfg.clone());
             if !cfg.enabled {
-                tool_filters.insert(server_name, ToolFilter::from_config(&cfg));
                 continue;
             }
 
-            let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
-            let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
-            tool_filters.insert(server_name.clone(), ToolFilter::from_config(&cfg));
-
-            let resolved_bearer_token = match &cfg.transport {
-                McpServerTransportConfig::StreamableHttp {
-                    bearer_token_env_var,
-                    ..
-                } => resolve_bearer_token(&server_name, bearer_token_env_var.as_deref()),
-                _ => Ok(None),
-            };
-
+            let launcher = launcher.clone();
             join_set.spawn(async move {
-                let McpServerConfig { transport, .. } = cfg;
-                let params = mcp_types::InitializeRequestParams {
-                    capabilities: ClientCapabilities {
-                        experimental: None,
-                        roots: None,
-                        sampling: None,
-                        // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
-                        // indicates this should be an empty object.
-                        elicitation: Some(json!({})),
-                    },
-                    client_info: Implementation {
-                        name: "codex-mcp-client".to_owned(),
-                        version: env!("CARGO_PKG_VERSION").to_owned(),
-                        title: Some("Codex".into()),
-                        // This field is used by Codex when it is an MCP
-                        // server: it should not be used when Codex is
-                        // an MCP client.
-                        user_agent: None,
-                    },
-                    protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
-                };
-
-                let resolved_bearer_token = resolved_bearer_token.unwrap_or_default();
-                let client_result = match transport {
-                    McpServerTransportConfig::Stdio {
-                        command,
-                        args,
-                        env,
-                        env_vars,
-                        cwd,
-                    } => {
-                        let command_os: OsString = command.into();
-                        let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
-                        match RmcpClient::new_stdio_client(command_os, args_os, env, &env_vars, cwd)
-                            .await
-                        {
-                            Ok(client) => {
-                                let client = Arc::new(client);
-                                client
-                                    .initialize(params.clone(), Some(startup_timeout))
-                                    .await
-                                    .map(|_| client)
-                            }
-                            Err(err) => Err(err.into()),
-                        }
-                    }
-                    McpServerTransportConfig::StreamableHttp {
-                        url,
-                        http_headers,
-                        env_http_headers,
-                        ..
-                    } => {
-                        match RmcpClient::new_streamable_http_client(
-                            &server_name,
-                            &url,
-                            resolved_bearer_token.clone(),
-                            http_headers,
-                            env_http_headers,
-                            store_mode,
-                        )
-                        .await
-                        {
-                            Ok(client) => {
-                                let client = Arc::new(client);
-                                client
-                                    .initialize(params.clone(), Some(startup_timeout))
-                                    .await
-                                    .map(|_| client)
-                            }
-                            Err(err) => Err(err),
-                        }
-                    }
-                };
-
-                (
-                    (server_name, tool_timeout),
-                    client_result.map(|client| (client, startup_timeout)),
-                )
+                let result = launcher.start(&server_name, cfg).await;
+                (server_name, result)
             });
         }
 
-        let mut clients: HashMap<String, ManagedClient> = HashMap::with_capacity(join_set.len());
-
         while let Some(res) = join_set.join_next().await {
-            let ((server_name, tool_timeout), client_res) = match res {
-                Ok(result) => result,
+            match res {
+                Ok((server_name, Ok(managed_client))) => {
+                    state.clients.insert(server_name, managed_client);
+                }
+                Ok((server_name, Err(e))) => {
+                    errors.insert(server_name, e);
+                }
                 Err(e) => {
                     warn!("Task panic when starting MCP server: {e:#}");
-                    continue;
                 }
+            }
+        }
+        state.rebuild_tools();
+
+        Ok((
+            Self {
+                state: RwLock::new(state),
+                launcher: Some(launcher),
+                restart_lock: Mutex::default(),
+                elicitation_requests,
+                roots,
+            },
+            errors,
+        ))
+    }
+
+    fn read_state(&self) -> RwLockReadGuard<'_, ManagerState> {
+        #[expect(clippy::expect_used)]
+        self.state.read().expect("MCP manager state lock poisoned")
+    }
+
+    fn write_state(&self) -> RwLockWriteGuard<'_, ManagerState> {
+        #[expect(clippy::expect_used)]
+        self.state.write().expect("MCP manager state lock poisoned")
+    }
+
+    fn client(&self, server: &str) -> Result<ManagedClient> {
+        self.read_state()
+            .clients
+            .get(server)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))
+    }
+
+    fn clients(&self) -> Vec<(String, ManagedClient)> {
+        self.read_state()
+            .clients
+            .iter()
+            .map(|(server_name, managed_client)| (server_name.clone(), managed_client.clone()))
+            .collect()
+    }
+
+    fn launcher(&self) -> Result<&ServerLauncher> {
+        self.launcher
+            .as_ref()
+            .ok_or_else(|| anyhow!("MCP servers are not available in this session"))
+    }
+
+    /// Replace the roots reported to servers and send
+    /// `notifications/roots/list_changed` to every server if they differ from
+    /// the current ones.
+    pub async fn update_roots(&self, roots: Vec<Root>) {
+        {
+            #[expect(clippy::expect_used)]
+            let mut current = self.roots.write().expect("MCP roots lock poisoned");
+            if *current == roots {
+                return;
+            }
+            *current = roots;
+        }
+
+        for (server_name, managed_client) in self.clients() {
+            if let Err(err) = managed_client.client.notify_roots_list_changed().await {
+                warn!("Failed to notify MCP server '{server_name}' of roots change: {err:#}");
+            }
+        }
+    }
+
+    /// Bring the tool set up to date between turns: restart stdio servers
+    /// whose process exited and list the tools again for servers that sent
+    /// `notifications/tools/list_changed`.
+    pub async fn refresh(&self) {
+        for (server_name, _) in self.clients() {
+            self.reconnect_if_crashed(&server_name).await;
+        }
+
+        let changed: Vec<(String, ManagedClient)> = self
+            .clients()
+            .into_iter()
+            .filter(|(_, managed_client)| managed_client.client.take_tool_list_changed())
+            .collect();
+        if changed.is_empty() {
+            return;
+        }
+
+        for (server_name, managed_client) in changed {
+            let tools =
+                match list_server_tools(&managed_client.client, managed_client.startup_timeout)
+                    .await
+                {
+                    Ok(tools) => tools,
+                    Err(err) => {
+                        warn!("Failed to list tools for MCP server '{server_name}': {err:#}");
+                        continue;
+                    }
+                };
+            info!("MCP server '{server_name}' now lists {} tools", tools.len());
+            let mut state = self.write_state();
+            if let Some(current) = state.clients.get_mut(&server_name)
+                && Arc::ptr_eq(&current.client, &managed_client.client)
+            {
+                current.tools = tools;
+            }
+        }
+        self.write_state().rebuild_tools();
+    }
+
+    /// Restart `server_name` if it is a stdio server whose process exited.
+    ///
+    /// Failed attempts back off exponentially and are abandoned after
+    /// [`MAX_RECONNECT_ATTEMPTS`]; [`Self::restart_server`] starts over.
+    async fn reconnect_if_crashed(&self, server_name: &str) {
+        let Some(launcher) = &self.launcher else {
+            return;
+        };
+        let Ok(managed_client) = self.client(server_name) else {
+            return;
+        };
+        if !managed_client.is_stdio || !managed_client.client.is_closed().await {
+            return;
+        }
+
+        let _restart = self.restart_lock.lock().await;
+        let cfg = {
+            let state = self.read_state();
+            // Someone else may have restarted the server while we waited.
+            match state.clients.get(server_name) {
+                Some(current) if Arc::ptr_eq(&current.client, &managed_client.client) => {}
+                _ => return,
+            }
+            if let Some(backoff) = state.reconnects.get(server_name)
+                && (backoff.failures >= MAX_RECONNECT_ATTEMPTS
+                    || Instant::now() < backoff.not_before)
+            {
+                return;
+            }
+            let Some(cfg) = state.configs.get(server_name).cloned() else {
+                return;
             };
+            cfg
+        };
 
-            match client_res {
-                Ok((client, startup_timeout)) => {
-                    clients.insert(
-                        server_name,
-                        ManagedClient {
-                            client,
-                            startup_timeout,
-                            tool_timeout: Some(tool_timeout),
+        warn!("MCP server '{server_name}' exited; restarting it");
+        let started = launcher.start(server_name, cfg).await;
+        let result = {
+            let mut state = self.write_state();
+            match started {
+                Ok(managed_client) => {
+                    state.reconnects.remove(server_name);
+                    state
+                        .clients
+                        .insert(server_name.to_string(), managed_client);
+                    state.rebuild_tools();
+                    Ok(state.tool_count(server_name))
+                }
+                Err(err) => {
+                    let failures = state
+                        .reconnects
+                        .get(server_name)
+                        .map_or(0, |backoff| backoff.failures)
+                        + 1;
+                    state.reconnects.insert(
+                        server_name.to_string(),
+                        ReconnectBackoff {
+                            failures,
+                            not_before: Instant::now() + reconnect_delay(failures),
                         },
                     );
-                }
-                Err(e) => {
-                    errors.insert(server_name, e);
+                    warn!(
+                        "Failed to restart MCP server '{server_name}' (attempt {failures}): {err:#}"
+                    );
+                    Err(format!("{err:#}"))
                 }
             }
+        };
+        launcher
+            .notify_update(server_name, McpServerUpdate::Reconnected, result)
+            .await;
+    }
+
+    /// Stop `server_name` and start it again. When given, `config` replaces
+    /// the configuration the server was started with (e.g. after
+    /// `config.toml` was edited). Returns the number of tools the server
+    /// exposes afterwards.
+    pub async fn restart_server(
+        &self,
+        server_name: &str,
+        config: Option<McpServerConfig>,
+    ) -> Result<usize> {
+        let launcher = self.launcher()?;
+        let _restart = self.restart_lock.lock().await;
+        let cfg = self.resolve_config(server_name, config)?;
+        if !cfg.enabled {
+            return Err(anyhow!("MCP server '{server_name}' is disabled"));
         }
+        self.start_server(launcher, server_name, cfg).await
+    }
 
-        let all_tools = match list_all_tools(&clients).await {
-            Ok(tools) => tools,
-            Err(e) => {
-                warn!("Failed to list tools from some MCP servers: {e:#}");
-                Vec::new()
+    /// Start or stop `server_name` for the rest of the session. Returns the
+    /// number of tools the server exposes afterwards.
+    pub async fn set_server_enabled(
+        &self,
+        server_name: &str,
+        enabled: bool,
+        config: Option<McpServerConfig>,
+    ) -> Result<usize> {
+        let launcher = self.launcher()?;
+        let _restart = self.restart_lock.lock().await;
+        let mut cfg = self.resolve_config(server_name, config)?;
+        cfg.enabled = enabled;
+
+        if !enabled {
+            let mut state = self.write_state();
+            state.clients.remove(server_name);
+            state.reconnects.remove(server_name);
+            state.configs.insert(server_name.to_string(), cfg);
+            state.rebuild_tools();
+            return Ok(0);
+        }
+
+        if let Ok(managed_client) = self.client(server_name)
+            && !managed_client.client.is_closed().await
+        {
+            let mut state = self.write_state();
+            state.configs.insert(server_name.to_string(), cfg);
+            return Ok(state.tool_count(server_name));
+        }
+        self.start_server(launcher, server_name, cfg).await
+    }
+
+    /// Pick the configuration to start `server_name` with: `config` if given,
+    /// keeping the session's `enabled` state, otherwise the one in use.
+    fn resolve_config(
+        &self,
+        server_name: &str,
+        config: Option<McpServerConfig>,
+    ) -> Result<McpServerConfig> {
+        if !is_valid_mcp_server_name(server_name) {
+            return Err(invalid_server_name_error(server_name));
+        }
+        let state = self.read_state();
+        match (config, state.configs.get(server_name)) {
+            (Some(mut cfg), Some(current)) => {
+                cfg.enabled = current.enabled;
+                Ok(cfg)
             }
-        };
+            (Some(cfg), None) => Ok(cfg),
+            (None, Some(current)) => Ok(current.clone()),
+            (None, None) => Err(anyhow!("unknown MCP server '{server_name}'")),
+        }
+    }
+
+    /// Replace any running instance of `server_name` with a fresh one started
+    /// from `cfg`. Callers must hold `restart_lock`.
+    async fn start_server(
+        &self,
+        launcher: &ServerLauncher,
+        server_name: &str,
+        cfg: McpServerConfig,
+    ) -> Result<usize> {
+        // Stop the old instance first so that servers holding exclusive
+        // resources (ports, lock files) can start again.
+        {
+            let mut state = self.write_state();
+            state.clients.remove(server_name);
+            state.reconnects.remove(server_name);
+            state
+                .tool_filters
+                .insert(server_name.to_string(), ToolFilter::from_config(&cfg));
+            state.configs.insert(server_name.to_string(), cfg.clone());
+            state.rebuild_tools();
+        }
 
-        let filtered_tools = filter_tools(all_tools, &tool_filters);
-        let tools = qualify_tools(filtered_tools);
+        let managed_client = launcher.start(server_name, cfg).await?;
+        let mut state = self.write_state();
+        state
+            .clients
+            .insert(server_name.to_string(), managed_client);
+        state.rebuild_tools();
+        Ok(state.tool_count(server_name))
+    }
 
-        Ok((
-            Self {
-                clients,
-                tools,
-                tool_filters,
-            },
-            errors,
-        ))
+    /// Cancel every elicitation request that is still waiting for the user.
+    pub async fn cancel_elicitations(&self) {
+        self.elicitation_requests.cancel_all().await;
+    }
+
+    /// Deliver the user's answer to a pending elicitation request.
+    pub async fn resolve_elicitation(
+        &self,
+        server_name: String,
+        id: RequestId,
+        action: ElicitationAction,
+        content: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let content = match action {
+            ElicitationAction::Accept => content,
+            ElicitationAction::Decline | ElicitationAction::Cancel => None,
+        };
+        self.elicitation_requests
+            .resolve(
+                server_name,
+                id,
+                ElicitResult {
+                    action: action.to_string(),
+                    content,
+                },
+            )
+            .await
+    }
+
+    /// Number of tools `server_name` exposes after filtering.
+    pub fn tool_count(&self, server_name: &str) -> usize {
+        self.read_state().tool_count(server_name)
     }
 
     /// Returns a single map that contains all tools. Each key is the
     /// fully-qualified name for the tool.
     pub fn list_all_tools(&self) -> HashMap<String, Tool> {
-        self.tools
+        self.read_state()
+            .tools
             .iter()
             .map(|(name, tool)| (name.clone(), tool.tool.clone()))
             .collect()
@@ -313,8 +966,8 @@ impl McpConnectionManager {
     pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
         let mut join_set = JoinSet::new();
 
-        for (server_name, managed_client) in &self.clients {
-            let server_name_cloned = server_name.clone();
+        for (server_name, managed_client) in self.clients() {
+            let server_name_cloned = server_name;
             let client_clone = managed_client.client.clone();
             let timeout = managed_client.tool_timeout;
 
@@ -373,8 +1026,8 @@ impl McpConnectionManager {
     pub async fn list_all_resource_templates(&self) -> HashMap<String, Vec<ResourceTemplate>> {
         let mut join_set = JoinSet::new();
 
-        for (server_name, managed_client) in &self.clients {
-            let server_name_cloned = server_name.clone();
+        for (server_name, managed_client) in self.clients() {
+            let server_name_cloned = server_name;
             let client_clone = managed_client.client.clone();
             let timeout = managed_client.tool_timeout;
 
@@ -435,6 +1088,70 @@ impl McpConnectionManager {
         aggregated
     }
 
+    /// Returns a single map that contains all prompts. Each key is the server
+    /// name and the value is a vector of prompts. Servers that did not
+    /// advertise the `prompts` capability are skipped.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+
+        for (server_name, managed_client) in self.clients() {
+            if !managed_client.supports_prompts {
+                continue;
+            }
+            let server_name_cloned = server_name;
+            let client_clone = managed_client.client.clone();
+            let timeout = managed_client.tool_timeout;
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client_clone.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name_cloned, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name_cloned,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => return (server_name_cloned, Ok(collected)),
+                    }
+                }
+            });
+        }
+
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
     /// Invoke the tool indicated by the (server, tool) pair.
     pub async fn call_tool(
         &self,
@@ -442,22 +1159,29 @@ impl McpConnectionManager {
         tool: &str,
         arguments: Option<serde_json::Value>,
     ) -> Result<mcp_types::CallToolResult> {
-        if let Some(filter) = self.tool_filters.get(server)
-            && !filter.allows(tool)
-        {
+        let allowed = self
+            .read_state()
+            .tool_filters
+            .get(server)
+            .is_none_or(|filter| filter.allows(tool));
+        if !allowed {
             return Err(anyhow!(
                 "tool '{tool}' is disabled for MCP server '{server}'"
             ));
         }
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        self.reconnect_if_crashed(server).await;
+        let managed = self.client(server)?;
         let client = &managed.client;
         let timeout = managed.tool_timeout;
 
-        client
-            .call_tool(tool.to_string(), arguments, timeout)
+        // The timeout is applied here rather than by the client so that it
+        // can be paused while the server waits on an elicitation.
+        self.elicitation_requests
+            .run_with_paused_timeout(
+                server,
+                client.call_tool(tool.to_string(), arguments, None),
+                timeout,
+            )
             .await
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
@@ -468,10 +1192,7 @@ impl McpConnectionManager {
         server: &str,
         params: Option<ListResourcesRequestParams>,
     ) -> Result<ListResourcesResult> {
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let managed = self.client(server)?;
         let client = managed.client.clone();
         let timeout = managed.tool_timeout;
 
@@ -487,10 +1208,7 @@ impl McpConnectionManager {
         server: &str,
         params: Option<ListResourceTemplatesRequestParams>,
     ) -> Result<ListResourceTemplatesResult> {
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let managed = self.client(server)?;
         let client = managed.client.clone();
         let timeout = managed.tool_timeout;
 
@@ -506,10 +1224,7 @@ impl McpConnectionManager {
         server: &str,
         params: ReadResourceRequestParams,
     ) -> Result<ReadResourceResult> {
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        let managed = self.client(server)?;
         let client = managed.client.clone();
         let timeout = managed.tool_timeout;
         let uri = params.uri.clone();
@@ -520,8 +1235,26 @@ impl McpConnectionManager {
             .with_context(|| format!("resources/read failed for `{server}` ({uri})"))
     }
 
+    /// Render a prompt from the specified server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> Result<GetPromptResult> {
+        let managed = self.client(server)?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+        let name = params.name.clone();
+
+        client
+            .get_prompt(params, timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}/{name}`"))
+    }
+
     pub fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
-        self.tools
+        self.read_state()
+            .tools
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
@@ -600,61 +1333,15 @@ fn resolve_bearer_token(
     }
 }
 
-/// Query every server for its available tools and return a single map that
-/// contains all tools. Each key is the fully-qualified name for the tool.
-async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<ToolInfo>> {
-    let mut join_set = JoinSet::new();
-
-    // Spawn one task per server so we can query them concurrently. This
-    // keeps the overall latency roughly at the slowest server instead of
-    // the cumulative latency.
-    for (server_name, managed_client) in clients {
-        let server_name_cloned = server_name.clone();
-        let client_clone = managed_client.client.clone();
-        let startup_timeout = managed_client.startup_timeout;
-        join_set.spawn(async move {
-            let res = client_clone.list_tools(None, Some(startup_timeout)).await;
-            (server_name_cloned, res)
-        });
-    }
-
-    let mut aggregated: Vec<ToolInfo> = Vec::with_capacity(join_set.len());
-
-    while let Some(join_res) = join_set.join_next().await {
-        let (server_name, list_result) = if let Ok(result) = join_res {
-            result
-        } else {
-            warn!("Task panic when listing tools for MCP server: {join_res:#?}");
-            continue;
-        };
-
-        let list_result = if let Ok(result) = list_result {
-            result
-        } else {
-            warn!("Failed to list tools for MCP server '{server_name}': {list_result:#?}");
-            continue;
-        };
-
-        for tool in list_result.tools {
-            let tool_info = ToolInfo {
-                server_name: server_name.clone(),
-                tool_name: tool.name.clone(),
-                tool,
-            };
-            aggregated.push(tool_info);
-        }
-    }
-
-    info!(
-        "aggregated {} tools from {} servers",
-        aggregated.len(),
-        clients.len()
-    );
+async fn list_server_tools(client: &RmcpClient, timeout: Duration) -> Result<Vec<Tool>> {
+    Ok(client.list_tools(None, Some(timeout)).await?.tools)
+}
 
-    Ok(aggregated)
+fn invalid_server_name_error(server_name: &str) -> anyhow::Error {
+    anyhow!("invalid server name '{server_name}': must match pattern ^[a-zA-Z0-9_-]+$")
 }
 
-fn is_valid_mcp_server_name(server_name: &str) -> bool {
+pub(crate) fn is_valid_mcp_server_name(server_name: &str) -> bool {
     !server_name.is_empty()
         && server_name
             .chars()
@@ -665,7 +1352,9 @@ fn is_valid_mcp_server_name(server_name: &str) -> bool {
 mod tests {
     use super::*;
     use mcp_types::ToolInputSchema;
+    use pretty_assertions::assert_eq;
     use std::collections::HashSet;
+    use std::path::PathBuf;
 
     fn create_test_tool(server_name: &str, tool_name: &str) -> ToolInfo {
         ToolInfo {
@@ -819,4 +1508,99 @@ mod tests {
         assert_eq!(filtered[0].server_name, "server1");
         assert_eq!(filtered[0].tool_name, "tool_a");
     }
+
+    #[test]
+    fn reconnect_delay_doubles_up_to_max() {
+        assert_eq!(reconnect_delay(0), Duration::ZERO);
+        assert_eq!(reconnect_delay(1), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(3), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(10), RECONNECT_MAX_BACKOFF);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn workspace_roots_lists_cwd_then_extra_writable_roots() {
+        let cwd = Path::new("/work/project");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![
+                PathBuf::from("/work/project"),
+                PathBuf::from("/work/shared lib"),
+            ],
+            network_access: false,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+            deny_read: Vec::new(),
+            network_allowlist: Vec::new(),
+        };
+
+        assert_eq!(
+            workspace_roots(cwd, &policy),
+            vec![
+                Root {
+                    name: Some("project".to_string()),
+                    uri: "file:///work/project".to_string(),
+                },
+                Root {
+                    name: Some("shared lib".to_string()),
+                    uri: "file:///work/shared%20lib".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            workspace_roots(cwd, &SandboxPolicy::DangerFullAccess),
+            vec![Root {
+                name: Some("project".to_string()),
+                uri: "file:///work/project".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_call_timeout_is_paused_while_elicitation_is_open() {
+        let manager = ElicitationRequestManager::default();
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let send_elicitation = manager.make_sender("srv".to_string(), tx_event);
+        let call = async move {
+            send_elicitation(
+                RequestId::Integer(1),
+                mcp_types::ElicitRequestParams {
+                    message: "Deploy?".to_string(),
+                    requested_schema: mcp_types::ElicitRequestParamsRequestedSchema {
+                        properties: json!({}),
+                        required: None,
+                        r#type: "object".to_string(),
+                    },
+                },
+            )
+            .await
+        };
+        // The user takes longer to answer than the tool timeout allows, then
+        // interrupts the turn.
+        let user = async {
+            rx_event.recv().await.expect("elicitation event");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            manager.cancel_all().await;
+        };
+
+        let (result, ()) = tokio::join!(
+            manager.run_with_paused_timeout("srv", call, Some(Duration::from_millis(50))),
+            user,
+        );
+        assert_eq!(
+            result.expect("call should not time out while the user is asked"),
+            ElicitResult {
+                action: "cancel".to_string(),
+                content: None,
+            }
+        );
+
+        let stalled = manager
+            .run_with_paused_timeout(
+                "srv",
+                std::future::pending::<Result<()>>(),
+                Some(Duration::from_millis(50)),
+            )
+            .await;
+        assert!(stalled.is_err());
+    }
 }
@@ -0,0 +1,423 @@
+//! Host side of MCP sampling (`sampling/createMessage`).
+//!
+//! Servers configured with `sampling = "ask"` or `sampling = "auto"` may ask
+//! Codex to run a completion against the session's model. The connection
+//! manager queues those requests on a channel that is served here once the
+//! [`Session`] exists: the user approves the request (unless the server is
+//! trusted), the messages are sent to the current provider with the session's
+//! auth, and the tokens spent are added to the session's usage totals.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Weak;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use async_channel::Receiver;
+use async_channel::Sender;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpSamplingApprovalRequestEvent;
+use codex_protocol::protocol::ReviewDecision;
+use codex_rmcp_client::SendSampling;
+use futures::FutureExt;
+use futures::StreamExt;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::RequestId;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::compact::content_items_to_text;
+use crate::config::types::McpSamplingPolicy;
+
+/// Sub id used for events emitted while serving a sampling request, which
+/// arrive outside of any particular submission.
+const SAMPLING_EVENT_ID: &str = "mcp_sampling_request";
+
+/// Instructions used when the server does not provide a system prompt.
+const DEFAULT_SAMPLING_INSTRUCTIONS: &str = "You are a helpful assistant.";
+
+/// Stop reason reported back to the server for completed responses.
+const STOP_REASON_END_TURN: &str = "endTurn";
+
+/// A sampling request from an MCP server, waiting to be served by the session.
+pub(crate) struct SamplingRequest {
+    server_name: String,
+    policy: McpSamplingPolicy,
+    id: RequestId,
+    params: CreateMessageRequestParams,
+    reply: oneshot::Sender<Result<CreateMessageResult>>,
+}
+
+/// Build the callback handed to the MCP client for `server_name`. Requests are
+/// queued on `tx` and answered by [`serve_sampling_requests`].
+pub(crate) fn make_sampling_sender(
+    server_name: String,
+    policy: McpSamplingPolicy,
+    tx: Sender<SamplingRequest>,
+) -> SendSampling {
+    Arc::new(move |id, params| {
+        let server_name = server_name.clone();
+        let tx = tx.clone();
+        async move {
+            let (reply, rx) = oneshot::channel();
+            tx.send(SamplingRequest {
+                server_name,
+                policy,
+                id,
+                params,
+                reply,
+            })
+            .await
+            .map_err(|_| anyhow!("session closed before the sampling request was served"))?;
+            rx.await
+                .map_err(|_| anyhow!("sampling request was dropped before it was answered"))?
+        }
+        .boxed()
+    })
+}
+
+/// Serve queued sampling requests until every sender is gone or the session
+/// has been dropped. Each request runs on its own task so a pending approval
+/// does not hold up other servers.
+pub(crate) async fn serve_sampling_requests(session: Weak<Session>, rx: Receiver<SamplingRequest>) {
+    while let Ok(request) = rx.recv().await {
+        let Some(sess) = session.upgrade() else {
+            request
+                .reply
+                .send(Err(anyhow!("session is no longer running")))
+                .ok();
+            break;
+        };
+        tokio::spawn(async move {
+            let SamplingRequest {
+                server_name,
+                policy,
+                id,
+                params,
+                reply,
+            } = request;
+            let result = handle_sampling_request(&sess, server_name, policy, id, params).await;
+            if let Err(err) = &result {
+                warn!("MCP sampling request failed: {err:#}");
+            }
+            reply.send(result).ok();
+        });
+    }
+}
+
+type PendingSamplingApprovals = HashMap<(String, RequestId), oneshot::Sender<ReviewDecision>>;
+
+/// Sampling approvals waiting for the user, plus the servers the user has
+/// approved for the rest of the session.
+#[derive(Default)]
+pub(crate) struct SamplingApprovals {
+    pending: Mutex<PendingSamplingApprovals>,
+    approved_servers: Mutex<HashSet<String>>,
+}
+
+impl SamplingApprovals {
+    /// Deliver the user's decision for a pending sampling request.
+    pub(crate) async fn resolve(
+        &self,
+        server_name: String,
+        id: RequestId,
+        decision: ReviewDecision,
+    ) -> Result<()> {
+        let tx = self
+            .pending
+            .lock()
+            .await
+            .remove(&(server_name.clone(), id.clone()))
+            .ok_or_else(|| {
+                anyhow!("no pending sampling request {id:?} for MCP server '{server_name}'")
+            })?;
+        tx.send(decision)
+            .map_err(|_| anyhow!("MCP server '{server_name}' is no longer waiting for {id:?}"))
+    }
+
+    async fn is_approved_for_session(&self, server_name: &str) -> bool {
+        self.approved_servers.lock().await.contains(server_name)
+    }
+
+    async fn approve_for_session(&self, server_name: String) {
+        self.approved_servers.lock().await.insert(server_name);
+    }
+
+    async fn request(
+        &self,
+        sess: &Session,
+        turn_context: &TurnContext,
+        server_name: String,
+        id: RequestId,
+        request: CreateMessageRequestParams,
+    ) -> ReviewDecision {
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .await
+            .insert((server_name.clone(), id.clone()), tx);
+        let event = EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+            server_name,
+            id,
+            request,
+        });
+        sess.send_event(turn_context, event).await;
+        rx.await.unwrap_or_default()
+    }
+}
+
+async fn handle_sampling_request(
+    sess: &Session,
+    server_name: String,
+    policy: McpSamplingPolicy,
+    id: RequestId,
+    params: CreateMessageRequestParams,
+) -> Result<CreateMessageResult> {
+    let turn_context = sess
+        .current_turn_context(SAMPLING_EVENT_ID.to_string())
+        .await;
+    let approvals = &sess.services.mcp_sampling_approvals;
+
+    if policy == McpSamplingPolicy::Ask && !approvals.is_approved_for_session(&server_name).await {
+        let decision = approvals
+            .request(sess, &turn_context, server_name.clone(), id, params.clone())
+            .await;
+        match decision {
+            ReviewDecision::Approved => {}
+            ReviewDecision::ApprovedForSession => {
+                approvals.approve_for_session(server_name).await;
+            }
+            ReviewDecision::Denied | ReviewDecision::Abort => {
+                bail!("the user declined the sampling request");
+            }
+        }
+    }
+
+    let prompt = build_prompt(&params)?;
+    let text = run_prompt(sess, &turn_context, &prompt).await?;
+    Ok(CreateMessageResult {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model: turn_context.client.get_model(),
+        role: Role::Assistant,
+        stop_reason: Some(STOP_REASON_END_TURN.to_string()),
+    })
+}
+
+/// Convert the server's messages into a prompt for the session's model.
+///
+/// `maxTokens` caps the response length. `temperature`, `stopSequences` and
+/// `modelPreferences` are advisory in the MCP spec and are not forwarded: the
+/// request always runs against the session's current model and settings.
+fn build_prompt(params: &CreateMessageRequestParams) -> Result<Prompt> {
+    let input = params
+        .messages
+        .iter()
+        .map(sampling_message_to_item)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Prompt {
+        input,
+        base_instructions_override: Some(
+            params
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SAMPLING_INSTRUCTIONS.to_string()),
+        ),
+        max_output_tokens: Some(params.max_tokens),
+        ..Default::default()
+    })
+}
+
+fn sampling_message_to_item(message: &SamplingMessage) -> Result<ResponseItem> {
+    let content = match (&message.role, &message.content) {
+        (Role::User, SamplingMessageContent::TextContent(text)) => ContentItem::InputText {
+            text: text.text.clone(),
+        },
+        (Role::Assistant, SamplingMessageContent::TextContent(text)) => ContentItem::OutputText {
+            text: text.text.clone(),
+        },
+        (Role::User, SamplingMessageContent::ImageContent(image)) => ContentItem::InputImage {
+            image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+        },
+        (Role::Assistant, SamplingMessageContent::ImageContent(_)) => {
+            bail!("image content is only supported in user messages");
+        }
+        (_, SamplingMessageContent::AudioContent(_)) => {
+            bail!("audio content is not supported");
+        }
+    };
+    let role = match message.role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+    };
+    Ok(ResponseItem::Message {
+        id: None,
+        role: role.to_string(),
+        content: vec![content],
+    })
+}
+
+/// Stream `prompt` to the model and return the assistant's text. Nothing is
+/// recorded in the conversation history.
+async fn run_prompt(sess: &Session, turn_context: &TurnContext, prompt: &Prompt) -> Result<String> {
+    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut pieces = Vec::new();
+    loop {
+        let Some(event) = stream.next().await else {
+            bail!("stream closed before response.completed");
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                pieces.extend(content_items_to_text(&content));
+            }
+            ResponseEvent::RateLimits(snapshot) => {
+                sess.update_rate_limits(turn_context, snapshot).await;
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                if let Some(token_usage) = token_usage {
+                    sess.add_out_of_band_token_usage(turn_context, &token_usage)
+                        .await;
+                }
+                return Ok(pieces.join("\n"));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::AudioContent;
+    use mcp_types::ImageContent;
+    use pretty_assertions::assert_eq;
+
+    fn text_message(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    fn params(
+        messages: Vec<SamplingMessage>,
+        system_prompt: Option<&str>,
+    ) -> CreateMessageRequestParams {
+        CreateMessageRequestParams {
+            include_context: None,
+            max_tokens: 64,
+            messages,
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: system_prompt.map(str::to_string),
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn build_prompt_maps_roles_and_system_prompt() {
+        let prompt = build_prompt(&params(
+            vec![
+                text_message(Role::User, "hi"),
+                text_message(Role::Assistant, "hello"),
+                SamplingMessage {
+                    content: SamplingMessageContent::ImageContent(ImageContent {
+                        annotations: None,
+                        data: "AAAA".to_string(),
+                        mime_type: "image/png".to_string(),
+                        r#type: "image".to_string(),
+                    }),
+                    role: Role::User,
+                },
+            ],
+            Some("Be brief."),
+        ))
+        .expect("prompt");
+
+        assert_eq!(
+            prompt.base_instructions_override.as_deref(),
+            Some("Be brief.")
+        );
+        assert_eq!(
+            prompt.input,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "hi".to_string()
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "hello".to_string()
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputImage {
+                        image_url: "data:image/png;base64,AAAA".to_string()
+                    }],
+                },
+            ]
+        );
+        assert!(prompt.tools.is_empty());
+        assert_eq!(prompt.max_output_tokens, Some(64));
+    }
+
+    #[test]
+    fn build_prompt_defaults_instructions() {
+        let prompt =
+            build_prompt(&params(vec![text_message(Role::User, "hi")], None)).expect("prompt");
+        assert_eq!(
+            prompt.base_instructions_override.as_deref(),
+            Some(DEFAULT_SAMPLING_INSTRUCTIONS)
+        );
+    }
+
+    #[test]
+    fn build_prompt_rejects_audio() {
+        let err = build_prompt(&params(
+            vec![SamplingMessage {
+                content: SamplingMessageContent::AudioContent(AudioContent {
+                    annotations: None,
+                    data: "AAAA".to_string(),
+                    mime_type: "audio/wav".to_string(),
+                    r#type: "audio".to_string(),
+                }),
+                role: Role::User,
+            }],
+            None,
+        ))
+        .expect_err("audio should be rejected");
+        assert_eq!(err.to_string(), "audio content is not supported");
+    }
+}
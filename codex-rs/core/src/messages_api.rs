@@ -0,0 +1,925 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::ModelProviderInfo;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::config::Config;
+use crate::default_client::CodexHttpClient;
+use crate::error::CodexErr;
+use crate::error::ConnectionFailedError;
+use crate::error::ResponseStreamFailed;
+use crate::error::Result;
+use crate::error::RetryLimitReachedError;
+use crate::error::UnexpectedResponseError;
+use crate::protocol::TokenUsage;
+use crate::tools::spec::create_tools_json_for_responses_api;
+use crate::util::backoff;
+use bytes::Bytes;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+/// Value sent in the `anthropic-version` header on Messages API requests.
+pub(crate) const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// `max_tokens` is mandatory for the Messages API. Used when neither the
+/// config nor the model info provide an output limit.
+const DEFAULT_MAX_TOKENS: i64 = 16_384;
+
+/// Smallest thinking budget accepted by the Messages API.
+const MIN_THINKING_BUDGET_TOKENS: i64 = 1_024;
+
+/// Implementation for the Anthropic Messages API.
+pub(crate) async fn stream_messages(
+    prompt: &Prompt,
+    config: &Config,
+    effort: Option<ReasoningEffortConfig>,
+    client: &CodexHttpClient,
+    provider: &ModelProviderInfo,
+    otel_event_manager: &OtelEventManager,
+) -> Result<ResponseStream> {
+    if prompt.output_schema.is_some() {
+        return Err(CodexErr::UnsupportedOperation(
+            "output_schema is not supported for Messages API".to_string(),
+        ));
+    }
+
+    let max_tokens = config.model_max_output_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let max_tokens = prompt
+        .max_output_tokens
+        .map_or(max_tokens, |requested| requested.min(max_tokens));
+    let mut payload = json!({
+        "model": config.model_family.slug,
+        "max_tokens": max_tokens,
+        "system": prompt.get_full_instructions(&config.model_family),
+        "messages": build_messages(&prompt.get_formatted_input()),
+        "stream": true,
+    });
+
+    let tools_json = create_tools_json_for_messages_api(prompt)?;
+    if let Some(obj) = payload.as_object_mut() {
+        if !tools_json.is_empty() {
+            obj.insert("tools".to_string(), json!(tools_json));
+            obj.insert(
+                "tool_choice".to_string(),
+                json!({
+                    "type": "auto",
+                    "disable_parallel_tool_use": !prompt.parallel_tool_calls,
+                }),
+            );
+        }
+        if let Some(budget_tokens) = thinking_budget(effort, max_tokens) {
+            obj.insert(
+                "thinking".to_string(),
+                json!({"type": "enabled", "budget_tokens": budget_tokens}),
+            );
+        }
+    }
+
+    debug!(
+        "POST to {}: {}",
+        provider.get_full_url(&None),
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+
+    let mut attempt = 0;
+    let max_retries = provider.request_max_retries();
+    loop {
+        attempt += 1;
+
+        let req_builder = provider.create_request_builder(client, &None).await?;
+
+        let res = otel_event_manager
+            .log_request(attempt, || {
+                req_builder
+                    .header(reqwest::header::ACCEPT, "text/event-stream")
+                    .json(&payload)
+                    .send()
+            })
+            .await;
+
+        match res {
+            Ok(resp) if resp.status().is_success() => {
+                let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+                let stream = resp.bytes_stream().map_err(|e| {
+                    CodexErr::ResponseStreamFailed(ResponseStreamFailed {
+                        source: e,
+                        request_id: None,
+                    })
+                });
+                tokio::spawn(process_messages_sse(
+                    stream,
+                    tx_event,
+                    provider.stream_idle_timeout(),
+                    otel_event_manager.clone(),
+                ));
+                return Ok(ResponseStream { rx_event });
+            }
+            Ok(res) => {
+                let status = res.status();
+                // 529 is Anthropic's "overloaded" status and is safe to retry.
+                if !(status == StatusCode::TOO_MANY_REQUESTS
+                    || status.is_server_error()
+                    || status.as_u16() == 529)
+                {
+                    let body = (res.text().await).unwrap_or_default();
+                    return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                        status,
+                        body,
+                        request_id: None,
+                    }));
+                }
+
+                if attempt > max_retries {
+                    return Err(CodexErr::RetryLimit(RetryLimitReachedError {
+                        status,
+                        request_id: None,
+                    }));
+                }
+
+                let retry_after_secs = res
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+
+                let delay = retry_after_secs
+                    .map(|s| Duration::from_millis(s * 1_000))
+                    .unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt > max_retries {
+                    return Err(CodexErr::ConnectionFailed(ConnectionFailedError {
+                        source: e,
+                    }));
+                }
+                let delay = backoff(attempt);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+/// Maps the configured reasoning effort onto an extended thinking budget.
+/// Thinking stays disabled when no effort is configured or the output limit
+/// is too small to fit the minimum budget.
+fn thinking_budget(effort: Option<ReasoningEffortConfig>, max_tokens: i64) -> Option<i64> {
+    let budget = match effort? {
+        ReasoningEffortConfig::Minimal => return None,
+        ReasoningEffortConfig::Low => MIN_THINKING_BUDGET_TOKENS,
+        ReasoningEffortConfig::Medium => 4_096,
+        ReasoningEffortConfig::High => 8_192,
+    };
+    // The budget must be strictly smaller than `max_tokens`.
+    let budget = budget.min(max_tokens - 1);
+    (budget >= MIN_THINKING_BUDGET_TOKENS).then_some(budget)
+}
+
+/// Converts the Responses-style tool definitions into Messages API tools.
+/// Only function tools are supported; hosted and freeform tools are dropped
+/// (see [`build_messages`] for how calls to them in the history are sent).
+fn create_tools_json_for_messages_api(prompt: &Prompt) -> Result<Vec<Value>> {
+    let responses_api_tools_json = create_tools_json_for_responses_api(&prompt.tools)?;
+    Ok(responses_api_tools_json
+        .into_iter()
+        .filter(|tool| tool.get("type").and_then(Value::as_str) == Some("function"))
+        .map(|tool| {
+            json!({
+                "name": tool.get("name").cloned().unwrap_or_default(),
+                "description": tool.get("description").cloned().unwrap_or_default(),
+                "input_schema": tool
+                    .get("parameters")
+                    .cloned()
+                    .unwrap_or_else(|| json!({"type": "object", "properties": {}})),
+            })
+        })
+        .collect())
+}
+
+/// Builds the `messages` array. Consecutive items that map onto the same
+/// role are merged into a single message because the Messages API requires
+/// user and assistant turns to alternate.
+///
+/// Local shell and freeform tool calls (recorded when the conversation ran on
+/// another provider) have no matching tool declaration here, so they and
+/// their outputs are replayed as text instead of `tool_use`/`tool_result`.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let text_call_ids: HashSet<&str> = input
+        .iter()
+        .filter_map(|item| match item {
+            ResponseItem::LocalShellCall { id, call_id, .. } => call_id.as_ref().or(id.as_ref()),
+            ResponseItem::CustomToolCall { call_id, .. } => Some(call_id),
+            _ => None,
+        })
+        .map(String::as_str)
+        .collect();
+    let mut messages: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    let mut push = |role: &'static str, blocks: Vec<Value>| {
+        if blocks.is_empty() {
+            return;
+        }
+        match messages.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => messages.push((role, blocks)),
+        }
+    };
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                let blocks = content
+                    .iter()
+                    .filter_map(|c| match c {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            text_block(text)
+                        }
+                        ContentItem::InputImage { image_url } => Some(image_block(image_url)),
+                    })
+                    .collect();
+                push(role, blocks);
+            }
+            ResponseItem::Reasoning {
+                summary,
+                encrypted_content: Some(signature),
+                ..
+            } => {
+                let thinking = summary
+                    .iter()
+                    .map(|ReasoningItemReasoningSummary::SummaryText { text }| text.as_str())
+                    .collect::<String>();
+                // Thinking blocks always carry text; a reasoning item with only
+                // an opaque payload came from a `redacted_thinking` block.
+                let block = if thinking.is_empty() {
+                    json!({"type": "redacted_thinking", "data": signature})
+                } else {
+                    json!({"type": "thinking", "thinking": thinking, "signature": signature})
+                };
+                push("assistant", vec![block]);
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push(
+                    "assistant",
+                    vec![json!({"type": "tool_use", "id": call_id, "name": name, "input": input})],
+                );
+            }
+            ResponseItem::LocalShellCall {
+                id,
+                call_id,
+                action,
+                ..
+            } => {
+                let call_id = call_id.as_deref().or(id.as_deref()).unwrap_or_default();
+                let action = serde_json::to_string(action).unwrap_or_default();
+                push(
+                    "assistant",
+                    text_block(&format!("[local_shell call {call_id}]\n{action}"))
+                        .into_iter()
+                        .collect(),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                push(
+                    "assistant",
+                    text_block(&format!("[{name} call {call_id}]\n{input}"))
+                        .into_iter()
+                        .collect(),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output }
+                if text_call_ids.contains(call_id.as_str()) =>
+            {
+                push(
+                    "user",
+                    text_block(&format!("[output of call {call_id}]\n{}", output.content))
+                        .into_iter()
+                        .collect(),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let content = if let Some(items) = &output.content_items {
+                    json!(
+                        items
+                            .iter()
+                            .filter_map(|it| match it {
+                                FunctionCallOutputContentItem::InputText { text } =>
+                                    text_block(text),
+                                FunctionCallOutputContentItem::InputImage { image_url } => {
+                                    Some(image_block(image_url))
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    )
+                } else {
+                    json!(output.content)
+                };
+                let mut block = json!({
+                    "type": "tool_result",
+                    "tool_use_id": call_id,
+                    "content": content,
+                });
+                if output.success == Some(false)
+                    && let Some(obj) = block.as_object_mut()
+                {
+                    obj.insert("is_error".to_string(), json!(true));
+                }
+                push("user", vec![block]);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                push(
+                    "user",
+                    text_block(&format!("[output of call {call_id}]\n{output}"))
+                        .into_iter()
+                        .collect(),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Other => {
+                // Reasoning without a signature cannot be replayed, and the
+                // remaining items never reach the model.
+                continue;
+            }
+        }
+    }
+
+    messages
+        .into_iter()
+        .map(|(role, content)| json!({"role": role, "content": content}))
+        .collect()
+}
+
+/// The Messages API rejects empty text blocks.
+fn text_block(text: &str) -> Option<Value> {
+    (!text.is_empty()).then(|| json!({"type": "text", "text": text}))
+}
+
+/// Converts a `data:` URL into a base64 image source; anything else is sent
+/// as a URL source.
+fn image_block(image_url: &str) -> Value {
+    let source = image_url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .map(|(media_type, data)| json!({"type": "base64", "media_type": media_type, "data": data}))
+        .unwrap_or_else(|| json!({"type": "url", "url": image_url}));
+    json!({"type": "image", "source": source})
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessagesUsage {
+    #[serde(default)]
+    input_tokens: Option<i64>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<i64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<i64>,
+    #[serde(default)]
+    output_tokens: Option<i64>,
+}
+
+impl MessagesUsage {
+    /// `message_start` reports the input side and `message_delta` reports the
+    /// cumulative output count, so later values overwrite earlier ones.
+    fn merge(&mut self, other: MessagesUsage) {
+        self.input_tokens = other.input_tokens.or(self.input_tokens);
+        self.cache_creation_input_tokens = other
+            .cache_creation_input_tokens
+            .or(self.cache_creation_input_tokens);
+        self.cache_read_input_tokens = other
+            .cache_read_input_tokens
+            .or(self.cache_read_input_tokens);
+        self.output_tokens = other.output_tokens.or(self.output_tokens);
+    }
+}
+
+impl From<&MessagesUsage> for TokenUsage {
+    fn from(usage: &MessagesUsage) -> Self {
+        let cached_input_tokens = usage.cache_read_input_tokens.unwrap_or(0);
+        // Anthropic reports cached and uncached input separately, whereas
+        // `input_tokens` includes cached tokens everywhere else in Codex.
+        let input_tokens = usage.input_tokens.unwrap_or(0)
+            + usage.cache_creation_input_tokens.unwrap_or(0)
+            + cached_input_tokens;
+        let output_tokens = usage.output_tokens.unwrap_or(0);
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessagesStreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: Value,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: Value,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        #[serde(default)]
+        usage: Option<MessagesUsage>,
+    },
+    MessageStop,
+    Ping,
+    Error {
+        error: MessagesError,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    #[serde(default)]
+    usage: Option<MessagesUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesError {
+    #[serde(default)]
+    r#type: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// A content block that is still receiving deltas.
+enum ContentBlock {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: String,
+    },
+    RedactedThinking(String),
+    ToolUse {
+        id: String,
+        name: String,
+        input_json: String,
+    },
+}
+
+impl ContentBlock {
+    fn into_response_item(self) -> ResponseItem {
+        match self {
+            ContentBlock::Text(text) => ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText { text }],
+            },
+            ContentBlock::Thinking { text, signature } => ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText { text }],
+                content: None,
+                encrypted_content: (!signature.is_empty()).then_some(signature),
+            },
+            ContentBlock::RedactedThinking(data) => ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: None,
+                encrypted_content: Some(data),
+            },
+            ContentBlock::ToolUse {
+                id,
+                name,
+                input_json,
+            } => ResponseItem::FunctionCall {
+                id: None,
+                name,
+                // Tools without parameters stream no input deltas at all.
+                arguments: if input_json.trim().is_empty() {
+                    "{}".to_string()
+                } else {
+                    input_json
+                },
+                call_id: id,
+            },
+        }
+    }
+}
+
+/// SSE processor for the Messages streaming format. Each content block is
+/// surfaced as its own [`ResponseItem`] so that the rest of the pipeline sees
+/// the same event sequence as with the Responses API.
+async fn process_messages_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent>>,
+    idle_timeout: Duration,
+    otel_event_manager: OtelEventManager,
+) where
+    S: Stream<Item = Result<Bytes>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+
+    let mut response_id = String::new();
+    let mut usage = MessagesUsage::default();
+    let mut blocks: HashMap<usize, ContentBlock> = HashMap::new();
+
+    loop {
+        let start = std::time::Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        let duration = start.elapsed();
+        otel_event_manager.log_sse_event(&response, duration);
+
+        let sse = match response {
+            Ok(Some(Ok(ev))) => ev,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(e.to_string(), None)))
+                    .await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "stream closed before message_stop".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(CodexErr::Stream(
+                        "idle timeout waiting for SSE".into(),
+                        None,
+                    )))
+                    .await;
+                return;
+            }
+        };
+
+        let event: MessagesStreamEvent = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!(
+                    "Failed to parse Messages SSE event: {e}, data: {}",
+                    &sse.data
+                );
+                continue;
+            }
+        };
+        trace!("messages received SSE event: {event:?}");
+
+        match event {
+            MessagesStreamEvent::MessageStart { message } => {
+                response_id = message.id;
+                if let Some(start_usage) = message.usage {
+                    usage.merge(start_usage);
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            MessagesStreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let str_field = |name: &str| {
+                    content_block
+                        .get(name)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let block = match content_block.get("type").and_then(Value::as_str) {
+                    Some("text") => ContentBlock::Text(String::new()),
+                    Some("thinking") => ContentBlock::Thinking {
+                        text: String::new(),
+                        signature: String::new(),
+                    },
+                    Some("redacted_thinking") => ContentBlock::RedactedThinking(str_field("data")),
+                    Some("tool_use") => ContentBlock::ToolUse {
+                        id: str_field("id"),
+                        name: str_field("name"),
+                        input_json: String::new(),
+                    },
+                    other => {
+                        debug!("ignoring Messages content block of type {other:?}");
+                        continue;
+                    }
+                };
+                // Announce streamed items up front so deltas have an active item.
+                let added = match &block {
+                    ContentBlock::Text(_) => Some(ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: Vec::new(),
+                    }),
+                    ContentBlock::Thinking { .. } => Some(ResponseItem::Reasoning {
+                        id: String::new(),
+                        summary: Vec::new(),
+                        content: None,
+                        encrypted_content: None,
+                    }),
+                    ContentBlock::RedactedThinking(_) | ContentBlock::ToolUse { .. } => None,
+                };
+                if let Some(item) = added {
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                        .await;
+                }
+                blocks.insert(index, block);
+            }
+            MessagesStreamEvent::ContentBlockDelta { index, delta } => {
+                let Some(block) = blocks.get_mut(&index) else {
+                    continue;
+                };
+                let str_field = |name: &str| delta.get(name).and_then(Value::as_str);
+                match (block, delta.get("type").and_then(Value::as_str)) {
+                    (ContentBlock::Text(text), Some("text_delta")) => {
+                        if let Some(fragment) = str_field("text") {
+                            text.push_str(fragment);
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::OutputTextDelta(fragment.to_string())))
+                                .await;
+                        }
+                    }
+                    (ContentBlock::Thinking { text, .. }, Some("thinking_delta")) => {
+                        if let Some(fragment) = str_field("thinking") {
+                            text.push_str(fragment);
+                            let _ = tx_event
+                                .send(Ok(ResponseEvent::ReasoningSummaryDelta(
+                                    fragment.to_string(),
+                                )))
+                                .await;
+                        }
+                    }
+                    (ContentBlock::Thinking { signature, .. }, Some("signature_delta")) => {
+                        if let Some(fragment) = str_field("signature") {
+                            signature.push_str(fragment);
+                        }
+                    }
+                    (ContentBlock::ToolUse { input_json, .. }, Some("input_json_delta")) => {
+                        if let Some(fragment) = str_field("partial_json") {
+                            input_json.push_str(fragment);
+                        }
+                    }
+                    (_, other) => {
+                        debug!("ignoring unexpected Messages delta of type {other:?}");
+                    }
+                }
+            }
+            MessagesStreamEvent::ContentBlockStop { index } => {
+                if let Some(block) = blocks.remove(&index) {
+                    let item = block.into_response_item();
+                    let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+                }
+            }
+            MessagesStreamEvent::MessageDelta {
+                usage: Some(delta_usage),
+            } => {
+                usage.merge(delta_usage);
+            }
+            MessagesStreamEvent::MessageStop => {
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(TokenUsage::from(&usage)),
+                    }))
+                    .await;
+                return;
+            }
+            MessagesStreamEvent::Error { error } => {
+                let message = error
+                    .message
+                    .or(error.r#type)
+                    .unwrap_or_else(|| "unknown Messages API error".to_string());
+                let _ = tx_event.send(Err(CodexErr::Stream(message, None))).await;
+                return;
+            }
+            MessagesStreamEvent::MessageDelta { usage: None }
+            | MessagesStreamEvent::Ping
+            | MessagesStreamEvent::Unknown => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::LocalShellAction;
+    use codex_protocol::models::LocalShellExecAction;
+    use codex_protocol::models::LocalShellStatus;
+    use pretty_assertions::assert_eq;
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn merges_tool_calls_and_results_into_alternating_turns() {
+        let input = vec![
+            user("list files"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "use ls".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("sig".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "boom".to_string(),
+                    success: Some(false),
+                    ..Default::default()
+                },
+            },
+            user("try again"),
+        ];
+
+        assert_eq!(
+            build_messages(&input),
+            vec![
+                json!({"role": "user", "content": [{"type": "text", "text": "list files"}]}),
+                json!({"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "use ls", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                ]}),
+                json!({"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "boom", "is_error": true},
+                    {"type": "text", "text": "try again"},
+                ]}),
+            ]
+        );
+    }
+
+    #[test]
+    fn replays_undeclared_tool_calls_as_text() {
+        let input = vec![
+            user("patch it"),
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call_1".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch".to_string(),
+            },
+            ResponseItem::CustomToolCallOutput {
+                call_id: "call_1".to_string(),
+                output: "Success".to_string(),
+            },
+            ResponseItem::LocalShellCall {
+                id: None,
+                call_id: Some("call_2".to_string()),
+                status: LocalShellStatus::Completed,
+                action: LocalShellAction::Exec(LocalShellExecAction {
+                    command: vec!["ls".to_string()],
+                    timeout_ms: None,
+                    working_directory: None,
+                    env: None,
+                    user: None,
+                }),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_2".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "README.md".to_string(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        let messages = build_messages(&input);
+        let blocks: Vec<&Value> = messages
+            .iter()
+            .flat_map(|message| message["content"].as_array().into_iter().flatten())
+            .collect();
+        assert!(
+            blocks.iter().all(|block| block["type"] == "text"),
+            "unexpected blocks: {blocks:?}"
+        );
+        assert_eq!(
+            messages[1],
+            json!({"role": "assistant", "content": [
+                {"type": "text", "text": "[apply_patch call call_1]\n*** Begin Patch"},
+            ]})
+        );
+        assert_eq!(
+            messages[4],
+            json!({"role": "user", "content": [
+                {"type": "text", "text": "[output of call call_2]\nREADME.md"},
+            ]})
+        );
+    }
+
+    #[test]
+    fn converts_data_urls_into_base64_image_sources() {
+        assert_eq!(
+            image_block("data:image/png;base64,AAAA"),
+            json!({"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}})
+        );
+        assert_eq!(
+            image_block("https://example.com/cat.png"),
+            json!({"type": "image", "source": {"type": "url", "url": "https://example.com/cat.png"}})
+        );
+    }
+
+    #[test]
+    fn thinking_budget_follows_effort_and_output_limit() {
+        assert_eq!(thinking_budget(None, 16_384), None);
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::Minimal), 16_384),
+            None
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::High), 16_384),
+            Some(8_192)
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::High), 4_096),
+            Some(4_095)
+        );
+        assert_eq!(
+            thinking_budget(Some(ReasoningEffortConfig::Low), 1_024),
+            None
+        );
+    }
+
+    #[test]
+    fn usage_counts_cached_input_as_input() {
+        let mut usage = MessagesUsage {
+            input_tokens: Some(10),
+            cache_creation_input_tokens: Some(5),
+            cache_read_input_tokens: Some(100),
+            output_tokens: Some(1),
+        };
+        usage.merge(MessagesUsage {
+            output_tokens: Some(42),
+            ..Default::default()
+        });
+        assert_eq!(
+            TokenUsage::from(&usage),
+            TokenUsage {
+                input_tokens: 115,
+                cached_input_tokens: 100,
+                output_tokens: 42,
+                reasoning_output_tokens: 0,
+                total_tokens: 157,
+            }
+        );
+    }
+}
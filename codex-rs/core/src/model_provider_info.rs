@@ -8,6 +8,7 @@
 use crate::CodexAuth;
 use crate::default_client::CodexHttpClient;
 use crate::default_client::CodexRequestBuilder;
+use crate::messages_api::ANTHROPIC_VERSION;
 use codex_app_server_protocol::AuthMode;
 use serde::Deserialize;
 use serde::Serialize;
@@ -27,9 +28,10 @@ const MAX_REQUEST_MAX_RETRIES: u64 = 100;
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
 /// itself (and a handful of others) additionally expose the more modern
-/// *Responses* API. The two protocols use different request/response shapes
-/// and *cannot* be auto-detected at runtime, therefore each provider entry
-/// must declare which one it expects.
+/// *Responses* API, and Anthropic exposes its own *Messages* API. The
+/// protocols use different request/response shapes and *cannot* be
+/// auto-detected at runtime, therefore each provider entry must declare which
+/// one it expects.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WireApi {
@@ -39,6 +41,9 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// The Anthropic Messages API exposed at `/v1/messages`.
+    Messages,
 }
 
 /// Serializable representation of a provider definition.
@@ -99,7 +104,8 @@ impl ModelProviderInfo {
     /// Construct a `POST` RequestBuilder for the given URL using the provided
     /// [`CodexHttpClient`] applying:
     ///   • provider-specific headers (static + env based)
-    ///   • Bearer auth header when an API key is available.
+    ///   • Bearer auth header when an API key is available (`x-api-key` plus
+    ///     `anthropic-version` for the Messages API).
     ///   • Auth token for OAuth.
     ///
     /// If the provider declares an `env_key` but the variable is missing/empty, returns an [`Err`] identical to the
@@ -129,7 +135,12 @@ impl ModelProviderInfo {
 
         let mut builder = client.post(url);
 
-        if let Some(auth) = effective_auth.as_ref() {
+        if self.wire_api == WireApi::Messages {
+            builder = builder.header("anthropic-version", ANTHROPIC_VERSION);
+            if let Some(auth) = effective_auth.as_ref() {
+                builder = builder.header("x-api-key", auth.get_token().await?);
+            }
+        } else if let Some(auth) = effective_auth.as_ref() {
             builder = builder.bearer_auth(auth.get_token().await?);
         }
 
@@ -170,6 +181,7 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            WireApi::Messages => format!("{base_url}/messages{query_string}"),
         }
     }
 
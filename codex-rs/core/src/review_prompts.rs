@@ -0,0 +1,65 @@
+use crate::protocol::ReviewRequest;
+
+// Review requests for the built-in `/review` presets. Kept here so every
+// frontend starts the same review for the same preset.
+
+/// Review the staged, unstaged and untracked changes in the working tree.
+pub fn uncommitted_changes() -> ReviewRequest {
+    ReviewRequest {
+        prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
+        user_facing_hint: "current changes".to_string(),
+    }
+}
+
+/// Review the changes that would be merged into `branch`.
+pub fn base_branch(branch: &str) -> ReviewRequest {
+    ReviewRequest {
+        prompt: format!(
+            "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
+        ),
+        user_facing_hint: format!("changes against '{branch}'"),
+    }
+}
+
+/// Review the changes introduced by a single commit. `subject` is the commit's
+/// title, when known.
+pub fn commit(sha: &str, subject: Option<&str>) -> ReviewRequest {
+    let short: String = sha.chars().take(7).collect();
+    let prompt = match subject {
+        Some(subject) => format!(
+            "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
+        ),
+        None => format!(
+            "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings."
+        ),
+    };
+    ReviewRequest {
+        prompt,
+        user_facing_hint: format!("commit {short}"),
+    }
+}
+
+/// Review with free-form instructions; the instructions double as the hint.
+pub fn custom(instructions: &str) -> ReviewRequest {
+    let instructions = instructions.trim().to_string();
+    ReviewRequest {
+        prompt: instructions.clone(),
+        user_facing_hint: instructions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn commit_hint_uses_short_sha() {
+        let request = commit("0123456789abcdef", Some("Fix the parser"));
+        assert_eq!(request.user_facing_hint, "commit 0123456");
+        assert_eq!(
+            request.prompt,
+            "Review the code changes introduced by commit 0123456789abcdef (\"Fix the parser\"). Provide prioritized, actionable findings."
+        );
+    }
+}
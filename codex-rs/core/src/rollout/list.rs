@@ -358,13 +358,13 @@ fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uui
     Some((ts, uuid))
 }
 
-struct ProviderMatcher<'a> {
+pub(super) struct ProviderMatcher<'a> {
     filters: &'a [String],
     matches_default_provider: bool,
 }
 
 impl<'a> ProviderMatcher<'a> {
-    fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
+    pub(super) fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
         if filters.is_empty() {
             return None;
         }
@@ -376,7 +376,7 @@ impl<'a> ProviderMatcher<'a> {
         })
     }
 
-    fn matches(&self, session_provider: Option<&str>) -> bool {
+    pub(super) fn matches(&self, session_provider: Option<&str>) -> bool {
         match session_provider {
             Some(provider) => self.filters.iter().any(|candidate| candidate == provider),
             None => self.matches_default_provider,
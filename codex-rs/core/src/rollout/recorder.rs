@@ -23,6 +23,8 @@ use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::search;
+use super::search::SessionSearchHit;
 use crate::config::Config;
 use crate::default_client::originator;
 use crate::git_info::collect_git_info;
@@ -111,6 +113,27 @@ impl RolloutRecorder {
         .await
     }
 
+    /// Full-text search over the user messages, assistant messages, commands
+    /// and touched files of conversations under the provided Codex home.
+    pub async fn search_conversations(
+        codex_home: &Path,
+        query: &str,
+        limit: usize,
+        allowed_sources: &[SessionSource],
+        model_providers: Option<&[String]>,
+        default_provider: &str,
+    ) -> std::io::Result<Vec<SessionSearchHit>> {
+        search::search_sessions(
+            codex_home,
+            query,
+            limit,
+            allowed_sources,
+            model_providers,
+            default_provider,
+        )
+        .await
+    }
+
     /// Attempt to create a new [`RolloutRecorder`]. If the sessions directory
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
@@ -163,6 +186,10 @@ impl RolloutRecorder {
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
+        let index = SearchIndexWriter {
+            codex_home: config.codex_home.clone(),
+            rollout_path: rollout_path.clone(),
+        };
 
         // A reasonably-sized bounded channel. If the buffer fills up the send
         // future will yield, which is fine – we only need to ensure we do not
@@ -172,7 +199,7 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(file, rx, meta, cwd, index));
 
         Ok(Self { tx, rollout_path })
     }
@@ -351,9 +378,18 @@ async fn rollout_writer(
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    index: SearchIndexWriter,
 ) -> std::io::Result<()> {
     let mut writer = JsonlWriter { file };
 
+    // A resumed rollout may predate the search index; make sure its earlier
+    // items are searchable before appending new ones.
+    if meta.is_none()
+        && let Err(e) = search::ensure_rollout_indexed(&index.codex_home, &index.rollout_path).await
+    {
+        warn!("failed to index resumed rollout: {e}");
+    }
+
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
         let git_info = collect_git_info(&cwd).await;
@@ -363,20 +399,23 @@ async fn rollout_writer(
         };
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
-        writer
+        let line = writer
             .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
             .await?;
+        index.append(&[line]).await;
     }
 
     // Process rollout commands
     while let Some(cmd) = rx.recv().await {
         match cmd {
             RolloutCmd::AddItems(items) => {
+                let mut written = Vec::with_capacity(items.len());
                 for item in items {
                     if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
+                        written.push(writer.write_rollout_item(item).await?);
                     }
                 }
+                index.append(&written).await;
             }
             RolloutCmd::Flush { ack } => {
                 // Ensure underlying file is flushed and then ack.
@@ -395,12 +434,38 @@ async fn rollout_writer(
     Ok(())
 }
 
+/// Mirrors recorded items into the session search index.
+struct SearchIndexWriter {
+    codex_home: PathBuf,
+    rollout_path: PathBuf,
+}
+
+impl SearchIndexWriter {
+    /// Index failures are logged rather than propagated: a stale search index
+    /// must never interrupt recording the rollout itself.
+    async fn append(&self, lines: &[RolloutLine]) {
+        let records = lines
+            .iter()
+            .flat_map(|line| {
+                search::index_records_for_item(&self.rollout_path, &line.timestamp, &line.item)
+            })
+            .collect();
+        if let Err(e) = search::append_records(&self.codex_home, records).await {
+            warn!("failed to update session search index: {e}");
+        }
+    }
+}
+
 struct JsonlWriter {
     file: tokio::fs::File,
 }
 
 impl JsonlWriter {
-    async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
+    /// Write `rollout_item` and return the line as it was recorded.
+    async fn write_rollout_item(
+        &mut self,
+        rollout_item: RolloutItem,
+    ) -> std::io::Result<RolloutLine> {
         let timestamp_format: &[FormatItem] = format_description!(
             "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
         );
@@ -412,7 +477,8 @@ impl JsonlWriter {
             timestamp,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line).await?;
+        Ok(line)
     }
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
@@ -0,0 +1,917 @@
+//! Full-text search over recorded sessions.
+//!
+//! The index is stored at `~/.codex/session_index.jsonl` with one JSON object
+//! per line. Every rollout contributes a `session` record (written when its
+//! `SessionMeta` is recorded) followed by one record per searchable item:
+//!
+//! ````text
+//! {"path":"<rollout>","ts":"<rfc3339>","kind":"session","id":"<uuid>","source":"cli","model_provider":"openai"}
+//! {"path":"<rollout>","ts":"<rfc3339>","kind":"user","text":"fix the flaky test"}
+//! {"path":"<rollout>","ts":"<rfc3339>","kind":"command","text":"cargo test -p codex-core"}
+//! ````
+//!
+//! [`super::RolloutRecorder`] appends records as it writes rollout items, so
+//! the index stays current without rescanning. Rollouts recorded before the
+//! index existed (or by older versions of Codex) are backfilled the first time
+//! a search runs. Appends follow the same single-write, advisory-lock scheme
+//! as the message history so concurrent Codex processes do not interleave.
+//!
+//! Searches stream the index rather than loading it. Records for rollouts that
+//! were deleted or archived are ignored, and once they make up a quarter of the
+//! file the index is compacted by rewriting it without them.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::io::{self};
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_protocol::ConversationId;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::SESSIONS_SUBDIR;
+use super::list::ProviderMatcher;
+use crate::bash::extract_bash_command;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Filename that stores the session search index inside `~/.codex`.
+const INDEX_FILENAME: &str = "session_index.jsonl";
+
+const MAX_RETRIES: usize = 10;
+const RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+/// Longest snippet returned for a single matching record.
+const SNIPPET_MAX_CHARS: usize = 160;
+/// Number of matching records reported per hit.
+const MAX_MATCHES_PER_HIT: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct IndexRecord {
+    path: PathBuf,
+    ts: String,
+    #[serde(flatten)]
+    entry: IndexEntry,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum IndexEntry {
+    Session {
+        id: ConversationId,
+        source: SessionSource,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model_provider: Option<String>,
+    },
+    User {
+        text: String,
+    },
+    Assistant {
+        text: String,
+    },
+    Command {
+        text: String,
+    },
+    File {
+        text: String,
+    },
+}
+
+/// Which part of a session a search match came from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSearchMatchKind {
+    UserMessage,
+    AgentMessage,
+    Command,
+    File,
+}
+
+/// A single indexed record that matched the query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSearchMatch {
+    pub kind: SessionSearchMatchKind,
+    /// The matching line of the record, trimmed to a short snippet.
+    pub snippet: String,
+}
+
+/// A recorded session that matched a search query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSearchHit {
+    /// Absolute path to the rollout file.
+    pub path: PathBuf,
+    pub conversation_id: ConversationId,
+    /// First user message of the session.
+    pub preview: String,
+    pub model_provider: Option<String>,
+    /// RFC3339 timestamp string for when the session was created.
+    pub created_at: String,
+    /// RFC3339 timestamp string for the most recent indexed record.
+    pub updated_at: String,
+    /// Up to a handful of records that matched, oldest first.
+    pub matches: Vec<SessionSearchMatch>,
+}
+
+fn index_filepath(codex_home: &Path) -> PathBuf {
+    codex_home.join(INDEX_FILENAME)
+}
+
+/// Build the index records for a rollout item written to `path` at `ts`.
+pub(crate) fn index_records_for_item(
+    path: &Path,
+    ts: &str,
+    item: &RolloutItem,
+) -> Vec<IndexRecord> {
+    let entries = match item {
+        RolloutItem::SessionMeta(meta_line) => vec![IndexEntry::Session {
+            id: meta_line.meta.id,
+            source: meta_line.meta.source.clone(),
+            model_provider: meta_line.meta.model_provider.clone(),
+        }],
+        RolloutItem::EventMsg(EventMsg::UserMessage(ev)) => vec![IndexEntry::User {
+            text: ev.message.clone(),
+        }],
+        RolloutItem::EventMsg(EventMsg::AgentMessage(ev)) => vec![IndexEntry::Assistant {
+            text: ev.message.clone(),
+        }],
+        RolloutItem::ResponseItem(item) => entries_for_response_item(item),
+        RolloutItem::EventMsg(_) | RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) => {
+            Vec::new()
+        }
+    };
+
+    entries
+        .into_iter()
+        .filter(|entry| match entry {
+            IndexEntry::Session { .. } => true,
+            IndexEntry::User { text }
+            | IndexEntry::Assistant { text }
+            | IndexEntry::Command { text }
+            | IndexEntry::File { text } => !text.trim().is_empty(),
+        })
+        .map(|entry| IndexRecord {
+            path: path.to_path_buf(),
+            ts: ts.to_string(),
+            entry,
+        })
+        .collect()
+}
+
+fn entries_for_response_item(item: &ResponseItem) -> Vec<IndexEntry> {
+    match item {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => match name.as_str() {
+            "shell" | "container.exec" => serde_json::from_str::<ShellToolCallParams>(arguments)
+                .map(|params| entries_for_command(&params.command))
+                .unwrap_or_default(),
+            "apply_patch" => serde_json::from_str::<serde_json::Value>(arguments)
+                .ok()
+                .and_then(|args| args.get("input")?.as_str().map(entries_for_patch))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        },
+        ResponseItem::CustomToolCall { name, input, .. } if name == "apply_patch" => {
+            entries_for_patch(input)
+        }
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => entries_for_command(&exec.command),
+        _ => Vec::new(),
+    }
+}
+
+fn entries_for_command(command: &[String]) -> Vec<IndexEntry> {
+    if let MaybeApplyPatch::Body(args) = codex_apply_patch::maybe_parse_apply_patch(command) {
+        return entries_for_hunks(&args.hunks);
+    }
+
+    let text = match extract_bash_command(command) {
+        Some((_, script)) => script.to_string(),
+        None => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    };
+    vec![IndexEntry::Command { text }]
+}
+
+fn entries_for_patch(patch: &str) -> Vec<IndexEntry> {
+    codex_apply_patch::parse_patch(patch)
+        .map(|args| entries_for_hunks(&args.hunks))
+        .unwrap_or_default()
+}
+
+fn entries_for_hunks(hunks: &[Hunk]) -> Vec<IndexEntry> {
+    let mut files = Vec::new();
+    for hunk in hunks {
+        let (path, move_path) = match hunk {
+            Hunk::AddFile { path, .. }
+            | Hunk::AddBinaryFile { path, .. }
+            | Hunk::AddSymlink { path, .. }
+            | Hunk::SetMode { path, .. }
+            | Hunk::DeleteFile { path } => (path, None),
+            Hunk::UpdateFile {
+                path, move_path, ..
+            } => (path, move_path.as_ref()),
+        };
+        files.extend(std::iter::once(path).chain(move_path));
+    }
+    files
+        .into_iter()
+        .map(|path| IndexEntry::File {
+            text: path.display().to_string(),
+        })
+        .collect()
+}
+
+/// Append `records` to the index under `codex_home`. Uses advisory file
+/// locking so concurrent writers do not interleave, which entails a small
+/// amount of blocking I/O internally.
+pub(crate) async fn append_records(codex_home: &Path, records: Vec<IndexRecord>) -> io::Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let path = index_filepath(codex_home);
+    tokio::task::spawn_blocking(move || append_records_blocking(&path, &records)).await?
+}
+
+fn append_records_blocking(index_path: &Path, records: &[IndexRecord]) -> io::Result<()> {
+    // Construct every line first so the batch is written in a single syscall.
+    let mut buf = String::new();
+    for record in records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| io::Error::other(format!("failed to serialise index record: {e}")))?;
+        buf.push_str(&line);
+        buf.push('\n');
+    }
+
+    if let Some(parent) = index_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.append(true).create(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+
+    for _ in 0..MAX_RETRIES {
+        let mut file = options.open(index_path)?;
+        match file.try_lock() {
+            Ok(()) => {
+                // A compaction may have replaced the index between opening and
+                // locking it; appending to the old file would lose the records.
+                if !is_current_index(&file, index_path)? {
+                    continue;
+                }
+                file.write_all(buf.as_bytes())?;
+                file.flush()?;
+                return Ok(());
+            }
+            Err(std::fs::TryLockError::WouldBlock) => {
+                std::thread::sleep(RETRY_SLEEP);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::WouldBlock,
+        "could not acquire exclusive lock on session index after multiple attempts",
+    ))
+}
+
+/// Whether `file` is still the index at `index_path`, i.e. it has not been
+/// replaced by [`compact_index`] since it was opened.
+#[cfg(unix)]
+fn is_current_index(file: &File, index_path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let opened = file.metadata()?;
+    match std::fs::metadata(index_path) {
+        Ok(current) => Ok(opened.dev() == current.dev() && opened.ino() == current.ino()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// The index is never replaced off Unix (see [`backfill_index`]).
+#[cfg(not(unix))]
+fn is_current_index(_file: &File, _index_path: &Path) -> io::Result<bool> {
+    Ok(true)
+}
+
+/// Stream the index line by line, calling `f` with each record (or `None` for
+/// a line that cannot be parsed) and the size of its line in bytes.
+fn scan_index(reader: impl BufRead, mut f: impl FnMut(Option<IndexRecord>, u64)) -> io::Result<()> {
+    let mut reader = reader;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let len = reader.read_line(&mut line)?;
+        if len == 0 {
+            return Ok(());
+        }
+        // Lines that fail to parse (e.g. a torn write from a crashed process)
+        // are reported as `None` so callers can count them as garbage.
+        f(serde_json::from_str::<IndexRecord>(&line).ok(), len as u64);
+    }
+}
+
+fn open_index(index_path: &Path) -> io::Result<Option<BufReader<File>>> {
+    match File::open(index_path) {
+        Ok(file) => Ok(Some(BufReader::new(file))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Index the rollout at `path` in full unless the index already knows about
+/// it. Used when resuming a session that may predate the index.
+pub(crate) async fn ensure_rollout_indexed(codex_home: &Path, path: &Path) -> io::Result<()> {
+    let index_path = index_filepath(codex_home);
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut indexed = false;
+        if let Some(reader) = open_index(&index_path)? {
+            scan_index(reader, |record, _| {
+                if let Some(record) = record
+                    && matches!(record.entry, IndexEntry::Session { .. })
+                    && record.path == path
+                {
+                    indexed = true;
+                }
+            })?;
+        }
+        if indexed {
+            return Ok(());
+        }
+        append_records_blocking(&index_path, &records_for_rollout(&path)?)
+    })
+    .await?
+}
+
+fn records_for_rollout(path: &Path) -> io::Result<Vec<IndexRecord>> {
+    let file = File::open(path)?;
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        let Ok(rollout_line) = serde_json::from_str::<RolloutLine>(&line) else {
+            continue;
+        };
+        records.extend(index_records_for_item(
+            path,
+            &rollout_line.timestamp,
+            &rollout_line.item,
+        ));
+    }
+    // Without session metadata the rollout cannot be filtered or attributed.
+    if !records
+        .iter()
+        .any(|record| matches!(record.entry, IndexEntry::Session { .. }))
+    {
+        return Ok(Vec::new());
+    }
+    Ok(records)
+}
+
+fn collect_rollout_paths(dir: &Path, out: &mut HashSet<PathBuf>) -> io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_rollout_paths(&path, out)?;
+        } else if file_type.is_file()
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+        {
+            out.insert(path);
+        }
+    }
+    Ok(())
+}
+
+/// Stream the index into per-session accumulators, indexing every rollout
+/// under `~/.codex/sessions` that the index does not know about yet.
+///
+/// Records for rollouts that no longer exist (deleted, or moved to
+/// `archived_sessions`) are skipped, and once they make up a quarter of the
+/// index it is rewritten without them.
+fn backfill_index(codex_home: &Path) -> io::Result<HashMap<PathBuf, SessionAccumulator>> {
+    let index_path = index_filepath(codex_home);
+
+    let mut rollouts = HashSet::new();
+    collect_rollout_paths(&codex_home.join(SESSIONS_SUBDIR), &mut rollouts)?;
+
+    let mut sessions: HashMap<PathBuf, SessionAccumulator> = HashMap::new();
+    let mut indexed: HashSet<PathBuf> = HashSet::new();
+    let mut total_bytes = 0;
+    let mut stale_bytes = 0;
+    if let Some(reader) = open_index(&index_path)? {
+        scan_index(reader, |record, len| {
+            total_bytes += len;
+            match record {
+                Some(record) if rollouts.contains(&record.path) => {
+                    if matches!(record.entry, IndexEntry::Session { .. }) {
+                        indexed.insert(record.path.clone());
+                    }
+                    add_record(&mut sessions, record);
+                }
+                _ => stale_bytes += len,
+            }
+        })?;
+    }
+
+    let mut missing: Vec<&PathBuf> = rollouts
+        .iter()
+        .filter(|path| !indexed.contains(*path))
+        .collect();
+    missing.sort();
+
+    let mut new_records = Vec::new();
+    for path in missing {
+        match records_for_rollout(path) {
+            Ok(rollout_records) => new_records.extend(rollout_records),
+            Err(e) => tracing::warn!("failed to index rollout {}: {e}", path.display()),
+        }
+    }
+    if !new_records.is_empty() {
+        append_records_blocking(&index_path, &new_records)?;
+        for record in new_records {
+            add_record(&mut sessions, record);
+        }
+    }
+
+    // Replacing the index is only safe where appenders can tell that the file
+    // they opened has been swapped out (see `is_current_index`).
+    if cfg!(unix)
+        && stale_bytes > 0
+        && stale_bytes * 4 >= total_bytes
+        && let Err(e) = compact_index(&index_path, &rollouts)
+    {
+        tracing::warn!("failed to compact session search index: {e}");
+    }
+    Ok(sessions)
+}
+
+/// Rewrite the index keeping only the records for `rollouts`. Holds the
+/// index lock while the replacement is written and renamed into place so
+/// concurrent appends are neither lost nor interleaved.
+fn compact_index(index_path: &Path, rollouts: &HashSet<PathBuf>) -> io::Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(index_path)?;
+    let mut locked = false;
+    for _ in 0..MAX_RETRIES {
+        match file.try_lock() {
+            Ok(()) => {
+                locked = true;
+                break;
+            }
+            Err(std::fs::TryLockError::WouldBlock) => std::thread::sleep(RETRY_SLEEP),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if !locked {
+        return Err(io::Error::new(
+            io::ErrorKind::WouldBlock,
+            "could not acquire exclusive lock on session index after multiple attempts",
+        ));
+    }
+
+    let dir = index_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut compacted = tempfile::NamedTempFile::new_in(dir)?;
+    let mut write_result = Ok(());
+    scan_index(BufReader::new(&mut file), |record, _| {
+        let Some(record) = record else {
+            return;
+        };
+        if write_result.is_err() || !rollouts.contains(&record.path) {
+            return;
+        }
+        write_result = serde_json::to_string(&record)
+            .map_err(|e| io::Error::other(format!("failed to serialise index record: {e}")))
+            .and_then(|line| writeln!(compacted, "{line}"));
+    })?;
+    write_result?;
+    compacted.flush()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        compacted
+            .as_file()
+            .set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    compacted.persist(index_path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct SessionAccumulator {
+    conversation_id: Option<ConversationId>,
+    source: Option<SessionSource>,
+    model_provider: Option<String>,
+    created_at: Option<String>,
+    updated_at: String,
+    preview: Option<String>,
+    seen: HashSet<(SessionSearchMatchKind, String)>,
+    texts: Vec<(SessionSearchMatchKind, String)>,
+}
+
+fn add_record(sessions: &mut HashMap<PathBuf, SessionAccumulator>, record: IndexRecord) {
+    let IndexRecord { path, ts, entry } = record;
+    let session = sessions.entry(path).or_default();
+    if ts > session.updated_at {
+        session.updated_at = ts.clone();
+    }
+    let (kind, text) = match entry {
+        IndexEntry::Session {
+            id,
+            source,
+            model_provider,
+        } => {
+            session.conversation_id.get_or_insert(id);
+            session.source.get_or_insert(source);
+            if session.model_provider.is_none() {
+                session.model_provider = model_provider;
+            }
+            session.created_at.get_or_insert(ts);
+            return;
+        }
+        IndexEntry::User { text } => {
+            if session.preview.is_none() {
+                session.preview = Some(text.clone());
+            }
+            (SessionSearchMatchKind::UserMessage, text)
+        }
+        IndexEntry::Assistant { text } => (SessionSearchMatchKind::AgentMessage, text),
+        IndexEntry::Command { text } => (SessionSearchMatchKind::Command, text),
+        IndexEntry::File { text } => (SessionSearchMatchKind::File, text),
+    };
+    // Backfill can race with a live recorder and index an item twice.
+    if session.seen.insert((kind, text.clone())) {
+        session.texts.push((kind, text));
+    }
+}
+
+/// Search recorded sessions for `query`. Every whitespace-separated term must
+/// appear (case-insensitively) somewhere in a session for it to match. Hits
+/// are ordered by how many records matched, then by most recent activity.
+pub(crate) async fn search_sessions(
+    codex_home: &Path,
+    query: &str,
+    limit: usize,
+    allowed_sources: &[SessionSource],
+    model_providers: Option<&[String]>,
+    default_provider: &str,
+) -> io::Result<Vec<SessionSearchHit>> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let codex_home = codex_home.to_path_buf();
+    let sessions = tokio::task::spawn_blocking(move || backfill_index(&codex_home)).await??;
+
+    let provider_matcher =
+        model_providers.and_then(|filters| ProviderMatcher::new(filters, default_provider));
+
+    let mut scored: Vec<(usize, SessionSearchHit)> = Vec::new();
+    for (path, session) in sessions {
+        let SessionAccumulator {
+            conversation_id: Some(conversation_id),
+            source: Some(source),
+            model_provider,
+            created_at: Some(created_at),
+            updated_at,
+            preview: Some(preview),
+            texts,
+            ..
+        } = session
+        else {
+            continue;
+        };
+        if !allowed_sources.is_empty() && !allowed_sources.contains(&source) {
+            continue;
+        }
+        if let Some(matcher) = &provider_matcher
+            && !matcher.matches(model_provider.as_deref())
+        {
+            continue;
+        }
+
+        let lowered: Vec<String> = texts.iter().map(|(_, text)| text.to_lowercase()).collect();
+        let all_terms_present = terms
+            .iter()
+            .all(|term| lowered.iter().any(|text| text.contains(term.as_str())));
+        if !all_terms_present {
+            continue;
+        }
+
+        let matching: Vec<usize> = lowered
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| terms.iter().any(|term| text.contains(term.as_str())))
+            .map(|(idx, _)| idx)
+            .collect();
+        let matches = matching
+            .iter()
+            .take(MAX_MATCHES_PER_HIT)
+            .map(|&idx| {
+                let (kind, text) = &texts[idx];
+                SessionSearchMatch {
+                    kind: *kind,
+                    snippet: snippet(text, &terms),
+                }
+            })
+            .collect();
+
+        scored.push((
+            matching.len(),
+            SessionSearchHit {
+                path,
+                conversation_id,
+                preview,
+                model_provider,
+                created_at,
+                updated_at,
+                matches,
+            },
+        ));
+    }
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| b.updated_at.cmp(&a.updated_at))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    Ok(scored.into_iter().take(limit).map(|(_, hit)| hit).collect())
+}
+
+/// Return the first line of `text` that mentions one of `terms`, truncated.
+fn snippet(text: &str, terms: &[String]) -> String {
+    let line = text
+        .lines()
+        .find(|line| {
+            let line = line.to_lowercase();
+            terms.iter().any(|term| line.contains(term.as_str()))
+        })
+        .unwrap_or(text)
+        .trim();
+    if line.chars().count() <= SNIPPET_MAX_CHARS {
+        return line.to_string();
+    }
+    let truncated: String = line.chars().take(SNIPPET_MAX_CHARS - 1).collect();
+    format!("{truncated}…")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const TS: &str = "2025-01-03T12:00:00.000Z";
+
+    fn session_meta(id: ConversationId, provider: &str) -> RolloutItem {
+        RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id,
+                timestamp: TS.to_string(),
+                source: SessionSource::Cli,
+                model_provider: Some(provider.to_string()),
+                ..Default::default()
+            },
+            git: None,
+        })
+    }
+
+    fn user_message(text: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: text.to_string(),
+            images: None,
+        }))
+    }
+
+    fn shell_call(command: &[&str]) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: json!({ "command": command }).to_string(),
+            call_id: "call-1".to_string(),
+        })
+    }
+
+    fn write_rollout(codex_home: &Path, name: &str, items: &[RolloutItem]) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/01/03");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(name);
+        let mut contents = String::new();
+        for item in items {
+            let line = RolloutLine {
+                timestamp: TS.to_string(),
+                item: item.clone(),
+            };
+            contents.push_str(&serde_json::to_string(&line).expect("serialize rollout line"));
+            contents.push('\n');
+        }
+        std::fs::write(&path, contents).expect("write rollout");
+        path
+    }
+
+    #[test]
+    fn indexes_commands_and_patched_files() {
+        let path = Path::new("/tmp/rollout.jsonl");
+        let command = index_records_for_item(
+            path,
+            TS,
+            &shell_call(&["bash", "-lc", "cargo test -p codex-core"]),
+        );
+        assert_eq!(
+            command.into_iter().map(|r| r.entry).collect::<Vec<_>>(),
+            vec![IndexEntry::Command {
+                text: "cargo test -p codex-core".to_string()
+            }]
+        );
+
+        let patch = "*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-a\n+b\n*** Add File: docs/new.md\n+hello\n*** End Patch";
+        let files = index_records_for_item(
+            path,
+            TS,
+            &RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-2".to_string(),
+                name: "apply_patch".to_string(),
+                input: patch.to_string(),
+            }),
+        );
+        assert_eq!(
+            files.into_iter().map(|r| r.entry).collect::<Vec<_>>(),
+            vec![
+                IndexEntry::File {
+                    text: "src/lib.rs".to_string()
+                },
+                IndexEntry::File {
+                    text: "docs/new.md".to_string()
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_backfills_and_requires_every_term() {
+        let home = TempDir::new().expect("tempdir");
+        let flaky = write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000001.jsonl",
+            &[
+                session_meta(ConversationId::new(), "openai"),
+                user_message("fix the flaky watcher test"),
+                shell_call(&["cargo", "test", "-p", "codex-core"]),
+            ],
+        );
+        write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000002.jsonl",
+            &[
+                session_meta(ConversationId::new(), "openai"),
+                user_message("write docs for the watcher"),
+            ],
+        );
+
+        let hits = search_sessions(home.path(), "Watcher CARGO", 10, &[], None, "openai")
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, flaky);
+        assert_eq!(hits[0].preview, "fix the flaky watcher test");
+        assert_eq!(
+            hits[0].matches,
+            vec![
+                SessionSearchMatch {
+                    kind: SessionSearchMatchKind::UserMessage,
+                    snippet: "fix the flaky watcher test".to_string(),
+                },
+                SessionSearchMatch {
+                    kind: SessionSearchMatchKind::Command,
+                    snippet: "cargo test -p codex-core".to_string(),
+                },
+            ]
+        );
+
+        // The backfill is persisted, so a second search does not re-index.
+        let index_lines = std::fs::read_to_string(index_filepath(home.path()))
+            .expect("read index")
+            .lines()
+            .count();
+        search_sessions(home.path(), "watcher", 10, &[], None, "openai")
+            .await
+            .expect("search");
+        let index_lines_after = std::fs::read_to_string(index_filepath(home.path()))
+            .expect("read index")
+            .lines()
+            .count();
+        assert_eq!(index_lines, index_lines_after);
+    }
+
+    // The index is only compacted on Unix.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn search_compacts_records_of_removed_rollouts() {
+        let home = TempDir::new().expect("tempdir");
+        let kept = write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000004.jsonl",
+            &[
+                session_meta(ConversationId::new(), "openai"),
+                user_message("tune the watcher"),
+            ],
+        );
+        let archived = write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000005.jsonl",
+            &[
+                session_meta(ConversationId::new(), "openai"),
+                user_message("delete the watcher"),
+                shell_call(&["rm", "-rf", "watcher"]),
+            ],
+        );
+        let hits = search_sessions(home.path(), "watcher", 10, &[], None, "openai")
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 2);
+
+        std::fs::remove_file(&archived).expect("remove rollout");
+        let hits = search_sessions(home.path(), "watcher", 10, &[], None, "openai")
+            .await
+            .expect("search");
+        assert_eq!(
+            hits.into_iter().map(|hit| hit.path).collect::<Vec<_>>(),
+            vec![kept.clone()]
+        );
+
+        let index = std::fs::read_to_string(index_filepath(home.path())).expect("read index");
+        let paths: HashSet<PathBuf> = index
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<IndexRecord>(line)
+                    .expect("parse index record")
+                    .path
+            })
+            .collect();
+        assert_eq!(paths, HashSet::from([kept]));
+    }
+
+    #[tokio::test]
+    async fn search_filters_by_provider() {
+        let home = TempDir::new().expect("tempdir");
+        write_rollout(
+            home.path(),
+            "rollout-2025-01-03T12-00-00-00000000-0000-0000-0000-000000000003.jsonl",
+            &[
+                session_meta(ConversationId::new(), "anthropic"),
+                user_message("refactor the parser"),
+            ],
+        );
+
+        let providers = vec!["openai".to_string()];
+        let hits = search_sessions(home.path(), "parser", 10, &[], Some(&providers), "openai")
+            .await
+            .expect("search");
+        assert_eq!(hits, Vec::new());
+
+        let providers = vec!["anthropic".to_string()];
+        let hits = search_sessions(home.path(), "parser", 10, &[], Some(&providers), "openai")
+            .await
+            .expect("search");
+        assert_eq!(hits.len(), 1);
+    }
+}
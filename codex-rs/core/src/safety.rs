@@ -5,8 +5,13 @@ use std::path::PathBuf;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
 use crate::exec::SandboxType;
+use crate::exec_policy::ExecPolicyDecision;
 
+use crate::protocol::ApprovalRule;
+use crate::protocol::ApprovalRuleKind;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
 
@@ -96,6 +101,79 @@ pub fn assess_patch_safety(
     }
 }
 
+/// What to do with a shell command before its first attempt.
+#[derive(Debug, PartialEq)]
+pub(crate) enum CommandSafetyCheck {
+    /// Run without prompting.
+    Run,
+    /// Ask the user first; `reason` names the execpolicy rule that requires
+    /// approval, if any.
+    AskUser {
+        reason: Option<String>,
+    },
+    Reject {
+        reason: String,
+    },
+}
+
+/// Combines the execpolicy verdict for a command with the tool's own
+/// approval heuristics (`wants_initial_approval`). The policy can forbid a
+/// command or demand approval for it, but an `Allow` only skips the prompt
+/// for commands that stay in the sandbox: a request for escalated
+/// permissions is always judged by the heuristics.
+pub(crate) fn assess_command_safety(
+    exec_policy_decision: Option<ExecPolicyDecision>,
+    approval_policy: AskForApproval,
+    wants_initial_approval: bool,
+    with_escalated_permissions: bool,
+) -> CommandSafetyCheck {
+    match exec_policy_decision {
+        Some(ExecPolicyDecision::Forbid { reason }) => CommandSafetyCheck::Reject {
+            reason: format!("forbidden by execpolicy: {reason}"),
+        },
+        Some(ExecPolicyDecision::RequireApproval { reason }) => {
+            if approval_policy == AskForApproval::Never {
+                CommandSafetyCheck::Reject {
+                    reason: format!(
+                        "execpolicy requires approval ({reason}) but approval policy is never"
+                    ),
+                }
+            } else {
+                CommandSafetyCheck::AskUser {
+                    reason: Some(reason),
+                }
+            }
+        }
+        Some(ExecPolicyDecision::Allow) if !with_escalated_permissions => CommandSafetyCheck::Run,
+        Some(ExecPolicyDecision::Allow) | None => {
+            if wants_initial_approval {
+                CommandSafetyCheck::AskUser { reason: None }
+            } else {
+                CommandSafetyCheck::Run
+            }
+        }
+    }
+}
+
+/// Returns `true` when persisted "always allow" rules cover `command`, so the
+/// user does not need to be prompted. A `bash -lc` script is covered when an
+/// exact rule names it or when every plain command in it matches a rule;
+/// scripts that cannot be split into plain commands only match exact rules.
+pub fn is_command_approved_by_rules(command: &[String], rules: &[ApprovalRule]) -> bool {
+    let matches_any = |command: &[String]| rules.iter().any(|rule| rule.matches(command));
+    if rules
+        .iter()
+        .any(|rule| rule.kind == ApprovalRuleKind::Exact && rule.matches(command))
+    {
+        return true;
+    }
+    match parse_shell_lc_plain_commands(command) {
+        Some(commands) => !commands.is_empty() && commands.iter().all(|c| matches_any(c)),
+        None if extract_bash_command(command).is_some() => false,
+        None => matches_any(command),
+    }
+}
+
 pub fn get_platform_sandbox() -> Option<SandboxType> {
     if cfg!(target_os = "macos") {
         Some(SandboxType::MacosSeatbelt)
@@ -121,7 +199,7 @@ fn is_write_patch_constrained_to_writable_paths(
 ) -> bool {
     // Early‑exit if there are no declared writable roots.
     let writable_roots = match sandbox_policy {
-        SandboxPolicy::ReadOnly => {
+        SandboxPolicy::ReadOnly { .. } => {
             return false;
         }
         SandboxPolicy::DangerFullAccess => {
@@ -167,11 +245,25 @@ fn is_write_patch_constrained_to_writable_paths(
 
     for (path, change) in action.changes() {
         match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
+            ApplyPatchFileChange::Add { .. }
+            | ApplyPatchFileChange::AddBinary { .. }
+            | ApplyPatchFileChange::Delete { .. }
+            | ApplyPatchFileChange::SetMode { .. } => {
                 if !is_path_writable(path) {
                     return false;
                 }
             }
+            ApplyPatchFileChange::AddSymlink { target } => {
+                // Later writes through the link land on its target, so the
+                // target has to be writable as well.
+                let target = match path.parent() {
+                    Some(parent) => parent.join(target),
+                    None => target.clone(),
+                };
+                if !is_path_writable(path) || !is_path_writable(&target) {
+                    return false;
+                }
+            }
             ApplyPatchFileChange::Update { move_path, .. } => {
                 if !is_path_writable(path) {
                     return false;
@@ -193,6 +285,50 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn execpolicy_allow_does_not_cover_escalated_commands() {
+        assert_eq!(
+            assess_command_safety(
+                Some(ExecPolicyDecision::Allow),
+                AskForApproval::OnRequest,
+                true,
+                false,
+            ),
+            CommandSafetyCheck::Run
+        );
+        assert_eq!(
+            assess_command_safety(
+                Some(ExecPolicyDecision::Allow),
+                AskForApproval::OnRequest,
+                true,
+                true,
+            ),
+            CommandSafetyCheck::AskUser { reason: None }
+        );
+    }
+
+    #[test]
+    fn execpolicy_require_approval_is_rejected_under_never() {
+        let decision = || {
+            Some(ExecPolicyDecision::RequireApproval {
+                reason: "deploys".to_string(),
+            })
+        };
+        assert_eq!(
+            assess_command_safety(decision(), AskForApproval::OnFailure, false, false),
+            CommandSafetyCheck::AskUser {
+                reason: Some("deploys".to_string())
+            }
+        );
+        assert_eq!(
+            assess_command_safety(decision(), AskForApproval::Never, false, false),
+            CommandSafetyCheck::Reject {
+                reason: "execpolicy requires approval (deploys) but approval policy is never"
+                    .to_string()
+            }
+        );
+    }
+
     #[test]
     fn test_writable_roots_constraint() {
         // Use a temporary directory as our workspace to avoid touching
@@ -214,6 +350,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            deny_read: vec![],
+            network_allowlist: vec![],
         };
 
         assert!(is_write_patch_constrained_to_writable_paths(
@@ -235,6 +373,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            deny_read: Vec::new(),
+            network_allowlist: Vec::new(),
         };
         assert!(is_write_patch_constrained_to_writable_paths(
             &add_outside,
@@ -242,4 +382,81 @@ mod tests {
             &cwd,
         ));
     }
+
+    #[test]
+    fn symlink_targets_must_be_writable() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let symlink_to = |target: &str| {
+            let argv = vec![
+                "apply_patch".to_string(),
+                format!(
+                    "*** Begin Patch\n*** Add Symlink: links/link\n*** Target: {target}\n*** End Patch"
+                ),
+            ];
+            match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, &cwd) {
+                codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+                other => panic!("expected a verified patch, got {other:?}"),
+            }
+        };
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            deny_read: Vec::new(),
+            network_allowlist: Vec::new(),
+        };
+
+        assert!(is_write_patch_constrained_to_writable_paths(
+            &symlink_to("../src/main.rs"),
+            &policy,
+            &cwd,
+        ));
+        assert!(!is_write_patch_constrained_to_writable_paths(
+            &symlink_to("../../outside.txt"),
+            &policy,
+            &cwd,
+        ));
+    }
+
+    #[test]
+    fn approval_rules_cover_plain_commands_in_scripts() {
+        let vec_str = |items: &[&str]| -> Vec<String> {
+            items.iter().map(std::string::ToString::to_string).collect()
+        };
+        let rules = vec![
+            ApprovalRule {
+                kind: ApprovalRuleKind::Prefix,
+                command: vec_str(&["cargo", "test"]),
+            },
+            ApprovalRule {
+                kind: ApprovalRuleKind::Program,
+                command: vec_str(&["pnpm"]),
+            },
+        ];
+
+        assert!(is_command_approved_by_rules(
+            &vec_str(&["cargo", "test", "-p", "codex-core"]),
+            &rules
+        ));
+        assert!(is_command_approved_by_rules(
+            &vec_str(&["bash", "-lc", "cargo test && pnpm lint"]),
+            &rules
+        ));
+        assert!(!is_command_approved_by_rules(
+            &vec_str(&["bash", "-lc", "cargo test && rm -rf target"]),
+            &rules
+        ));
+        assert!(!is_command_approved_by_rules(
+            &vec_str(&["cargo", "build"]),
+            &rules
+        ));
+        // A script that cannot be split into plain commands is never covered
+        // by prefix or program rules.
+        assert!(!is_command_approved_by_rules(
+            &vec_str(&["bash", "-lc", "pnpm lint > $(mktemp)"]),
+            &rules
+        ));
+    }
 }
@@ -120,6 +120,7 @@ pub(crate) async fn assess_command(
         parallel_tool_calls: false,
         base_instructions_override: Some(system_prompt),
         output_schema: Some(sandbox_assessment_schema()),
+        max_output_tokens: None,
     };
 
     let child_otel =
@@ -195,7 +196,7 @@ pub(crate) async fn assess_command(
 fn summarize_sandbox_policy(policy: &SandboxPolicy) -> String {
     match policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
-        SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::ReadOnly { .. } => "read-only".to_string(),
         SandboxPolicy::WorkspaceWrite { network_access, .. } => {
             let network = if *network_access {
                 "network"
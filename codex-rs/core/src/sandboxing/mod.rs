@@ -7,6 +7,7 @@ ready‑to‑spawn environment.
 */
 
 pub mod assessment;
+pub mod network;
 
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
@@ -49,6 +50,37 @@ pub struct ExecEnv {
     pub arg0: Option<String>,
 }
 
+/// Describes the parts of `sandbox_policy` that `sandbox`, the sandbox this
+/// platform provides (if any), does not enforce, so they can be surfaced to
+/// the user instead of being silently ignored.
+pub(crate) fn unenforced_policy_warnings(
+    sandbox_policy: &SandboxPolicy,
+    sandbox: Option<SandboxType>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !sandbox_policy.get_deny_read_paths().is_empty()
+        && !matches!(
+            sandbox,
+            Some(SandboxType::LinuxSeccomp | SandboxType::MacosSeatbelt)
+        )
+    {
+        warnings.push(
+            "`deny_read` is not enforced by the sandbox on this platform; the configured paths remain readable by commands."
+                .to_string(),
+        );
+    }
+    if !sandbox_policy.get_network_allowlist().is_empty()
+        && !sandbox_policy.has_full_network_access()
+        && !matches!(sandbox, Some(SandboxType::LinuxSeccomp))
+    {
+        warnings.push(
+            "`network_allowlist` is only supported by the Linux sandbox; network access stays fully blocked for commands on this platform."
+                .to_string(),
+        );
+    }
+    warnings
+}
+
 pub enum SandboxPreference {
     Auto,
     Require,
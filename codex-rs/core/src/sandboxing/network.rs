@@ -0,0 +1,278 @@
+/*
+Module: sandboxing::network
+
+Host allowlist matching for the sandbox's filtering network proxy, plus the
+stable one-line report the proxy writes when it refuses a connection so the
+exec pipeline can surface blocked hosts as structured sandbox denials.
+
+Reports travel over a pipe that only the proxy holds (see
+`NetworkReportPipe`), never through the command's own output, so a sandboxed
+command cannot forge them.
+*/
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Environment variable naming the inherited file descriptor on which the
+/// Linux sandbox helper's filtering proxy writes its reports. The helper
+/// removes it and keeps the descriptor away from the sandboxed command.
+pub const CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR: &str = "CODEX_SANDBOX_NETWORK_REPORT_FD";
+
+/// Prefix of the line the filtering proxy reports for every connection it
+/// refuses.
+const BLOCKED_CONNECTION_PREFIX: &str = "codex sandbox: blocked network connection to ";
+const BLOCKED_CONNECTION_SUFFIX: &str = " (not in network_allowlist)";
+
+/// A single parsed `network_allowlist` entry: `host`, `host:port`,
+/// `*.domain`, or `*.domain:port`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkAllowlistEntry {
+    host: String,
+    port: Option<u16>,
+}
+
+impl NetworkAllowlistEntry {
+    /// Returns `None` for entries that cannot be parsed (e.g. an empty host or
+    /// a non-numeric port) so that malformed config never widens access.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        let (host, port) = if let Some(rest) = entry.strip_prefix('[') {
+            // Bracketed IPv6 literal, e.g. `[::1]` or `[::1]:8080`.
+            let (host, rest) = rest.split_once(']')?;
+            let port = match rest {
+                "" => None,
+                rest => Some(rest.strip_prefix(':')?.parse::<u16>().ok()?),
+            };
+            (host, port)
+        } else {
+            match entry.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port.parse::<u16>().ok()?)),
+                None => (entry, None),
+            }
+        };
+        if host.is_empty() || host == "*." {
+            return None;
+        }
+        Some(Self {
+            host: host.to_ascii_lowercase(),
+            port,
+        })
+    }
+
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        if self.port.is_some_and(|allowed| allowed != port) {
+            return false;
+        }
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        match self.host.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.')),
+            None => host == self.host,
+        }
+    }
+}
+
+/// Returns `true` if `host:port` matches any entry of `allowlist`.
+pub fn is_host_allowed(allowlist: &[String], host: &str, port: u16) -> bool {
+    allowlist
+        .iter()
+        .filter_map(|entry| NetworkAllowlistEntry::parse(entry))
+        .any(|entry| entry.matches(host, port))
+}
+
+/// A connection attempt refused by the filtering proxy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockedConnection {
+    pub host: String,
+    pub port: u16,
+}
+
+impl BlockedConnection {
+    /// The line the proxy writes to its report channel; parsed back by
+    /// [`parse_blocked_connections`].
+    pub fn report_line(&self) -> String {
+        format!(
+            "{BLOCKED_CONNECTION_PREFIX}{}:{}{BLOCKED_CONNECTION_SUFFIX}",
+            self.host, self.port
+        )
+    }
+}
+
+/// Extracts every distinct connection the filtering proxy reported as blocked
+/// from the contents of its report channel, in the order they were first
+/// reported.
+pub fn parse_blocked_connections(output: &str) -> Vec<BlockedConnection> {
+    let mut blocked: Vec<BlockedConnection> = Vec::new();
+    for line in output.lines() {
+        let Some(target) = line
+            .trim()
+            .strip_prefix(BLOCKED_CONNECTION_PREFIX)
+            .and_then(|rest| rest.strip_suffix(BLOCKED_CONNECTION_SUFFIX))
+        else {
+            continue;
+        };
+        let Some((host, port)) = target.rsplit_once(':') else {
+            continue;
+        };
+        let Ok(port) = port.parse::<u16>() else {
+            continue;
+        };
+        let connection = BlockedConnection {
+            host: host.to_string(),
+            port,
+        };
+        if !blocked.contains(&connection) {
+            blocked.push(connection);
+        }
+    }
+    blocked
+}
+
+/// Pipe over which the filtering proxy inside the Linux sandbox helper reports
+/// blocked connections back to the exec pipeline.
+#[cfg(target_os = "linux")]
+pub(crate) struct NetworkReportPipe {
+    read: std::os::fd::OwnedFd,
+    write: Option<std::os::fd::OwnedFd>,
+}
+
+#[cfg(target_os = "linux")]
+impl NetworkReportPipe {
+    /// Both ends are close-on-exec; the write end is made inheritable only in
+    /// the spawned helper (see `spawn_child_async`).
+    pub(crate) fn new() -> std::io::Result<Self> {
+        use std::os::fd::FromRawFd;
+
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let (read, write) = unsafe {
+            (
+                std::os::fd::OwnedFd::from_raw_fd(fds[0]),
+                std::os::fd::OwnedFd::from_raw_fd(fds[1]),
+            )
+        };
+        Ok(Self {
+            read,
+            write: Some(write),
+        })
+    }
+
+    pub(crate) fn write_fd(&self) -> i32 {
+        use std::os::fd::AsRawFd;
+
+        self.write.as_ref().map_or(-1, AsRawFd::as_raw_fd)
+    }
+
+    /// Drops this process's copy of the write end once the helper has been
+    /// spawned with its own.
+    pub(crate) fn close_write(&mut self) {
+        self.write = None;
+    }
+
+    /// Reads whatever the proxy has reported so far without waiting for it to
+    /// exit; reports are written before the refused client gets its response.
+    pub(crate) fn read_reports(self) -> Vec<BlockedConnection> {
+        use std::io::Read;
+        use std::os::fd::AsRawFd;
+
+        let fd = self.read.as_raw_fd();
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+        }
+        let mut file = std::fs::File::from(self.read);
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            match file.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => buf.extend_from_slice(&chunk[..read]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        parse_blocked_connections(&String::from_utf8_lossy(&buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|entry| (*entry).to_string()).collect()
+    }
+
+    #[test]
+    fn exact_host_matches_any_port_when_port_omitted() {
+        let allowlist = allowlist(&["registry.example.com"]);
+        assert!(is_host_allowed(&allowlist, "registry.example.com", 443));
+        assert!(is_host_allowed(&allowlist, "Registry.Example.com", 8080));
+        assert!(!is_host_allowed(&allowlist, "example.com", 443));
+        assert!(!is_host_allowed(
+            &allowlist,
+            "evil-registry.example.com",
+            443
+        ));
+    }
+
+    #[test]
+    fn port_restricts_match() {
+        let allowlist = allowlist(&["registry.example.com:443"]);
+        assert!(is_host_allowed(&allowlist, "registry.example.com", 443));
+        assert!(!is_host_allowed(&allowlist, "registry.example.com", 80));
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let allowlist = allowlist(&["*.crates.io:443"]);
+        assert!(is_host_allowed(&allowlist, "index.crates.io", 443));
+        assert!(is_host_allowed(&allowlist, "static.index.crates.io", 443));
+        assert!(!is_host_allowed(&allowlist, "crates.io", 443));
+        assert!(!is_host_allowed(&allowlist, "notcrates.io", 443));
+    }
+
+    #[test]
+    fn malformed_entries_never_match() {
+        let allowlist = allowlist(&["", "host:port", "*.", ":443"]);
+        assert!(!is_host_allowed(&allowlist, "host", 443));
+        assert!(!is_host_allowed(&allowlist, "", 443));
+    }
+
+    #[test]
+    fn ipv6_literals_match() {
+        let allowlist = allowlist(&["[::1]:8080", "[fd00::2]"]);
+        assert!(is_host_allowed(&allowlist, "::1", 8080));
+        assert!(is_host_allowed(&allowlist, "[::1]", 8080));
+        assert!(!is_host_allowed(&allowlist, "::1", 8081));
+        assert!(is_host_allowed(&allowlist, "fd00::2", 22));
+    }
+
+    #[test]
+    fn blocked_connections_round_trip_through_report() {
+        let first = BlockedConnection {
+            host: "example.com".to_string(),
+            port: 443,
+        };
+        let second = BlockedConnection {
+            host: "pypi.org".to_string(),
+            port: 80,
+        };
+        let output = format!(
+            "npm ERR! network\n{}\n{}\n{}\n",
+            first.report_line(),
+            second.report_line(),
+            first.report_line()
+        );
+
+        assert_eq!(parse_blocked_connections(&output), vec![first, second]);
+    }
+}
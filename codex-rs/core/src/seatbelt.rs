@@ -39,6 +39,7 @@ pub async fn spawn_command_under_seatbelt(
         sandbox_policy,
         stdio_policy,
         env,
+        None,
     )
     .await
 }
@@ -48,6 +49,17 @@ pub(crate) fn create_seatbelt_command_args(
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
 ) -> Vec<String> {
+    let writable_roots = if sandbox_policy.has_full_disk_write_access() {
+        Vec::new()
+    } else {
+        sandbox_policy.get_writable_roots_with_cwd(sandbox_policy_cwd)
+    };
+    // Canonicalize to avoid mismatches like /var vs /private/var on macOS.
+    let canonical_writable_roots: Vec<PathBuf> = writable_roots
+        .iter()
+        .map(|wr| wr.root.canonicalize().unwrap_or_else(|_| wr.root.clone()))
+        .collect();
+
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
             // Allegedly, this is more permissive than `(allow file-write*)`.
@@ -56,17 +68,16 @@ pub(crate) fn create_seatbelt_command_args(
                 Vec::new(),
             )
         } else {
-            let writable_roots = sandbox_policy.get_writable_roots_with_cwd(sandbox_policy_cwd);
-
             let mut writable_folder_policies: Vec<String> = Vec::new();
             let mut file_write_params = Vec::new();
 
-            for (index, wr) in writable_roots.iter().enumerate() {
-                // Canonicalize to avoid mismatches like /var vs /private/var on macOS.
-                let canonical_root = wr.root.canonicalize().unwrap_or_else(|_| wr.root.clone());
+            for (index, (wr, canonical_root)) in writable_roots
+                .iter()
+                .zip(canonical_writable_roots.iter())
+                .enumerate()
+            {
                 let root_param = format!("WRITABLE_ROOT_{index}");
-                file_write_params.push((root_param.clone(), canonical_root));
-
+                file_write_params.push((root_param.clone(), canonical_root.clone()));
                 if wr.read_only_subpaths.is_empty() {
                     writable_folder_policies.push(format!("(subpath (param \"{root_param}\"))"));
                 } else {
@@ -98,10 +109,57 @@ pub(crate) fn create_seatbelt_command_args(
         }
     };
 
-    let file_read_policy = if sandbox_policy.has_full_disk_read_access() {
-        "; allow read-only file operations\n(allow file-read*)"
+    let (file_read_policy, file_read_dir_params) = if sandbox_policy.has_full_disk_read_access() {
+        (
+            "; allow read-only file operations\n(allow file-read*)".to_string(),
+            Vec::new(),
+        )
     } else {
-        ""
+        let deny_read: Vec<PathBuf> = sandbox_policy
+            .get_deny_read_paths()
+            .iter()
+            .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+            .collect();
+        let require_not =
+            |index: usize| format!("(require-not (subpath (param \"DENY_READ_{index}\")))");
+
+        let mut readable_policies = vec![format!(
+            "(require-all {} )",
+            (0..deny_read.len())
+                .map(require_not)
+                .collect::<Vec<_>>()
+                .join(" ")
+        )];
+        // Writable roots nested under a denied path remain readable, minus
+        // any denied paths nested inside them.
+        for (index, root) in canonical_writable_roots.iter().enumerate() {
+            if !deny_read.iter().any(|denied| root.starts_with(denied)) {
+                continue;
+            }
+            let nested_denials: Vec<String> = deny_read
+                .iter()
+                .enumerate()
+                .filter(|(_, denied)| denied.starts_with(root) && *denied != root)
+                .map(|(deny_index, _)| require_not(deny_index))
+                .collect();
+            readable_policies.push(format!(
+                "(require-all (subpath (param \"WRITABLE_ROOT_{index}\")) {} )",
+                nested_denials.join(" ")
+            ));
+        }
+
+        let file_read_params = deny_read
+            .into_iter()
+            .enumerate()
+            .map(|(index, path)| (format!("DENY_READ_{index}"), path))
+            .collect();
+        (
+            format!(
+                "; allow read-only file operations outside of denied paths\n(allow file-read*\n{}\n)",
+                readable_policies.join(" ")
+            ),
+            file_read_params,
+        )
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
@@ -115,7 +173,12 @@ pub(crate) fn create_seatbelt_command_args(
         "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}"
     );
 
-    let dir_params = [file_write_dir_params, macos_dir_params()].concat();
+    let dir_params = [
+        file_read_dir_params,
+        file_write_dir_params,
+        macos_dir_params(),
+    ]
+    .concat();
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     let definition_args = dir_params
@@ -186,6 +249,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: true,
             exclude_slash_tmp: true,
+            deny_read: vec![],
+            network_allowlist: vec![],
         };
 
         let args = create_seatbelt_command_args(
@@ -262,6 +327,8 @@ mod tests {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            deny_read: vec![],
+            network_allowlist: vec![],
         };
 
         let args = create_seatbelt_command_args(
@@ -336,6 +403,66 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_with_deny_read() {
+        let tmp = TempDir::new().expect("tempdir");
+        let denied = tmp.path().join("denied");
+        let workspace = denied.join("workspace");
+        fs::create_dir_all(&workspace).expect("create workspace");
+        let denied_canon = denied.canonicalize().expect("canonicalize denied");
+        let workspace_canon = workspace.canonicalize().expect("canonicalize workspace");
+
+        // The workspace lives under a denied path, so it must be re-allowed
+        // for reads explicitly.
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+            deny_read: vec![denied],
+            network_allowlist: vec![],
+        };
+
+        let args = create_seatbelt_command_args(
+            vec!["/bin/echo".to_string(), "hello".to_string()],
+            &policy,
+            &workspace,
+        );
+
+        let expected_policy = format!(
+            r#"{MACOS_SEATBELT_BASE_POLICY}
+; allow read-only file operations outside of denied paths
+(allow file-read*
+(require-all (require-not (subpath (param "DENY_READ_0"))) ) (require-all (subpath (param "WRITABLE_ROOT_0"))  )
+)
+(allow file-write*
+(subpath (param "WRITABLE_ROOT_0"))
+)
+"#,
+        );
+
+        let mut expected_args = vec![
+            "-p".to_string(),
+            expected_policy,
+            format!("-DDENY_READ_0={}", denied_canon.to_string_lossy()),
+            format!("-DWRITABLE_ROOT_0={}", workspace_canon.to_string_lossy()),
+        ];
+
+        expected_args.extend(
+            macos_dir_params()
+                .into_iter()
+                .map(|(key, value)| format!("-D{key}={value}", value = value.to_string_lossy())),
+        );
+
+        expected_args.extend(vec![
+            "--".to_string(),
+            "/bin/echo".to_string(),
+            "hello".to_string(),
+        ]);
+
+        assert_eq!(expected_args, args);
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
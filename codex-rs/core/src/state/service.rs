@@ -1,22 +1,33 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::approval_rules::ApprovalRulesCache;
+use crate::hooks::HookRunner;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_sampling::SamplingApprovals;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use codex_execpolicy::ExecvChecker;
 use codex_otel::otel_event_manager::OtelEventManager;
 use tokio::sync::Mutex;
 
 pub(crate) struct SessionServices {
     pub(crate) mcp_connection_manager: McpConnectionManager,
+    pub(crate) mcp_sampling_approvals: SamplingApprovals,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: HookRunner,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) exec_policy: ExecvChecker,
+    /// Where project-scoped approval rules are persisted.
+    pub(crate) codex_home: PathBuf,
+    pub(crate) approval_rules: Mutex<ApprovalRulesCache>,
 }
@@ -134,6 +134,7 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    blocked_network_connections: Vec::new(),
                 };
                 let output_items = [user_shell_command_record_item(&raw_command, &exec_output)];
                 session
@@ -185,6 +186,7 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    blocked_network_connections: Vec::new(),
                 };
                 session
                     .send_event(
@@ -195,11 +195,14 @@ Your patch language is a stripped‑down, file‑oriented diff format designed t
 
 Within that envelope, you get a sequence of file operations.
 You MUST include a header to specify the action you are taking.
-Each operation starts with one of three headers:
+Each operation starts with one of these headers:
 
 *** Add File: <path> - create a new file. Every following line is a + line (the initial contents).
+*** Add Binary File: <path> - create a binary file. Every following line is a + line of its base64-encoded contents.
+*** Add Symlink: <path> - create a symbolic link. Followed by a single *** Target: <target> line.
 *** Delete File: <path> - remove an existing file. Nothing follows.
 *** Update File: <path> - patch an existing file in place (optionally with a rename).
+*** Set Mode: <mode> <path> - set the permission bits of a file (not a symlink) to an octal mode such as 755. Unix only. Nothing follows.
 
 May be immediately followed by *** Move to: <new path> if you want to rename the file.
 Then one or more “hunks”, each introduced by @@ (optionally followed by a hunk header).
@@ -227,10 +230,13 @@ The full grammar definition is below:
 Patch := Begin { FileOp } End
 Begin := "*** Begin Patch" NEWLINE
 End := "*** End Patch" NEWLINE
-FileOp := AddFile | DeleteFile | UpdateFile
+FileOp := AddFile | AddBinaryFile | AddSymlink | DeleteFile | UpdateFile | SetMode
 AddFile := "*** Add File: " path NEWLINE { "+" line NEWLINE }
+AddBinaryFile := "*** Add Binary File: " path NEWLINE { "+" base64 NEWLINE }
+AddSymlink := "*** Add Symlink: " path NEWLINE "*** Target: " target NEWLINE
 DeleteFile := "*** Delete File: " path NEWLINE
 UpdateFile := "*** Update File: " path NEWLINE [ MoveTo ] { Hunk }
+SetMode := "*** Set Mode: " mode " " path NEWLINE
 MoveTo := "*** Move to: " newPath NEWLINE
 Hunk := "@@" [ header ] NEWLINE { HunkLine } [ "*** End of File" NEWLINE ]
 HunkLine := (" " | "-" | "+") text NEWLINE
@@ -11,6 +11,7 @@ pub mod spec;
 
 use crate::context_manager::format_output_for_model_body;
 use crate::exec::ExecToolCallOutput;
+use crate::sandboxing::network::BlockedConnection;
 pub use router::ToolRouter;
 use serde::Serialize;
 
@@ -33,6 +34,8 @@ pub fn format_exec_output_for_model(exec_output: &ExecToolCallOutput) -> String
     struct ExecMetadata {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        blocked_network_connections: Vec<BlockedConnection>,
     }
 
     #[derive(Serialize)]
@@ -51,6 +54,7 @@ pub fn format_exec_output_for_model(exec_output: &ExecToolCallOutput) -> String
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            blocked_network_connections: exec_output.blocked_network_connections.clone(),
         },
     };
 
@@ -9,6 +9,9 @@ use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
 use crate::exec::ExecToolCallOutput;
+use crate::exec_policy::evaluate_command;
+use crate::safety::CommandSafetyCheck;
+use crate::safety::assess_command_safety;
 use crate::sandboxing::SandboxManager;
 use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ProvidesSandboxRetryData;
@@ -48,12 +51,59 @@ impl ToolOrchestrator {
         let otel_user = codex_otel::otel_event_manager::ToolDecisionSource::User;
         let otel_cfg = codex_otel::otel_event_manager::ToolDecisionSource::Config;
 
-        // 1) Approval
-        let needs_initial_approval =
-            tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy);
+        // 1) Approval. The configured execpolicy and the tool's own
+        // heuristics are combined by `assess_command_safety`.
+        let exec_policy_decision = req.sandbox_retry_data().and_then(|metadata| {
+            evaluate_command(
+                &tool_ctx.session.services.exec_policy,
+                &metadata.command,
+                &metadata.cwd,
+                &turn_ctx.sandbox_policy,
+                approval_policy,
+            )
+            .decision
+        });
+        let safety = assess_command_safety(
+            exec_policy_decision,
+            approval_policy,
+            tool.wants_initial_approval(req, approval_policy, &turn_ctx.sandbox_policy),
+            tool.wants_escalated_first_attempt(req),
+        );
+        let (needs_initial_approval, exec_policy_reason) = match safety {
+            CommandSafetyCheck::Reject { reason } => {
+                otel.tool_decision(otel_tn, otel_ci, ReviewDecision::Denied, otel_cfg);
+                return Err(ToolError::Rejected(reason));
+            }
+            CommandSafetyCheck::AskUser { reason } => (true, reason),
+            CommandSafetyCheck::Run => (false, None),
+        };
+
+        // Commands covered by a rule the user saved for this project run in
+        // the sandbox without a prompt. Rules never override an execpolicy
+        // that explicitly requires approval, never cover a request for
+        // escalated permissions, and never approve the unsandboxed retry.
         let mut already_approved = false;
+        let approved_by_rule = needs_initial_approval
+            && exec_policy_reason.is_none()
+            && !tool.wants_escalated_first_attempt(req)
+            && match req.sandbox_retry_data() {
+                Some(metadata) => {
+                    tool_ctx
+                        .session
+                        .is_approved_by_persisted_rule(&metadata.command, &metadata.cwd)
+                        .await
+                }
+                None => false,
+            };
 
-        if needs_initial_approval {
+        if approved_by_rule {
+            otel.tool_decision(
+                otel_tn,
+                otel_ci,
+                ReviewDecision::ApprovedForSession,
+                otel_cfg,
+            );
+        } else if needs_initial_approval {
             let mut risk = None;
 
             if let Some(metadata) = req.sandbox_retry_data() {
@@ -67,7 +117,7 @@ impl ToolOrchestrator {
                 session: tool_ctx.session,
                 turn: turn_ctx,
                 call_id: &tool_ctx.call_id,
-                retry_reason: None,
+                retry_reason: exec_policy_reason.clone(),
                 risk,
             };
             let decision = tool.start_approval_async(req, approval_ctx).await;
@@ -179,8 +229,19 @@ impl ToolOrchestrator {
     }
 }
 
-fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
-    // Keep approval reason terse and stable for UX/tests, but accept the
-    // output so we can evolve heuristics later without touching call sites.
-    "command failed; retry without sandbox?".to_string()
+fn build_denial_reason_from_output(output: &ExecToolCallOutput) -> String {
+    // Keep approval reason terse and stable for UX/tests; only name the hosts
+    // when the filtering proxy reported exactly why the command failed.
+    let blocked = &output.blocked_network_connections;
+    if blocked.is_empty() {
+        return "command failed; retry without sandbox?".to_string();
+    }
+    let hosts: Vec<String> = blocked
+        .iter()
+        .map(|connection| format!("{}:{}", connection.host, connection.port))
+        .collect();
+    format!(
+        "network access to {} is not in the sandbox allowlist; retry without sandbox?",
+        hosts.join(", ")
+    )
 }
@@ -4,10 +4,16 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::HookTrigger;
 use tracing::warn;
 
 use crate::client_common::tools::ToolSpec;
+use crate::codex::Session;
+use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::hooks;
+use crate::hooks::HookToolCall;
+use crate::hooks::HookToolOutput;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -58,11 +64,10 @@ impl ToolRegistry {
         &self,
         invocation: ToolInvocation,
     ) -> Result<ResponseInputItem, FunctionCallError> {
+        let mut invocation = invocation;
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
         let otel = invocation.turn.client.get_otel_event_manager();
-        let payload_for_response = invocation.payload.clone();
-        let log_payload = payload_for_response.log_payload();
 
         let handler = match self.handler(tool_name.as_ref()) {
             Some(handler) => handler,
@@ -72,7 +77,7 @@ impl ToolRegistry {
                 otel.tool_result(
                     tool_name.as_ref(),
                     &call_id_owned,
-                    log_payload.as_ref(),
+                    invocation.payload.log_payload().as_ref(),
                     Duration::ZERO,
                     false,
                     &message,
@@ -86,7 +91,7 @@ impl ToolRegistry {
             otel.tool_result(
                 tool_name.as_ref(),
                 &call_id_owned,
-                log_payload.as_ref(),
+                invocation.payload.log_payload().as_ref(),
                 Duration::ZERO,
                 false,
                 &message,
@@ -94,6 +99,22 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        if let Err(message) = run_before_tool_hooks(&mut invocation).await {
+            otel.tool_result(
+                tool_name.as_ref(),
+                &call_id_owned,
+                invocation.payload.log_payload().as_ref(),
+                Duration::ZERO,
+                false,
+                &message,
+            );
+            return Err(FunctionCallError::RespondToModel(message));
+        }
+
+        let session = Arc::clone(&invocation.session);
+        let turn = Arc::clone(&invocation.turn);
+        let payload_for_response = invocation.payload.clone();
+        let log_payload = payload_for_response.log_payload();
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -127,13 +148,95 @@ impl ToolRegistry {
                 let output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                run_after_tool_hooks(
+                    &session,
+                    &turn,
+                    &tool_name,
+                    &call_id_owned,
+                    &payload_for_response,
+                    hooks::tool_output_for_hook(&output),
+                )
+                .await;
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
+            Err(FunctionCallError::RespondToModel(message)) => {
+                run_after_tool_hooks(
+                    &session,
+                    &turn,
+                    &tool_name,
+                    &call_id_owned,
+                    &payload_for_response,
+                    HookToolOutput {
+                        success: false,
+                        content: message.clone(),
+                    },
+                )
+                .await;
+                Err(FunctionCallError::RespondToModel(message))
+            }
             Err(err) => Err(err),
         }
     }
 }
 
+/// Runs the `before_tool_call` hooks for `invocation`, applying any
+/// arguments they rewrite. Returns the message for the model when a hook
+/// blocks the call.
+async fn run_before_tool_hooks(invocation: &mut ToolInvocation) -> Result<(), String> {
+    let session = Arc::clone(&invocation.session);
+    let turn = Arc::clone(&invocation.turn);
+    if !session.has_hooks(HookTrigger::BeforeToolCall, Some(&invocation.tool_name)) {
+        return Ok(());
+    }
+
+    let mut input = session.hook_input(&turn, HookTrigger::BeforeToolCall);
+    input.tool = Some(HookToolCall {
+        name: invocation.tool_name.clone(),
+        call_id: invocation.call_id.clone(),
+        arguments: hooks::payload_arguments(&invocation.payload),
+    });
+    let result = session.run_hooks(&turn, input).await;
+    session.queue_hook_messages(&turn, &result.messages).await;
+
+    if let Some(reason) = result.blocked {
+        return Err(format!("tool call blocked by hook: {reason}"));
+    }
+    if let Some(arguments) = result.arguments {
+        // Fail closed: a hook that meant to sanitize the call must not let the
+        // original arguments through.
+        invocation.payload = hooks::rewrite_payload(&invocation.payload, arguments)
+            .map_err(|err| format!("tool call blocked: hook returned invalid arguments: {err}"))?;
+    }
+    Ok(())
+}
+
+async fn run_after_tool_hooks(
+    session: &Session,
+    turn: &TurnContext,
+    tool_name: &str,
+    call_id: &str,
+    payload: &ToolPayload,
+    output: HookToolOutput,
+) {
+    if !session.has_hooks(HookTrigger::AfterToolCall, Some(tool_name)) {
+        return;
+    }
+
+    let mut input = session.hook_input(turn, HookTrigger::AfterToolCall);
+    input.tool = Some(HookToolCall {
+        name: tool_name.to_string(),
+        call_id: call_id.to_string(),
+        arguments: hooks::payload_arguments(payload),
+    });
+    input.output = Some(output);
+    let result = session.run_hooks(turn, input).await;
+    // The call already ran, so a blocking decision can only be reported back
+    // to the model.
+    let mut messages = result.messages;
+    messages.extend(result.blocked);
+    session.queue_hook_messages(turn, &messages).await;
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfiguredToolSpec {
     pub spec: ToolSpec,
@@ -62,7 +62,8 @@ impl TurnDiffTracker {
                     .insert(internal.clone(), path.clone());
 
                 // If the file exists on disk now, snapshot as baseline; else leave missing to represent /dev/null.
-                let baseline_file_info = if path.exists() {
+                // Dangling symlinks still count as existing.
+                let baseline_file_info = if path_exists(path) {
                     let mode = file_mode_for_path(path);
                     let mode_val = mode.unwrap_or(FileMode::Regular);
                     let content = blob_bytes(path, mode_val).unwrap_or_default();
@@ -294,8 +295,14 @@ impl TurnDiffTracker {
             None
         };
 
-        // Fast path: identical bytes or both missing.
+        // Fast path: identical bytes or both missing. A mode-only change (e.g. chmod +x) still
+        // produces a header-only diff, as git does.
         if left_bytes == right_bytes.as_deref() {
+            if left_bytes.is_some() && baseline_mode != current_mode {
+                aggregated.push_str(&format!("diff --git a/{left_display} b/{right_display}\n"));
+                aggregated.push_str(&format!("old mode {baseline_mode}\n"));
+                aggregated.push_str(&format!("new mode {current_mode}\n"));
+            }
             return aggregated;
         }
 
@@ -427,8 +434,13 @@ fn file_mode_for_path(_path: &Path) -> Option<FileMode> {
     Some(FileMode::Regular)
 }
 
+/// Like `Path::exists`, but does not follow symlinks so dangling links are reported as present.
+fn path_exists(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
 fn blob_bytes(path: &Path, mode: FileMode) -> Option<Vec<u8>> {
-    if path.exists() {
+    if path_exists(path) {
         let contents = if mode == FileMode::Symlink {
             symlink_blob_bytes(path)
                 .ok_or_else(|| anyhow!("failed to read symlink target for {}", path.display()))
@@ -515,6 +527,7 @@ mod tests {
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -545,6 +558,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -620,6 +634,7 @@ index {left_oid}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -660,6 +675,7 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv_changes);
@@ -682,6 +698,7 @@ index {left_oid}..{right_oid}
             FileChange::Update {
                 unified_diff: "".into(),
                 move_path: Some(dest.clone()),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&mv);
@@ -722,6 +739,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_a);
@@ -802,6 +820,7 @@ index {left_oid_b}..{ZERO_OID}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -826,6 +845,66 @@ Binary files differ
         assert_eq!(diff, expected);
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn mode_only_change_yields_header_only_diff() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("run.sh");
+        fs::write(&file, "echo hi\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        acc.on_patch_begin(&HashMap::from([(
+            file.clone(),
+            FileChange::SetMode { mode: 0o755 },
+        )]));
+
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        let expected = r#"diff --git a/<TMP>/run.sh b/<TMP>/run.sh
+old mode 100644
+new mode 100755
+"#;
+        assert_eq!(diff, expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dangling_symlink_add_is_reported() {
+        let dir = tempdir().unwrap();
+        let link = dir.path().join("link");
+
+        let mut acc = TurnDiffTracker::new();
+        acc.on_patch_begin(&HashMap::from([(
+            link.clone(),
+            FileChange::AddSymlink {
+                target: PathBuf::from("missing.txt"),
+            },
+        )]));
+
+        std::os::unix::fs::symlink("missing.txt", &link).unwrap();
+
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        let right_oid = git_blob_sha1_hex("missing.txt");
+        let expected = format!(
+            r#"diff --git a/<TMP>/link b/<TMP>/link
+new file mode 120000
+index {ZERO_OID}..{right_oid}
+--- {DEV_NULL}
++++ b/<TMP>/link
+@@ -0,0 +1 @@
++missing.txt
+\ No newline at end of file
+"#
+        );
+        assert_eq!(diff, expected);
+    }
+
     #[test]
     fn filenames_with_spaces_add_and_update() {
         let mut acc = TurnDiffTracker::new();
@@ -838,6 +917,7 @@ Binary files differ
             file.clone(),
             FileChange::Add {
                 content: "foo\n".to_string(),
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&add_changes);
@@ -868,6 +948,7 @@ index {ZERO_OID}..{right_oid}
             FileChange::Update {
                 unified_diff: "".to_owned(),
                 move_path: None,
+                mode: None,
             },
         )]);
         acc.on_patch_begin(&update_changes);
@@ -162,6 +162,7 @@ impl UnifiedExecSession {
             aggregated_output: StreamOutput::new(aggregated_text.clone()),
             duration: Duration::ZERO,
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
 
         if is_likely_sandbox_denied(self.sandbox_type(), &exec_output) {
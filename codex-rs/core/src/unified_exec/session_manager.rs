@@ -257,6 +257,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let event_ctx = ToolEventCtx::new(
             entry.session_ref.as_ref(),
@@ -285,6 +286,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            blocked_network_connections: Vec::new(),
         };
         let event_ctx = ToolEventCtx::new(
             context.session.as_ref(),
@@ -0,0 +1,330 @@
+#![allow(clippy::expect_used)]
+
+use std::sync::Arc;
+
+use codex_app_server_protocol::AuthMode;
+use codex_core::ContentItem;
+use codex_core::ModelClient;
+use codex_core::ModelProviderInfo;
+use codex_core::Prompt;
+use codex_core::ResponseEvent;
+use codex_core::ResponseItem;
+use codex_core::WireApi;
+use codex_core::protocol::TokenUsage;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::ConversationId;
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use core_test_support::load_default_config_for_test;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::header;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn network_disabled() -> bool {
+    std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok()
+}
+
+/// Formats Messages API stream events the way the API does: every event
+/// carries both an `event:` line and a JSON `data:` payload.
+fn messages_sse(events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            let kind = event["type"].as_str().unwrap_or_default();
+            format!("event: {kind}\ndata: {event}\n\n")
+        })
+        .collect()
+}
+
+async fn run_stream(
+    input: Vec<ResponseItem>,
+    effort: Option<ReasoningEffort>,
+    sse_body: String,
+) -> (Vec<ResponseEvent>, Value) {
+    let server = MockServer::start().await;
+
+    let template = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(sse_body, "text/event-stream");
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(header("x-api-key", "test-key"))
+        .and(header("anthropic-version", "2023-06-01"))
+        .respond_with(template)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let provider = ModelProviderInfo {
+        name: "anthropic".into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: Some("test-key".into()),
+        wire_api: WireApi::Messages,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(5_000),
+        requires_openai_auth: false,
+    };
+
+    let codex_home = TempDir::new().expect("create TempDir");
+    let mut config = load_default_config_for_test(&codex_home);
+    config.model_provider_id = provider.name.clone();
+    config.model_provider = provider.clone();
+    config.model_max_output_tokens = Some(16_000);
+    let summary = config.model_reasoning_summary;
+    let config = Arc::new(config);
+
+    let conversation_id = ConversationId::new();
+
+    let otel_event_manager = OtelEventManager::new(
+        conversation_id,
+        config.model.as_str(),
+        config.model_family.slug.as_str(),
+        None,
+        Some("test@test.com".to_string()),
+        Some(AuthMode::ApiKey),
+        false,
+        "test".to_string(),
+    );
+
+    let client = ModelClient::new(
+        Arc::clone(&config),
+        None,
+        otel_event_manager,
+        provider,
+        effort,
+        summary,
+        conversation_id,
+        codex_protocol::protocol::SessionSource::Exec,
+    );
+
+    let mut prompt = Prompt::default();
+    prompt.input = input;
+
+    let mut stream = client.stream(&prompt).await.expect("stream messages");
+    let mut events = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ev) => events.push(ev),
+            Err(e) => panic!("unexpected stream error: {e}"),
+        }
+    }
+
+    let requests = server.received_requests().await.expect("recorded requests");
+    let body = requests[0].body_json::<Value>().expect("json request body");
+    (events, body)
+}
+
+fn user_message(text: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: text.to_string(),
+        }],
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn streams_thinking_text_and_tool_use_with_usage() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sse = messages_sse(&[
+        json!({"type": "message_start", "message": {
+            "id": "msg_1",
+            "usage": {"input_tokens": 20, "cache_read_input_tokens": 80, "output_tokens": 1},
+        }}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "list "}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "files"}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig-1"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "ping"}),
+        json!({"type": "content_block_start", "index": 1, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Running ls"}}),
+        json!({"type": "content_block_stop", "index": 1}),
+        json!({"type": "content_block_start", "index": 2, "content_block": {
+            "type": "tool_use", "id": "toolu_1", "name": "shell", "input": {},
+        }}),
+        json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+        json!({"type": "content_block_delta", "index": 2, "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+        json!({"type": "content_block_stop", "index": 2}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 30}}),
+        json!({"type": "message_stop"}),
+    ]);
+
+    let (events, body) = run_stream(
+        vec![user_message("what is here?")],
+        Some(ReasoningEffort::Medium),
+        sse,
+    )
+    .await;
+
+    assert_eq!(body["max_tokens"], json!(16_000));
+    assert_eq!(body["stream"], json!(true));
+    assert_eq!(
+        body["thinking"],
+        json!({"type": "enabled", "budget_tokens": 4_096})
+    );
+    assert!(body["system"].as_str().is_some_and(|s| !s.is_empty()));
+    assert_eq!(
+        body["messages"],
+        json!([{"role": "user", "content": [{"type": "text", "text": "what is here?"}]}])
+    );
+
+    let done_items: Vec<&ResponseItem> = events
+        .iter()
+        .filter_map(|event| match event {
+            ResponseEvent::OutputItemDone(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        done_items,
+        vec![
+            &ResponseItem::Reasoning {
+                id: String::new(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "list files".to_string(),
+                }],
+                content: None,
+                encrypted_content: Some("sig-1".to_string()),
+            },
+            &ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "Running ls".to_string(),
+                }],
+            },
+            &ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+        ]
+    );
+
+    let summary_deltas: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            ResponseEvent::ReasoningSummaryDelta(delta) => Some(delta.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(summary_deltas, vec!["list ", "files"]);
+
+    match events.last() {
+        Some(ResponseEvent::Completed {
+            response_id,
+            token_usage,
+        }) => {
+            assert_eq!(response_id, "msg_1");
+            assert_eq!(
+                token_usage,
+                &Some(TokenUsage {
+                    input_tokens: 100,
+                    cached_input_tokens: 80,
+                    output_tokens: 30,
+                    reasoning_output_tokens: 0,
+                    total_tokens: 130,
+                })
+            );
+        }
+        other => panic!("expected Completed, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn replays_tool_results_and_images_as_content_blocks() {
+    if network_disabled() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let sse = messages_sse(&[
+        json!({"type": "message_start", "message": {"id": "msg_2", "usage": {"input_tokens": 5}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "done"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 2}}),
+        json!({"type": "message_stop"}),
+    ]);
+
+    let input = vec![
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![
+                ContentItem::InputText {
+                    text: "describe".to_string(),
+                },
+                ContentItem::InputImage {
+                    image_url: "data:image/png;base64,iVBORw0KGgo=".to_string(),
+                },
+            ],
+        },
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+            call_id: "toolu_1".to_string(),
+        },
+        ResponseItem::FunctionCallOutput {
+            call_id: "toolu_1".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "README.md".to_string(),
+                ..Default::default()
+            },
+        },
+    ];
+
+    let (events, body) = run_stream(input, None, sse).await;
+
+    assert_eq!(body.get("thinking"), None);
+    assert_eq!(
+        body["messages"],
+        json!([
+            {"role": "user", "content": [
+                {"type": "text", "text": "describe"},
+                {"type": "image", "source": {
+                    "type": "base64", "media_type": "image/png", "data": "iVBORw0KGgo=",
+                }},
+            ]},
+            {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+            ]},
+            {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "toolu_1", "content": "README.md"},
+            ]},
+        ])
+    );
+    assert!(
+        matches!(events.last(), Some(ResponseEvent::Completed { response_id, .. }) if response_id == "msg_2"),
+        "unexpected events: {events:?}"
+    );
+}
@@ -463,6 +463,8 @@ async fn apply_patch_cli_rejects_path_traversal_outside_workspace() -> Result<()
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        deny_read: vec![],
+        network_allowlist: vec![],
     };
     harness
         .submit_with_policy(
@@ -512,6 +514,8 @@ async fn apply_patch_cli_rejects_move_path_traversal_outside_workspace() -> Resu
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        deny_read: vec![],
+        network_allowlist: vec![],
     };
     harness
         .submit_with_policy("attempt move traversal via apply_patch", sandbox_policy)
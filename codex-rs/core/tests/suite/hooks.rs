@@ -0,0 +1,184 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::config::types::HookConfig;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::HookOutcome;
+use codex_core::protocol::HookTrigger;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use serde_json::json;
+use wiremock::matchers::any;
+
+fn sh_hook(event: HookTrigger, script: &str) -> HookConfig {
+    HookConfig {
+        event,
+        command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        tools: Some(vec!["shell".to_string()]),
+        timeout_ms: Some(10_000),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn before_tool_call_hook_blocks_and_after_hook_adds_message() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.hooks = vec![
+            sh_hook(
+                HookTrigger::BeforeToolCall,
+                "grep -q migrations && { echo 'migrations are read-only' >&2; exit 2; }; exit 0",
+            ),
+            sh_hook(
+                HookTrigger::AfterToolCall,
+                r#"echo '{"message": "remember to run the linter"}'"#,
+            ),
+        ];
+    });
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = builder.build(&server).await?;
+
+    let blocked_args = json!({ "command": ["rm", "-rf", "migrations"], "timeout_ms": 1_000 });
+    let allowed_args = json!({ "command": ["/bin/echo", "hi"], "timeout_ms": 1_000 });
+    let first_response = sse(vec![
+        ev_response_created("resp-1"),
+        ev_function_call("blocked-call", "shell", &blocked_args.to_string()),
+        ev_function_call("allowed-call", "shell", &allowed_args.to_string()),
+        ev_completed("resp-1"),
+    ]);
+    responses::mount_sse_once_match(&server, any(), first_response).await;
+    let second_response = sse(vec![
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-2"),
+    ]);
+    let second_mock = responses::mount_sse_once_match(&server, any(), second_response).await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "clean up".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let blocked = wait_for_event_match(&codex, |event| match event {
+        EventMsg::HookCompleted(ev) if ev.outcome == HookOutcome::Blocked => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(blocked.call_id.as_deref(), Some("blocked-call"));
+    assert_eq!(blocked.reason.as_deref(), Some("migrations are read-only"));
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let request = second_mock.single_request();
+    let blocked_output = request
+        .function_call_output_text("blocked-call")
+        .expect("blocked call output");
+    assert!(
+        blocked_output.contains("migrations are read-only"),
+        "unexpected output: {blocked_output}"
+    );
+    assert!(!cwd.path().join("migrations").exists());
+    let allowed_output = request
+        .function_call_output_text("allowed-call")
+        .expect("allowed call output");
+    assert!(
+        allowed_output.contains("hi"),
+        "unexpected output: {allowed_output}"
+    );
+    assert!(
+        request
+            .message_input_texts("user")
+            .iter()
+            .any(|text| text.contains("<hook_message>")
+                && text.contains("remember to run the linter")),
+        "hook message missing from the follow-up request"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn turn_end_hook_that_always_blocks_ends_the_turn_with_an_error() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.hooks = vec![HookConfig {
+            tools: None,
+            ..sh_hook(HookTrigger::TurnEnd, "echo 'keep going' >&2; exit 2")
+        }];
+    });
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = builder.build(&server).await?;
+
+    let response = sse(vec![
+        ev_response_created("resp-1"),
+        ev_assistant_message("msg-1", "done"),
+        ev_completed("resp-1"),
+    ]);
+    let mock = responses::mount_sse(&server, response).await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "finish up".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let error = wait_for_event_match(&codex, |event| match event {
+        EventMsg::Error(ev) => Some(ev.message.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(
+        error.contains("Turn-end hooks kept blocking"),
+        "unexpected error: {error}"
+    );
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    // The first response plus one per allowed continuation.
+    assert_eq!(mock.requests().len(), 6);
+
+    Ok(())
+}
@@ -31,6 +31,7 @@ mod deprecation_notice;
 mod exec;
 mod fork_conversation;
 mod grep_files;
+mod hooks;
 mod items;
 mod json_result;
 mod list_dir;
@@ -39,6 +40,7 @@ mod model_overrides;
 mod model_tools;
 mod otel;
 mod prompt_caching;
+mod provider_fallback;
 mod quota_exceeded;
 mod read_file;
 mod resume;
@@ -786,6 +786,7 @@ async fn handle_container_exec_user_approved_records_tool_decision() {
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::Approved,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -838,6 +839,7 @@ async fn handle_container_exec_user_approved_for_session_records_tool_decision()
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::ApprovedForSession,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -890,6 +892,7 @@ async fn handle_sandbox_error_user_approves_retry_records_tool_decision() {
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::Approved,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -942,6 +945,7 @@ async fn handle_container_exec_user_denies_records_tool_decision() {
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::Denied,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -994,6 +998,7 @@ async fn handle_sandbox_error_user_approves_for_session_records_tool_decision()
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::ApprovedForSession,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -1046,6 +1051,7 @@ async fn handle_sandbox_error_user_denies_records_tool_decision() {
         .submit(Op::ExecApproval {
             id: "0".into(),
             decision: ReviewDecision::Denied,
+            persist_rule: None,
         })
         .await
         .unwrap();
@@ -458,6 +458,8 @@ async fn overrides_turn_context_but_keeps_cached_prefix_and_key_constant() {
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                deny_read: Vec::new(),
+                network_allowlist: Vec::new(),
             }),
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
@@ -591,6 +593,8 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() {
                 network_access: true,
                 exclude_tmpdir_env_var: true,
                 exclude_slash_tmp: true,
+                deny_read: Vec::new(),
+                network_allowlist: Vec::new(),
             },
             model: "o3".to_string(),
             effort: Some(ReasoningEffort::High),
@@ -0,0 +1,184 @@
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::config::types::ModelFallback;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn mock_provider(name: &str, base_url: String) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: name.into(),
+        base_url: Some(base_url),
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: Some("test-key".into()),
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+        requires_openai_auth: false,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn switches_to_fallback_provider_after_retries_are_exhausted() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let ok = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_assistant_message("msg-1", "served by backup"),
+                ev_completed("resp-1"),
+            ]),
+            "text/event-stream",
+        );
+    Mock::given(method("POST"))
+        .and(path("/backup/v1/responses"))
+        .respond_with(ok)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let primary = mock_provider("primary", format!("{}/v1", server.uri()));
+    let backup = mock_provider("Backup", format!("{}/backup/v1", server.uri()));
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_provider = primary;
+            config.model_providers.insert("backup".to_string(), backup);
+            config.model_fallbacks = vec![ModelFallback {
+                provider: "backup".to_string(),
+                model: Some("gpt-5".to_string()),
+            }];
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    let warning = wait_for_event_match(&codex, |event| match event {
+        EventMsg::Warning(ev) => Some(ev.message.clone()),
+        _ => None,
+    })
+    .await;
+    assert!(
+        warning.contains("Backup") && warning.contains("`gpt-5`"),
+        "unexpected warning: {warning}"
+    );
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let backup_request = requests
+        .iter()
+        .find(|request| request.url.path() == "/backup/v1/responses")
+        .ok_or_else(|| anyhow::anyhow!("no request sent to the fallback provider"))?;
+    let body = backup_request.body_json::<serde_json::Value>()?;
+    assert_eq!(body["model"], "gpt-5");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fallback_provider_does_not_receive_openai_credentials() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    let ok = ResponseTemplate::new(200)
+        .insert_header("content-type", "text/event-stream")
+        .set_body_raw(
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_assistant_message("msg-1", "served by backup"),
+                ev_completed("resp-1"),
+            ]),
+            "text/event-stream",
+        );
+    Mock::given(method("POST"))
+        .and(path("/backup/v1/responses"))
+        .respond_with(ok)
+        .mount(&server)
+        .await;
+
+    let primary = ModelProviderInfo {
+        experimental_bearer_token: None,
+        requires_openai_auth: true,
+        ..mock_provider("primary", format!("{}/v1", server.uri()))
+    };
+    let backup = ModelProviderInfo {
+        experimental_bearer_token: None,
+        ..mock_provider("Backup", format!("{}/backup/v1", server.uri()))
+    };
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(move |config| {
+            config.model_provider = primary;
+            config.model_providers.insert("backup".to_string(), backup);
+            config.model_fallbacks = vec![ModelFallback {
+                provider: "backup".to_string(),
+                model: None,
+            }];
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let primary_request = requests
+        .iter()
+        .find(|request| request.url.path() == "/v1/responses")
+        .ok_or_else(|| anyhow::anyhow!("no request sent to the primary provider"))?;
+    assert!(primary_request.headers.contains_key("authorization"));
+    let backup_request = requests
+        .iter()
+        .find(|request| request.url.path() == "/backup/v1/responses")
+        .ok_or_else(|| anyhow::anyhow!("no request sent to the fallback provider"))?;
+    assert!(!backup_request.headers.contains_key("authorization"));
+
+    Ok(())
+}
@@ -8,15 +8,19 @@ use std::time::Duration;
 use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
+use codex_core::config::types::McpSamplingPolicy;
 use codex_core::config::types::McpServerConfig;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
 
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpServerUpdate;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxPolicy;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::user_input::UserInput;
@@ -37,6 +41,7 @@ use tokio::process::Command;
 use tokio::time::Instant;
 use tokio::time::sleep;
 use wiremock::matchers::any;
+use wiremock::matchers::body_string_contains;
 
 static OPENAI_PNG: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAD0AAAA9CAYAAAAeYmHpAAAE6klEQVR4Aeyau44UVxCGx1fZsmRLlm3Zoe0XcGQ5cUiCCIgJeS9CHgAhMkISQnIuGQgJEkBcxLW+nqnZ6uqqc+nuWRC7q/P3qetf9e+MtOwyX25O4Nep6JPyop++0qev9HrfgZ+F6r2DuB/vHOrt/UIkqdDHYvujOW6fO7h/CNEI+a5jc+pBR8uy0jVFsziYu5HtfSUk+Io34q921hLNctFSX0gwww+S8wce8K1LfCU+cYW4888aov8NxqvQILUPPReLOrm6zyLxa4i+6VZuFbJo8d1MOHZm+7VUtB/aIvhPWc/3SWg49JcwFLlHxuXKjtyloo+YNhuW3VS+WPBuUEMvCFKjEDVgFBQHXrnazpqiSxNZCkQ1kYiozsbm9Oz7l4i2Il7vGccGNWAc3XosDrZe/9P3ZnMmzHNEQw4smf8RQ87XEAMsC7Az0Au+dgXerfH4+sHvEc0SYGic8WBBUGqFH2gN7yDrazy7m2pbRTeRmU3+MjZmr1h6LJgPbGy23SI6GlYT0brQ71IY8Us4PNQCm+zepSbaD2BY9xCaAsD9IIj/IzFmKMSdHHonwdZATbTnYREf6/VZGER98N9yCWIvXQwXDoDdhZJoT8jwLnJXDB9w4Sb3e6nK5ndzlkTLnP3JBu4LKkbrYrU69gCVceV0JvpyuW1xlsUVngzhwMetn/XamtTORF9IO5YnWNiyeF9zCAfqR3fUW+vZZKLtgP+ts8BmQRBREAdRDhH3o8QuRh/YucNFz2BEjxbRN6LGzphfKmvP6v6QhqIQyZ8XNJ0W0X83MR1PEcJBNO2KC2Z1TW/v244scp9FwRViZxIOBF0Lctk7ZVSavdLvRlV1hz/ysUi9sr8CIcB3nvWBwA93ykTz18eAYxQ6N/K2DkPA1lv3iXCwmDUT7YkjIby9siXueIJj9H+pzSqJ9oIuJWTUgSSt4WO7o/9GGg0viR4VinNRUDoIj34xoCd6pxD3aK3zfdbnx5v1J3ZNNEJsE0sBG7N27ReDrJc4sFxz7dI/ZAbOmmiKvHBitQXpAdR6+F7v+/ol/tOouUV01EeMZQF2BoQDn6dP4XNr+j9GZEtEK1/L8pFw7bd3a53tsTa7WD+054jOFmPg1XBKPQgnqFfmFcy32ZRvjmiIIQTYFvyDxQ8nH8WIwwGwlyDjDznnilYyFr6njrlZwsKkBpO59A7OwgdzPEWRm+G+oeb7IfyNuzjEEVLrOVxJsxvxwF8kmCM6I2QYmJunz4u4TrADpfl7mlbRTWQ7VmrBzh3+C9f6Grc3YoGN9dg/SXFthpRsT6vobfXRs2VBlgBHXVMLHjDNbIZv1sZ9+X3hB09cXdH1JKViyG0+W9bWZDa/r2f9zAFR71sTzGpMSWz2iI4YssWjWo3REy1MDGjdwe5e0dFSiAC1JakBvu4/CUS8Eh6dqHdU0Or0ioY3W5ClSqDXAy7/6SRfgw8vt4I+tbvvNtFT2kVDhY5+IGb1rCqYaXNF08vSALsXCPmt0kQNqJT1p5eI1mkIV/BxCY1z85lOzeFbPBQHURkkPTlwTYK9gTVE25l84IbFFN+YJDHjdpn0gq6mrHht0dkcjbM4UL9283O5p77GN+SPW/QwVB4IUYg7Or+Kp7naR6qktP98LNF2UxWo9yObPIT9KYg+hK4i56no4rfnM0qeyFf6AwAAAP//trwR3wAAAAZJREFUAwBZ0sR75itw5gAAAABJRU5ErkJggg==";
 
@@ -101,6 +106,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -117,7 +123,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -178,6 +184,851 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_server_elicitation_round_trip() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+
+    let call_id = "call-elicit";
+    let server_name = "rmcp";
+    let tool_name = format!("mcp__{server_name}__confirm");
+
+    mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_response_created("resp-1"),
+            responses::ev_function_call(call_id, &tool_name, "{}"),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "deploy confirmed."),
+            responses::ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    sampling: None,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "deploy the build".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let request_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::ElicitationRequest(_))
+    })
+    .await;
+    let EventMsg::ElicitationRequest(request) = request_event else {
+        unreachable!("event guard guarantees ElicitationRequest");
+    };
+    assert_eq!(request.server_name, server_name);
+    assert_eq!(request.message, "Deploy the current build?");
+    assert_eq!(
+        request.requested_schema.required,
+        Some(vec!["approved".to_string()])
+    );
+
+    fixture
+        .codex
+        .submit(Op::ResolveElicitation {
+            server_name: request.server_name,
+            request_id: request.id,
+            decision: ElicitationAction::Accept,
+            content: Some(json!({ "approved": true })),
+        })
+        .await?;
+
+    let end_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpToolCallEnd(_))
+    })
+    .await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    let result = end
+        .result
+        .as_ref()
+        .expect("rmcp confirm tool should return success");
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "action": "accept",
+            "content": { "approved": true },
+        }))
+    );
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    server.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_server_sampling_round_trip() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+
+    let call_id = "call-summarize";
+    let server_name = "rmcp";
+    let tool_name = format!("mcp__{server_name}__summarize");
+
+    // The sampling request is recognised by the server's system prompt; the
+    // two conversation turns are served in order by the catch-all mocks.
+    let sampling_mock = mount_sse_once_match(
+        &server,
+        body_string_contains("You write one-line summaries."),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-sample", "Release notes, briefly."),
+            responses::ev_completed_with_tokens("resp-sample", 42),
+        ]),
+    )
+    .await;
+    mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_response_created("resp-1"),
+            responses::ev_function_call(
+                call_id,
+                &tool_name,
+                &json!({ "text": "release notes" }).to_string(),
+            ),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "summarized."),
+            responses::ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    sampling: Some(McpSamplingPolicy::Ask),
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "summarize the release notes".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            model: session_model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let request_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpSamplingApprovalRequest(_))
+    })
+    .await;
+    let EventMsg::McpSamplingApprovalRequest(request) = request_event else {
+        unreachable!("event guard guarantees McpSamplingApprovalRequest");
+    };
+    assert_eq!(request.server_name, server_name);
+    assert_eq!(request.request.max_tokens, 64);
+
+    fixture
+        .codex
+        .submit(Op::McpSamplingApproval {
+            server_name: request.server_name,
+            request_id: request.id,
+            decision: ReviewDecision::Approved,
+        })
+        .await?;
+
+    let token_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(
+            ev,
+            EventMsg::TokenCount(count)
+                if count
+                    .info
+                    .as_ref()
+                    .is_some_and(|info| info.total_token_usage.total_tokens > 0)
+        )
+    })
+    .await;
+    let EventMsg::TokenCount(token_count) = token_event else {
+        unreachable!("event guard guarantees TokenCount");
+    };
+    let info = token_count.info.expect("token info");
+    assert_eq!(info.total_token_usage.total_tokens, 42);
+    assert_eq!(info.last_token_usage.total_tokens, 0);
+
+    let end_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpToolCallEnd(_))
+    })
+    .await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    let result = end
+        .result
+        .as_ref()
+        .expect("rmcp summarize tool should return success");
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "model": session_model,
+            "stop_reason": "endTurn",
+            "text": "Release notes, briefly.",
+        }))
+    );
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // The recorder sees every request that reaches the mock, so pick out the
+    // one that carried the server's system prompt as its instructions.
+    let sampling_requests: Vec<_> = sampling_mock
+        .requests()
+        .into_iter()
+        .filter(|request| {
+            request.body_json()["instructions"] == json!("You write one-line summaries.")
+        })
+        .collect();
+    assert_eq!(sampling_requests.len(), 1);
+    assert_eq!(
+        sampling_requests[0].message_input_texts("user"),
+        vec!["Summarize: release notes".to_string()]
+    );
+    assert_eq!(
+        sampling_requests[0].body_json()["max_output_tokens"],
+        json!(64)
+    );
+
+    server.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_server_prompts_round_trip() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    sampling: None,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+
+    fixture.codex.submit(Op::ListMcpPrompts).await?;
+    let list_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpListPromptsResponse(_))
+    })
+    .await;
+    let EventMsg::McpListPromptsResponse(list) = list_event else {
+        unreachable!("event guard guarantees McpListPromptsResponse");
+    };
+    let prompts = list
+        .prompts
+        .get(server_name)
+        .expect("rmcp server should publish prompts");
+    let names: Vec<&str> = prompts.iter().map(|prompt| prompt.name.as_str()).collect();
+    assert_eq!(names, vec!["review"]);
+    let required: Vec<(&str, Option<bool>)> = prompts[0]
+        .arguments
+        .iter()
+        .flatten()
+        .map(|arg| (arg.name.as_str(), arg.required))
+        .collect();
+    assert_eq!(required, vec![("file", Some(true)), ("focus", Some(false))]);
+
+    fixture
+        .codex
+        .submit(Op::GetMcpPrompt {
+            server: server_name.to_string(),
+            name: "review".to_string(),
+            arguments: Some(json!({ "file": "src/main.rs" })),
+        })
+        .await?;
+    let get_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpGetPromptResponse(_))
+    })
+    .await;
+    let EventMsg::McpGetPromptResponse(response) = get_event else {
+        unreachable!("event guard guarantees McpGetPromptResponse");
+    };
+    assert_eq!(response.server, server_name);
+    assert_eq!(response.name, "review");
+    let result = response.result.expect("prompts/get should succeed");
+    let texts: Vec<&str> = result
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            ContentBlock::TextContent(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, vec!["Please review src/main.rs."]);
+
+    fixture
+        .codex
+        .submit(Op::GetMcpPrompt {
+            server: server_name.to_string(),
+            name: "review".to_string(),
+            arguments: None,
+        })
+        .await?;
+    let error_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpGetPromptResponse(_))
+    })
+    .await;
+    let EventMsg::McpGetPromptResponse(response) = error_event else {
+        unreachable!("event guard guarantees McpGetPromptResponse");
+    };
+    let error = response.result.expect_err("missing argument should fail");
+    assert!(
+        error.contains("missing argument: file"),
+        "unexpected error: {error}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_server_sees_roots_follow_cwd_override() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+    let tool_name = format!("mcp__{server_name}__roots");
+
+    // Each turn asks for the tool once and then finishes; the follow-up
+    // requests fall through to the catch-all completions mounted last.
+    for call_id in ["call-roots-1", "call-roots-2"] {
+        mount_sse_once_match(
+            &server,
+            body_string_contains(format!("list roots {call_id}")),
+            responses::sse(vec![
+                responses::ev_response_created(call_id),
+                responses::ev_function_call(call_id, &tool_name, "{}"),
+                responses::ev_completed(call_id),
+            ]),
+        )
+        .await;
+    }
+    for response_id in ["resp-done-1", "resp-done-2"] {
+        mount_sse_once_match(
+            &server,
+            any(),
+            responses::sse(vec![
+                responses::ev_assistant_message(response_id, "done"),
+                responses::ev_completed(response_id),
+            ]),
+        )
+        .await;
+    }
+
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config.mcp_servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    sampling: None,
+                },
+            );
+        })
+        .build(&server)
+        .await?;
+
+    let first = list_roots_via_tool(&fixture.codex, "call-roots-1").await?;
+    let dir_name = |path: &Path| {
+        path.file_name()
+            .and_then(OsStr::to_str)
+            .map(str::to_string)
+            .expect("temp dir name")
+    };
+    assert_roots(&first, &dir_name(fixture.cwd.path()), 0);
+
+    let new_cwd = tempdir()?;
+    fixture
+        .codex
+        .submit(Op::OverrideTurnContext {
+            cwd: Some(new_cwd.path().to_path_buf()),
+            approval_policy: None,
+            sandbox_policy: None,
+            model: None,
+            effort: None,
+            summary: None,
+        })
+        .await?;
+
+    let second = list_roots_via_tool(&fixture.codex, "call-roots-2").await?;
+    assert_roots(&second, &dir_name(new_cwd.path()), 1);
+
+    Ok(())
+}
+
+async fn list_roots_via_tool(
+    codex: &codex_core::CodexConversation,
+    call_id: &str,
+) -> anyhow::Result<Value> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: format!("list roots {call_id}"),
+            }],
+        })
+        .await?;
+
+    let end_event = wait_for_event(codex, |ev| matches!(ev, EventMsg::McpToolCallEnd(_))).await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    wait_for_event(codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let result = end
+        .result
+        .map_err(|err| anyhow::anyhow!("rmcp roots tool failed: {err}"))?;
+    result
+        .structured_content
+        .ok_or_else(|| anyhow::anyhow!("roots tool returned no structured content"))
+}
+
+fn assert_roots(structured: &Value, expected_dir: &str, expected_changes: u64) {
+    let roots: Vec<&str> = structured["roots"]
+        .as_array()
+        .unwrap_or_else(|| panic!("roots should be an array: {structured:?}"))
+        .iter()
+        .filter_map(Value::as_str)
+        .collect();
+    assert_eq!(roots.len(), 1, "unexpected roots: {roots:?}");
+    assert!(
+        roots[0].starts_with("file://") && roots[0].ends_with(expected_dir),
+        "unexpected root: {}",
+        roots[0]
+    );
+    assert_eq!(structured["list_changed"], json!(expected_changes));
+}
+
+fn stdio_test_server_config() -> anyhow::Result<McpServerConfig> {
+    let rmcp_test_server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+    Ok(McpServerConfig {
+        transport: McpServerTransportConfig::Stdio {
+            command: rmcp_test_server_bin,
+            args: Vec::new(),
+            env: None,
+            env_vars: Vec::new(),
+            cwd: None,
+        },
+        enabled: true,
+        startup_timeout_sec: Some(Duration::from_secs(10)),
+        tool_timeout_sec: None,
+        enabled_tools: None,
+        disabled_tools: None,
+        sampling: None,
+    })
+}
+
+/// Mount one tool call per `(prompt, tool)` pair, each answered once when the
+/// request contains `prompt`, followed by one final answer per pair.
+async fn mount_tool_call_turns(server: &wiremock::MockServer, turns: &[(&str, &str)]) {
+    for (prompt, tool_name) in turns {
+        let call_id = format!("call-{prompt}");
+        mount_sse_once_match(
+            server,
+            body_string_contains(*prompt),
+            responses::sse(vec![
+                responses::ev_response_created(&call_id),
+                responses::ev_function_call(&call_id, tool_name, "{\"message\":\"ping\"}"),
+                responses::ev_completed(&call_id),
+            ]),
+        )
+        .await;
+    }
+    for (prompt, _) in turns {
+        let response_id = format!("resp-done-{prompt}");
+        mount_sse_once_match(
+            server,
+            any(),
+            responses::sse(vec![
+                responses::ev_assistant_message(&response_id, "done"),
+                responses::ev_completed(&response_id),
+            ]),
+        )
+        .await;
+    }
+}
+
+async fn run_tool_call_turn(
+    codex: &codex_core::CodexConversation,
+    prompt: &str,
+) -> anyhow::Result<Result<mcp_types::CallToolResult, String>> {
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: prompt.to_string(),
+            }],
+        })
+        .await?;
+
+    let end_event = wait_for_event(codex, |ev| matches!(ev, EventMsg::McpToolCallEnd(_))).await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    wait_for_event(codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    Ok(end.result)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_server_tool_list_change_is_picked_up_next_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+    mount_tool_call_turns(
+        &server,
+        &[
+            ("unlock-tools", "mcp__rmcp__unlock"),
+            ("use-secret", "mcp__rmcp__secret"),
+        ],
+    )
+    .await;
+
+    let server_config = stdio_test_server_config()?;
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config
+                .mcp_servers
+                .insert(server_name.to_string(), server_config);
+        })
+        .build(&server)
+        .await?;
+
+    let unlocked = run_tool_call_turn(&fixture.codex, "unlock-tools")
+        .await?
+        .map_err(|err| anyhow::anyhow!("unlock tool failed: {err}"))?;
+    assert_eq!(
+        unlocked.structured_content,
+        Some(json!({ "unlocked": "secret" }))
+    );
+
+    let secret = run_tool_call_turn(&fixture.codex, "use-secret")
+        .await?
+        .map_err(|err| anyhow::anyhow!("secret tool failed: {err}"))?;
+    assert_eq!(
+        secret.structured_content,
+        Some(json!({ "secret": "open sesame" }))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn crashed_stdio_server_is_restarted_before_next_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+    mount_tool_call_turns(
+        &server,
+        &[
+            ("crash-server", "mcp__rmcp__crash"),
+            ("echo-after-crash", "mcp__rmcp__echo"),
+        ],
+    )
+    .await;
+
+    let server_config = stdio_test_server_config()?;
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config
+                .mcp_servers
+                .insert(server_name.to_string(), server_config);
+        })
+        .build(&server)
+        .await?;
+
+    let crashed = run_tool_call_turn(&fixture.codex, "crash-server").await?;
+    assert!(crashed.is_err(), "crash tool should fail: {crashed:?}");
+
+    fixture
+        .codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "echo-after-crash".to_string(),
+            }],
+        })
+        .await?;
+    let updated = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpServerUpdated(_))
+    })
+    .await;
+    let EventMsg::McpServerUpdated(updated) = updated else {
+        unreachable!("event guard guarantees McpServerUpdated");
+    };
+    assert_eq!(updated.server, server_name);
+    assert_eq!(updated.update, McpServerUpdate::Reconnected);
+    assert!(
+        matches!(updated.result, Ok(count) if count > 0),
+        "unexpected result: {:?}",
+        updated.result
+    );
+
+    let end_event = wait_for_event(&fixture.codex, |ev| {
+        matches!(ev, EventMsg::McpToolCallEnd(_))
+    })
+    .await;
+    let EventMsg::McpToolCallEnd(end) = end_event else {
+        unreachable!("event guard guarantees McpToolCallEnd");
+    };
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    let echoed = end
+        .result
+        .map_err(|err| anyhow::anyhow!("echo after restart failed: {err}"))?;
+    assert_eq!(
+        echoed
+            .structured_content
+            .as_ref()
+            .map(|value| &value["echo"]),
+        Some(&json!("ECHOING: ping"))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn mcp_server_can_be_disabled_enabled_and_restarted() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+    let server_name = "rmcp";
+    let server_config = stdio_test_server_config()?;
+    let fixture = test_codex()
+        .with_config(move |config| {
+            config.features.enable(Feature::RmcpClient);
+            config
+                .mcp_servers
+                .insert(server_name.to_string(), server_config);
+        })
+        .build(&server)
+        .await?;
+    let codex = &fixture.codex;
+
+    let update = |op: Op| async move {
+        codex.submit(op).await?;
+        let event = wait_for_event(codex, |ev| matches!(ev, EventMsg::McpServerUpdated(_))).await;
+        let EventMsg::McpServerUpdated(event) = event else {
+            unreachable!("event guard guarantees McpServerUpdated");
+        };
+        anyhow::Ok(event)
+    };
+    let has_echo_tool = || async move {
+        codex.submit(Op::ListMcpTools).await?;
+        let event =
+            wait_for_event(codex, |ev| matches!(ev, EventMsg::McpListToolsResponse(_))).await;
+        let EventMsg::McpListToolsResponse(list) = event else {
+            unreachable!("event guard guarantees McpListToolsResponse");
+        };
+        anyhow::Ok(list.tools.contains_key("mcp__rmcp__echo"))
+    };
+
+    assert!(has_echo_tool().await?);
+
+    let disabled = update(Op::SetMcpServerEnabled {
+        server: server_name.to_string(),
+        enabled: false,
+    })
+    .await?;
+    assert_eq!(disabled.update, McpServerUpdate::Disabled);
+    assert_eq!(disabled.result, Ok(0));
+    assert!(!has_echo_tool().await?);
+
+    let restart_disabled = update(Op::RestartMcpServer {
+        server: server_name.to_string(),
+    })
+    .await?;
+    assert_eq!(
+        restart_disabled.result,
+        Err("MCP server 'rmcp' is disabled".to_string())
+    );
+
+    let enabled = update(Op::SetMcpServerEnabled {
+        server: server_name.to_string(),
+        enabled: true,
+    })
+    .await?;
+    assert_eq!(enabled.update, McpServerUpdate::Enabled);
+    assert!(matches!(enabled.result, Ok(count) if count > 0));
+    assert!(has_echo_tool().await?);
+
+    let restarted = update(Op::RestartMcpServer {
+        server: server_name.to_string(),
+    })
+    .await?;
+    assert_eq!(restarted.update, McpServerUpdate::Restarted);
+    assert_eq!(restarted.result, enabled.result);
+    assert!(has_echo_tool().await?);
+
+    let unknown = update(Op::RestartMcpServer {
+        server: "missing".to_string(),
+    })
+    .await?;
+    assert_eq!(
+        unknown.result,
+        Err("unknown MCP server 'missing'".to_string())
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[serial(mcp_test_value)]
 async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
@@ -241,6 +1092,7 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -257,7 +1109,7 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -437,6 +1289,7 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -453,7 +1306,7 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -579,6 +1432,7 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -595,7 +1449,7 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -730,6 +1584,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -746,7 +1601,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -913,6 +1768,7 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             );
         })
@@ -929,7 +1785,7 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
             final_output_json_schema: None,
             cwd: fixture.cwd.path().to_path_buf(),
             approval_policy: AskForApproval::Never,
-            sandbox_policy: SandboxPolicy::ReadOnly,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
             model: session_model,
             effort: None,
             summary: ReasoningSummary::Auto,
@@ -56,6 +56,12 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Path to a JSON file with answers to MCP elicitation requests, keyed by
+    /// server name (`"*"` matches any server). Requests without an answer are
+    /// declined.
+    #[arg(long = "elicitation-answers", value_name = "FILE")]
+    pub elicitation_answers: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -5,13 +5,18 @@ use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::HookCompletedEvent;
+use codex_core::protocol::HookOutcome;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpServerUpdatedEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
@@ -185,6 +190,50 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::McpServerUpdated(McpServerUpdatedEvent {
+                server,
+                update,
+                result,
+            }) => match result {
+                Ok(_) => {
+                    let message = format!("MCP server `{server}` {update}");
+                    ts_msg!(self, "{}", message.style(self.dimmed));
+                }
+                Err(err) => {
+                    ts_msg!(
+                        self,
+                        "{} MCP server `{server}` could not be {update}: {err}",
+                        "warning:".style(self.yellow).style(self.bold)
+                    );
+                }
+            },
+            EventMsg::HookCompleted(HookCompletedEvent {
+                command,
+                tool_name,
+                outcome,
+                reason,
+                ..
+            }) => {
+                let command = command.join(" ");
+                let reason = reason.unwrap_or_default();
+                let message = match (outcome, tool_name) {
+                    (HookOutcome::Continued, _) => None,
+                    (HookOutcome::Blocked, Some(tool_name)) => {
+                        Some(format!("`{command}` blocked `{tool_name}`: {reason}"))
+                    }
+                    (HookOutcome::Blocked, None) => {
+                        Some(format!("`{command}` blocked the turn: {reason}"))
+                    }
+                    (HookOutcome::Failed, _) => Some(format!("`{command}` failed: {reason}")),
+                };
+                if let Some(message) = message {
+                    ts_msg!(
+                        self,
+                        "{} {message}",
+                        "hook:".style(self.yellow).style(self.bold)
+                    );
+                }
+            }
             EventMsg::StreamError(StreamErrorEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
@@ -273,6 +322,30 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     format_mcp_invocation(&invocation).style(self.bold),
                 );
             }
+            EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                server_name,
+                message,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {} {}",
+                    "elicitation".style(self.magenta),
+                    server_name.style(self.bold),
+                    message,
+                );
+            }
+            EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+                server_name,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {} denied (set sampling = \"auto\" to allow it in exec)",
+                    "sampling".style(self.magenta),
+                    server_name.style(self.bold),
+                );
+            }
             EventMsg::McpToolCallEnd(tool_call_end_event) => {
                 let is_success = tool_call_end_event.is_success();
                 let McpToolCallEndEvent {
@@ -331,11 +404,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 // it's easy to scan in the terminal output.
                 for (path, change) in changes.iter() {
                     match change {
-                        FileChange::Add { content } => {
+                        FileChange::Add { content, mode } => {
                             let header = format!(
-                                "{} {}",
+                                "{} {}{}",
                                 format_file_change(change),
-                                path.to_string_lossy()
+                                path.to_string_lossy(),
+                                format_mode(*mode)
                             );
                             eprintln!("{}", header.style(self.magenta));
                             for line in content.lines() {
@@ -356,16 +430,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                         FileChange::Update {
                             unified_diff,
                             move_path,
+                            mode,
                         } => {
                             let header = if let Some(dest) = move_path {
                                 format!(
-                                    "{} {} -> {}",
+                                    "{} {} -> {}{}",
                                     format_file_change(change),
                                     path.to_string_lossy(),
-                                    dest.to_string_lossy()
+                                    dest.to_string_lossy(),
+                                    format_mode(*mode)
                                 )
                             } else {
-                                format!("{} {}", format_file_change(change), path.to_string_lossy())
+                                format!(
+                                    "{} {}{}",
+                                    format_file_change(change),
+                                    path.to_string_lossy(),
+                                    format_mode(*mode)
+                                )
                             };
                             eprintln!("{}", header.style(self.magenta));
 
@@ -384,6 +465,32 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                                 }
                             }
                         }
+                        FileChange::AddBinary { size, mode } => {
+                            let header = format!(
+                                "{} {} (binary, {size} bytes){}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                format_mode(*mode)
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
+                        FileChange::AddSymlink { target } => {
+                            let header = format!(
+                                "{} {} -> {}",
+                                format_file_change(change),
+                                path.to_string_lossy(),
+                                target.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
+                        FileChange::SetMode { mode } => {
+                            let header = format!(
+                                "{} {} (mode {mode:o})",
+                                format_file_change(change),
+                                path.to_string_lossy()
+                            );
+                            eprintln!("{}", header.style(self.magenta));
+                        }
                     }
                 }
             }
@@ -521,6 +628,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::McpListPromptsResponse(_)
+            | EventMsg::McpGetPromptResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -569,17 +678,26 @@ fn escape_command(command: &[String]) -> String {
 
 fn format_file_change(change: &FileChange) -> &'static str {
     match change {
-        FileChange::Add { .. } => "A",
+        FileChange::Add { .. } | FileChange::AddBinary { .. } | FileChange::AddSymlink { .. } => {
+            "A"
+        }
         FileChange::Delete { .. } => "D",
         FileChange::Update {
             move_path: Some(_), ..
         } => "R",
         FileChange::Update {
             move_path: None, ..
-        } => "M",
+        }
+        | FileChange::SetMode { .. } => "M",
     }
 }
 
+/// Suffix noting the permission bits an added or updated file ends up with.
+fn format_mode(mode: Option<u32>) -> String {
+    mode.map(|mode| format!(" (mode {mode:o})"))
+        .unwrap_or_default()
+}
+
 fn format_mcp_invocation(invocation: &McpInvocation) -> String {
     // Build fully-qualified tool name: server.tool
     let fq_tool_name = format!("{}.{}", invocation.server, invocation.tool);
@@ -330,9 +330,11 @@ impl EventProcessorWithJsonOutput {
 
     fn map_change_kind(&self, kind: &FileChange) -> PatchChangeKind {
         match kind {
-            FileChange::Add { .. } => PatchChangeKind::Add,
+            FileChange::Add { .. }
+            | FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. } => PatchChangeKind::Add,
             FileChange::Delete { .. } => PatchChangeKind::Delete,
-            FileChange::Update { .. } => PatchChangeKind::Update,
+            FileChange::Update { .. } | FileChange::SetMode { .. } => PatchChangeKind::Update,
         }
     }
 
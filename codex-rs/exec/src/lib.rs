@@ -20,9 +20,12 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::ElicitationAction;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SessionSource;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
@@ -31,6 +34,7 @@ use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
@@ -68,6 +72,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        elicitation_answers: elicitation_answers_path,
         config_overrides,
     } = cli;
 
@@ -114,6 +119,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     };
 
     let output_schema = load_output_schema(output_schema_path);
+    let elicitation_answers = load_elicitation_answers(elicitation_answers_path);
 
     let (stdout_with_ansi, stderr_with_ansi) = match color {
         cli::Color::Always => (true, true),
@@ -351,6 +357,24 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if let EventMsg::ElicitationRequest(request) = &event.msg {
+            // There is nobody to ask in headless mode, so answer from the
+            // provided file or decline.
+            conversation
+                .submit(elicitation_response(&elicitation_answers, request))
+                .await?;
+        }
+        if let EventMsg::McpSamplingApprovalRequest(request) = &event.msg {
+            // Likewise nobody can approve sampling; servers that should sample
+            // in exec must be configured with `sampling = "auto"`.
+            conversation
+                .submit(Op::McpSamplingApproval {
+                    server_name: request.server_name.clone(),
+                    request_id: request.id.clone(),
+                    decision: ReviewDecision::Denied,
+                })
+                .await?;
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
@@ -425,3 +449,49 @@ fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
         }
     }
 }
+
+fn load_elicitation_answers(path: Option<PathBuf>) -> HashMap<String, Value> {
+    let Some(path) = path else {
+        return HashMap::new();
+    };
+
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            eprintln!(
+                "Failed to read elicitation answers file {}: {err}",
+                path.display()
+            );
+            std::process::exit(1);
+        }
+    };
+
+    match serde_json::from_str::<HashMap<String, Value>>(&contents) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!(
+                "Elicitation answers file {} must be a JSON object keyed by server name: {err}",
+                path.display()
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn elicitation_response(answers: &HashMap<String, Value>, request: &ElicitationRequestEvent) -> Op {
+    let content = answers
+        .get(&request.server_name)
+        .or_else(|| answers.get("*"))
+        .cloned();
+    let decision = if content.is_some() {
+        ElicitationAction::Accept
+    } else {
+        ElicitationAction::Decline
+    };
+    Op::ResolveElicitation {
+        server_name: request.server_name.clone(),
+        request_id: request.id.clone(),
+        decision,
+        content,
+    }
+}
@@ -766,6 +766,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         PathBuf::from("a/added.txt"),
         FileChange::Add {
             content: "+hello".to_string(),
+            mode: None,
         },
     );
     changes.insert(
@@ -779,6 +780,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
         FileChange::Update {
             unified_diff: "--- c/modified.txt\n+++ c/modified.txt\n@@\n-old\n+new\n".to_string(),
             move_path: Some(PathBuf::from("c/renamed.txt")),
+            mode: None,
         },
     );
 
@@ -848,6 +850,7 @@ fn patch_apply_failure_produces_item_completed_patchapply_failed() {
         FileChange::Update {
             unified_diff: "--- file.txt\n+++ file.txt\n@@\n-old\n+new\n".to_string(),
             move_path: None,
+            mode: None,
         },
     );
 
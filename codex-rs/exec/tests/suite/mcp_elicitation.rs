@@ -0,0 +1,89 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::test_codex_exec::test_codex_exec;
+use escargot::CargoBuild;
+use serde_json::Value;
+use serde_json::json;
+use wiremock::matchers::any;
+
+async fn run_confirm_tool(answers: Option<Value>) -> anyhow::Result<Value> {
+    let test = test_codex_exec();
+    let server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?
+        .path()
+        .to_string_lossy()
+        .into_owned();
+
+    let call_id = "call-confirm";
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_response_created("resp-1"),
+            responses::ev_function_call(call_id, "mcp__rmcp__confirm", "{}"),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let final_mock = responses::mount_sse_once_match(
+        &server,
+        any(),
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "done"),
+            responses::ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let mut cmd = test.cmd_with_server(&server);
+    cmd.arg("--skip-git-repo-check")
+        .arg("-c")
+        .arg("features.rmcp_client=true")
+        .arg("-c")
+        .arg(format!("mcp_servers.rmcp.command={server_bin:?}"));
+    if let Some(answers) = answers {
+        let answers_path = test.cwd_path().join("answers.json");
+        std::fs::write(&answers_path, serde_json::to_vec(&answers)?)?;
+        cmd.arg("--elicitation-answers").arg(&answers_path);
+    }
+    cmd.arg("deploy it").assert().success();
+
+    let output = final_mock
+        .single_request()
+        .function_call_output_text(call_id)
+        .expect("confirm tool output should be sent back to the model");
+    Ok(serde_json::from_str(&output)?)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_declines_elicitation_without_answers() -> anyhow::Result<()> {
+    let output = run_confirm_tool(None).await?;
+
+    assert_eq!(output, json!({ "action": "decline", "content": null }));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exec_answers_elicitation_from_file() -> anyhow::Result<()> {
+    let output = run_confirm_tool(Some(json!({
+        "rmcp": { "approved": true, "environment": "staging" },
+        "*": { "approved": false },
+    })))
+    .await?;
+
+    assert_eq!(
+        output,
+        json!({
+            "action": "accept",
+            "content": { "approved": true, "environment": "staging" },
+        })
+    );
+
+    Ok(())
+}
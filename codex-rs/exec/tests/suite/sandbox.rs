@@ -72,6 +72,8 @@ async fn python_multiprocessing_lock_works_under_sandbox() {
         network_access: false,
         exclude_tmpdir_env_var: false,
         exclude_slash_tmp: false,
+        deny_read: Vec::new(),
+        network_allowlist: Vec::new(),
     };
 
     let python_code = r#"import multiprocessing
@@ -132,6 +134,8 @@ async fn sandbox_distinguishes_command_and_policy_cwds() {
         network_access: false,
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        deny_read: vec![],
+        network_allowlist: vec![],
     };
 
     // Attempt to write inside the command cwd, which is outside of the sandbox policy cwd.
@@ -269,7 +273,7 @@ fn unix_sock_body() {
 async fn allow_unix_socketpair_recvfrom() {
     run_code_under_sandbox(
         "allow_unix_socketpair_recvfrom",
-        &SandboxPolicy::ReadOnly,
+        &SandboxPolicy::new_read_only_policy(),
         || async { unix_sock_body() },
     )
     .await
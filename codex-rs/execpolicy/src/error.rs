@@ -29,6 +29,11 @@ pub enum Error {
         program: String,
         option: String,
     },
+    OptionDoesNotTakeValue {
+        program: String,
+        option: String,
+        value: String,
+    },
     UnexpectedArguments {
         program: String,
         args: Vec<PositionalArg>,
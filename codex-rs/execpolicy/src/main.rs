@@ -17,6 +17,7 @@ use std::str::FromStr;
 const MATCHED_BUT_WRITES_FILES_EXIT_CODE: i32 = 12;
 const MIGHT_BE_SAFE_EXIT_CODE: i32 = 13;
 const FORBIDDEN_EXIT_CODE: i32 = 14;
+const REQUIRES_APPROVAL_EXIT_CODE: i32 = 15;
 
 #[derive(Parser, Deserialize, Debug)]
 #[command(version, about, long_about = None)]
@@ -113,6 +114,20 @@ fn check_command(
                 (Output::Safe { r#match: exec }, 0)
             }
         }
+        Ok(MatchedExec::RequiresApproval { exec, reason }) => {
+            let exit_code = if check {
+                REQUIRES_APPROVAL_EXIT_CODE
+            } else {
+                0
+            };
+            (
+                Output::RequiresApproval {
+                    reason,
+                    r#match: exec,
+                },
+                exit_code,
+            )
+        }
         Ok(MatchedExec::Forbidden { reason, cause }) => {
             let exit_code = if check { FORBIDDEN_EXIT_CODE } else { 0 };
             (Output::Forbidden { reason, cause }, exit_code)
@@ -136,6 +151,11 @@ pub enum Output {
     #[serde(rename = "match")]
     Match { r#match: ValidExec },
 
+    /// The command has matched a rule in the policy that requires the user to
+    /// explicitly approve it before it runs.
+    #[serde(rename = "requires_approval")]
+    RequiresApproval { reason: String, r#match: ValidExec },
+
     /// The user is forbidden from running the command.
     #[serde(rename = "forbidden")]
     Forbidden {
@@ -15,6 +15,7 @@ use crate::program::PositiveExampleFailedCheck;
 pub struct Policy {
     programs: MultiMap<String, ProgramSpec>,
     forbidden_program_regexes: Vec<ForbiddenProgramRegex>,
+    forbidden_substrings: Vec<String>,
     forbidden_substrings_pattern: Option<Regex>,
 }
 
@@ -37,10 +38,33 @@ impl Policy {
         Ok(Self {
             programs,
             forbidden_program_regexes,
+            forbidden_substrings,
             forbidden_substrings_pattern,
         })
     }
 
+    /// Combines two policies. Program specs from `other` are consulted before
+    /// the ones already in `self`, so a later policy can tighten a program
+    /// that an earlier one allows (e.g. with `require_approval`). Forbidden
+    /// program regexes and substrings from both policies always apply.
+    pub fn merge(self, other: Policy) -> std::result::Result<Self, RegexError> {
+        let mut programs = self.programs;
+        for (program, mut specs) in other.programs {
+            if let Some(existing) = programs.remove(&program) {
+                specs.extend(existing);
+            }
+            programs.insert_many(program, specs);
+        }
+
+        let mut forbidden_program_regexes = self.forbidden_program_regexes;
+        forbidden_program_regexes.extend(other.forbidden_program_regexes);
+
+        let mut forbidden_substrings = self.forbidden_substrings;
+        forbidden_substrings.extend(other.forbidden_substrings);
+
+        Self::new(programs, forbidden_program_regexes, forbidden_substrings)
+    }
+
     pub fn check(&self, exec_call: &ExecCall) -> Result<MatchedExec> {
         let ExecCall { program, args } = &exec_call;
         for ForbiddenProgramRegex { regex, reason } in &self.forbidden_program_regexes {
@@ -128,6 +128,7 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         options: Option<UnpackList<Opt>>,
         args: Option<UnpackList<ArgMatcher>>,
         forbidden: Option<String>,
+        require_approval: Option<String>,
         should_match: Option<UnpackList<UnpackList<String>>>,
         should_not_match: Option<UnpackList<UnpackList<String>>>,
         eval: &mut Evaluator,
@@ -157,6 +158,7 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
             allowed_options,
             args,
             forbidden,
+            require_approval,
             should_match
                 .map_or_else(Vec::new, |v| v.items.to_vec())
                 .into_iter()
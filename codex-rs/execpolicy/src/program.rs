@@ -24,6 +24,7 @@ pub struct ProgramSpec {
     pub allowed_options: HashMap<String, Opt>,
     pub arg_patterns: Vec<ArgMatcher>,
     forbidden: Option<String>,
+    requires_approval: Option<String>,
     required_options: HashSet<String>,
     should_match: Vec<Vec<String>>,
     should_not_match: Vec<Vec<String>>,
@@ -38,6 +39,7 @@ impl ProgramSpec {
         allowed_options: HashMap<String, Opt>,
         arg_patterns: Vec<ArgMatcher>,
         forbidden: Option<String>,
+        requires_approval: Option<String>,
         should_match: Vec<Vec<String>>,
         should_not_match: Vec<Vec<String>>,
     ) -> Self {
@@ -59,6 +61,7 @@ impl ProgramSpec {
             allowed_options,
             arg_patterns,
             forbidden,
+            requires_approval,
             required_options,
             should_match,
             should_not_match,
@@ -68,8 +71,19 @@ impl ProgramSpec {
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub enum MatchedExec {
-    Match { exec: ValidExec },
-    Forbidden { cause: Forbidden, reason: String },
+    Match {
+        exec: ValidExec,
+    },
+    /// The command matched a rule that allows it, but only after the user
+    /// has explicitly approved it.
+    RequiresApproval {
+        exec: ValidExec,
+        reason: String,
+    },
+    Forbidden {
+        cause: Forbidden,
+        reason: String,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
@@ -137,6 +151,21 @@ impl ProgramSpec {
                     }
                 }
 
+                if self.combined_format
+                    && arg.starts_with("--")
+                    && let Some((name, value)) = arg.split_once('=')
+                {
+                    matched_opts.push(self.check_combined_option(name, value)?);
+                    continue;
+                }
+
+                // ...or a bundle of single-character options, e.g. `-al`.
+                if self.option_bundling && !arg.starts_with("--") && arg.len() > 2 {
+                    expecting_option_value =
+                        self.check_bundled_options(arg, &mut matched_flags, &mut matched_opts)?;
+                    continue;
+                }
+
                 return Err(Error::UnknownOption {
                     program: self.program.clone(),
                     option: arg.clone(),
@@ -185,13 +214,67 @@ impl ProgramSpec {
             args: matched_args,
             system_path: self.system_path.clone(),
         };
-        match &self.forbidden {
-            Some(reason) => Ok(MatchedExec::Forbidden {
+        match (&self.forbidden, &self.requires_approval) {
+            (Some(reason), _) => Ok(MatchedExec::Forbidden {
                 cause: Forbidden::Exec { exec },
                 reason: reason.clone(),
             }),
-            None => Ok(MatchedExec::Match { exec }),
+            (None, Some(reason)) => Ok(MatchedExec::RequiresApproval {
+                exec,
+                reason: reason.clone(),
+            }),
+            (None, None) => Ok(MatchedExec::Match { exec }),
+        }
+    }
+
+    /// Matches an `--option=value` argument against the allowed options.
+    fn check_combined_option(&self, name: &str, value: &str) -> Result<MatchedOpt> {
+        match self.allowed_options.get(name).map(|opt| &opt.meta) {
+            Some(OptMeta::Value(arg_type)) => MatchedOpt::new(name, value, arg_type.clone()),
+            Some(OptMeta::Flag) => Err(Error::OptionDoesNotTakeValue {
+                program: self.program.clone(),
+                option: name.to_string(),
+                value: value.to_string(),
+            }),
+            None => Err(Error::UnknownOption {
+                program: self.program.clone(),
+                option: name.to_string(),
+            }),
+        }
+    }
+
+    /// Expands a bundle such as `-al` into `-a -l`, following getopt(3): the
+    /// first option in the bundle that takes a value consumes the rest of the
+    /// bundle (`-C3`) or, if nothing is left, the next argument. Returns the
+    /// option still waiting for its value in the latter case.
+    fn check_bundled_options(
+        &self,
+        arg: &str,
+        matched_flags: &mut Vec<MatchedFlag>,
+        matched_opts: &mut Vec<MatchedOpt>,
+    ) -> Result<Option<(String, ArgType)>> {
+        let bundle = &arg[1..];
+        for (offset, ch) in bundle.char_indices() {
+            let name = format!("-{ch}");
+            match self.allowed_options.get(&name).map(|opt| &opt.meta) {
+                Some(OptMeta::Flag) => matched_flags.push(MatchedFlag { name }),
+                Some(OptMeta::Value(arg_type)) => {
+                    let value = &bundle[offset + ch.len_utf8()..];
+                    if value.is_empty() {
+                        return Ok(Some((name, arg_type.clone())));
+                    }
+                    matched_opts.push(MatchedOpt::new(&name, value, arg_type.clone())?);
+                    return Ok(None);
+                }
+                None => {
+                    return Err(Error::UnknownOption {
+                        program: self.program.clone(),
+                        option: name,
+                    });
+                }
+            }
         }
+        Ok(None)
     }
 
     pub fn verify_should_match_list(&self) -> Vec<PositiveExampleFailedCheck> {
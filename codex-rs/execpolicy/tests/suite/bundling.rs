@@ -0,0 +1,189 @@
+extern crate codex_execpolicy;
+
+use codex_execpolicy::ArgType;
+use codex_execpolicy::Error;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::MatchedArg;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::MatchedFlag;
+use codex_execpolicy::MatchedOpt;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::Result;
+use codex_execpolicy::ValidExec;
+
+#[expect(clippy::expect_used)]
+fn setup() -> Policy {
+    let unparsed_policy = r#"
+define_program(
+    program="tool",
+    option_bundling=True,
+    combined_format=True,
+    options=[
+        flag("-a"),
+        flag("-b"),
+        flag("--verbose"),
+        opt("-C", ARG_POS_INT),
+        opt("--context", ARG_POS_INT),
+        opt("-o", ARG_WFILE),
+    ],
+    args=[ARG_RFILES],
+)
+
+define_program(
+    program="strict",
+    options=[
+        flag("-a"),
+        flag("-b"),
+        opt("--context", ARG_POS_INT),
+    ],
+    args=[ARG_RFILES],
+)
+"#;
+    PolicyParser::new("#test", unparsed_policy)
+        .parse()
+        .expect("failed to parse policy")
+}
+
+#[test]
+fn test_bundled_flags() -> Result<()> {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["-ab", "foo"]);
+    assert_eq!(
+        Ok(MatchedExec::Match {
+            exec: ValidExec {
+                program: "tool".into(),
+                flags: vec![MatchedFlag::new("-a"), MatchedFlag::new("-b")],
+                args: vec![MatchedArg::new(1, ArgType::ReadableFile, "foo")?],
+                ..Default::default()
+            }
+        }),
+        policy.check(&tool)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bundled_option_takes_rest_of_bundle_as_value() -> Result<()> {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["-aC3", "foo"]);
+    assert_eq!(
+        Ok(MatchedExec::Match {
+            exec: ValidExec {
+                program: "tool".into(),
+                flags: vec![MatchedFlag::new("-a")],
+                opts: vec![MatchedOpt::new("-C", "3", ArgType::PositiveInteger)?],
+                args: vec![MatchedArg::new(1, ArgType::ReadableFile, "foo")?],
+                ..Default::default()
+            }
+        }),
+        policy.check(&tool)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bundled_option_takes_next_arg_as_value() -> Result<()> {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["-bo", "out.txt", "foo"]);
+    assert_eq!(
+        Ok(MatchedExec::Match {
+            exec: ValidExec {
+                program: "tool".into(),
+                flags: vec![MatchedFlag::new("-b")],
+                opts: vec![MatchedOpt::new("-o", "out.txt", ArgType::WriteableFile)?],
+                args: vec![MatchedArg::new(2, ArgType::ReadableFile, "foo")?],
+                ..Default::default()
+            }
+        }),
+        policy.check(&tool)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_bundled_value_is_validated() {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["-C0", "foo"]);
+    assert_eq!(
+        Err(Error::InvalidPositiveInteger { value: "0".into() }),
+        policy.check(&tool)
+    );
+}
+
+#[test]
+fn test_bundle_with_unknown_option() {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["-az", "foo"]);
+    assert_eq!(
+        Err(Error::UnknownOption {
+            program: "tool".into(),
+            option: "-z".into()
+        }),
+        policy.check(&tool)
+    );
+}
+
+#[test]
+fn test_combined_format() -> Result<()> {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["--context=2", "foo"]);
+    assert_eq!(
+        Ok(MatchedExec::Match {
+            exec: ValidExec {
+                program: "tool".into(),
+                opts: vec![MatchedOpt::new("--context", "2", ArgType::PositiveInteger)?],
+                args: vec![MatchedArg::new(1, ArgType::ReadableFile, "foo")?],
+                ..Default::default()
+            }
+        }),
+        policy.check(&tool)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_combined_format_flag_does_not_take_value() {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["--verbose=yes", "foo"]);
+    assert_eq!(
+        Err(Error::OptionDoesNotTakeValue {
+            program: "tool".into(),
+            option: "--verbose".into(),
+            value: "yes".into(),
+        }),
+        policy.check(&tool)
+    );
+}
+
+#[test]
+fn test_combined_format_unknown_option() {
+    let policy = setup();
+    let tool = ExecCall::new("tool", &["--pre=pwned", "foo"]);
+    assert_eq!(
+        Err(Error::UnknownOption {
+            program: "tool".into(),
+            option: "--pre".into()
+        }),
+        policy.check(&tool)
+    );
+}
+
+#[test]
+fn test_bundling_and_combined_format_are_opt_in() {
+    let policy = setup();
+    assert_eq!(
+        Err(Error::UnknownOption {
+            program: "strict".into(),
+            option: "-ab".into()
+        }),
+        policy.check(&ExecCall::new("strict", &["-ab", "foo"]))
+    );
+    assert_eq!(
+        Err(Error::UnknownOption {
+            program: "strict".into(),
+            option: "--context=2".into()
+        }),
+        policy.check(&ExecCall::new("strict", &["--context=2", "foo"]))
+    );
+}
@@ -0,0 +1,115 @@
+extern crate codex_execpolicy;
+
+use codex_execpolicy::ArgType;
+use codex_execpolicy::ExecCall;
+use codex_execpolicy::Forbidden;
+use codex_execpolicy::MatchedArg;
+use codex_execpolicy::MatchedExec;
+use codex_execpolicy::Policy;
+use codex_execpolicy::PolicyParser;
+use codex_execpolicy::Result;
+use codex_execpolicy::ValidExec;
+use codex_execpolicy::get_default_policy;
+
+#[expect(clippy::expect_used)]
+fn parse(source: &str, unparsed_policy: &str) -> Policy {
+    PolicyParser::new(source, unparsed_policy)
+        .parse()
+        .expect("failed to parse policy")
+}
+
+#[test]
+fn test_require_approval() -> Result<()> {
+    let policy = parse(
+        "test_require_approval",
+        r#"
+define_program(
+    program="terraform",
+    args=["apply"],
+    require_approval="terraform apply changes shared infrastructure",
+)
+"#,
+    );
+    let terraform = ExecCall::new("terraform", &["apply"]);
+    assert_eq!(
+        Ok(MatchedExec::RequiresApproval {
+            exec: ValidExec::new(
+                "terraform",
+                vec![MatchedArg::new(
+                    0,
+                    ArgType::Literal("apply".to_string()),
+                    "apply"
+                )?],
+                &[]
+            ),
+            reason: "terraform apply changes shared infrastructure".to_string(),
+        }),
+        policy.check(&terraform)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_merged_rules_take_precedence() -> Result<()> {
+    let project_policy = parse(
+        "project.policy",
+        r#"
+define_program(
+    program="cp",
+    args=[ARG_RFILES, ARG_WFILE],
+    require_approval="copies must be reviewed in this repo",
+)
+"#,
+    );
+    let policy = get_default_policy()
+        .expect("failed to load default policy")
+        .merge(project_policy)
+        .expect("failed to merge policies");
+
+    let cp = ExecCall::new("cp", &["foo", "bar"]);
+    assert_eq!(
+        Ok(MatchedExec::RequiresApproval {
+            exec: ValidExec::new(
+                "cp",
+                vec![
+                    MatchedArg::new(0, ArgType::ReadableFile, "foo")?,
+                    MatchedArg::new(1, ArgType::WriteableFile, "bar")?,
+                ],
+                &[]
+            ),
+            reason: "copies must be reviewed in this repo".to_string(),
+        }),
+        policy.check(&cp)
+    );
+
+    // Programs the project policy does not mention still use the default rules.
+    let pwd = ExecCall::new("pwd", &[]);
+    assert!(matches!(policy.check(&pwd), Ok(MatchedExec::Match { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_merged_forbidden_substrings_apply() {
+    let project_policy = parse(
+        "project.policy",
+        r#"
+forbid_substrings(["secrets/"])
+"#,
+    );
+    let policy = get_default_policy()
+        .expect("failed to load default policy")
+        .merge(project_policy)
+        .expect("failed to merge policies");
+
+    let cat = ExecCall::new("cat", &["secrets/prod.env"]);
+    assert_eq!(
+        Ok(MatchedExec::Forbidden {
+            cause: Forbidden::Arg {
+                arg: "secrets/prod.env".to_string(),
+                exec_call: cat.clone(),
+            },
+            reason: "arg `secrets/prod.env` contains forbidden substring".to_string(),
+        }),
+        policy.check(&cat)
+    );
+}
@@ -1,10 +1,12 @@
 // Aggregates all former standalone integration tests as modules.
 mod bad;
+mod bundling;
 mod cp;
 mod good;
 mod head;
 mod literal;
 mod ls;
+mod merge;
 mod parse_sed_command;
 mod pwd;
 mod sed;
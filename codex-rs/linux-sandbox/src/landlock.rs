@@ -1,4 +1,6 @@
 use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::os::fd::OwnedFd;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -6,6 +8,7 @@ use codex_core::error::CodexErr;
 use codex_core::error::Result;
 use codex_core::error::SandboxErr;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::WritableRoot;
 
 use landlock::ABI;
 use landlock::Access;
@@ -25,52 +28,259 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+use crate::mounts::mount_restrictions;
+use crate::proxy::start_filtering_proxy;
+
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
+///
+/// `network_report` is where the filtering proxy reports refused
+/// connections; it is closed in this process either way so the sandboxed
+/// command never inherits it.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_report: Option<OwnedFd>,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
+    // Mounts must be set up before Landlock is applied because a
+    // Landlock-restricted thread may no longer change the mount topology.
+    let landlock_paths = if sandbox_policy.has_full_disk_write_access() {
+        None
+    } else {
+        let writable_roots = sandbox_policy.get_writable_roots_with_cwd(cwd);
+        Some(prepare_landlock_paths(
+            writable_roots,
+            sandbox_policy.get_deny_read_paths(),
+        )?)
+    };
+
+    // With an allowlist, network access is confined by a network namespace
+    // whose only way out is the filtering proxy. If the namespace cannot be
+    // set up, fall back to blocking the network entirely.
+    let network_allowlist = sandbox_policy.get_network_allowlist();
+    let proxy_addr = if network_allowlist.is_empty() {
+        None
+    } else {
+        start_filtering_proxy(network_allowlist, network_report).ok()
+    };
+
+    if let Some(proxy_addr) = proxy_addr {
+        set_proxy_env_vars(proxy_addr);
+        install_proxy_network_seccomp_filter_on_current_thread()?;
+    } else if !sandbox_policy.has_full_network_access() {
         install_network_seccomp_filter_on_current_thread()?;
     }
 
-    if !sandbox_policy.has_full_disk_write_access() {
-        let writable_roots = sandbox_policy
-            .get_writable_roots_with_cwd(cwd)
+    if let Some(LandlockPaths {
+        writable_roots,
+        deny_read,
+    }) = landlock_paths
+    {
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, &deny_read)?;
+    }
+
+    Ok(())
+}
+
+/// Points HTTP clients in the sandboxed command at the filtering proxy. Both
+/// spellings are set because tools disagree on which one they honor.
+fn set_proxy_env_vars(proxy_addr: SocketAddr) {
+    let proxy_url = format!("http://{proxy_addr}");
+    for (key, value) in [
+        ("HTTP_PROXY", proxy_url.as_str()),
+        ("HTTPS_PROXY", proxy_url.as_str()),
+        ("ALL_PROXY", proxy_url.as_str()),
+        ("NO_PROXY", "localhost,127.0.0.1,::1"),
+    ] {
+        // SAFETY: the sandbox process is still single-threaded here.
+        unsafe {
+            std::env::set_var(key, value);
+            std::env::set_var(key.to_ascii_lowercase(), value);
+        }
+    }
+}
+
+/// Reported when read-only subpaths cannot be enforced because no mount
+/// namespace could be created. Landlock alone could only do so by narrowing
+/// write access to the existing children of each writable root, so the
+/// sandbox refuses to start instead of silently changing what is writable.
+const READ_ONLY_SUBPATHS_UNSUPPORTED: &str = "codex-linux-sandbox: cannot keep read-only subpaths (such as .git) of writable roots read-only because a mount namespace could not be created; enable unprivileged user namespaces or run the command outside the sandbox";
+
+/// Printed to the sandboxed command's stderr when `deny_read` has to be
+/// enforced with Landlock alone; see [`readable_roots_excluding`].
+const DENY_READ_FALLBACK_WARNING: &str = "codex-linux-sandbox: warning: deny_read is enforced without mounts; directories containing a denied path cannot be listed and entries created in them later are unreadable";
+
+/// File-system access left for Landlock to enforce once the mount-based
+/// restrictions are in place.
+struct LandlockPaths {
+    writable_roots: Vec<PathBuf>,
+    deny_read: Vec<PathBuf>,
+}
+
+/// Applies as much of the file-system policy as possible with mounts and
+/// returns the paths Landlock still has to handle.
+///
+/// The preferred mechanism is a private mount namespace (see
+/// [`mount_restrictions`]): read-only subpaths become read-only bind mounts
+/// and `deny_read` paths are masked, which leaves the writable roots and the
+/// rest of the file-system untouched. When a mount namespace cannot be
+/// created (e.g. unprivileged user namespaces are disabled):
+///
+/// - read-only subpaths cannot be enforced without narrowing write access to
+///   the rest of their writable root, so setup fails with
+///   [`READ_ONLY_SUBPATHS_UNSUPPORTED`].
+/// - `deny_read` falls back to read grants for everything else, with the
+///   side effects described on [`readable_roots_excluding`].
+///
+/// Masking a denied directory would also hide any writable root nested
+/// inside it, so `deny_read` is always left to Landlock in that case.
+fn prepare_landlock_paths(
+    writable_roots: Vec<WritableRoot>,
+    deny_read: &[PathBuf],
+) -> Result<LandlockPaths> {
+    let read_only_subpaths: Vec<PathBuf> = writable_roots
+        .iter()
+        .flat_map(|writable_root| writable_root.read_only_subpaths.iter().cloned())
+        .collect();
+    let can_mask_deny_read = !deny_read.iter().any(|denied| {
+        writable_roots
+            .iter()
+            .any(|writable_root| writable_root.root.starts_with(denied))
+    });
+    let masked_deny_read: &[PathBuf] = if can_mask_deny_read { deny_read } else { &[] };
+
+    let mount_result = if read_only_subpaths.is_empty() && masked_deny_read.is_empty() {
+        Ok(())
+    } else {
+        mount_restrictions(&read_only_subpaths, masked_deny_read)
+    };
+    if let Err(err) = &mount_result
+        && !read_only_subpaths.is_empty()
+    {
+        return Err(CodexErr::UnsupportedOperation(format!(
+            "{READ_ONLY_SUBPATHS_UNSUPPORTED}: {err}"
+        )));
+    }
+    let mounted = mount_result.is_ok();
+
+    let landlock_deny_read = if mounted && can_mask_deny_read {
+        Vec::new()
+    } else {
+        deny_read.to_vec()
+    };
+    if !landlock_deny_read.is_empty() {
+        eprintln!("{DENY_READ_FALLBACK_WARNING}");
+    }
+
+    Ok(LandlockPaths {
+        writable_roots: writable_roots
             .into_iter()
             .map(|writable_root| writable_root.root)
-            .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+            .collect(),
+        deny_read: landlock_deny_read,
+    })
+}
+
+/// Landlock can only grant access, so reading everything except `deny_read`
+/// is expressed as read rules for every path under `/` that is neither a
+/// denied path nor an ancestor of one. Writable roots nested under a denied
+/// path stay readable.
+///
+/// This is only used when the denied paths cannot be masked with mounts,
+/// because the grants are computed from the entries that exist when the
+/// sandbox starts: every ancestor of a denied path becomes unlistable, and
+/// files created later directly inside such an ancestor are unreadable.
+fn readable_roots_excluding(
+    deny_read: &[PathBuf],
+    writable_roots: &[PathBuf],
+) -> std::io::Result<Vec<PathBuf>> {
+    // Deny both the configured and the canonical form so a symlinked
+    // ancestor cannot be used to reach a denied path.
+    let mut excluded: Vec<PathBuf> = Vec::new();
+    for path in deny_read {
+        excluded.push(path.clone());
+        if let Ok(canonical) = path.canonicalize()
+            && canonical != *path
+        {
+            excluded.push(canonical);
+        }
     }
 
-    // TODO(ragona): Add appropriate restrictions if
-    // `sandbox_policy.has_full_disk_read_access()` is `false`.
+    let mut paths = Vec::new();
+    collect_paths_excluding(Path::new("/"), &excluded, &mut paths)?;
+    for writable_root in writable_roots {
+        if excluded
+            .iter()
+            .any(|denied| writable_root.starts_with(denied))
+        {
+            collect_paths_excluding(writable_root, &excluded, &mut paths)?;
+        }
+    }
+    Ok(paths)
+}
 
+/// Pushes `path` onto `out` if none of `excluded` live beneath it; otherwise
+/// recurses into its children, skipping the excluded paths. A directory that
+/// cannot be listed is an error because its entries could not be granted.
+fn collect_paths_excluding(
+    path: &Path,
+    excluded: &[PathBuf],
+    out: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+    if excluded.iter().any(|excluded| excluded == path) {
+        return Ok(());
+    }
+    if !excluded.iter().any(|excluded| excluded.starts_with(path)) {
+        out.push(path.to_path_buf());
+        return Ok(());
+    }
+    let entries = std::fs::read_dir(path).map_err(|err| {
+        std::io::Error::new(
+            err.kind(),
+            format!("failed to list {}: {err}", path.display()),
+        )
+    })?;
+    for entry in entries {
+        collect_paths_excluding(&entry?.path(), excluded, out)?;
+    }
     Ok(())
 }
 
 /// Installs Landlock file-system rules on the current thread allowing read
-/// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// access to the entire file-system except `deny_read`, while restricting
+/// write access to `/dev/null` and the provided list of `writable_roots`.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
-fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathBuf>) -> Result<()> {
+fn install_filesystem_landlock_rules_on_current_thread(
+    writable_roots: Vec<PathBuf>,
+    deny_read: &[PathBuf],
+) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
 
+    // With read restrictions in place, writable roots only receive the write
+    // rights so they cannot re-grant read access to a denied path inside them.
+    let (readable_roots, access_write) = if deny_read.is_empty() {
+        (vec![PathBuf::from("/")], access_rw)
+    } else {
+        (
+            readable_roots_excluding(deny_read, &writable_roots)?,
+            access_rw & !access_ro,
+        )
+    };
+
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
+        .add_rules(landlock::path_beneath_rules(&readable_roots, access_ro))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
     if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
+        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_write))?;
     }
 
     let status = ruleset.restrict_self()?;
@@ -112,6 +322,8 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     deny_syscall(libc::SYS_getsockopt);
     deny_syscall(libc::SYS_setsockopt);
     deny_syscall(libc::SYS_ptrace);
+    // io_uring can issue connect/send operations without the syscalls above.
+    deny_syscall(libc::SYS_io_uring_setup);
 
     // For `socket` we allow AF_UNIX (arg0 == AF_UNIX) and deny everything else.
     let unix_only_rule = SeccompRule::new(vec![SeccompCondition::new(
@@ -124,6 +336,74 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
     rules.insert(libc::SYS_socket, vec![unix_only_rule.clone()]);
     rules.insert(libc::SYS_socketpair, vec![unix_only_rule]); // always deny (Unix can use socketpair but fine, keep open?)
 
+    apply_deny_rules(rules)
+}
+
+/// Installs the seccomp filter used while the filtering proxy is running.
+///
+/// The network namespace only contains loopback, where the proxy is the only
+/// listener that leads anywhere, so TCP `connect` has to stay allowed. BPF
+/// cannot inspect the address passed to `connect`; instead the filter only
+/// lets the command create TCP sockets. Every other socket type is denied:
+/// AF_UNIX pathname sockets live in the file-system rather than the network
+/// namespace (e.g. docker.sock, ssh-agent, the D-Bus session bus), and raw or
+/// datagram sockets have no use when all traffic goes through the proxy.
+/// `socketpair` stays available for AF_UNIX, which only connects the two
+/// returned descriptors.
+fn install_proxy_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+    rules.insert(libc::SYS_ptrace, vec![]);
+    rules.insert(libc::SYS_io_uring_setup, vec![]);
+
+    let mut socket_rules = vec![SeccompRule::new(vec![
+        SeccompCondition::new(
+            0, // domain
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET as u64,
+        )?,
+        SeccompCondition::new(
+            0, // domain
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_INET6 as u64,
+        )?,
+    ])?];
+    // The type argument also carries SOCK_NONBLOCK/SOCK_CLOEXEC, so compare
+    // only its low bits.
+    for denied_type in [
+        libc::SOCK_DGRAM,
+        libc::SOCK_RAW,
+        libc::SOCK_RDM,
+        libc::SOCK_SEQPACKET,
+        libc::SOCK_DCCP,
+        10, // SOCK_PACKET, deprecated in `libc` but still accepted by the kernel
+    ] {
+        socket_rules.push(SeccompRule::new(vec![SeccompCondition::new(
+            1, // type
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::MaskedEq(0xf),
+            denied_type as u64,
+        )?])?);
+    }
+    rules.insert(libc::SYS_socket, socket_rules);
+
+    rules.insert(
+        libc::SYS_socketpair,
+        vec![SeccompRule::new(vec![SeccompCondition::new(
+            0, // domain
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_UNIX as u64,
+        )?])?],
+    );
+
+    apply_deny_rules(rules)
+}
+
+/// Installs a seccomp filter on the current thread that fails every call
+/// matching `rules` with `EPERM` and allows everything else.
+fn apply_deny_rules(rules: BTreeMap<i64, Vec<SeccompRule>>) -> std::result::Result<(), SandboxErr> {
     let filter = SeccompFilter::new(
         rules,
         SeccompAction::Allow,                     // default – allow
@@ -1,5 +1,8 @@
 use clap::Parser;
+use codex_core::sandboxing::network::CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR;
 use std::ffi::CString;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
@@ -26,7 +29,10 @@ pub fn run_main() -> ! {
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    let network_report = take_network_report_fd();
+    if let Err(e) =
+        apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd, network_report)
+    {
         panic!("error running landlock: {e:?}");
     }
 
@@ -54,3 +60,17 @@ pub fn run_main() -> ! {
     let err = std::io::Error::last_os_error();
     panic!("Failed to execvp {}: {err}", command[0].as_str());
 }
+
+/// Takes ownership of the descriptor that Codex passed for the filtering
+/// proxy's reports and removes its environment variable, so neither reaches
+/// the sandboxed command.
+fn take_network_report_fd() -> Option<OwnedFd> {
+    let fd = std::env::var(CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR).ok()?;
+    // SAFETY: the sandbox process is still single-threaded here.
+    unsafe { std::env::remove_var(CODEX_SANDBOX_NETWORK_REPORT_FD_ENV_VAR) };
+    let fd: i32 = fd.parse().ok()?;
+    if fd <= libc::STDERR_FILENO || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return None;
+    }
+    Some(unsafe { OwnedFd::from_raw_fd(fd) })
+}
@@ -0,0 +1,130 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::namespaces::check;
+use crate::namespaces::unshare_namespaces;
+
+/// Enters a private mount namespace and applies the mount-based parts of the
+/// sandbox policy for this process and its children:
+///
+/// - each of `read_only_subpaths` is bind-remounted on top of itself with
+///   `MS_RDONLY`. Landlock rules only ever grant access, so once a writable
+///   root is added to the ruleset there is no way to carve a read-only hole
+///   (e.g. `.git`) out of it. A read-only bind mount enforces the carve-out
+///   underneath Landlock.
+/// - each existing path in `deny_read` is masked: directories with an empty
+///   read-only tmpfs and files with a read-only bind of `/dev/null`. Unlike
+///   expressing `deny_read` with Landlock, this leaves the rest of the
+///   file-system, including the ancestors of denied paths, fully readable.
+///
+/// Must be called before Landlock is applied (Landlock forbids changes to the
+/// mount topology, which also keeps the sandboxed command from undoing these
+/// mounts) and while the process is still single-threaded (required by
+/// `unshare(CLONE_NEWUSER)`).
+pub(crate) fn mount_restrictions(
+    read_only_subpaths: &[PathBuf],
+    deny_read: &[PathBuf],
+) -> io::Result<()> {
+    enter_private_mount_namespace()?;
+    for subpath in read_only_subpaths {
+        bind_remount_read_only(subpath, subpath)?;
+    }
+    for path in deny_read {
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.is_dir() => mount_empty_tmpfs(path)?,
+            Ok(_) => bind_remount_read_only(Path::new("/dev/null"), path)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Unshares the mount namespace and marks all mounts private so nothing
+/// propagates back to the host.
+fn enter_private_mount_namespace() -> io::Result<()> {
+    unshare_namespaces(libc::CLONE_NEWNS)?;
+
+    let root = c_path(Path::new("/"))?;
+    check(unsafe {
+        libc::mount(
+            std::ptr::null(),
+            root.as_ptr(),
+            std::ptr::null(),
+            libc::MS_REC | libc::MS_PRIVATE,
+            std::ptr::null(),
+        )
+    })
+}
+
+/// Mounts an empty, read-only tmpfs over `path` so none of its original
+/// contents are visible.
+fn mount_empty_tmpfs(path: &Path) -> io::Result<()> {
+    let c_path = c_path(path)?;
+    let fstype = c_path_str("tmpfs")?;
+    let options = c_path_str("mode=0555")?;
+    check(unsafe {
+        libc::mount(
+            fstype.as_ptr(),
+            c_path.as_ptr(),
+            fstype.as_ptr(),
+            libc::MS_RDONLY | libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+            options.as_ptr().cast(),
+        )
+    })
+}
+
+/// Bind-mounts `source` on top of `target` and remounts the result read-only.
+fn bind_remount_read_only(source: &Path, target: &Path) -> io::Result<()> {
+    let c_source = c_path(source)?;
+    let c_path = c_path(target)?;
+    check(unsafe {
+        libc::mount(
+            c_source.as_ptr(),
+            c_path.as_ptr(),
+            std::ptr::null(),
+            libc::MS_BIND | libc::MS_REC,
+            std::ptr::null(),
+        )
+    })?;
+
+    // Inside a user namespace the kernel refuses a remount that would clear
+    // flags locked by the parent mount, so carry them over.
+    let mut flags = libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    check(unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) })?;
+    for (st_flag, ms_flag) in [
+        (libc::ST_NOSUID, libc::MS_NOSUID),
+        (libc::ST_NODEV, libc::MS_NODEV),
+        (libc::ST_NOEXEC, libc::MS_NOEXEC),
+        (libc::ST_NOATIME, libc::MS_NOATIME),
+        (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+        (libc::ST_RELATIME, libc::MS_RELATIME),
+    ] {
+        if stat.f_flag & st_flag != 0 {
+            flags |= ms_flag;
+        }
+    }
+
+    check(unsafe {
+        libc::mount(
+            std::ptr::null(),
+            c_path.as_ptr(),
+            std::ptr::null(),
+            flags,
+            std::ptr::null(),
+        )
+    })
+}
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+fn c_path_str(value: &str) -> io::Result<CString> {
+    c_path(Path::new(value))
+}
@@ -0,0 +1,28 @@
+use std::io;
+
+/// Unshares the namespaces in `flags`. When not running as root, a new user
+/// namespace is created alongside them so that no privileges are required;
+/// the current user and group are mapped onto themselves so files created
+/// inside the sandbox keep their ownership.
+pub(crate) fn unshare_namespaces(flags: libc::c_int) -> io::Result<()> {
+    let uid = unsafe { libc::geteuid() };
+    let gid = unsafe { libc::getegid() };
+
+    if uid == 0 {
+        return check(unsafe { libc::unshare(flags) });
+    }
+
+    check(unsafe { libc::unshare(libc::CLONE_NEWUSER | flags) })?;
+    std::fs::write("/proc/self/setgroups", "deny")?;
+    std::fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    std::fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+    Ok(())
+}
+
+pub(crate) fn check(ret: libc::c_int) -> io::Result<()> {
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
@@ -0,0 +1,398 @@
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::net::Shutdown;
+use std::net::SocketAddr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use codex_core::sandboxing::network::BlockedConnection;
+use codex_core::sandboxing::network::is_host_allowed;
+
+use crate::namespaces::check;
+use crate::namespaces::unshare_namespaces;
+
+/// Upper bound on the size of a request head the proxy is willing to buffer.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// How long a client may take to send its request head.
+const REQUEST_HEAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Upper bound on clients served at once. Each client costs up to two
+/// threads; further connections are refused with `503` until one finishes.
+const MAX_CONCURRENT_CLIENTS: usize = 64;
+
+/// Moves this process into a fresh network namespace whose only interface is
+/// loopback, and starts an HTTP proxy that forwards connections to the hosts
+/// in `allowlist` only. The proxy runs in a forked child that stays in the
+/// original network namespace; it accepts connections on a loopback listener
+/// created inside the new namespace, which is handed to it over a socketpair.
+///
+/// Refused connections are reported on `report`, a descriptor that only the
+/// proxy keeps, so the sandboxed command can neither see nor forge them.
+///
+/// Returns the address commands should use as their `HTTP(S)_PROXY`.
+///
+/// Must be called while the process is still single-threaded (required by
+/// both `fork` and `unshare(CLONE_NEWUSER)`).
+pub(crate) fn start_filtering_proxy(
+    allowlist: &[String],
+    report: Option<OwnedFd>,
+) -> io::Result<SocketAddr> {
+    let (parent_socket, child_socket) = UnixStream::pair()?;
+    let parent_pid = unsafe { libc::getpid() };
+
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            drop(parent_socket);
+            run_proxy_process(parent_pid, &child_socket, allowlist, report.map(File::from))
+        }
+        _ => {
+            drop(child_socket);
+            drop(report);
+            unshare_namespaces(libc::CLONE_NEWNET)?;
+            bring_up_loopback()?;
+            let listener = TcpListener::bind("127.0.0.1:0")?;
+            send_fd(&parent_socket, listener.as_raw_fd())?;
+            listener.local_addr()
+        }
+    }
+}
+
+/// Body of the forked proxy process. Never returns: the proxy exits once the
+/// sandboxed command (which replaces the parent via `execvp`) goes away.
+fn run_proxy_process(
+    parent_pid: libc::pid_t,
+    socket: &UnixStream,
+    allowlist: &[String],
+    report: Option<File>,
+) -> ! {
+    unsafe {
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent_pid {
+            libc::_exit(0);
+        }
+        // Reports go to `report`, so do not hold on to any of the command's
+        // standard streams.
+        let dev_null = libc::open(c"/dev/null".as_ptr(), libc::O_RDWR);
+        if dev_null >= 0 {
+            libc::dup2(dev_null, libc::STDIN_FILENO);
+            libc::dup2(dev_null, libc::STDOUT_FILENO);
+            libc::dup2(dev_null, libc::STDERR_FILENO);
+            libc::close(dev_null);
+        }
+    }
+
+    // If the parent could not set up the network namespace it closes its end
+    // of the socketpair without sending a listener, and there is nothing to
+    // serve.
+    if let Ok(fd) = recv_fd(socket) {
+        let listener = unsafe { TcpListener::from_raw_fd(fd) };
+        serve(&listener, allowlist, report.map(Arc::new));
+    }
+    unsafe { libc::_exit(0) }
+}
+
+fn serve(listener: &TcpListener, allowlist: &[String], report: Option<Arc<File>>) {
+    let active_clients = Arc::new(AtomicUsize::new(0));
+    for mut client in listener.incoming().flatten() {
+        if active_clients.fetch_add(1, Ordering::SeqCst) >= MAX_CONCURRENT_CLIENTS {
+            active_clients.fetch_sub(1, Ordering::SeqCst);
+            let _ = respond(
+                &mut client,
+                "503 Service Unavailable",
+                "too many concurrent proxy connections",
+            );
+            continue;
+        }
+        let allowlist = allowlist.to_vec();
+        let report = report.clone();
+        let active_clients = Arc::clone(&active_clients);
+        std::thread::spawn(move || {
+            let _ = handle_client(client, &allowlist, report.as_deref());
+            active_clients.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+/// Handles a single proxy client: either a `CONNECT host:port` tunnel or a
+/// plain HTTP request in absolute form (`GET http://host/path`).
+fn handle_client(client: TcpStream, allowlist: &[String], report: Option<&File>) -> io::Result<()> {
+    client.set_read_timeout(Some(REQUEST_HEAD_TIMEOUT))?;
+    let mut reader = BufReader::new(client.try_clone()?);
+    let mut client = client;
+
+    let head = read_request_head(&mut reader)?;
+    let Some((method, target, version)) = parse_request_line(&head[0]) else {
+        return respond(&mut client, "400 Bad Request", "malformed request line");
+    };
+
+    let is_connect = method.eq_ignore_ascii_case("CONNECT");
+    let parsed = if is_connect {
+        split_host_port(target, None).map(|(host, port)| (host, port, None))
+    } else {
+        parse_absolute_http_url(target).map(|(host, port, path)| (host, port, Some(path)))
+    };
+    let Some((host, port, path)) = parsed else {
+        return respond(
+            &mut client,
+            "400 Bad Request",
+            "only CONNECT and absolute http:// requests are supported",
+        );
+    };
+
+    if !is_host_allowed(allowlist, &host, port) {
+        let blocked = BlockedConnection { host, port };
+        if let Some(mut report) = report {
+            // A single short write to a pipe is atomic, so concurrent
+            // clients cannot interleave their reports.
+            let _ = report.write_all(format!("{}\n", blocked.report_line()).as_bytes());
+        }
+        return respond(&mut client, "403 Forbidden", &blocked.report_line());
+    }
+
+    let mut upstream = match TcpStream::connect((host.as_str(), port)) {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            return respond(
+                &mut client,
+                "502 Bad Gateway",
+                &format!("failed to connect to {host}:{port}: {err}"),
+            );
+        }
+    };
+
+    if let Some(path) = path {
+        // Force a fresh proxy connection per request so a kept-alive client
+        // cannot reuse this tunnel to reach a different host.
+        let mut request = format!("{method} {path} {version}\r\n");
+        for header in &head[1..] {
+            let name = header.split(':').next().unwrap_or_default().trim();
+            if [
+                "connection",
+                "proxy-connection",
+                "proxy-authorization",
+                "keep-alive",
+            ]
+            .iter()
+            .any(|hop| name.eq_ignore_ascii_case(hop))
+            {
+                continue;
+            }
+            request.push_str(header);
+            request.push_str("\r\n");
+        }
+        request.push_str("Connection: close\r\n\r\n");
+        upstream.write_all(request.as_bytes())?;
+    } else {
+        client.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
+    }
+
+    // Forward anything the client sent after the request head.
+    upstream.write_all(reader.buffer())?;
+    client.set_read_timeout(None)?;
+    splice(client, upstream)
+}
+
+/// Reads header lines up to (and excluding) the blank line terminating the
+/// request head.
+fn read_request_head(reader: &mut BufReader<TcpStream>) -> io::Result<Vec<String>> {
+    let mut head = Vec::new();
+    let mut total = 0;
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line)?;
+        total += read;
+        if read == 0 || total > MAX_REQUEST_HEAD_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete or oversized request head",
+            ));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if head.is_empty() {
+                continue;
+            }
+            return Ok(head);
+        }
+        head.push(line.to_string());
+    }
+}
+
+fn parse_request_line(line: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    let version = parts.next()?;
+    Some((method, target, version))
+}
+
+/// Splits `http://host[:port]/path` into its host, port, and origin-form path.
+fn parse_absolute_http_url(url: &str) -> Option<(String, u16, String)> {
+    let rest = url
+        .get(.."http://".len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case("http://"))
+        .map(|scheme| &url[scheme.len()..])?;
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let path = if path.starts_with('?') {
+        format!("/{path}")
+    } else {
+        path.to_string()
+    };
+    // Drop any userinfo; it is not part of the destination.
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    let (host, port) = split_host_port(authority, Some(80))?;
+    Some((host, port, path))
+}
+
+/// Splits `host:port` (or `[v6]:port`). `default_port` is used when the port
+/// is omitted; without one, a port is required.
+fn split_host_port(authority: &str, default_port: Option<u16>) -> Option<(String, u16)> {
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        match rest.strip_prefix(':') {
+            Some(port) => (host, Some(port)),
+            None if rest.is_empty() => (host, None),
+            None => return None,
+        }
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port?,
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), port))
+}
+
+fn respond(client: &mut TcpStream, status: &str, message: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{message}\n",
+        message.len() + 1
+    );
+    client.write_all(response.as_bytes())?;
+    client.shutdown(Shutdown::Both)
+}
+
+/// Copies bytes in both directions until each side has closed its half of the
+/// connection.
+fn splice(client: TcpStream, upstream: TcpStream) -> io::Result<()> {
+    let mut client_read = client.try_clone()?;
+    let mut upstream_write = upstream.try_clone()?;
+    let to_upstream = std::thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+
+    let mut upstream_read = upstream;
+    let mut client_write = client;
+    let _ = io::copy(&mut upstream_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = to_upstream.join();
+    Ok(())
+}
+
+/// Marks the loopback interface of the current network namespace as up; a
+/// fresh namespace starts with it down.
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+    if socket < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let socket = unsafe { std::os::fd::OwnedFd::from_raw_fd(socket) };
+
+    let mut request: libc::ifreq = unsafe { std::mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo\0") {
+        *dst = *src as libc::c_char;
+    }
+    check(unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCGIFFLAGS, &mut request) })?;
+    unsafe {
+        request.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    }
+    check(unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS, &request) })
+}
+
+/// Sends `fd` over a Unix socket as `SCM_RIGHTS` ancillary data.
+fn send_fd(socket: &UnixStream, fd: RawFd) -> io::Result<()> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let space = unsafe { libc::CMSG_SPACE(std::mem::size_of::<RawFd>() as u32) } as usize;
+    let mut control = vec![0u8; space];
+
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_iov = &mut iov;
+    message.msg_iovlen = 1;
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = space as _;
+
+    unsafe {
+        let header = libc::CMSG_FIRSTHDR(&message);
+        (*header).cmsg_level = libc::SOL_SOCKET;
+        (*header).cmsg_type = libc::SCM_RIGHTS;
+        (*header).cmsg_len = libc::CMSG_LEN(std::mem::size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(header).cast::<RawFd>(), fd);
+    }
+
+    if unsafe { libc::sendmsg(socket.as_raw_fd(), &message, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Receives a file descriptor sent with [`send_fd`].
+fn recv_fd(socket: &UnixStream) -> io::Result<RawFd> {
+    let mut payload = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+    let space = unsafe { libc::CMSG_SPACE(std::mem::size_of::<RawFd>() as u32) } as usize;
+    let mut control = vec![0u8; space];
+
+    let mut message: libc::msghdr = unsafe { std::mem::zeroed() };
+    message.msg_iov = &mut iov;
+    message.msg_iovlen = 1;
+    message.msg_control = control.as_mut_ptr().cast();
+    message.msg_controllen = space as _;
+
+    if unsafe { libc::recvmsg(socket.as_raw_fd(), &mut message, libc::MSG_CMSG_CLOEXEC) } <= 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let header = unsafe { libc::CMSG_FIRSTHDR(&message) };
+    if header.is_null()
+        || unsafe { (*header).cmsg_level } != libc::SOL_SOCKET
+        || unsafe { (*header).cmsg_type } != libc::SCM_RIGHTS
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected a file descriptor",
+        ));
+    }
+    Ok(unsafe { std::ptr::read_unaligned(libc::CMSG_DATA(header).cast::<RawFd>()) })
+}
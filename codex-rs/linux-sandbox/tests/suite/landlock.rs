@@ -3,11 +3,16 @@ use codex_core::config::types::ShellEnvironmentPolicy;
 use codex_core::error::CodexErr;
 use codex_core::error::SandboxErr;
 use codex_core::exec::ExecParams;
+use codex_core::exec::ExecToolCallOutput;
 use codex_core::exec::SandboxType;
 use codex_core::exec::process_exec_tool_call;
 use codex_core::exec_env::create_env;
 use codex_core::protocol::SandboxPolicy;
+use codex_core::sandboxing::network::BlockedConnection;
 use std::collections::HashMap;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
@@ -33,8 +38,34 @@ fn create_env_from_core_vars() -> HashMap<String, String> {
     create_env(&policy)
 }
 
-#[expect(clippy::print_stdout, clippy::expect_used, clippy::unwrap_used)]
 async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
+    run_cmd_with_deny_read(cmd, writable_roots, &[], timeout_ms).await;
+}
+
+#[expect(clippy::print_stdout)]
+async fn run_cmd_with_deny_read(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    deny_read: &[PathBuf],
+    timeout_ms: u64,
+) {
+    let res = run_cmd_output(cmd, writable_roots, deny_read, timeout_ms).await;
+    if res.exit_code != 0 {
+        println!("stdout:\n{}", res.stdout.text);
+        println!("stderr:\n{}", res.stderr.text);
+        panic!("exit code: {}", res.exit_code);
+    }
+}
+
+/// Runs `cmd` in workspace-write mode and returns its output whether or not
+/// the command succeeded.
+#[expect(clippy::expect_used)]
+async fn run_cmd_output(
+    cmd: &[&str],
+    writable_roots: &[PathBuf],
+    deny_read: &[PathBuf],
+    timeout_ms: u64,
+) -> ExecToolCallOutput {
     let cwd = std::env::current_dir().expect("cwd should exist");
     let sandbox_cwd = cwd.clone();
     let params = ExecParams {
@@ -55,10 +86,12 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         // writing to in the sandbox.
         exclude_tmpdir_env_var: true,
         exclude_slash_tmp: true,
+        deny_read: deny_read.to_vec(),
+        network_allowlist: Vec::new(),
     };
     let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
     let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
-    let res = process_exec_tool_call(
+    let result = process_exec_tool_call(
         params,
         SandboxType::LinuxSeccomp,
         &sandbox_policy,
@@ -66,13 +99,12 @@ async fn run_cmd(cmd: &[&str], writable_roots: &[PathBuf], timeout_ms: u64) {
         &codex_linux_sandbox_exe,
         None,
     )
-    .await
-    .unwrap();
+    .await;
 
-    if res.exit_code != 0 {
-        println!("stdout:\n{}", res.stdout.text);
-        println!("stderr:\n{}", res.stderr.text);
-        panic!("exit code: {}", res.exit_code);
+    match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        _ => panic!("unexpected sandbox result: {result:?}"),
     }
 }
 
@@ -124,6 +156,205 @@ async fn test_writable_root() {
     .await;
 }
 
+#[tokio::test]
+async fn test_writable_root_with_git_dir() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(tmpdir.path().join(".git")).unwrap();
+    std::fs::create_dir(tmpdir.path().join("src")).unwrap();
+    let top_level_path = tmpdir.path().join("test");
+    let nested_path = tmpdir.path().join("src").join("test");
+    run_cmd(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "echo blah > {} && echo blah > {}",
+                top_level_path.to_string_lossy(),
+                nested_path.to_string_lossy()
+            ),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+/// Creating a new top-level file in a root that contains `.git` must succeed.
+/// Without a mount namespace `.git` cannot be kept read-only, so the sandbox
+/// must refuse to run the command rather than narrow write access.
+#[tokio::test]
+async fn test_new_file_in_writable_root_with_git_dir() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(tmpdir.path().join(".git")).unwrap();
+    let new_path = tmpdir.path().join("new-file");
+    let output = run_cmd_output(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {}", new_path.to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        &[],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+
+    if output
+        .stderr
+        .text
+        .contains("cannot keep read-only subpaths")
+    {
+        assert_ne!(output.exit_code, 0, "sandbox ran without protecting .git");
+        assert!(!new_path.exists());
+    } else {
+        assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+        assert!(new_path.exists());
+    }
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_git_dir_under_writable_root_is_read_only() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let git_dir = tmpdir.path().join(".git");
+    std::fs::create_dir(&git_dir).unwrap();
+    std::fs::write(git_dir.join("config"), "").unwrap();
+    run_cmd(
+        &[
+            "bash",
+            "-lc",
+            &format!("echo blah > {}", git_dir.join("config").to_string_lossy()),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_git_hooks_cannot_be_created_under_writable_root() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let hooks_dir = tmpdir.path().join(".git").join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    run_cmd(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "echo blah > {}",
+                hooks_dir.join("pre-commit").to_string_lossy()
+            ),
+        ],
+        &[tmpdir.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[should_panic]
+async fn test_deny_read_blocks_reads() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    std::fs::write(secrets.join("id_rsa"), "secret").unwrap();
+    run_cmd_with_deny_read(
+        &[
+            "bash",
+            "-lc",
+            &format!("cat {}", secrets.join("id_rsa").to_string_lossy()),
+        ],
+        &[],
+        &[secrets],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deny_read_allows_reads_elsewhere() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    let public_path = tmpdir.path().join("public");
+    std::fs::write(&public_path, "public").unwrap();
+    run_cmd_with_deny_read(
+        &[
+            "bash",
+            "-lc",
+            &format!("cat {} && ls /bin", public_path.to_string_lossy()),
+        ],
+        &[],
+        &[secrets],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
+/// With a mount namespace, denied paths are masked and their parent stays
+/// listable. Without one the sandbox announces the Landlock fallback, which
+/// makes the parent unlistable.
+#[tokio::test]
+async fn test_deny_read_keeps_parent_listable() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let secrets = tmpdir.path().join("secrets");
+    std::fs::create_dir(&secrets).unwrap();
+    std::fs::write(secrets.join("id_rsa"), "secret").unwrap();
+    let output = run_cmd_output(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "ls {} && ! cat {}",
+                tmpdir.path().to_string_lossy(),
+                secrets.join("id_rsa").to_string_lossy()
+            ),
+        ],
+        &[],
+        std::slice::from_ref(&secrets),
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+
+    if output
+        .stderr
+        .text
+        .contains("deny_read is enforced without mounts")
+    {
+        assert_ne!(output.exit_code, 0, "stdout: {}", output.stdout.text);
+    } else {
+        assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+        assert!(
+            output.stdout.text.contains("secrets"),
+            "stdout: {}",
+            output.stdout.text
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_deny_read_keeps_nested_writable_root_readable() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let workspace = tmpdir.path().join("workspace");
+    std::fs::create_dir(&workspace).unwrap();
+    let file_path = workspace.join("test");
+    run_cmd_with_deny_read(
+        &[
+            "bash",
+            "-lc",
+            &format!(
+                "echo blah > {path} && cat {path}",
+                path = file_path.to_string_lossy()
+            ),
+        ],
+        std::slice::from_ref(&workspace),
+        &[tmpdir.path().to_path_buf()],
+        LONG_TIMEOUT_MS,
+    )
+    .await;
+}
+
 #[tokio::test]
 #[should_panic(expected = "Sandbox(Timeout")]
 async fn test_timeout() {
@@ -236,3 +467,171 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+/// Runs `cmd` in workspace-write mode with `network_allowlist` and returns its
+/// output whether or not the command succeeded.
+#[expect(clippy::expect_used)]
+async fn run_cmd_with_network_allowlist(
+    cmd: &[&str],
+    network_allowlist: &[&str],
+) -> ExecToolCallOutput {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let sandbox_cwd = cwd.clone();
+    let params = ExecParams {
+        command: cmd.iter().copied().map(str::to_owned).collect(),
+        cwd,
+        timeout_ms: Some(NETWORK_TIMEOUT_MS),
+        env: create_env_from_core_vars(),
+        with_escalated_permissions: None,
+        justification: None,
+        arg0: None,
+    };
+
+    let sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: Vec::new(),
+        network_access: false,
+        exclude_tmpdir_env_var: true,
+        exclude_slash_tmp: true,
+        deny_read: Vec::new(),
+        network_allowlist: network_allowlist
+            .iter()
+            .map(|entry| (*entry).to_string())
+            .collect(),
+    };
+    let sandbox_program = env!("CARGO_BIN_EXE_codex-linux-sandbox");
+    let codex_linux_sandbox_exe = Some(PathBuf::from(sandbox_program));
+    let result = process_exec_tool_call(
+        params,
+        SandboxType::LinuxSeccomp,
+        &sandbox_policy,
+        sandbox_cwd.as_path(),
+        &codex_linux_sandbox_exe,
+        None,
+    )
+    .await;
+
+    match result {
+        Ok(output) => output,
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => *output,
+        _ => panic!("unexpected sandbox result: {result:?}"),
+    }
+}
+
+/// Serves a single canned HTTP response on a loopback port outside the
+/// sandbox and returns that port.
+#[expect(clippy::expect_used)]
+fn spawn_upstream_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind upstream");
+    let port = listener.local_addr().expect("upstream addr").port();
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 19\r\nConnection: close\r\n\r\nhello from upstream",
+            );
+        }
+    });
+    port
+}
+
+/// Shell snippet that sends `request` to the sandbox's filtering proxy and
+/// prints the response.
+fn send_via_proxy(request: &str) -> String {
+    format!(
+        r#"proxy=${{HTTP_PROXY#http://}}
+exec 3<>/dev/tcp/${{proxy%:*}}/${{proxy##*:}}
+printf '{request}' >&3
+cat <&3"#
+    )
+}
+
+#[tokio::test]
+async fn sandbox_allows_allowlisted_host_via_proxy() {
+    let port = spawn_upstream_server();
+    let request = format!("GET http://127.0.0.1:{port}/ HTTP/1.1\\r\\nHost: 127.0.0.1\\r\\n\\r\\n");
+    let output = run_cmd_with_network_allowlist(
+        &["bash", "-c", &send_via_proxy(&request)],
+        &[&format!("127.0.0.1:{port}")],
+    )
+    .await;
+
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert!(
+        output.stdout.text.contains("hello from upstream"),
+        "stdout: {}",
+        output.stdout.text
+    );
+}
+
+#[tokio::test]
+async fn sandbox_reports_blocked_host_via_proxy() {
+    let port = spawn_upstream_server();
+    let request = format!("CONNECT 127.0.0.1:{port} HTTP/1.1\\r\\n\\r\\n");
+    let output = run_cmd_with_network_allowlist(
+        &["bash", "-c", &send_via_proxy(&request)],
+        &["registry.example.com"],
+    )
+    .await;
+
+    assert!(
+        output.stdout.text.contains("403 Forbidden"),
+        "stdout: {}",
+        output.stdout.text
+    );
+    assert_eq!(
+        output.blocked_network_connections,
+        vec![BlockedConnection {
+            host: "127.0.0.1".to_string(),
+            port,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn sandbox_with_allowlist_blocks_direct_connections() {
+    let port = spawn_upstream_server();
+    let output = run_cmd_with_network_allowlist(
+        &[
+            "bash",
+            "-c",
+            &format!("echo hi > /dev/tcp/127.0.0.1/{port}"),
+        ],
+        &[&format!("127.0.0.1:{port}")],
+    )
+    .await;
+
+    assert_ne!(output.exit_code, 0, "direct connection bypassed the proxy");
+}
+
+#[tokio::test]
+async fn sandbox_ignores_blocked_reports_forged_by_command() {
+    let forged = BlockedConnection {
+        host: "forged.example.com".to_string(),
+        port: 443,
+    }
+    .report_line();
+    let output = run_cmd_with_network_allowlist(
+        &["bash", "-c", &format!("echo '{forged}' >&2")],
+        &["registry.example.com"],
+    )
+    .await;
+
+    assert_eq!(output.exit_code, 0, "stderr: {}", output.stderr.text);
+    assert_eq!(output.blocked_network_connections, Vec::new());
+}
+
+#[tokio::test]
+async fn sandbox_with_allowlist_blocks_unix_sockets() {
+    let output = run_cmd_with_network_allowlist(
+        &[
+            "python3",
+            "-c",
+            "import socket; socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)",
+        ],
+        &["registry.example.com"],
+    )
+    .await;
+
+    assert_ne!(output.exit_code, 0, "AF_UNIX socket was created");
+}
@@ -9,16 +9,21 @@ use crate::exec_approval::handle_exec_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
+use crate::session_resources::SessionResources;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
 use codex_core::config::Config as CodexConfig;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ElicitationAction;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_protocol::ConversationId;
@@ -43,6 +48,7 @@ pub async fn run_codex_tool_session(
     outgoing: Arc<OutgoingMessageSender>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    session_resources: Arc<SessionResources>,
 ) {
     let NewConversation {
         conversation_id,
@@ -65,6 +71,14 @@ pub async fn run_codex_tool_session(
         }
     };
 
+    session_resources
+        .register_session(
+            conversation_id,
+            session_configured.rollout_path.clone(),
+            &outgoing,
+        )
+        .await;
+
     let session_configured_event = Event {
         // Use a fake id value for now.
         id: "".to_string(),
@@ -109,6 +123,8 @@ pub async fn run_codex_tool_session(
         outgoing,
         id,
         running_requests_id_to_codex_uuid,
+        conversation_id,
+        session_resources,
     )
     .await;
 }
@@ -120,6 +136,7 @@ pub async fn run_codex_tool_session_reply(
     prompt: String,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
     conversation_id: ConversationId,
+    session_resources: Arc<SessionResources>,
 ) {
     running_requests_id_to_codex_uuid
         .lock()
@@ -145,6 +162,8 @@ pub async fn run_codex_tool_session_reply(
         outgoing,
         request_id,
         running_requests_id_to_codex_uuid,
+        conversation_id,
+        session_resources,
     )
     .await;
 }
@@ -154,6 +173,8 @@ async fn run_codex_tool_session_inner(
     outgoing: Arc<OutgoingMessageSender>,
     request_id: RequestId,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    conversation_id: ConversationId,
+    session_resources: Arc<SessionResources>,
 ) {
     let request_id_str = match &request_id {
         RequestId::String(s) => s.clone(),
@@ -171,6 +192,9 @@ async fn run_codex_tool_session_inner(
                         Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
                     )
                     .await;
+                session_resources
+                    .observe_event(conversation_id, &event.msg, &outgoing)
+                    .await;
 
                 match event.msg {
                     EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
@@ -207,6 +231,45 @@ async fn run_codex_tool_session_inner(
                     EventMsg::Warning(_) => {
                         continue;
                     }
+                    EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                        server_name,
+                        id,
+                        ..
+                    }) => {
+                        // Elicitations from nested MCP servers are not
+                        // forwarded to our own client; decline so the tool
+                        // call does not hang.
+                        if let Err(err) = codex
+                            .submit(Op::ResolveElicitation {
+                                server_name,
+                                request_id: id,
+                                decision: ElicitationAction::Decline,
+                                content: None,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to decline MCP elicitation: {err}");
+                        }
+                        continue;
+                    }
+                    EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+                        server_name,
+                        id,
+                        ..
+                    }) => {
+                        // Same for sampling approvals: deny rather than hang.
+                        if let Err(err) = codex
+                            .submit(Op::McpSamplingApproval {
+                                server_name,
+                                request_id: id,
+                                decision: ReviewDecision::Denied,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to deny MCP sampling request: {err}");
+                        }
+                        continue;
+                    }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
                         call_id,
                         reason,
@@ -271,10 +334,14 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::McpListPromptsResponse(_)
+                    | EventMsg::McpGetPromptResponse(_)
+                    | EventMsg::McpServerUpdated(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
                     | EventMsg::BackgroundEvent(_)
+                    | EventMsg::HookCompleted(_)
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
@@ -0,0 +1,248 @@
+//! Serves the user's custom prompts (`$CODEX_HOME/prompts/*.md`) over MCP.
+//!
+//! Named placeholders such as `$FILE` become required prompt arguments.
+//! Prompts that use `$1`..`$9` or `$ARGUMENTS` take a single optional
+//! `ARGUMENTS` argument that is split like a shell command line.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use codex_protocol::custom_prompts::CustomPrompt;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt;
+use mcp_types::PromptArgument;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+use mcp_types::TextContent;
+use regex_lite::Regex;
+use serde_json::Value;
+
+const POSITIONAL_ARGUMENT: &str = "ARGUMENTS";
+
+static PROMPT_ARG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$[A-Z][A-Z0-9_]*").unwrap_or_else(|_| std::process::abort()));
+
+/// Named placeholders in `content`, without the leading `$`, in order of
+/// first appearance. `$$NAME` is an escaped dollar sign and `$ARGUMENTS` is
+/// positional, so neither counts.
+fn named_placeholders(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    PROMPT_ARG_REGEX
+        .find_iter(content)
+        .filter(|m| !is_escaped(content, m.start()))
+        .map(|m| &content[m.start() + 1..m.end()])
+        .filter(|name| *name != POSITIONAL_ARGUMENT && seen.insert(*name))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_escaped(content: &str, dollar: usize) -> bool {
+    dollar > 0 && content.as_bytes()[dollar - 1] == b'$'
+}
+
+fn has_positional_placeholders(content: &str) -> bool {
+    content.contains("$ARGUMENTS")
+        || content
+            .as_bytes()
+            .windows(2)
+            .any(|pair| pair[0] == b'$' && (b'1'..=b'9').contains(&pair[1]))
+}
+
+pub(crate) fn to_mcp_prompt(prompt: &CustomPrompt) -> Prompt {
+    let mut arguments: Vec<PromptArgument> = named_placeholders(&prompt.content)
+        .into_iter()
+        .map(|name| PromptArgument {
+            description: None,
+            name,
+            required: Some(true),
+            title: None,
+        })
+        .collect();
+    if arguments.is_empty() && has_positional_placeholders(&prompt.content) {
+        arguments.push(PromptArgument {
+            description: Some(prompt.argument_hint.clone().unwrap_or_else(|| {
+                "Positional arguments, quoted like a shell command line.".to_string()
+            })),
+            name: POSITIONAL_ARGUMENT.to_string(),
+            required: Some(false),
+            title: None,
+        });
+    }
+
+    Prompt {
+        arguments: (!arguments.is_empty()).then_some(arguments),
+        description: prompt.description.clone(),
+        name: prompt.name.clone(),
+        title: None,
+    }
+}
+
+/// Expand `prompt` with the `prompts/get` arguments and wrap it as a single
+/// user message.
+pub(crate) fn render_prompt(
+    prompt: &CustomPrompt,
+    arguments: Option<&Value>,
+) -> Result<GetPromptResult, String> {
+    let argument = |name: &str| -> Option<String> {
+        match arguments?.get(name)? {
+            Value::String(value) => Some(value.clone()),
+            Value::Null => None,
+            other => Some(other.to_string()),
+        }
+    };
+
+    let content = &prompt.content;
+    let names = named_placeholders(content);
+    let text = if names.is_empty() {
+        let positional = match argument(POSITIONAL_ARGUMENT) {
+            Some(raw) => shlex::split(&raw)
+                .ok_or_else(|| format!("could not parse {POSITIONAL_ARGUMENT}: {raw}"))?,
+            None => Vec::new(),
+        };
+        expand_positional(content, &positional)
+    } else {
+        let missing: Vec<&str> = names
+            .iter()
+            .filter(|name| argument(name).is_none())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("missing arguments: {}", missing.join(", ")));
+        }
+        PROMPT_ARG_REGEX
+            .replace_all(content, |caps: &regex_lite::Captures<'_>| {
+                let whole = &caps[0];
+                match caps.get(0) {
+                    Some(m) if is_escaped(content, m.start()) => whole.to_string(),
+                    _ => argument(&whole[1..]).unwrap_or_else(|| whole.to_string()),
+                }
+            })
+            .into_owned()
+    };
+
+    Ok(GetPromptResult {
+        description: prompt.description.clone(),
+        messages: vec![PromptMessage {
+            content: ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text,
+                r#type: "text".to_string(),
+            }),
+            role: Role::User,
+        }],
+    })
+}
+
+/// Replace `$1`..`$9` and `$ARGUMENTS`, leaving `$$` untouched.
+fn expand_positional(content: &str, args: &[String]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(offset) = rest.find('$') {
+        out.push_str(&rest[..offset]);
+        let tail = &rest[offset..];
+        let next = tail.as_bytes().get(1).copied();
+        if next == Some(b'$') {
+            out.push_str("$$");
+            rest = &tail[2..];
+        } else if let Some(digit @ b'1'..=b'9') = next {
+            if let Some(value) = args.get(usize::from(digit - b'1')) {
+                out.push_str(value);
+            }
+            rest = &tail[2..];
+        } else if let Some(after) = tail[1..].strip_prefix(POSITIONAL_ARGUMENT) {
+            out.push_str(&args.join(" "));
+            rest = after;
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn prompt(content: &str) -> CustomPrompt {
+        CustomPrompt {
+            name: "review".to_string(),
+            path: PathBuf::from("/tmp/review.md"),
+            content: content.to_string(),
+            description: Some("Review a file".to_string()),
+            argument_hint: None,
+        }
+    }
+
+    fn rendered_text(result: GetPromptResult) -> String {
+        match &result.messages[0].content {
+            ContentBlock::TextContent(text) => text.text.clone(),
+            other => panic!("unexpected content: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn named_placeholders_become_required_arguments() {
+        let mcp_prompt = to_mcp_prompt(&prompt("Review $FILE for $FOCUS; cost $$USD. $FILE"));
+        let arguments: Vec<(String, Option<bool>)> = mcp_prompt
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|arg| (arg.name, arg.required))
+            .collect();
+        assert_eq!(
+            arguments,
+            vec![
+                ("FILE".to_string(), Some(true)),
+                ("FOCUS".to_string(), Some(true)),
+            ]
+        );
+
+        let result = render_prompt(
+            &prompt("Review $FILE for $FOCUS; cost $$USD."),
+            Some(&json!({ "FILE": "src/lib.rs", "FOCUS": "errors" })),
+        )
+        .expect("render");
+        assert_eq!(
+            rendered_text(result),
+            "Review src/lib.rs for errors; cost $$USD."
+        );
+
+        let err = render_prompt(
+            &prompt("Review $FILE for $FOCUS"),
+            Some(&json!({ "FILE": "a" })),
+        )
+        .expect_err("missing FOCUS");
+        assert_eq!(err, "missing arguments: FOCUS");
+    }
+
+    #[test]
+    fn positional_placeholders_use_single_optional_argument() {
+        let content = "First $1, all: $ARGUMENTS";
+        let mcp_prompt = to_mcp_prompt(&prompt(content));
+        let arguments = mcp_prompt.arguments.unwrap_or_default();
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments[0].name, "ARGUMENTS");
+        assert_eq!(arguments[0].required, Some(false));
+
+        let result = render_prompt(
+            &prompt(content),
+            Some(&json!({ "ARGUMENTS": "one \"two words\"" })),
+        )
+        .expect("render");
+        assert_eq!(rendered_text(result), "First one, all: one two words");
+
+        let result = render_prompt(&prompt(content), None).expect("render");
+        assert_eq!(rendered_text(result), "First , all: ");
+    }
+
+    #[test]
+    fn prompt_without_placeholders_has_no_arguments() {
+        assert_eq!(to_mcp_prompt(&prompt("Just do it.")).arguments, None);
+    }
+}
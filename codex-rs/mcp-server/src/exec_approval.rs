@@ -150,6 +150,7 @@ async fn on_exec_approval_response(
         .submit(Op::ExecApproval {
             id: event_id,
             decision: response.decision,
+            persist_rule: None,
         })
         .await
     {
@@ -22,11 +22,13 @@ use tracing_subscriber::EnvFilter;
 
 mod codex_tool_config;
 mod codex_tool_runner;
+mod custom_prompts;
 mod error_code;
 mod exec_approval;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod session_resources;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
@@ -0,0 +1,281 @@
+//! Exposes the state of Codex sessions started through this server as MCP
+//! resources.
+//!
+//! Every session gets three resources, addressed as
+//! `codex://session/<conversation_id>/<kind>`:
+//!
+//! * `rollout` – the session's rollout file (JSON Lines).
+//! * `diff` – the unified diff of the changes made in the latest turn.
+//! * `plan` – the most recent plan published with the `update_plan` tool.
+//!
+//! Clients may `resources/subscribe` to any of these URIs and receive
+//! `notifications/resources/updated` while the session runs.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use codex_core::protocol::EventMsg;
+use codex_protocol::ConversationId;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::ResourceListChangedNotification;
+use mcp_types::ResourceTemplate;
+use mcp_types::ResourceUpdatedNotification;
+use mcp_types::ResourceUpdatedNotificationParams;
+use mcp_types::TextResourceContents;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+const SESSION_URI_PREFIX: &str = "codex://session/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionResourceKind {
+    Rollout,
+    Diff,
+    Plan,
+}
+
+impl SessionResourceKind {
+    const ALL: [Self; 3] = [Self::Rollout, Self::Diff, Self::Plan];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Rollout => "rollout",
+            Self::Diff => "diff",
+            Self::Plan => "plan",
+        }
+    }
+
+    fn from_str(kind: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == kind)
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            Self::Rollout => "application/jsonl",
+            Self::Diff => "text/x-diff",
+            Self::Plan => "application/json",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Rollout => "Recorded events of the session, one JSON object per line.",
+            Self::Diff => "Unified diff of the changes made during the latest turn.",
+            Self::Plan => "Most recent plan published by the agent.",
+        }
+    }
+}
+
+fn session_uri(conversation_id: &ConversationId, kind: SessionResourceKind) -> String {
+    format!("{SESSION_URI_PREFIX}{conversation_id}/{}", kind.as_str())
+}
+
+fn parse_session_uri(uri: &str) -> Option<(ConversationId, SessionResourceKind)> {
+    let rest = uri.strip_prefix(SESSION_URI_PREFIX)?;
+    let (id, kind) = rest.split_once('/')?;
+    let conversation_id = ConversationId::from_string(id).ok()?;
+    Some((conversation_id, SessionResourceKind::from_str(kind)?))
+}
+
+fn text_resource(uri: &str, kind: SessionResourceKind, text: String) -> ReadResourceResult {
+    ReadResourceResult {
+        contents: vec![ReadResourceResultContents::TextResourceContents(
+            TextResourceContents {
+                mime_type: Some(kind.mime_type().to_string()),
+                text,
+                uri: uri.to_string(),
+            },
+        )],
+    }
+}
+
+struct SessionState {
+    rollout_path: PathBuf,
+    turn_diff: Option<String>,
+    plan: Option<UpdatePlanArgs>,
+}
+
+/// Tracks the resources of every running session and the URIs the client
+/// subscribed to.
+#[derive(Default)]
+pub(crate) struct SessionResources {
+    sessions: Mutex<HashMap<ConversationId, SessionState>>,
+    subscriptions: Mutex<HashSet<String>>,
+}
+
+impl SessionResources {
+    /// Start tracking a new session and tell the client the resource list
+    /// changed.
+    pub(crate) async fn register_session(
+        &self,
+        conversation_id: ConversationId,
+        rollout_path: PathBuf,
+        outgoing: &OutgoingMessageSender,
+    ) {
+        self.sessions.lock().await.insert(
+            conversation_id,
+            SessionState {
+                rollout_path,
+                turn_diff: None,
+                plan: None,
+            },
+        );
+        outgoing
+            .send_notification(OutgoingNotification {
+                method: ResourceListChangedNotification::METHOD.to_string(),
+                params: None,
+            })
+            .await;
+    }
+
+    /// Record the parts of `msg` that feed a resource and notify subscribers
+    /// of the resources that changed.
+    pub(crate) async fn observe_event(
+        &self,
+        conversation_id: ConversationId,
+        msg: &EventMsg,
+        outgoing: &OutgoingMessageSender,
+    ) {
+        let kind = {
+            let mut sessions = self.sessions.lock().await;
+            let Some(state) = sessions.get_mut(&conversation_id) else {
+                return;
+            };
+            match msg {
+                EventMsg::TurnDiff(event) => {
+                    state.turn_diff = Some(event.unified_diff.clone());
+                    SessionResourceKind::Diff
+                }
+                EventMsg::PlanUpdate(plan) => {
+                    state.plan = Some(plan.clone());
+                    SessionResourceKind::Plan
+                }
+                // The rollout is flushed at the end of every turn.
+                EventMsg::TaskComplete(_) => SessionResourceKind::Rollout,
+                _ => return,
+            }
+        };
+
+        let uri = session_uri(&conversation_id, kind);
+        if !self.subscriptions.lock().await.contains(&uri) {
+            return;
+        }
+        let params = ResourceUpdatedNotificationParams { uri };
+        outgoing
+            .send_notification(OutgoingNotification {
+                method: ResourceUpdatedNotification::METHOD.to_string(),
+                params: serde_json::to_value(params).ok(),
+            })
+            .await;
+    }
+
+    pub(crate) async fn list(&self) -> Vec<Resource> {
+        let sessions = self.sessions.lock().await;
+        let mut ids: Vec<&ConversationId> = sessions.keys().collect();
+        ids.sort_by_key(ToString::to_string);
+        ids.into_iter()
+            .flat_map(|id| {
+                SessionResourceKind::ALL
+                    .into_iter()
+                    .map(move |kind| Resource {
+                        annotations: None,
+                        description: Some(kind.description().to_string()),
+                        mime_type: Some(kind.mime_type().to_string()),
+                        name: format!("{id}/{}", kind.as_str()),
+                        size: None,
+                        title: None,
+                        uri: session_uri(id, kind),
+                    })
+            })
+            .collect()
+    }
+
+    pub(crate) fn templates() -> Vec<ResourceTemplate> {
+        SessionResourceKind::ALL
+            .into_iter()
+            .map(|kind| ResourceTemplate {
+                annotations: None,
+                description: Some(kind.description().to_string()),
+                mime_type: Some(kind.mime_type().to_string()),
+                name: format!("session-{}", kind.as_str()),
+                title: None,
+                uri_template: format!("{SESSION_URI_PREFIX}{{conversation_id}}/{}", kind.as_str()),
+            })
+            .collect()
+    }
+
+    pub(crate) async fn read(&self, uri: &str) -> Result<ReadResourceResult, String> {
+        let (conversation_id, kind) =
+            parse_session_uri(uri).ok_or_else(|| format!("unknown resource: {uri}"))?;
+
+        let rollout_path = {
+            let sessions = self.sessions.lock().await;
+            let state = sessions
+                .get(&conversation_id)
+                .ok_or_else(|| format!("unknown session: {conversation_id}"))?;
+            match kind {
+                SessionResourceKind::Rollout => state.rollout_path.clone(),
+                SessionResourceKind::Diff => {
+                    let diff = state.turn_diff.clone().unwrap_or_default();
+                    return Ok(text_resource(uri, kind, diff));
+                }
+                SessionResourceKind::Plan => {
+                    let plan = match &state.plan {
+                        Some(plan) => serde_json::to_string(plan).map_err(|err| err.to_string())?,
+                        None => json!({ "plan": [] }).to_string(),
+                    };
+                    return Ok(text_resource(uri, kind, plan));
+                }
+            }
+        };
+
+        let rollout = tokio::fs::read_to_string(&rollout_path)
+            .await
+            .map_err(|err| format!("failed to read {}: {err}", rollout_path.display()))?;
+        Ok(text_resource(uri, kind, rollout))
+    }
+
+    /// Subscribe to updates of `uri`. Only URIs of known sessions are
+    /// accepted.
+    pub(crate) async fn subscribe(&self, uri: String) -> Result<(), String> {
+        let (conversation_id, _) =
+            parse_session_uri(&uri).ok_or_else(|| format!("unknown resource: {uri}"))?;
+        if !self.sessions.lock().await.contains_key(&conversation_id) {
+            return Err(format!("unknown session: {conversation_id}"));
+        }
+        self.subscriptions.lock().await.insert(uri);
+        Ok(())
+    }
+
+    pub(crate) async fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().await.remove(uri);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn session_uris_round_trip() {
+        let conversation_id = ConversationId::new();
+        for kind in SessionResourceKind::ALL {
+            let uri = session_uri(&conversation_id, kind);
+            assert_eq!(parse_session_uri(&uri), Some((conversation_id, kind)));
+        }
+        assert_eq!(parse_session_uri("codex://session/not-an-id/diff"), None);
+        assert_eq!(
+            parse_session_uri(&format!("codex://session/{conversation_id}/unknown")),
+            None
+        );
+    }
+}
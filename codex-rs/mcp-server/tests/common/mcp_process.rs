@@ -15,16 +15,20 @@ use codex_mcp_server::CodexToolCallParam;
 
 use mcp_types::CallToolRequestParams;
 use mcp_types::ClientCapabilities;
+use mcp_types::GetPromptRequestParams;
 use mcp_types::Implementation;
 use mcp_types::InitializeRequestParams;
 use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
 use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ReadResourceRequestParams;
 use mcp_types::RequestId;
+use mcp_types::SubscribeRequestParams;
 use pretty_assertions::assert_eq;
 use serde_json::json;
 use std::process::Command as StdCommand;
@@ -156,6 +160,11 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "prompts": {},
+                        "resources": {
+                            "listChanged": true,
+                            "subscribe": true
+                        },
                         "tools": {
                             "listChanged": true
                         },
@@ -200,6 +209,49 @@ impl McpProcess {
         .await
     }
 
+    pub async fn send_list_resources_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListResourcesRequest::METHOD, None)
+            .await
+    }
+
+    pub async fn send_read_resource_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        let params = ReadResourceRequestParams {
+            uri: uri.to_string(),
+        };
+        self.send_request(
+            mcp_types::ReadResourceRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
+    pub async fn send_subscribe_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        let params = SubscribeRequestParams {
+            uri: uri.to_string(),
+        };
+        self.send_request(
+            mcp_types::SubscribeRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
+    pub async fn send_list_prompts_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListPromptsRequest::METHOD, None)
+            .await
+    }
+
+    pub async fn send_get_prompt_request(
+        &mut self,
+        params: GetPromptRequestParams,
+    ) -> anyhow::Result<i64> {
+        self.send_request(
+            mcp_types::GetPromptRequest::METHOD,
+            Some(serde_json::to_value(params)?),
+        )
+        .await
+    }
+
     async fn send_request(
         &mut self,
         method: &str,
@@ -340,4 +392,60 @@ impl McpProcess {
             }
         }
     }
+
+    pub async fn read_stream_until_error_message(
+        &mut self,
+        request_id: RequestId,
+    ) -> anyhow::Result<JSONRPCError> {
+        eprintln!("in read_stream_until_error_message({request_id:?})");
+
+        loop {
+            let message = self.read_jsonrpc_message().await?;
+            match message {
+                JSONRPCMessage::Notification(_) => {
+                    eprintln!("notification: {message:?}");
+                }
+                JSONRPCMessage::Request(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Request: {message:?}");
+                }
+                JSONRPCMessage::Error(err) => {
+                    if err.id == request_id {
+                        return Ok(err);
+                    }
+                }
+                JSONRPCMessage::Response(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Response: {message:?}");
+                }
+            }
+        }
+    }
+
+    /// Reads messages until a notification with the given `method` arrives.
+    /// Responses and other notifications seen on the way are dropped.
+    pub async fn read_stream_until_notification_message(
+        &mut self,
+        method: &str,
+    ) -> anyhow::Result<JSONRPCNotification> {
+        eprintln!("in read_stream_until_notification_message({method})");
+
+        loop {
+            let message = self.read_jsonrpc_message().await?;
+            match message {
+                JSONRPCMessage::Notification(notification) => {
+                    if notification.method == method {
+                        return Ok(notification);
+                    }
+                }
+                JSONRPCMessage::Request(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Request: {message:?}");
+                }
+                JSONRPCMessage::Error(_) => {
+                    anyhow::bail!("unexpected JSONRPCMessage::Error: {message:?}");
+                }
+                JSONRPCMessage::Response(_) => {
+                    eprintln!("response: {message:?}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,102 @@
+use mcp_test_support::McpProcess;
+use mcp_test_support::to_response;
+use mcp_types::ContentBlock;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::ListPromptsResult;
+use mcp_types::Prompt;
+use mcp_types::PromptArgument;
+use mcp_types::PromptMessage;
+use mcp_types::RequestId;
+use mcp_types::Role;
+use mcp_types::TextContent;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn custom_prompts_are_served_as_mcp_prompts() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let prompts_dir = codex_home.path().join("prompts");
+    std::fs::create_dir(&prompts_dir)?;
+    std::fs::write(
+        prompts_dir.join("review.md"),
+        "---\ndescription: Review one file\n---\nReview $FILE, focusing on $FOCUS.",
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let list_id = mcp.send_list_prompts_request().await?;
+    let list: ListPromptsResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+        )
+        .await??,
+    )?;
+    let required = |name: &str| PromptArgument {
+        description: None,
+        name: name.to_string(),
+        required: Some(true),
+        title: None,
+    };
+    assert_eq!(
+        list,
+        ListPromptsResult {
+            next_cursor: None,
+            prompts: vec![Prompt {
+                arguments: Some(vec![required("FILE"), required("FOCUS")]),
+                description: Some("Review one file".to_string()),
+                name: "review".to_string(),
+                title: None,
+            }],
+        }
+    );
+
+    let get_id = mcp
+        .send_get_prompt_request(GetPromptRequestParams {
+            arguments: Some(json!({ "FILE": "src/lib.rs", "FOCUS": "error handling" })),
+            name: "review".to_string(),
+        })
+        .await?;
+    let prompt: GetPromptResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(get_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(
+        prompt,
+        GetPromptResult {
+            description: Some("Review one file".to_string()),
+            messages: vec![PromptMessage {
+                content: ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: "Review src/lib.rs, focusing on error handling.".to_string(),
+                    r#type: "text".to_string(),
+                }),
+                role: Role::User,
+            }],
+        }
+    );
+
+    let missing_id = mcp
+        .send_get_prompt_request(GetPromptRequestParams {
+            arguments: Some(json!({ "FILE": "src/lib.rs" })),
+            name: "review".to_string(),
+        })
+        .await?;
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_id)),
+    )
+    .await??;
+    assert_eq!(error.error.message, "missing arguments: FOCUS");
+
+    Ok(())
+}
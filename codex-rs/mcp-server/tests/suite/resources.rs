@@ -0,0 +1,183 @@
+use std::path::Path;
+
+use codex_core::protocol::ReviewDecision;
+use codex_mcp_server::CodexToolCallParam;
+use codex_mcp_server::PatchApprovalResponse;
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_apply_patch_sse_response;
+use mcp_test_support::create_final_assistant_message_sse_response;
+use mcp_test_support::create_mock_chat_completions_server;
+use mcp_test_support::to_response;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+use core_test_support::skip_if_no_network;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn session_resources_track_turn_diff_and_rollout() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let cwd = TempDir::new()?;
+    let test_file = cwd.path().join("destination_file.txt");
+    std::fs::write(&test_file, "original content\n")?;
+    let patch_content = format!(
+        "*** Begin Patch\n*** Update File: {}\n-original content\n+modified content\n*** End Patch",
+        test_file.to_string_lossy()
+    );
+
+    let server = create_mock_chat_completions_server(vec![
+        create_apply_patch_sse_response(&patch_content, "call1234")?,
+        create_final_assistant_message_sse_response("Patch has been applied successfully!")?,
+    ])
+    .await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let codex_request_id = mcp
+        .send_codex_tool_call(CodexToolCallParam {
+            cwd: Some(cwd.path().to_string_lossy().to_string()),
+            prompt: "please modify the test file".to_string(),
+            ..Default::default()
+        })
+        .await?;
+
+    // The session is parked on the patch approval, so it is safe to inspect
+    // and subscribe before the diff is produced.
+    let elicitation_request = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_request_message(),
+    )
+    .await??;
+
+    let list_id = mcp.send_list_resources_request().await?;
+    let list: ListResourcesResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+        )
+        .await??,
+    )?;
+    let uris: Vec<&str> = list.resources.iter().map(|r| r.uri.as_str()).collect();
+    assert_eq!(uris.len(), 3, "unexpected resources: {uris:?}");
+    let session_prefix = uris[0]
+        .rsplit_once('/')
+        .map(|(prefix, _)| prefix.to_string())
+        .expect("session uri");
+    assert!(session_prefix.starts_with("codex://session/"));
+    assert_eq!(
+        uris,
+        vec![
+            format!("{session_prefix}/rollout"),
+            format!("{session_prefix}/diff"),
+            format!("{session_prefix}/plan"),
+        ]
+    );
+    let diff_uri = format!("{session_prefix}/diff");
+
+    let subscribe_id = mcp.send_subscribe_request(&diff_uri).await?;
+    let subscribed = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await??;
+    assert_eq!(subscribed.result, json!({}));
+
+    mcp.send_response(
+        elicitation_request.id,
+        serde_json::to_value(PatchApprovalResponse {
+            decision: ReviewDecision::Approved,
+        })?,
+    )
+    .await?;
+
+    let updated = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("notifications/resources/updated"),
+    )
+    .await??;
+    assert_eq!(updated.params, Some(json!({ "uri": diff_uri })));
+
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(codex_request_id)),
+    )
+    .await??;
+
+    let diff = read_text(&mut mcp, &diff_uri).await?;
+    assert!(
+        diff.contains("-original content\n+modified content"),
+        "unexpected diff: {diff}"
+    );
+    let rollout = read_text(&mut mcp, &format!("{session_prefix}/rollout")).await?;
+    assert!(
+        rollout.contains("\"session_meta\""),
+        "unexpected rollout: {rollout}"
+    );
+    let plan = read_text(&mut mcp, &format!("{session_prefix}/plan")).await?;
+    assert_eq!(plan, json!({ "plan": [] }).to_string());
+
+    let missing_id = mcp
+        .send_read_resource_request("codex://session/not-a-session/diff")
+        .await?;
+    let error = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_id)),
+    )
+    .await??;
+    assert_eq!(
+        error.error.message,
+        "unknown resource: codex://session/not-a-session/diff"
+    );
+
+    Ok(())
+}
+
+async fn read_text(mcp: &mut McpProcess, uri: &str) -> anyhow::Result<String> {
+    let request_id = mcp.send_read_resource_request(uri).await?;
+    let result: ReadResourceResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+        )
+        .await??,
+    )?;
+    match result.contents.as_slice() {
+        [ReadResourceResultContents::TextResourceContents(contents)] => {
+            assert_eq!(contents.uri, uri);
+            Ok(contents.text.clone())
+        }
+        other => anyhow::bail!("unexpected contents for {uri}: {other:?}"),
+    }
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "untrusted"
+sandbox_policy = "workspace-write"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
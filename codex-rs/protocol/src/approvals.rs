@@ -61,3 +61,36 @@ pub struct ApplyPatchApprovalRequestEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grant_root: Option<PathBuf>,
 }
+
+/// How a persisted "always allow" rule is compared against a command.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleKind {
+    /// The command's argv must equal `command` exactly.
+    Exact,
+    /// The command's argv must start with `command`.
+    Prefix,
+    /// The command's program (`argv[0]`) must equal `command[0]`.
+    Program,
+}
+
+/// A rule, stored per project, that approves matching commands without
+/// prompting the user.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+pub struct ApprovalRule {
+    pub kind: ApprovalRuleKind,
+    pub command: Vec<String>,
+}
+
+impl ApprovalRule {
+    pub fn matches(&self, command: &[String]) -> bool {
+        if self.command.is_empty() {
+            return false;
+        }
+        match self.kind {
+            ApprovalRuleKind::Exact => command == self.command.as_slice(),
+            ApprovalRuleKind::Prefix => command.starts_with(&self.command),
+            ApprovalRuleKind::Program => command.first() == self.command.first(),
+        }
+    }
+}
@@ -23,6 +23,11 @@ use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use crate::user_input::UserInput;
 use mcp_types::CallToolResult;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::RequestId as McpRequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
 use mcp_types::Tool as McpTool;
@@ -35,6 +40,8 @@ use strum_macros::Display;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalRule;
+pub use crate::approvals::ApprovalRuleKind;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::SandboxCommandAssessment;
 pub use crate::approvals::SandboxRiskLevel;
@@ -143,6 +150,11 @@ pub enum Op {
         id: String,
         /// The user's decision in response to the request.
         decision: ReviewDecision,
+        /// When set alongside an approving decision, the rule is saved for the
+        /// current project so that matching commands are approved without
+        /// prompting in future sessions.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        persist_rule: Option<ApprovalRule>,
     },
 
     /// Approve a code patch
@@ -153,6 +165,32 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Answer an elicitation request previously surfaced via
+    /// `EventMsg::ElicitationRequest`.
+    ResolveElicitation {
+        /// Name of the MCP server that issued the request.
+        server_name: String,
+        /// Id the MCP server assigned to the request.
+        request_id: McpRequestId,
+        /// The user's decision in response to the request.
+        decision: ElicitationAction,
+        /// Values for the requested form; only meaningful when `decision`
+        /// is `Accept`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<Value>,
+    },
+
+    /// Approve (or deny) a sampling request previously surfaced via
+    /// `EventMsg::McpSamplingApprovalRequest`.
+    McpSamplingApproval {
+        /// Name of the MCP server that issued the request.
+        server_name: String,
+        /// Id the MCP server assigned to the request.
+        request_id: McpRequestId,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -172,6 +210,39 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the prompts published by the configured MCP servers.
+    /// Reply is delivered via `EventMsg::McpListPromptsResponse`.
+    ListMcpPrompts,
+
+    /// Render a prompt published by an MCP server with the given arguments.
+    /// Reply is delivered via `EventMsg::McpGetPromptResponse`.
+    GetMcpPrompt {
+        /// Name of the MCP server that publishes the prompt.
+        server: String,
+        /// Name of the prompt as reported by `prompts/list`.
+        name: String,
+        /// Argument values keyed by argument name.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        arguments: Option<Value>,
+    },
+
+    /// Stop an MCP server and start it again, re-reading its
+    /// `[mcp_servers.<server>]` entry from `config.toml`.
+    /// Reply is delivered via `EventMsg::McpServerUpdated`.
+    RestartMcpServer {
+        /// Name of the MCP server as configured in `mcp_servers`.
+        server: String,
+    },
+
+    /// Start or stop an MCP server for the rest of the session. The
+    /// `enabled` setting in `config.toml` is left untouched.
+    /// Reply is delivered via `EventMsg::McpServerUpdated`.
+    SetMcpServerEnabled {
+        /// Name of the MCP server as configured in `mcp_servers`.
+        server: String,
+        enabled: bool,
+    },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -247,9 +318,14 @@ pub enum SandboxPolicy {
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
 
-    /// Read-only access to the entire file-system.
+    /// Read-only access to the entire file-system, minus `deny_read`.
     #[serde(rename = "read-only")]
-    ReadOnly,
+    ReadOnly {
+        /// Paths (and everything beneath them) that must not be readable from
+        /// within the sandbox, e.g. `~/.ssh`.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<PathBuf>,
+    },
 
     /// Same as `ReadOnly` but additionally grants write access to the current
     /// working directory ("workspace").
@@ -275,6 +351,18 @@ pub enum SandboxPolicy {
         /// writable roots on UNIX. Defaults to `false`.
         #[serde(default)]
         exclude_slash_tmp: bool,
+
+        /// Paths (and everything beneath them) that must not be readable from
+        /// within the sandbox. Writable roots nested under a denied path remain
+        /// readable.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny_read: Vec<PathBuf>,
+
+        /// Hosts that remain reachable through the sandbox's filtering proxy
+        /// when `network_access` is `false`. Entries take the form `host`,
+        /// `host:port`, or `*.domain[:port]`; omitting the port allows any port.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        network_allowlist: Vec<String>,
     },
 }
 
@@ -320,7 +408,9 @@ impl FromStr for SandboxPolicy {
 impl SandboxPolicy {
     /// Returns a policy with read-only disk access and no network.
     pub fn new_read_only_policy() -> Self {
-        SandboxPolicy::ReadOnly
+        SandboxPolicy::ReadOnly {
+            deny_read: Vec::new(),
+        }
     }
 
     /// Returns a policy that can read the entire disk, but can only write to
@@ -332,18 +422,29 @@ impl SandboxPolicy {
             network_access: false,
             exclude_tmpdir_env_var: false,
             exclude_slash_tmp: false,
+            deny_read: Vec::new(),
+            network_allowlist: Vec::new(),
         }
     }
 
-    /// Always returns `true`; restricting read access is not supported.
+    /// Returns `false` when `deny_read` hides part of the file-system.
     pub fn has_full_disk_read_access(&self) -> bool {
-        true
+        self.get_deny_read_paths().is_empty()
+    }
+
+    /// Returns the paths that must not be readable from within the sandbox.
+    pub fn get_deny_read_paths(&self) -> &[PathBuf] {
+        match self {
+            SandboxPolicy::DangerFullAccess => &[],
+            SandboxPolicy::ReadOnly { deny_read } => deny_read,
+            SandboxPolicy::WorkspaceWrite { deny_read, .. } => deny_read,
+        }
     }
 
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -351,23 +452,39 @@ impl SandboxPolicy {
     pub fn has_full_network_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly { .. } => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
 
+    /// Hosts that may be reached through the filtering proxy even though
+    /// general network access is disabled. Empty when the policy grants full
+    /// network access, since there is nothing left to filter.
+    pub fn get_network_allowlist(&self) -> &[String] {
+        match self {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: false,
+                network_allowlist,
+                ..
+            } => network_allowlist,
+            _ => &[],
+        }
+    }
+
     /// Returns the list of writable roots (tailored to the current working
     /// directory) together with subpaths that should remain read‑only under
     /// each writable root.
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly { .. } => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
                 exclude_slash_tmp,
                 network_access: _,
+                deny_read: _,
+                network_allowlist: _,
             } => {
                 // Start from explicitly configured writable roots.
                 let mut roots: Vec<PathBuf> = writable_roots.clone();
@@ -481,6 +598,14 @@ pub enum EventMsg {
 
     McpToolCallEnd(McpToolCallEndEvent),
 
+    /// An MCP server asked the user to fill in a form (or confirm an action)
+    /// in the middle of a tool call. Answer with `Op::ResolveElicitation`.
+    ElicitationRequest(ElicitationRequestEvent),
+
+    /// An MCP server wants to run a completion against the session's model.
+    /// Answer with `Op::McpSamplingApproval`.
+    McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent),
+
     WebSearchBegin(WebSearchBeginEvent),
 
     WebSearchEnd(WebSearchEndEvent),
@@ -506,6 +631,9 @@ pub enum EventMsg {
 
     BackgroundEvent(BackgroundEventEvent),
 
+    /// A user-configured lifecycle hook finished running.
+    HookCompleted(HookCompletedEvent),
+
     UndoStarted(UndoStartedEvent),
 
     UndoCompleted(UndoCompletedEvent),
@@ -532,6 +660,16 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// List of prompts published by MCP servers.
+    McpListPromptsResponse(McpListPromptsResponseEvent),
+
+    /// Messages of an MCP prompt rendered in response to `Op::GetMcpPrompt`.
+    McpGetPromptResponse(McpGetPromptResponseEvent),
+
+    /// An MCP server was restarted, reconnected after a crash, enabled or
+    /// disabled.
+    McpServerUpdated(McpServerUpdatedEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -690,7 +828,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema, TS)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq, Eq, JsonSchema, TS)]
 pub struct TokenUsage {
     #[ts(type = "number")]
     pub input_tokens: i64,
@@ -967,6 +1105,43 @@ impl McpToolCallEndEvent {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ElicitationRequestEvent {
+    /// Name of the MCP server that issued the request.
+    pub server_name: String,
+    /// Id the MCP server assigned to the request; echo it back in
+    /// `Op::ResolveElicitation`.
+    pub id: McpRequestId,
+    /// Human-readable message explaining what is being requested.
+    pub message: String,
+    /// Flat JSON schema describing the values the server wants back.
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct McpSamplingApprovalRequestEvent {
+    /// Name of the MCP server that issued the request.
+    pub server_name: String,
+    /// Id the MCP server assigned to the request; echo it back in
+    /// `Op::McpSamplingApproval`.
+    pub id: McpRequestId,
+    /// The messages, system prompt and limits the server wants sampled.
+    pub request: CreateMessageRequestParams,
+}
+
+/// How the user responded to an elicitation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ElicitationAction {
+    /// The user submitted the form; `content` carries the values.
+    Accept,
+    /// The user explicitly refused to provide the requested values.
+    Decline,
+    /// The user dismissed the request without making a choice.
+    Cancel,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct WebSearchBeginEvent {
     pub call_id: String,
@@ -1127,6 +1302,10 @@ pub struct TurnContextItem {
     pub approval_policy: AskForApproval,
     pub sandbox_policy: SandboxPolicy,
     pub model: String,
+    /// Key of the provider serving the turn. Differs from the configured
+    /// provider after a switch to a fallback provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub effort: Option<ReasoningEffortConfig>,
     pub summary: ReasoningSummaryConfig,
@@ -1272,6 +1451,57 @@ pub struct BackgroundEventEvent {
     pub message: String,
 }
 
+/// Point in the agent loop at which a user-configured hook runs.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum HookTrigger {
+    /// Before a tool call runs; the hook may block it or rewrite its arguments.
+    BeforeToolCall,
+    /// After a tool call produced its output.
+    AfterToolCall,
+    /// After the user's input is recorded and before the model is sampled.
+    TurnStart,
+    /// When the model has finished responding to the user's input.
+    TurnEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum HookOutcome {
+    /// The hook let the call or turn proceed.
+    Continued,
+    /// The hook blocked the call or turn.
+    Blocked,
+    /// The hook could not be run, timed out, or produced invalid output. It
+    /// is ignored.
+    Failed,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct HookCompletedEvent {
+    pub trigger: HookTrigger,
+    /// The configured hook command.
+    pub command: Vec<String>,
+    /// Tool call the hook ran for (tool hooks only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub outcome: HookOutcome,
+    /// Why the hook blocked the call or turn, or why it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Whether the hook replaced the tool call's arguments.
+    #[serde(default)]
+    pub rewrote_arguments: bool,
+    /// Message the hook added to the conversation for the model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct DeprecationNoticeEvent {
     /// Concise summary of what is deprecated.
@@ -1350,6 +1580,11 @@ pub struct McpListToolsResponseEvent {
     pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
     /// Authentication status for each configured MCP server.
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
+    /// Unix timestamp (seconds since epoch) when the stored OAuth access token
+    /// of each server expires. Only servers logged in with OAuth are listed.
+    #[serde(default)]
+    #[ts(type = "{ [key in string]?: number }")]
+    pub auth_expirations: std::collections::HashMap<String, i64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
@@ -1380,6 +1615,46 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListMcpPrompts`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpListPromptsResponseEvent {
+    /// Known prompts grouped by server name.
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
+}
+
+/// Response payload for `Op::GetMcpPrompt`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpGetPromptResponseEvent {
+    pub server: String,
+    pub name: String,
+    /// Rendered prompt, or an error message if the server rejected it.
+    pub result: Result<GetPromptResult, String>,
+}
+
+/// What happened to the server reported in an [`McpServerUpdatedEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum McpServerUpdate {
+    /// Restarted in response to `Op::RestartMcpServer`.
+    Restarted,
+    /// Started again automatically after its process exited.
+    Reconnected,
+    /// Started in response to `Op::SetMcpServerEnabled`.
+    Enabled,
+    /// Stopped in response to `Op::SetMcpServerEnabled`.
+    Disabled,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpServerUpdatedEvent {
+    pub server: String,
+    pub update: McpServerUpdate,
+    /// Number of tools the server exposes after the update, or the error
+    /// that left it stopped.
+    pub result: Result<usize, String>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1435,6 +1710,21 @@ pub enum ReviewDecision {
 pub enum FileChange {
     Add {
         content: String,
+        /// Permission bits set on the new file, e.g. `0o755`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+    },
+    /// A binary file; only its size is reported.
+    AddBinary {
+        size: u64,
+        /// Permission bits set on the new file, e.g. `0o755`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+    },
+    AddSymlink {
+        target: PathBuf,
     },
     Delete {
         content: String,
@@ -1442,6 +1732,14 @@ pub enum FileChange {
     Update {
         unified_diff: String,
         move_path: Option<PathBuf>,
+        /// Permission bits set on the updated file, e.g. `0o755`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        mode: Option<u32>,
+    },
+    /// Permission bits of an existing file changed, e.g. `0o755`.
+    SetMode {
+        mode: u32,
     },
 }
 
@@ -1,17 +1,31 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 
 use rmcp::ErrorData as McpError;
 use rmcp::ServiceExt;
 use rmcp::handler::server::ServerHandler;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::CallToolResult;
+use rmcp::model::Content;
+use rmcp::model::CreateElicitationRequestParam;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::GetPromptRequestParam;
+use rmcp::model::GetPromptResult;
 use rmcp::model::JsonObject;
+use rmcp::model::ListPromptsResult;
 use rmcp::model::ListResourceTemplatesResult;
 use rmcp::model::ListResourcesResult;
 use rmcp::model::ListToolsResult;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::Prompt;
+use rmcp::model::PromptArgument;
+use rmcp::model::PromptMessage;
+use rmcp::model::PromptMessageRole;
 use rmcp::model::RawResource;
 use rmcp::model::RawResourceTemplate;
 use rmcp::model::ReadResourceRequestParam;
@@ -19,6 +33,8 @@ use rmcp::model::ReadResourceResult;
 use rmcp::model::Resource;
 use rmcp::model::ResourceContents;
 use rmcp::model::ResourceTemplate;
+use rmcp::model::Role;
+use rmcp::model::SamplingMessage;
 use rmcp::model::ServerCapabilities;
 use rmcp::model::ServerInfo;
 use rmcp::model::Tool;
@@ -28,9 +44,11 @@ use tokio::task;
 
 #[derive(Clone)]
 struct TestToolServer {
-    tools: Arc<Vec<Tool>>,
+    tools: Arc<Mutex<Vec<Tool>>>,
     resources: Arc<Vec<Resource>>,
     resource_templates: Arc<Vec<ResourceTemplate>>,
+    prompts: Arc<Vec<Prompt>>,
+    roots_list_changed: Arc<AtomicUsize>,
 }
 
 const MEMO_URI: &str = "memo://codex/example-note";
@@ -40,13 +58,24 @@ pub fn stdio() -> (tokio::io::Stdin, tokio::io::Stdout) {
 }
 impl TestToolServer {
     fn new() -> Self {
-        let tools = vec![Self::echo_tool(), Self::image_tool()];
+        let tools = vec![
+            Self::echo_tool(),
+            Self::image_tool(),
+            Self::confirm_tool(),
+            Self::summarize_tool(),
+            Self::roots_tool(),
+            Self::unlock_tool(),
+            Self::crash_tool(),
+        ];
         let resources = vec![Self::memo_resource()];
         let resource_templates = vec![Self::memo_template()];
+        let prompts = vec![Self::review_prompt()];
         Self {
-            tools: Arc::new(tools),
+            tools: Arc::new(Mutex::new(tools)),
             resources: Arc::new(resources),
             resource_templates: Arc::new(resource_templates),
+            prompts: Arc::new(prompts),
+            roots_list_changed: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -86,6 +115,88 @@ impl TestToolServer {
         )
     }
 
+    fn confirm_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }))
+        .expect("confirm tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed("confirm"),
+            Cow::Borrowed("Ask the user to confirm via elicitation and return their answer."),
+            Arc::new(schema),
+        )
+    }
+
+    fn summarize_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "text": { "type": "string" }
+            },
+            "required": ["text"],
+            "additionalProperties": false
+        }))
+        .expect("summarize tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed("summarize"),
+            Cow::Borrowed("Ask the client's model to summarize the text via sampling."),
+            Arc::new(schema),
+        )
+    }
+
+    fn roots_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }))
+        .expect("roots tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed("roots"),
+            Cow::Borrowed("Return the client's roots and how often they changed."),
+            Arc::new(schema),
+        )
+    }
+
+    fn unlock_tool() -> Tool {
+        Self::no_args_tool(
+            "unlock",
+            "Add the `secret` tool and send notifications/tools/list_changed.",
+        )
+    }
+
+    fn secret_tool() -> Tool {
+        Self::no_args_tool("secret", "Only listed after `unlock` was called.")
+    }
+
+    fn crash_tool() -> Tool {
+        Self::no_args_tool("crash", "Exit the server process without replying.")
+    }
+
+    fn no_args_tool(name: &'static str, description: &'static str) -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }))
+        .expect("tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed(name),
+            Cow::Borrowed(description),
+            Arc::new(schema),
+        )
+    }
+
     fn memo_resource() -> Resource {
         let raw = RawResource {
             uri: MEMO_URI.to_string(),
@@ -112,6 +223,27 @@ impl TestToolServer {
         ResourceTemplate::new(raw, None)
     }
 
+    fn review_prompt() -> Prompt {
+        Prompt::new(
+            "review",
+            Some("Ask for a code review of a single file."),
+            Some(vec![
+                PromptArgument {
+                    name: "file".to_string(),
+                    title: None,
+                    description: Some("Path of the file to review.".to_string()),
+                    required: Some(true),
+                },
+                PromptArgument {
+                    name: "focus".to_string(),
+                    title: None,
+                    description: Some("Optional area to focus on.".to_string()),
+                    required: Some(false),
+                },
+            ]),
+        )
+    }
+
     fn memo_text() -> &'static str {
         MEMO_CONTENT
     }
@@ -124,6 +256,11 @@ struct EchoArgs {
     env_var: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct SummarizeArgs {
+    text: String,
+}
+
 impl ServerHandler for TestToolServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -131,6 +268,7 @@ impl ServerHandler for TestToolServer {
                 .enable_tools()
                 .enable_tool_list_changed()
                 .enable_resources()
+                .enable_prompts()
                 .build(),
             ..ServerInfo::default()
         }
@@ -141,10 +279,14 @@ impl ServerHandler for TestToolServer {
         _request: Option<PaginatedRequestParam>,
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
-        let tools = self.tools.clone();
+        let tools = self
+            .tools
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
         async move {
             Ok(ListToolsResult {
-                tools: (*tools).clone(),
+                tools,
                 next_cursor: None,
             })
         }
@@ -197,10 +339,53 @@ impl ServerHandler for TestToolServer {
         }
     }
 
+    async fn on_roots_list_changed(
+        &self,
+        _context: rmcp::service::NotificationContext<rmcp::service::RoleServer>,
+    ) {
+        self.roots_list_changed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            prompts: (*self.prompts).clone(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        GetPromptRequestParam { name, arguments }: GetPromptRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        if name != "review" {
+            return Err(McpError::invalid_params(
+                format!("unknown prompt: {name}"),
+                None,
+            ));
+        }
+        let arguments = arguments.unwrap_or_default();
+        let Some(file) = arguments.get("file").and_then(|value| value.as_str()) else {
+            return Err(McpError::invalid_params("missing argument: file", None));
+        };
+        let mut text = format!("Please review {file}.");
+        if let Some(focus) = arguments.get("focus").and_then(|value| value.as_str()) {
+            text.push_str(&format!(" Focus on {focus}."));
+        }
+        Ok(GetPromptResult {
+            description: Some("Code review request".to_string()),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         match request.name.as_ref() {
             "echo" => {
@@ -259,6 +444,134 @@ impl ServerHandler for TestToolServer {
                     data_b64, mime_type,
                 )]))
             }
+            "confirm" => {
+                #[expect(clippy::expect_used)]
+                let requested_schema: JsonObject = serde_json::from_value(json!({
+                    "type": "object",
+                    "properties": {
+                        "approved": { "type": "boolean", "title": "Approve deploy" },
+                        "environment": {
+                            "type": "string",
+                            "enum": ["staging", "production"]
+                        }
+                    },
+                    "required": ["approved"]
+                }))
+                .expect("elicitation schema should deserialize");
+
+                let result = context
+                    .peer
+                    .create_elicitation(CreateElicitationRequestParam {
+                        message: "Deploy the current build?".to_string(),
+                        requested_schema,
+                    })
+                    .await
+                    .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(json!({
+                        "action": result.action,
+                        "content": result.content,
+                    })),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
+            "summarize" => {
+                let args: SummarizeArgs = match request.arguments {
+                    Some(arguments) => serde_json::from_value(serde_json::Value::Object(
+                        arguments.into_iter().collect(),
+                    ))
+                    .map_err(|err| McpError::invalid_params(err.to_string(), None))?,
+                    None => {
+                        return Err(McpError::invalid_params(
+                            "missing arguments for summarize tool",
+                            None,
+                        ));
+                    }
+                };
+
+                let result = context
+                    .peer
+                    .create_message(CreateMessageRequestParam {
+                        messages: vec![SamplingMessage {
+                            role: Role::User,
+                            content: Content::text(format!("Summarize: {}", args.text)),
+                        }],
+                        model_preferences: None,
+                        system_prompt: Some("You write one-line summaries.".to_string()),
+                        include_context: None,
+                        temperature: None,
+                        max_tokens: 64,
+                        stop_sequences: None,
+                        metadata: None,
+                    })
+                    .await;
+
+                let structured_content = match result {
+                    Ok(result) => json!({
+                        "model": result.model,
+                        "stop_reason": result.stop_reason,
+                        "text": result.message.content.as_text().map(|text| text.text.clone()),
+                    }),
+                    Err(err) => json!({ "error": err.to_string() }),
+                };
+
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(structured_content),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
+            "roots" => {
+                let structured_content = match context.peer.list_roots().await {
+                    Ok(result) => json!({
+                        "roots": result
+                            .roots
+                            .iter()
+                            .map(|root| root.uri.clone())
+                            .collect::<Vec<_>>(),
+                        "list_changed": self.roots_list_changed.load(Ordering::SeqCst),
+                    }),
+                    Err(err) => json!({ "error": err.to_string() }),
+                };
+
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(structured_content),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
+            "unlock" => {
+                {
+                    let mut tools = self.tools.lock().unwrap_or_else(PoisonError::into_inner);
+                    if !tools.iter().any(|tool| tool.name == "secret") {
+                        tools.push(Self::secret_tool());
+                    }
+                }
+                context
+                    .peer
+                    .notify_tool_list_changed()
+                    .await
+                    .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(json!({ "unlocked": "secret" })),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
+            "secret" => Ok(CallToolResult {
+                content: Vec::new(),
+                structured_content: Some(json!({ "secret": "open sesame" })),
+                is_error: Some(false),
+                meta: None,
+            }),
+            "crash" => std::process::exit(1),
             other => Err(McpError::invalid_params(
                 format!("unknown tool: {other}"),
                 None,
@@ -15,6 +15,7 @@ use axum::middleware;
 use axum::middleware::Next;
 use axum::response::Response;
 use axum::routing::get;
+use axum::routing::post;
 use rmcp::ErrorData as McpError;
 use rmcp::handler::server::ServerHandler;
 use rmcp::model::CallToolRequestParam;
@@ -279,6 +280,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }),
         )
+        .route("/oauth/token", post(refresh_token))
         .nest_service(
             "/mcp",
             StreamableHttpService::new(
@@ -300,12 +302,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Token endpoint that answers refresh token grants with the access token the
+/// server expects (`MCP_EXPECT_BEARER`).
+async fn refresh_token(body: String) -> Response {
+    let access_token = std::env::var("MCP_EXPECT_BEARER").ok();
+    let (status, payload) = match access_token {
+        Some(access_token) if body.contains("grant_type=refresh_token") => (
+            StatusCode::OK,
+            json!({
+                "access_token": access_token,
+                "token_type": "bearer",
+                "expires_in": 3600,
+                "refresh_token": "refreshed-refresh-token",
+            }),
+        ),
+        _ => (
+            StatusCode::BAD_REQUEST,
+            json!({ "error": "unsupported_grant_type" }),
+        ),
+    };
+    #[expect(clippy::expect_used)]
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(payload.to_string()))
+        .expect("valid token response")
+}
+
 async fn require_bearer(
     State(expected): State<Arc<String>>,
     request: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    if request.uri().path().contains("/.well-known/") {
+    let path = request.uri().path();
+    if path.contains("/.well-known/") || path.starts_with("/oauth/") {
         return Ok(next.run(request).await);
     }
     if request
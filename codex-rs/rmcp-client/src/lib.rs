@@ -3,17 +3,23 @@ mod find_codex_home;
 mod logging_client_handler;
 mod oauth;
 mod perform_oauth_login;
+mod refreshing_auth_client;
 mod rmcp_client;
 mod utils;
 
 pub use auth_status::determine_streamable_http_auth_status;
 pub use auth_status::supports_oauth_login;
 pub use codex_protocol::protocol::McpAuthStatus;
+pub use logging_client_handler::ListRoots;
+pub use logging_client_handler::SendElicitation;
+pub use logging_client_handler::SendSampling;
 pub use oauth::OAuthCredentialsStoreMode;
 pub use oauth::StoredOAuthTokens;
 pub use oauth::WrappedOAuthTokenResponse;
 pub use oauth::delete_oauth_tokens;
 pub(crate) use oauth::load_oauth_tokens;
+pub use oauth::oauth_token_expiry;
 pub use oauth::save_oauth_tokens;
+pub use perform_oauth_login::OAuthLogin;
 pub use perform_oauth_login::perform_oauth_login;
 pub use rmcp_client::RmcpClient;
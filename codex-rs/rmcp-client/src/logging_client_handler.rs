@@ -1,10 +1,26 @@
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use futures::future::BoxFuture;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::ElicitRequestParams;
+use mcp_types::ElicitResult;
+use mcp_types::ListRootsResult;
+use mcp_types::RequestId;
+use mcp_types::Root;
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
 use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParam;
 use rmcp::model::CreateElicitationResult;
-use rmcp::model::ElicitationAction;
+use rmcp::model::CreateMessageRequestMethod;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::CreateMessageResult as RmcpCreateMessageResult;
+use rmcp::model::ListRootsRequestMethod;
+use rmcp::model::ListRootsResult as RmcpListRootsResult;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
 use rmcp::model::ProgressNotificationParam;
@@ -16,32 +32,116 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-#[derive(Debug, Clone)]
+use crate::utils::convert_to_mcp;
+use crate::utils::convert_to_rmcp;
+
+/// Callback invoked when the server sends an `elicitation/create` request.
+/// The returned future resolves once the user has answered.
+pub type SendElicitation = Arc<
+    dyn Fn(RequestId, ElicitRequestParams) -> BoxFuture<'static, anyhow::Result<ElicitResult>>
+        + Send
+        + Sync,
+>;
+
+/// Callback invoked when the server sends a `sampling/createMessage` request.
+/// The returned future resolves with the model's reply, or an error when the
+/// request was rejected.
+pub type SendSampling = Arc<
+    dyn Fn(
+            RequestId,
+            CreateMessageRequestParams,
+        ) -> BoxFuture<'static, anyhow::Result<CreateMessageResult>>
+        + Send
+        + Sync,
+>;
+
+/// Callback invoked when the server sends a `roots/list` request. Returns the
+/// directories the client currently exposes to the server.
+pub type ListRoots = Arc<dyn Fn() -> Vec<Root> + Send + Sync>;
+
+#[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
+    send_elicitation: SendElicitation,
+    send_sampling: Option<SendSampling>,
+    list_roots: Option<ListRoots>,
+    /// Set when the server sends `notifications/tools/list_changed`.
+    tool_list_changed: Arc<AtomicBool>,
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo) -> Self {
-        Self { client_info }
+    pub(crate) fn new(
+        client_info: ClientInfo,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
+        list_roots: Option<ListRoots>,
+        tool_list_changed: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            client_info,
+            send_elicitation,
+            send_sampling,
+            list_roots,
+            tool_list_changed,
+        }
     }
 }
 
 impl ClientHandler for LoggingClientHandler {
-    // TODO (CODEX-3571): support elicitations.
     async fn create_elicitation(
         &self,
         request: CreateElicitationRequestParam,
-        _context: RequestContext<RoleClient>,
+        context: RequestContext<RoleClient>,
     ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
+        info!("MCP server requested elicitation ({})", request.message);
+        let id: RequestId = convert_to_mcp(context.id)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        let params: ElicitRequestParams = convert_to_mcp(request)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let result = (self.send_elicitation)(id, params)
+            .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        convert_to_rmcp(result)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
+    async fn create_message(
+        &self,
+        request: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<RmcpCreateMessageResult, rmcp::ErrorData> {
+        let Some(send_sampling) = &self.send_sampling else {
+            return Err(rmcp::ErrorData::method_not_found::<
+                CreateMessageRequestMethod,
+            >());
+        };
         info!(
-            "MCP server requested elicitation ({}). Elicitations are not supported yet. Declining.",
-            request.message
+            "MCP server requested sampling ({} messages, max_tokens: {})",
+            request.messages.len(),
+            request.max_tokens
         );
-        Ok(CreateElicitationResult {
-            action: ElicitationAction::Decline,
-            content: None,
-        })
+        let id: RequestId = convert_to_mcp(context.id)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        let params: CreateMessageRequestParams = convert_to_mcp(request)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let result = send_sampling(id, params)
+            .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        convert_to_rmcp(result)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<RmcpListRootsResult, rmcp::ErrorData> {
+        let Some(list_roots) = &self.list_roots else {
+            return Err(rmcp::ErrorData::method_not_found::<ListRootsRequestMethod>());
+        };
+        let roots = list_roots();
+        debug!("MCP server requested roots ({} roots)", roots.len());
+        convert_to_rmcp(ListRootsResult { roots })
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
     }
 
     async fn on_cancelled(
@@ -80,6 +180,7 @@ impl ClientHandler for LoggingClientHandler {
 
     async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server tool list changed");
+        self.tool_list_changed.store(true, Ordering::SeqCst);
     }
 
     async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
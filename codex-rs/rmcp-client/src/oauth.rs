@@ -57,6 +57,11 @@ pub struct StoredOAuthTokens {
     pub url: String,
     pub client_id: String,
     pub token_response: WrappedOAuthTokenResponse,
+    /// When the access token expires, in milliseconds since the Unix epoch.
+    /// `expires_in` in `token_response` is relative to when the token was
+    /// issued, so this is what tells a stored token apart from a fresh one.
+    #[serde(default)]
+    pub expires_at: Option<u64>,
 }
 
 /// Determine where Codex should store and read MCP credentials.
@@ -113,6 +118,18 @@ pub(crate) fn has_oauth_tokens(
     Ok(load_oauth_tokens(server_name, url, store_mode)?.is_some())
 }
 
+/// When the stored OAuth access token for `server_name` expires, if tokens are
+/// stored and the expiry is known.
+pub fn oauth_token_expiry(
+    server_name: &str,
+    url: &str,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Result<Option<SystemTime>> {
+    Ok(load_oauth_tokens(server_name, url, store_mode)?
+        .and_then(|tokens| tokens.expires_at)
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis)))
+}
+
 fn load_oauth_tokens_from_keyring_with_fallback_to_file<K: KeyringStore>(
     keyring_store: &K,
     server_name: &str,
@@ -286,14 +303,19 @@ impl OAuthPersistor {
 
         match maybe_credentials {
             Some(credentials) => {
-                let stored = StoredOAuthTokens {
-                    server_name: self.inner.server_name.clone(),
-                    url: self.inner.url.clone(),
-                    client_id,
-                    token_response: WrappedOAuthTokenResponse(credentials.clone()),
-                };
+                let token_response = WrappedOAuthTokenResponse(credentials);
                 let mut last_credentials = self.inner.last_credentials.lock().await;
-                if last_credentials.as_ref() != Some(&stored) {
+                let unchanged = last_credentials.as_ref().is_some_and(|last| {
+                    last.client_id == client_id && last.token_response == token_response
+                });
+                if !unchanged {
+                    let stored = StoredOAuthTokens {
+                        server_name: self.inner.server_name.clone(),
+                        url: self.inner.url.clone(),
+                        client_id,
+                        expires_at: compute_expires_at_millis(&token_response.0),
+                        token_response,
+                    };
                     save_oauth_tokens(&self.inner.server_name, &stored, self.inner.store_mode)?;
                     *last_credentials = Some(stored);
                 }
@@ -378,6 +400,7 @@ fn load_oauth_tokens_from_file(server_name: &str, url: &str) -> Result<Option<St
             url: entry.server_url.clone(),
             client_id: entry.client_id.clone(),
             token_response: WrappedOAuthTokenResponse(token_response),
+            expires_at: entry.expires_at,
         };
 
         return Ok(Some(stored));
@@ -403,7 +426,9 @@ fn save_oauth_tokens_to_file(tokens: &StoredOAuthTokens) -> Result<()> {
         server_url: tokens.url.clone(),
         client_id: tokens.client_id.clone(),
         access_token: token_response.access_token().secret().to_string(),
-        expires_at: compute_expires_at_millis(token_response),
+        expires_at: tokens
+            .expires_at
+            .or_else(|| compute_expires_at_millis(token_response)),
         refresh_token,
         scopes,
     };
@@ -427,7 +452,7 @@ fn delete_oauth_tokens_from_file(key: &str) -> Result<bool> {
     Ok(removed)
 }
 
-fn compute_expires_at_millis(response: &OAuthTokenResponse) -> Option<u64> {
+pub(crate) fn compute_expires_at_millis(response: &OAuthTokenResponse) -> Option<u64> {
     let expires_in = response.expires_in()?;
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -610,6 +635,22 @@ mod tests {
         )?
         .expect("tokens should load from fallback");
         assert_tokens_match_without_expiry(&loaded, &expected);
+        assert_eq!(loaded.expires_at, expected.expires_at);
+        Ok(())
+    }
+
+    #[test]
+    fn stored_tokens_without_expiry_still_deserialize() -> Result<()> {
+        let tokens = sample_tokens();
+        let mut value = serde_json::to_value(&tokens)?;
+        value
+            .as_object_mut()
+            .expect("tokens serialize to an object")
+            .remove("expires_at");
+
+        let loaded: StoredOAuthTokens = serde_json::from_value(value)?;
+        assert_eq!(loaded.expires_at, None);
+        assert_tokens_match_without_expiry(&loaded, &tokens);
         Ok(())
     }
 
@@ -808,6 +849,7 @@ mod tests {
             server_name: "test-server".to_string(),
             url: "https://example.test".to_string(),
             client_id: "client-id".to_string(),
+            expires_at: compute_expires_at_millis(&response),
             token_response: WrappedOAuthTokenResponse(response),
         }
     }
@@ -17,6 +17,7 @@ use urlencoding::decode;
 use crate::OAuthCredentialsStoreMode;
 use crate::StoredOAuthTokens;
 use crate::WrappedOAuthTokenResponse;
+use crate::oauth::compute_expires_at_millis;
 use crate::save_oauth_tokens;
 use crate::utils::apply_default_headers;
 use crate::utils::build_default_headers;
@@ -39,68 +40,137 @@ pub async fn perform_oauth_login(
     env_http_headers: Option<HashMap<String, String>>,
     scopes: &[String],
 ) -> Result<()> {
-    let server = Arc::new(Server::http("127.0.0.1:0").map_err(|err| anyhow!(err))?);
-    let guard = CallbackServerGuard {
-        server: Arc::clone(&server),
-    };
-
-    let redirect_uri = match server.server_addr() {
-        tiny_http::ListenAddr::IP(std::net::SocketAddr::V4(addr)) => {
-            format!("http://{}:{}/callback", addr.ip(), addr.port())
-        }
-        tiny_http::ListenAddr::IP(std::net::SocketAddr::V6(addr)) => {
-            format!("http://[{}]:{}/callback", addr.ip(), addr.port())
-        }
-        #[cfg(not(target_os = "windows"))]
-        _ => return Err(anyhow!("unable to determine callback address")),
-    };
+    let login = OAuthLogin::start(
+        server_name,
+        server_url,
+        store_mode,
+        http_headers,
+        env_http_headers,
+        scopes,
+    )
+    .await?;
+    let auth_url = login.authorization_url();
 
-    let (tx, rx) = oneshot::channel();
-    spawn_callback_server(server, tx);
+    println!("Authorize `{server_name}` by opening this URL in your browser:\n{auth_url}\n");
 
-    let default_headers = build_default_headers(http_headers, env_http_headers)?;
-    let http_client = apply_default_headers(ClientBuilder::new(), &default_headers).build()?;
+    if webbrowser::open(auth_url).is_err() {
+        println!("(Browser launch failed; please copy the URL above manually.)");
+    }
 
-    let mut oauth_state = OAuthState::new(server_url, Some(http_client)).await?;
-    let scope_refs: Vec<&str> = scopes.iter().map(String::as_str).collect();
-    oauth_state
-        .start_authorization(&scope_refs, &redirect_uri, Some("Codex"))
-        .await?;
-    let auth_url = oauth_state.get_authorization_url().await?;
+    login.finish().await
+}
 
-    println!("Authorize `{server_name}` by opening this URL in your browser:\n{auth_url}\n");
+/// An OAuth login waiting for the user to authorize Codex in their browser.
+///
+/// [`OAuthLogin::start`] registers the client and listens for the redirect on
+/// a local port; callers show [`OAuthLogin::authorization_url`] to the user
+/// and then await [`OAuthLogin::finish`], which stores the tokens.
+pub struct OAuthLogin {
+    server_name: String,
+    server_url: String,
+    store_mode: OAuthCredentialsStoreMode,
+    oauth_state: OAuthState,
+    auth_url: String,
+    callback: oneshot::Receiver<(String, String)>,
+    _guard: CallbackServerGuard,
+}
 
-    if webbrowser::open(&auth_url).is_err() {
-        println!("(Browser launch failed; please copy the URL above manually.)");
+impl OAuthLogin {
+    pub async fn start(
+        server_name: &str,
+        server_url: &str,
+        store_mode: OAuthCredentialsStoreMode,
+        http_headers: Option<HashMap<String, String>>,
+        env_http_headers: Option<HashMap<String, String>>,
+        scopes: &[String],
+    ) -> Result<Self> {
+        let server = Arc::new(Server::http("127.0.0.1:0").map_err(|err| anyhow!(err))?);
+        let guard = CallbackServerGuard {
+            server: Arc::clone(&server),
+        };
+
+        let redirect_uri = match server.server_addr() {
+            tiny_http::ListenAddr::IP(std::net::SocketAddr::V4(addr)) => {
+                format!("http://{}:{}/callback", addr.ip(), addr.port())
+            }
+            tiny_http::ListenAddr::IP(std::net::SocketAddr::V6(addr)) => {
+                format!("http://[{}]:{}/callback", addr.ip(), addr.port())
+            }
+            #[cfg(not(target_os = "windows"))]
+            _ => return Err(anyhow!("unable to determine callback address")),
+        };
+
+        let (tx, rx) = oneshot::channel();
+        spawn_callback_server(server, tx);
+
+        let default_headers = build_default_headers(http_headers, env_http_headers)?;
+        let http_client = apply_default_headers(ClientBuilder::new(), &default_headers).build()?;
+
+        let mut oauth_state = OAuthState::new(server_url, Some(http_client)).await?;
+        let scope_refs: Vec<&str> = scopes.iter().map(String::as_str).collect();
+        oauth_state
+            .start_authorization(&scope_refs, &redirect_uri, Some("Codex"))
+            .await?;
+        let auth_url = oauth_state.get_authorization_url().await?;
+
+        Ok(Self {
+            server_name: server_name.to_string(),
+            server_url: server_url.to_string(),
+            store_mode,
+            oauth_state,
+            auth_url,
+            callback: rx,
+            _guard: guard,
+        })
     }
 
-    let (code, csrf_state) = timeout(Duration::from_secs(300), rx)
-        .await
-        .context("timed out waiting for OAuth callback")?
-        .context("OAuth callback was cancelled")?;
-
-    oauth_state
-        .handle_callback(&code, &csrf_state)
-        .await
-        .context("failed to handle OAuth callback")?;
-
-    let (client_id, credentials_opt) = oauth_state
-        .get_credentials()
-        .await
-        .context("failed to retrieve OAuth credentials")?;
-    let credentials =
-        credentials_opt.ok_or_else(|| anyhow!("OAuth provider did not return credentials"))?;
-
-    let stored = StoredOAuthTokens {
-        server_name: server_name.to_string(),
-        url: server_url.to_string(),
-        client_id,
-        token_response: WrappedOAuthTokenResponse(credentials),
-    };
-    save_oauth_tokens(server_name, &stored, store_mode)?;
-
-    drop(guard);
-    Ok(())
+    /// URL the user must open to authorize the login.
+    pub fn authorization_url(&self) -> &str {
+        &self.auth_url
+    }
+
+    /// Wait for the browser redirect, exchange the code for tokens and store
+    /// them.
+    pub async fn finish(self) -> Result<()> {
+        let Self {
+            server_name,
+            server_url,
+            store_mode,
+            mut oauth_state,
+            callback,
+            _guard: guard,
+            ..
+        } = self;
+
+        let (code, csrf_state) = timeout(Duration::from_secs(300), callback)
+            .await
+            .context("timed out waiting for OAuth callback")?
+            .context("OAuth callback was cancelled")?;
+
+        oauth_state
+            .handle_callback(&code, &csrf_state)
+            .await
+            .context("failed to handle OAuth callback")?;
+
+        let (client_id, credentials_opt) = oauth_state
+            .get_credentials()
+            .await
+            .context("failed to retrieve OAuth credentials")?;
+        let credentials =
+            credentials_opt.ok_or_else(|| anyhow!("OAuth provider did not return credentials"))?;
+
+        let stored = StoredOAuthTokens {
+            server_name: server_name.clone(),
+            url: server_url,
+            client_id,
+            expires_at: compute_expires_at_millis(&credentials),
+            token_response: WrappedOAuthTokenResponse(credentials),
+        };
+        save_oauth_tokens(&server_name, &stored, store_mode)?;
+
+        drop(guard);
+        Ok(())
+    }
 }
 
 fn spawn_callback_server(server: Arc<Server>, tx: oneshot::Sender<(String, String)>) {
@@ -0,0 +1,207 @@
+//! Keeps OAuth access tokens of streamable HTTP servers fresh.
+//!
+//! rmcp's [`AuthClient`] only refreshes a token when it knows when the token
+//! expires, which it never does for credentials restored from storage, and it
+//! turns a `401 Unauthorized` into a hard error. [`RefreshingAuthClient`]
+//! refreshes shortly before the stored expiry and, when the server still
+//! rejects the token, refreshes once and retries the request.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use futures::stream::BoxStream;
+use oauth2::TokenResponse;
+use reqwest::StatusCode;
+use rmcp::model::ClientJsonRpcMessage;
+use rmcp::transport::auth::AuthClient;
+use rmcp::transport::auth::AuthError;
+use rmcp::transport::streamable_http_client::SseError;
+use rmcp::transport::streamable_http_client::StreamableHttpClient;
+use rmcp::transport::streamable_http_client::StreamableHttpError;
+use rmcp::transport::streamable_http_client::StreamableHttpPostResponse;
+use sse_stream::Sse;
+use tokio::sync::Mutex;
+use tracing::info;
+use tracing::warn;
+
+/// Refresh this long before the access token is known to expire.
+const EXPIRY_SKEW: Duration = Duration::from_secs(30);
+
+type HttpError = StreamableHttpError<reqwest::Error>;
+
+#[derive(Clone)]
+pub(crate) struct RefreshingAuthClient {
+    inner: AuthClient<reqwest::Client>,
+    server_name: Arc<str>,
+    /// When the current access token expires, if known. The lock also
+    /// serializes refreshes so concurrent requests don't race each other.
+    expires_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RefreshingAuthClient {
+    /// `expires_at_millis` is the stored expiry of the initial access token,
+    /// in milliseconds since the Unix epoch.
+    pub(crate) fn new(
+        inner: AuthClient<reqwest::Client>,
+        server_name: &str,
+        expires_at_millis: Option<u64>,
+    ) -> Self {
+        Self {
+            inner,
+            server_name: Arc::from(server_name),
+            expires_at: Arc::new(Mutex::new(
+                expires_at_millis.and_then(instant_from_unix_millis),
+            )),
+        }
+    }
+
+    /// The access token to send, refreshed first if it is about to expire.
+    async fn access_token(&self) -> Result<String, AuthError> {
+        let mut expires_at = self.expires_at.lock().await;
+        if expires_at.is_some_and(|at| at <= Instant::now() + EXPIRY_SKEW) {
+            *expires_at = match self.refresh().await {
+                Ok(next) => next,
+                Err(error) => {
+                    // Try the current token anyway; a rejection is handled by
+                    // `refresh_after_rejection`.
+                    warn!(
+                        "failed to refresh OAuth token for MCP server `{}`: {error}",
+                        self.server_name
+                    );
+                    None
+                }
+            };
+        }
+        drop(expires_at);
+        self.inner.get_access_token().await
+    }
+
+    /// Refresh after the server rejected `rejected_token`, unless a concurrent
+    /// request already replaced it.
+    async fn refresh_after_rejection(&self, rejected_token: &str) -> Result<(), AuthError> {
+        let mut expires_at = self.expires_at.lock().await;
+        if self.inner.get_access_token().await? == rejected_token {
+            *expires_at = self.refresh().await?;
+        }
+        Ok(())
+    }
+
+    /// Exchange the refresh token for a new access token and return when the
+    /// new token expires. Callers must hold the `expires_at` lock.
+    async fn refresh(&self) -> Result<Option<Instant>, AuthError> {
+        info!(
+            "refreshing OAuth access token for MCP server `{}`",
+            self.server_name
+        );
+        let response = self.inner.auth_manager.lock().await.refresh_token().await?;
+        Ok(response
+            .expires_in()
+            .and_then(|expires_in| Instant::now().checked_add(expires_in)))
+    }
+
+    /// Run `send` with a fresh access token and retry it once with a refreshed
+    /// token if the server answers `401 Unauthorized`.
+    async fn send_with_refresh<T, F, Fut>(&self, send: F) -> Result<T, HttpError>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T, HttpError>>,
+    {
+        let token = self.access_token().await?;
+        match send(token.clone()).await {
+            Err(error) if is_unauthorized(&error) => {
+                self.refresh_after_rejection(&token).await?;
+                send(self.inner.get_access_token().await?).await
+            }
+            result => result,
+        }
+    }
+}
+
+impl StreamableHttpClient for RefreshingAuthClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_token: Option<String>,
+    ) -> Result<StreamableHttpPostResponse, HttpError> {
+        if auth_token.is_some() {
+            return self
+                .inner
+                .post_message(uri, message, session_id, auth_token)
+                .await;
+        }
+        self.send_with_refresh(|token| {
+            self.inner.post_message(
+                uri.clone(),
+                message.clone(),
+                session_id.clone(),
+                Some(token),
+            )
+        })
+        .await
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_token: Option<String>,
+    ) -> Result<(), HttpError> {
+        if auth_token.is_some() {
+            return self.inner.delete_session(uri, session_id, auth_token).await;
+        }
+        self.send_with_refresh(|token| {
+            self.inner
+                .delete_session(uri.clone(), session_id.clone(), Some(token))
+        })
+        .await
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_token: Option<String>,
+    ) -> Result<BoxStream<'static, Result<Sse, SseError>>, HttpError> {
+        if auth_token.is_some() {
+            return self
+                .inner
+                .get_stream(uri, session_id, last_event_id, auth_token)
+                .await;
+        }
+        self.send_with_refresh(|token| {
+            self.inner.get_stream(
+                uri.clone(),
+                session_id.clone(),
+                last_event_id.clone(),
+                Some(token),
+            )
+        })
+        .await
+    }
+}
+
+fn is_unauthorized(error: &HttpError) -> bool {
+    match error {
+        StreamableHttpError::AuthRequired(_) => true,
+        StreamableHttpError::Client(error) => error.status() == Some(StatusCode::UNAUTHORIZED),
+        _ => false,
+    }
+}
+
+fn instant_from_unix_millis(millis: u64) -> Option<Instant> {
+    let now_millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let remaining = u64::try_from(u128::from(millis).saturating_sub(now_millis)).ok()?;
+    Instant::now().checked_add(Duration::from_millis(remaining))
+}
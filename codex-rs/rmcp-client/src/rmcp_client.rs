@@ -4,6 +4,8 @@ use std::io;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -11,8 +13,12 @@ use anyhow::anyhow;
 use futures::FutureExt;
 use mcp_types::CallToolRequestParams;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializeResult;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -23,6 +29,7 @@ use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use reqwest::header::HeaderMap;
 use rmcp::model::CallToolRequestParam;
+use rmcp::model::GetPromptRequestParam;
 use rmcp::model::InitializeRequestParam;
 use rmcp::model::PaginatedRequestParam;
 use rmcp::model::ReadResourceRequestParam;
@@ -43,10 +50,14 @@ use tracing::info;
 use tracing::warn;
 
 use crate::load_oauth_tokens;
+use crate::logging_client_handler::ListRoots;
 use crate::logging_client_handler::LoggingClientHandler;
+use crate::logging_client_handler::SendElicitation;
+use crate::logging_client_handler::SendSampling;
 use crate::oauth::OAuthCredentialsStoreMode;
 use crate::oauth::OAuthPersistor;
 use crate::oauth::StoredOAuthTokens;
+use crate::refreshing_auth_client::RefreshingAuthClient;
 use crate::utils::apply_default_headers;
 use crate::utils::build_default_headers;
 use crate::utils::convert_call_tool_result;
@@ -61,7 +72,7 @@ enum PendingTransport {
         transport: StreamableHttpClientTransport<reqwest::Client>,
     },
     StreamableHttpWithOAuth {
-        transport: StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
+        transport: StreamableHttpClientTransport<RefreshingAuthClient>,
         oauth_persistor: OAuthPersistor,
     },
 }
@@ -80,6 +91,7 @@ enum ClientState {
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
     state: Mutex<ClientState>,
+    tool_list_changed: Arc<AtomicBool>,
 }
 
 impl RmcpClient {
@@ -129,6 +141,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(PendingTransport::ChildProcess(transport)),
             }),
+            tool_list_changed: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -183,6 +196,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
             }),
+            tool_list_changed: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -192,9 +206,18 @@ impl RmcpClient {
         &self,
         params: InitializeRequestParams,
         timeout: Option<Duration>,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
+        list_roots: Option<ListRoots>,
     ) -> Result<InitializeResult> {
         let rmcp_params: InitializeRequestParam = convert_to_rmcp(params.clone())?;
-        let client_handler = LoggingClientHandler::new(rmcp_params);
+        let client_handler = LoggingClientHandler::new(
+            rmcp_params,
+            send_elicitation,
+            send_sampling,
+            list_roots,
+            Arc::clone(&self.tool_list_changed),
+        );
 
         let (transport, oauth_persistor) = {
             let mut guard = self.state.lock().await;
@@ -319,6 +342,37 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        let service = self.service().await?;
+        let rmcp_params = params
+            .map(convert_to_rmcp::<_, PaginatedRequestParam>)
+            .transpose()?;
+
+        let fut = service.list_prompts(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/list").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    pub async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        let service = self.service().await?;
+        let rmcp_params: GetPromptRequestParam = convert_to_rmcp(params)?;
+        let fut = service.get_prompt(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/get").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
     pub async fn call_tool(
         &self,
         name: String,
@@ -335,6 +389,29 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    /// Tell the server that the result of `roots/list` has changed.
+    pub async fn notify_roots_list_changed(&self) -> Result<()> {
+        let service = self.service().await?;
+        service.notify_roots_list_changed().await?;
+        Ok(())
+    }
+
+    /// Returns `true` if the server sent `notifications/tools/list_changed`
+    /// since the last call, and clears the flag.
+    pub fn take_tool_list_changed(&self) -> bool {
+        self.tool_list_changed.swap(false, Ordering::SeqCst)
+    }
+
+    /// Whether the connection to an initialized server was lost, e.g. because
+    /// the server process exited.
+    pub async fn is_closed(&self) -> bool {
+        let guard = self.state.lock().await;
+        match &*guard {
+            ClientState::Ready { service, .. } => service.is_transport_closed(),
+            ClientState::Connecting { .. } => false,
+        }
+    }
+
     async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
         let guard = self.state.lock().await;
         match &*guard {
@@ -372,7 +449,7 @@ async fn create_oauth_transport_and_runtime(
     credentials_store: OAuthCredentialsStoreMode,
     default_headers: HeaderMap,
 ) -> Result<(
-    StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
+    StreamableHttpClientTransport<RefreshingAuthClient>,
     OAuthPersistor,
 )> {
     let http_client =
@@ -396,6 +473,8 @@ async fn create_oauth_transport_and_runtime(
 
     let auth_client = AuthClient::new(http_client, manager);
     let auth_manager = auth_client.auth_manager.clone();
+    let auth_client =
+        RefreshingAuthClient::new(auth_client, server_name, initial_tokens.expires_at);
 
     let transport = StreamableHttpClientTransport::with_client(
         auth_client,
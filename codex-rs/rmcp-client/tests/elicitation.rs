@@ -0,0 +1,104 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::ElicitRequestParams;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp elicitation test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn elicitation_requests_are_forwarded_to_callback() -> anyhow::Result<()> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+
+    let seen: Arc<Mutex<Vec<ElicitRequestParams>>> = Arc::new(Mutex::new(Vec::new()));
+    let send_elicitation: SendElicitation = {
+        let seen = Arc::clone(&seen);
+        Arc::new(move |_id, params| {
+            if let Ok(mut seen) = seen.lock() {
+                seen.push(params);
+            }
+            async {
+                Ok(ElicitResult {
+                    action: "accept".to_string(),
+                    content: Some(json!({ "approved": true, "environment": "staging" })),
+                })
+            }
+            .boxed()
+        })
+    };
+
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            send_elicitation,
+            None,
+            None,
+        )
+        .await?;
+
+    let result = client
+        .call_tool("confirm".to_string(), None, Some(Duration::from_secs(5)))
+        .await?;
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "action": "accept",
+            "content": { "approved": true, "environment": "staging" },
+        }))
+    );
+
+    let seen = seen.lock().map_err(|_| anyhow::anyhow!("lock poisoned"))?;
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].message, "Deploy the current build?");
+    assert_eq!(seen[0].requested_schema.r#type, "object");
+    assert_eq!(
+        seen[0].requested_schema.required,
+        Some(vec!["approved".to_string()])
+    );
+
+    Ok(())
+}
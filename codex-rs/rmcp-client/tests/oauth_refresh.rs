@@ -0,0 +1,158 @@
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::oauth_token_expiry;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use serde_json::Value;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+const SERVER_NAME: &str = "docs";
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp OAuth refresh test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn decline_elicitations() -> SendElicitation {
+    Arc::new(|_, _| {
+        async {
+            Ok(ElicitResult {
+                action: "decline".to_string(),
+                content: None,
+            })
+        }
+        .boxed()
+    })
+}
+
+fn unix_millis_in(duration: Duration) -> anyhow::Result<u64> {
+    let at = SystemTime::now()
+        .checked_add(duration)
+        .ok_or_else(|| anyhow::anyhow!("failed to compute expiry time"))?;
+    Ok(u64::try_from(at.duration_since(UNIX_EPOCH)?.as_millis())?)
+}
+
+fn write_fallback_tokens(home: &Path, server_url: &str, access_token: &str) -> anyhow::Result<()> {
+    let store = json!({
+        "stub": {
+            "server_name": SERVER_NAME,
+            "server_url": server_url,
+            "client_id": "test-client-id",
+            "access_token": access_token,
+            "expires_at": unix_millis_in(Duration::from_secs(3600))?,
+            "refresh_token": "initial-refresh-token",
+            "scopes": ["profile"],
+        }
+    });
+    std::fs::write(home.join(".credentials.json"), serde_json::to_vec(&store)?)?;
+    Ok(())
+}
+
+async fn wait_for_server(address: &str) -> anyhow::Result<()> {
+    for _ in 0..100 {
+        if TcpStream::connect(address).await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    Err(anyhow::anyhow!(
+        "streamable HTTP server at {address} did not start"
+    ))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rejected_oauth_token_is_refreshed_and_persisted() -> anyhow::Result<()> {
+    let server_bin = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_streamable_http_server")
+        .run()?
+        .path()
+        .to_path_buf();
+
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let bind_addr = listener.local_addr()?.to_string();
+    drop(listener);
+    let server_url = format!("http://{bind_addr}/mcp");
+
+    let _server = Command::new(server_bin)
+        .kill_on_drop(true)
+        .env("MCP_STREAMABLE_HTTP_BIND_ADDR", &bind_addr)
+        .env("MCP_EXPECT_BEARER", "fresh-access-token")
+        .spawn()?;
+    wait_for_server(&bind_addr).await?;
+
+    // The stored token has not expired yet, but the server no longer accepts
+    // it, so the client has to fall back to the refresh token.
+    let codex_home = tempfile::tempdir()?;
+    // SAFETY: this is the only test in this binary that touches the process
+    // environment.
+    unsafe {
+        std::env::set_var("CODEX_HOME", codex_home.path());
+    }
+    write_fallback_tokens(codex_home.path(), &server_url, "stale-access-token")?;
+
+    let client = RmcpClient::new_streamable_http_client(
+        SERVER_NAME,
+        &server_url,
+        None,
+        None,
+        None,
+        OAuthCredentialsStoreMode::File,
+    )
+    .await?;
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(10)),
+            decline_elicitations(),
+            None,
+            None,
+        )
+        .await?;
+    let tools = client
+        .list_tools(None, Some(Duration::from_secs(10)))
+        .await?;
+    assert!(tools.tools.iter().any(|tool| tool.name == "echo"));
+
+    let stored: Value =
+        serde_json::from_slice(&std::fs::read(codex_home.path().join(".credentials.json"))?)?;
+    let entry = stored
+        .as_object()
+        .and_then(|entries| entries.values().next())
+        .ok_or_else(|| anyhow::anyhow!("no stored credentials: {stored}"))?;
+    assert_eq!(entry["access_token"], json!("fresh-access-token"));
+    assert_eq!(entry["refresh_token"], json!("refreshed-refresh-token"));
+
+    let expiry = oauth_token_expiry(SERVER_NAME, &server_url, OAuthCredentialsStoreMode::File)?
+        .ok_or_else(|| anyhow::anyhow!("refreshed token should have an expiry"))?;
+    assert!(expiry > SystemTime::now() + Duration::from_secs(3000));
+
+    Ok(())
+}
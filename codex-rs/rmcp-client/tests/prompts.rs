@@ -0,0 +1,150 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::ContentBlock;
+use mcp_types::ElicitResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use mcp_types::ListPromptsResult;
+use mcp_types::Prompt;
+use mcp_types::PromptArgument;
+use mcp_types::PromptMessage;
+use mcp_types::Role;
+use mcp_types::TextContent;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp prompt test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn decline_elicitations() -> SendElicitation {
+    Arc::new(|_, _| {
+        async {
+            Ok(ElicitResult {
+                action: "decline".to_string(),
+                content: None,
+            })
+        }
+        .boxed()
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_can_list_and_get_prompts() -> anyhow::Result<()> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            None,
+            None,
+        )
+        .await?;
+
+    let list = client
+        .list_prompts(None, Some(Duration::from_secs(5)))
+        .await?;
+    assert_eq!(
+        list,
+        ListPromptsResult {
+            next_cursor: None,
+            prompts: vec![Prompt {
+                arguments: Some(vec![
+                    PromptArgument {
+                        description: Some("Path of the file to review.".to_string()),
+                        name: "file".to_string(),
+                        required: Some(true),
+                        title: None,
+                    },
+                    PromptArgument {
+                        description: Some("Optional area to focus on.".to_string()),
+                        name: "focus".to_string(),
+                        required: Some(false),
+                        title: None,
+                    },
+                ]),
+                description: Some("Ask for a code review of a single file.".to_string()),
+                name: "review".to_string(),
+                title: None,
+            }],
+        }
+    );
+
+    let prompt = client
+        .get_prompt(
+            GetPromptRequestParams {
+                arguments: Some(json!({ "file": "src/lib.rs", "focus": "error handling" })),
+                name: "review".to_string(),
+            },
+            Some(Duration::from_secs(5)),
+        )
+        .await?;
+    assert_eq!(
+        prompt,
+        GetPromptResult {
+            description: Some("Code review request".to_string()),
+            messages: vec![PromptMessage {
+                content: ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: "Please review src/lib.rs. Focus on error handling.".to_string(),
+                    r#type: "text".to_string(),
+                }),
+                role: Role::User,
+            }],
+        }
+    );
+
+    let missing = client
+        .get_prompt(
+            GetPromptRequestParams {
+                arguments: None,
+                name: "review".to_string(),
+            },
+            Some(Duration::from_secs(5)),
+        )
+        .await;
+    assert!(missing.is_err(), "expected missing argument to be rejected");
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use codex_rmcp_client::ListRoots;
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use mcp_types::Root;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp roots test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn decline_elicitations() -> SendElicitation {
+    Arc::new(|_, _| {
+        async {
+            Ok(ElicitResult {
+                action: "decline".to_string(),
+                content: None,
+            })
+        }
+        .boxed()
+    })
+}
+
+async fn call_roots_tool(client: &RmcpClient) -> anyhow::Result<Value> {
+    let result = client
+        .call_tool("roots".to_string(), None, Some(Duration::from_secs(5)))
+        .await?;
+    result
+        .structured_content
+        .ok_or_else(|| anyhow::anyhow!("roots tool returned no structured content"))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_answers_roots_list_and_notifies_changes() -> anyhow::Result<()> {
+    let roots = Arc::new(Mutex::new(vec![Root {
+        name: Some("project".to_string()),
+        uri: "file:///work/project".to_string(),
+    }]));
+    let list_roots: ListRoots = {
+        let roots = Arc::clone(&roots);
+        Arc::new(move || roots.lock().unwrap_or_else(PoisonError::into_inner).clone())
+    };
+
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            None,
+            Some(list_roots),
+        )
+        .await?;
+
+    assert_eq!(
+        call_roots_tool(&client).await?,
+        json!({ "roots": ["file:///work/project"], "list_changed": 0 })
+    );
+
+    roots
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Root {
+            name: None,
+            uri: "file:///work/shared".to_string(),
+        });
+    client.notify_roots_list_changed().await?;
+
+    // The server handles notifications and requests concurrently, so give the
+    // notification a moment to land before asserting on the counter.
+    let mut structured = call_roots_tool(&client).await?;
+    for _ in 0..20 {
+        if structured["list_changed"] == json!(1) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        structured = call_roots_tool(&client).await?;
+    }
+    assert_eq!(
+        structured,
+        json!({
+            "roots": ["file:///work/project", "file:///work/shared"],
+            "list_changed": 1,
+        })
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_without_roots_rejects_roots_list() -> anyhow::Result<()> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            None,
+            None,
+        )
+        .await?;
+
+    let structured = call_roots_tool(&client).await?;
+    assert!(
+        structured.get("error").is_some(),
+        "expected roots/list to fail without a handler: {structured:?}"
+    );
+
+    Ok(())
+}
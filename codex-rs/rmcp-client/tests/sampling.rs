@@ -0,0 +1,178 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::SendSampling;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use mcp_types::Role;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params(sampling: Option<serde_json::Value>) -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp sampling test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn decline_elicitations() -> SendElicitation {
+    Arc::new(|_, _| {
+        async {
+            Ok(ElicitResult {
+                action: "decline".to_string(),
+                content: None,
+            })
+        }
+        .boxed()
+    })
+}
+
+async fn start_client() -> anyhow::Result<RmcpClient> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+    Ok(client)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn sampling_requests_are_forwarded_to_callback() -> anyhow::Result<()> {
+    let client = start_client().await?;
+
+    let seen: Arc<Mutex<Vec<CreateMessageRequestParams>>> = Arc::new(Mutex::new(Vec::new()));
+    let send_sampling: SendSampling = {
+        let seen = Arc::clone(&seen);
+        Arc::new(move |_id, params| {
+            if let Ok(mut seen) = seen.lock() {
+                seen.push(params);
+            }
+            async {
+                Ok(CreateMessageResult {
+                    content: CreateMessageResultContent::TextContent(TextContent {
+                        annotations: None,
+                        text: "A short summary.".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                    model: "test-model".to_string(),
+                    role: Role::Assistant,
+                    stop_reason: Some("endTurn".to_string()),
+                })
+            }
+            .boxed()
+        })
+    };
+
+    client
+        .initialize(
+            init_params(Some(json!({}))),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            Some(send_sampling),
+            None,
+        )
+        .await?;
+
+    let result = client
+        .call_tool(
+            "summarize".to_string(),
+            Some(json!({ "text": "a long document" })),
+            Some(Duration::from_secs(5)),
+        )
+        .await?;
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "model": "test-model",
+            "stop_reason": "endTurn",
+            "text": "A short summary.",
+        }))
+    );
+
+    let seen = seen.lock().map_err(|_| anyhow::anyhow!("lock poisoned"))?;
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].max_tokens, 64);
+    assert_eq!(
+        seen[0].system_prompt.as_deref(),
+        Some("You write one-line summaries.")
+    );
+    let SamplingMessageContent::TextContent(text) = &seen[0].messages[0].content else {
+        panic!(
+            "expected text content, got {:?}",
+            seen[0].messages[0].content
+        );
+    };
+    assert_eq!(text.text, "Summarize: a long document");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn sampling_is_rejected_without_a_callback() -> anyhow::Result<()> {
+    let client = start_client().await?;
+    client
+        .initialize(
+            init_params(None),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            None,
+            None,
+        )
+        .await?;
+
+    let result = client
+        .call_tool(
+            "summarize".to_string(),
+            Some(json!({ "text": "a long document" })),
+            Some(Duration::from_secs(5)),
+        )
+        .await?;
+    let error = result
+        .structured_content
+        .as_ref()
+        .and_then(|content| content.get("error"))
+        .and_then(serde_json::Value::as_str);
+    assert!(
+        error.is_some_and(|error| error.contains("sampling/createMessage")),
+        "expected a method-not-found error, got {:?}",
+        result.structured_content
+    );
+
+    Ok(())
+}
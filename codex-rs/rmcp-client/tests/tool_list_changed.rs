@@ -0,0 +1,131 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use escargot::CargoBuild;
+use futures::FutureExt;
+use mcp_types::ClientCapabilities;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use serde_json::json;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp tool list test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+fn decline_elicitations() -> SendElicitation {
+    Arc::new(|_, _| {
+        async {
+            Ok(ElicitResult {
+                action: "decline".to_string(),
+                content: None,
+            })
+        }
+        .boxed()
+    })
+}
+
+async fn start_client() -> anyhow::Result<RmcpClient> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            decline_elicitations(),
+            None,
+            None,
+        )
+        .await?;
+    Ok(client)
+}
+
+async fn tool_names(client: &RmcpClient) -> anyhow::Result<Vec<String>> {
+    let tools = client
+        .list_tools(None, Some(Duration::from_secs(5)))
+        .await?
+        .tools;
+    Ok(tools.into_iter().map(|tool| tool.name).collect())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_records_tool_list_changed_notification() -> anyhow::Result<()> {
+    let client = start_client().await?;
+
+    assert!(!tool_names(&client).await?.contains(&"secret".to_string()));
+    assert!(!client.take_tool_list_changed());
+
+    client
+        .call_tool("unlock".to_string(), None, Some(Duration::from_secs(5)))
+        .await?;
+
+    // The notification is handled concurrently with the tool call response.
+    let mut changed = client.take_tool_list_changed();
+    for _ in 0..20 {
+        if changed {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        changed = client.take_tool_list_changed();
+    }
+    assert!(changed, "expected notifications/tools/list_changed");
+    assert!(!client.take_tool_list_changed(), "flag should be cleared");
+    assert!(tool_names(&client).await?.contains(&"secret".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_reports_closed_after_server_exits() -> anyhow::Result<()> {
+    let client = start_client().await?;
+    assert!(!client.is_closed().await);
+
+    let result = client
+        .call_tool("crash".to_string(), None, Some(Duration::from_secs(5)))
+        .await;
+    assert!(result.is_err(), "crashing tool should not return a result");
+
+    let mut closed = client.is_closed().await;
+    for _ in 0..20 {
+        if closed {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        closed = client.is_closed().await;
+    }
+    assert!(closed, "expected the connection to be closed");
+
+    Ok(())
+}
@@ -16,6 +16,10 @@ use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::approval_rules::describe_rule;
+use codex_core::approval_rules::suggested_rules;
+use codex_core::protocol::ApprovalRule;
+use codex_core::protocol::ApprovalRuleKind;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
@@ -25,6 +29,10 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::RequestId as McpRequestId;
+use mcp_types::Role;
+use mcp_types::SamplingMessageContent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -48,6 +56,11 @@ pub(crate) enum ApprovalRequest {
         cwd: PathBuf,
         changes: HashMap<PathBuf, FileChange>,
     },
+    McpSampling {
+        server_name: String,
+        request_id: McpRequestId,
+        request: CreateMessageRequestParams,
+    },
 }
 
 /// Modal overlay asking the user to approve or deny one or more requests.
@@ -97,14 +110,18 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
+            ApprovalVariant::Exec { command, .. } => (
+                exec_options(command),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
                 patch_options(),
                 "Would you like to make the following edits?".to_string(),
             ),
+            ApprovalVariant::McpSampling { server_name, .. } => (
+                sampling_options(),
+                format!("Allow MCP server {server_name} to use the model for this request?"),
+            ),
         };
 
         let header = Box::new(ColumnRenderable::with([
@@ -151,11 +168,20 @@ impl ApprovalOverlay {
         if let Some(variant) = self.current_variant.as_ref() {
             match (&variant, option.decision) {
                 (ApprovalVariant::Exec { id, command }, decision) => {
-                    self.handle_exec_decision(id, command, decision);
+                    self.handle_exec_decision(id, command, decision, option.persist_rule.clone());
                 }
                 (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
                     self.handle_patch_decision(id, decision);
                 }
+                (
+                    ApprovalVariant::McpSampling {
+                        server_name,
+                        request_id,
+                    },
+                    decision,
+                ) => {
+                    self.handle_sampling_decision(server_name, request_id, decision);
+                }
             }
         }
 
@@ -163,12 +189,22 @@ impl ApprovalOverlay {
         self.advance_queue();
     }
 
-    fn handle_exec_decision(&self, id: &str, command: &[String], decision: ReviewDecision) {
-        let cell = history_cell::new_approval_decision_cell(command.to_vec(), decision);
+    fn handle_exec_decision(
+        &self,
+        id: &str,
+        command: &[String],
+        decision: ReviewDecision,
+        persist_rule: Option<ApprovalRule>,
+    ) {
+        let cell = match persist_rule.as_ref() {
+            Some(rule) => history_cell::new_approval_rule_decision_cell(command.to_vec(), rule),
+            None => history_cell::new_approval_decision_cell(command.to_vec(), decision),
+        };
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
         self.app_event_tx.send(AppEvent::CodexOp(Op::ExecApproval {
             id: id.to_string(),
             decision,
+            persist_rule,
         }));
     }
 
@@ -179,6 +215,20 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_sampling_decision(
+        &self,
+        server_name: &str,
+        request_id: &McpRequestId,
+        decision: ReviewDecision,
+    ) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::McpSamplingApproval {
+                server_name: server_name.to_string(),
+                request_id: request_id.clone(),
+                decision,
+            }));
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -239,11 +289,17 @@ impl BottomPaneView for ApprovalOverlay {
         {
             match &variant {
                 ApprovalVariant::Exec { id, command } => {
-                    self.handle_exec_decision(id, command, ReviewDecision::Abort);
+                    self.handle_exec_decision(id, command, ReviewDecision::Abort, None);
                 }
                 ApprovalVariant::ApplyPatch { id, .. } => {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
+                ApprovalVariant::McpSampling {
+                    server_name,
+                    request_id,
+                } => {
+                    self.handle_sampling_decision(server_name, request_id, ReviewDecision::Denied);
+                }
             }
         }
         self.queue.clear();
@@ -336,10 +392,61 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
+            ApprovalRequest::McpSampling {
+                server_name,
+                request_id,
+                request,
+            } => Self {
+                header: Box::new(
+                    Paragraph::new(sampling_request_lines(&request)).wrap(Wrap { trim: false }),
+                ),
+                variant: ApprovalVariant::McpSampling {
+                    server_name,
+                    request_id,
+                },
+            },
         }
     }
 }
 
+/// Describe a sampling request: the system prompt, the token limit and each
+/// message the server wants sent to the model.
+pub(crate) fn sampling_request_lines(request: &CreateMessageRequestParams) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Some(system_prompt) = request
+        .system_prompt
+        .as_ref()
+        .filter(|prompt| !prompt.is_empty())
+    {
+        lines.push(Line::from(vec![
+            "System prompt: ".into(),
+            system_prompt.clone().italic(),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        "Max tokens: ".into(),
+        request.max_tokens.to_string().into(),
+    ]));
+    lines.push(Line::from(""));
+    for message in &request.messages {
+        let role = match message.role {
+            Role::User => "user: ".cyan(),
+            Role::Assistant => "assistant: ".magenta(),
+        };
+        let content = match &message.content {
+            SamplingMessageContent::TextContent(text) => text.text.clone().into(),
+            SamplingMessageContent::ImageContent(image) => {
+                format!("[image: {}]", image.mime_type).dim()
+            }
+            SamplingMessageContent::AudioContent(audio) => {
+                format!("[audio: {}]", audio.mime_type).dim()
+            }
+        };
+        lines.push(Line::from(vec![role, content]));
+    }
+    lines
+}
+
 fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
     let level_span = match risk.risk_level {
         SandboxRiskLevel::Low => "LOW".green().bold(),
@@ -364,14 +471,25 @@ fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
 
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+    },
+    ApplyPatch {
+        id: String,
+    },
+    McpSampling {
+        server_name: String,
+        request_id: McpRequestId,
+    },
 }
 
 #[derive(Clone)]
 struct ApprovalOption {
     label: String,
     decision: ReviewDecision,
+    /// Rule to save for the project when this option is chosen.
+    persist_rule: Option<ApprovalRule>,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
 }
@@ -384,40 +502,108 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+fn exec_options(command: &[String]) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
+            persist_rule: None,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
             decision: ReviewDecision::ApprovedForSession,
+            persist_rule: None,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+    ];
+    options.extend(project_rule_options(command));
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ReviewDecision::Abort,
+        persist_rule: None,
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
+}
+
+/// "Always allow" options that save a rule for the current project: the
+/// argv prefix (or the exact command when there is no useful prefix), plus
+/// the whole program when that is broader.
+fn project_rule_options(command: &[String]) -> Vec<ApprovalOption> {
+    let suggestions = suggested_rules(command);
+    let narrow = suggestions
+        .iter()
+        .find(|rule| rule.kind == ApprovalRuleKind::Prefix)
+        .or_else(|| suggestions.first());
+    let program = suggestions
+        .iter()
+        .find(|rule| rule.kind == ApprovalRuleKind::Program);
+
+    let mut options = Vec::new();
+    if let Some(rule) = narrow {
+        options.push(ApprovalOption {
+            label: format!("Yes, always allow {} in this project", describe_rule(rule)),
+            decision: ReviewDecision::ApprovedForSession,
+            persist_rule: Some(rule.clone()),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        });
+    }
+    if let Some(rule) = program {
+        options.push(ApprovalOption {
+            label: format!("Yes, always allow {} in this project", describe_rule(rule)),
+            decision: ReviewDecision::ApprovedForSession,
+            persist_rule: Some(rule.clone()),
+            display_shortcut: None,
+            additional_shortcuts: Vec::new(),
+        });
+    }
+    options
+}
+
+fn patch_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, proceed".to_string(),
+            decision: ReviewDecision::Approved,
+            persist_rule: None,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ReviewDecision::Abort,
+            persist_rule: None,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
+fn sampling_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
-            label: "Yes, proceed".to_string(),
+            label: "Yes, send this request".to_string(),
             decision: ReviewDecision::Approved,
+            persist_rule: None,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            label: "Yes, and don't ask again for this server".to_string(),
+            decision: ReviewDecision::ApprovedForSession,
+            persist_rule: None,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: "No, deny the request".to_string(),
+            decision: ReviewDecision::Denied,
+            persist_rule: None,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
@@ -500,6 +686,89 @@ mod tests {
         );
     }
 
+    fn make_sampling_request() -> ApprovalRequest {
+        ApprovalRequest::McpSampling {
+            server_name: "notes".to_string(),
+            request_id: McpRequestId::Integer(4),
+            request: serde_json::from_value(serde_json::json!({
+                "messages": [
+                    { "role": "user", "content": { "type": "text", "text": "Summarize: release notes" } }
+                ],
+                "systemPrompt": "You write one-line summaries.",
+                "maxTokens": 64
+            }))
+            .expect("sampling request"),
+        }
+    }
+
+    #[test]
+    fn sampling_header_shows_messages_and_limits() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::new(make_sampling_request(), tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        for expected in [
+            "Allow MCP server notes to use the model",
+            "System prompt: You write one-line summaries.",
+            "Max tokens: 64",
+            "user: Summarize: release notes",
+        ] {
+            assert!(
+                rendered.iter().any(|line| line.contains(expected)),
+                "expected {expected:?} in {rendered:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn sampling_shortcuts_send_sampling_decisions() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_sampling_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::McpSamplingApproval {
+                server_name,
+                request_id,
+                decision: d,
+            }) = ev
+            {
+                assert_eq!(server_name, "notes");
+                assert_eq!(request_id, McpRequestId::Integer(4));
+                decision = Some(d);
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
+    }
+
+    #[test]
+    fn sampling_ctrl_c_denies_request() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_sampling_request(), tx);
+        assert_eq!(CancellationEvent::Handled, view.on_ctrl_c());
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::McpSamplingApproval { decision: d, .. }) = ev {
+                decision = Some(d);
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::Denied));
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -549,4 +818,46 @@ mod tests {
         }
         assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
     }
+
+    #[test]
+    fn project_rule_shortcut_persists_rule() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::Exec {
+            id: "test".to_string(),
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "cargo test -p codex-core".to_string(),
+            ],
+            reason: None,
+            risk: None,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+
+        let mut persisted = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval {
+                decision,
+                persist_rule,
+                ..
+            }) = ev
+            {
+                persisted = Some((decision, persist_rule));
+                break;
+            }
+        }
+        assert_eq!(
+            persisted,
+            Some((
+                ReviewDecision::ApprovedForSession,
+                Some(ApprovalRule {
+                    kind: ApprovalRuleKind::Prefix,
+                    command: vec!["cargo".to_string(), "test".to_string()],
+                })
+            ))
+        );
+    }
 }
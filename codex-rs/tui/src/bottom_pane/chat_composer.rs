@@ -29,8 +29,11 @@ use super::footer::toggle_shortcut_mode;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use crate::bottom_pane::paste_burst::FlushResult;
+use crate::bottom_pane::prompt_args::McpPromptCommand;
+use crate::bottom_pane::prompt_args::McpPromptInvocation;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
+use crate::bottom_pane::prompt_args::parse_mcp_prompt_invocation;
 use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
 use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
@@ -69,6 +72,10 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command that takes arguments, e.g. `/mcp restart <server>`.
+    CommandWithArgs(SlashCommand, String),
+    /// An MCP prompt to fetch from its server and then submit.
+    McpPrompt(McpPromptInvocation),
     None,
 }
 
@@ -109,6 +116,7 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
@@ -152,6 +160,7 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            mcp_prompts: Vec::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -475,6 +484,13 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(prompt) = popup.mcp_prompt(idx) {
+                                let (text, cursor) = prompt.command_with_arg_placeholders();
+                                self.textarea.set_text(&text);
+                                cursor_target = Some(cursor);
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -528,6 +544,25 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(prompt) = popup.mcp_prompt(idx) {
+                                // Prompts without arguments can be fetched right away;
+                                // otherwise let the user fill in the placeholders first.
+                                if prompt.argument_names().is_empty() {
+                                    let invocation = McpPromptInvocation {
+                                        server: prompt.server.clone(),
+                                        name: prompt.prompt.name.clone(),
+                                        arguments: HashMap::new(),
+                                    };
+                                    self.textarea.set_text("");
+                                    return (InputResult::McpPrompt(invocation), true);
+                                }
+                                let (text, cursor) = prompt.command_with_arg_placeholders();
+                                self.textarea.set_text(&text);
+                                self.textarea.set_cursor(cursor);
+                            }
+                            return (InputResult::None, true);
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -997,6 +1032,17 @@ impl ChatComposer {
                 // If there is neither text nor attachments, suppress submission entirely.
                 let has_attachments = !self.attached_images.is_empty();
                 text = text.trim().to_string();
+                if !input_starts_with_space
+                    && let Some((name, rest)) = parse_slash_name(&text)
+                    && !rest.is_empty()
+                    && let Some((_, cmd)) = built_in_slash_commands()
+                        .into_iter()
+                        .find(|(command_name, _)| *command_name == name)
+                    && cmd.takes_args()
+                {
+                    self.history.record_local_submission(&text);
+                    return (InputResult::CommandWithArgs(cmd, rest.to_string()), true);
+                }
                 if let Some((name, _rest)) = parse_slash_name(&text) {
                     let treat_as_plain_text = input_starts_with_space || name.contains('/');
                     if !treat_as_plain_text {
@@ -1012,7 +1058,11 @@ impl ChatComposer {
                                     .any(|prompt| prompt.name == prompt_name)
                             })
                             .unwrap_or(false);
-                        if !is_builtin && !is_known_prompt {
+                        let is_mcp_prompt = self
+                            .mcp_prompts
+                            .iter()
+                            .any(|prompt| prompt.command_name() == name);
+                        if !is_builtin && !is_known_prompt && !is_mcp_prompt {
                             let message = format!(
                                 r#"Unrecognized command '/{name}'. Type "/" for a list of supported commands."#
                             );
@@ -1040,6 +1090,21 @@ impl ChatComposer {
                 if let Some(expanded) = expanded_prompt {
                     text = expanded;
                 }
+                match parse_mcp_prompt_invocation(&text, &self.mcp_prompts) {
+                    Ok(Some(invocation)) => {
+                        self.history.record_local_submission(&text);
+                        return (InputResult::McpPrompt(invocation), true);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                            history_cell::new_error_event(err.user_message()),
+                        )));
+                        self.textarea.set_text(&original_input);
+                        self.textarea.set_cursor(original_input.len());
+                        return (InputResult::None, true);
+                    }
+                }
                 if text.is_empty() && !has_attachments {
                     return (InputResult::None, true);
                 }
@@ -1454,6 +1519,7 @@ impl ChatComposer {
             _ => {
                 if is_editing_slash_command_name {
                     let mut command_popup = CommandPopup::new(self.custom_prompts.clone());
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1468,6 +1534,13 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<McpPromptCommand>) {
+        self.mcp_prompts = prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(prompts);
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self) {
@@ -2353,7 +2426,7 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -2407,6 +2480,12 @@ mod tests {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
             InputResult::None => panic!("expected Command result for '/init'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected a bare command, got /{} {args}", cmd.command())
+            }
+            InputResult::McpPrompt(invocation) => {
+                panic!("expected Command result for '/init', got {invocation:?}")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
     }
@@ -2480,10 +2559,44 @@ mod tests {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
             InputResult::None => panic!("expected Command result for '/diff'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected a bare command, got /{} {args}", cmd.command())
+            }
+            InputResult::McpPrompt(invocation) => {
+                panic!("expected Command result for '/diff', got {invocation:?}")
+            }
         }
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn slash_command_with_args_is_dispatched_with_args() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.textarea.set_text("/mcp restart docs");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(
+            result,
+            InputResult::CommandWithArgs(SlashCommand::Mcp, "restart docs".to_string())
+        );
+        assert!(composer.textarea.is_empty());
+
+        // Commands without arguments keep treating trailing text as a message.
+        composer.textarea.set_text("/diff please");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(result, InputResult::Submitted("/diff please".to_string()));
+    }
+
     #[test]
     fn slash_mention_dispatches_command_and_inserts_at() {
         use crossterm::event::KeyCode;
@@ -2513,6 +2626,12 @@ mod tests {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
             InputResult::None => panic!("expected Command result for '/mention'"),
+            InputResult::CommandWithArgs(cmd, args) => {
+                panic!("expected a bare command, got /{} {args}", cmd.command())
+            }
+            InputResult::McpPrompt(invocation) => {
+                panic!("expected Command result for '/mention', got {invocation:?}")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
         composer.insert_str("@");
@@ -2997,6 +3116,74 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    fn mcp_review_prompt() -> McpPromptCommand {
+        McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: mcp_types::Prompt {
+                arguments: Some(vec![mcp_types::PromptArgument {
+                    description: None,
+                    name: "file".to_string(),
+                    required: Some(true),
+                    title: None,
+                }]),
+                description: None,
+                name: "review".to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_submission_returns_invocation() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_mcp_prompts(vec![mcp_review_prompt()]);
+
+        composer.textarea.set_text("/docs:review file=src/lib.rs");
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::McpPrompt(McpPromptInvocation {
+                server: "docs".to_string(),
+                name: "review".to_string(),
+                arguments: HashMap::from([("file".to_string(), "src/lib.rs".to_string())]),
+            }),
+            result
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
+    #[test]
+    fn selecting_mcp_prompt_with_args_inserts_placeholders() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_mcp_prompts(vec![mcp_review_prompt()]);
+
+        type_chars_humanlike(&mut composer, &['/', 'd', 'o', 'c', 's', ':', 'r', 'e']);
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(InputResult::None, result);
+        assert_eq!("/docs:review file=\"\"", composer.textarea.text());
+        assert_eq!(composer.textarea.cursor(), "/docs:review file=\"".len());
+    }
+
     #[test]
     fn custom_prompt_submission_accepts_quoted_values() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -3,6 +3,7 @@ use ratatui::layout::Rect;
 use ratatui::widgets::WidgetRef;
 
 use super::popup_consts::MAX_POPUP_ROWS;
+use super::prompt_args::McpPromptCommand;
 use super::scroll_state::ScrollState;
 use super::selection_popup_common::GenericDisplayRow;
 use super::selection_popup_common::render_rows;
@@ -15,18 +16,22 @@ use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use std::collections::HashSet;
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt, or a
+/// prompt published by an MCP server.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `mcp_prompts`
+    McpPrompt(usize),
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
     state: ScrollState,
 }
 
@@ -41,6 +46,7 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            mcp_prompts: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -60,6 +66,19 @@ impl CommandPopup {
         self.prompts.get(idx)
     }
 
+    /// Replace the MCP prompts shown as `/server:prompt`. Prompts from a server
+    /// named like the custom prompt namespace are dropped so `/prompts:` stays
+    /// unambiguous.
+    pub(crate) fn set_mcp_prompts(&mut self, mut prompts: Vec<McpPromptCommand>) {
+        prompts.retain(|p| p.server != PROMPTS_CMD_PREFIX);
+        prompts.sort_by_key(McpPromptCommand::command_name);
+        self.mcp_prompts = prompts;
+    }
+
+    pub(crate) fn mcp_prompt(&self, idx: usize) -> Option<&McpPromptCommand> {
+        self.mcp_prompts.get(idx)
+    }
+
     /// Update the filter string based on the current composer text. The text
     /// passed in is expected to start with a leading '/'. Everything after the
     /// *first* '/" on the *first* line becomes the active filter that is used
@@ -100,9 +119,9 @@ impl CommandPopup {
         measure_rows_height(&rows, &self.state, MAX_POPUP_ROWS, width)
     }
 
-    /// Compute fuzzy-filtered matches over built-in commands and user prompts,
-    /// paired with optional highlight indices and score. Sorted by ascending
-    /// score, then by name for stability.
+    /// Compute fuzzy-filtered matches over built-in commands, user prompts and
+    /// MCP prompts, paired with optional highlight indices and score. Sorted by
+    /// ascending score, then by name for stability.
     fn filtered(&self) -> Vec<(CommandItem, Option<Vec<usize>>, i32)> {
         let filter = self.command_filter.trim();
         let mut out: Vec<(CommandItem, Option<Vec<usize>>, i32)> = Vec::new();
@@ -115,6 +134,9 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None, 0));
             }
+            for idx in 0..self.mcp_prompts.len() {
+                out.push((CommandItem::McpPrompt(idx), None, 0));
+            }
             return out;
         }
 
@@ -132,23 +154,27 @@ impl CommandPopup {
                 out.push((CommandItem::UserPrompt(idx), Some(indices), score));
             }
         }
+        for (idx, p) in self.mcp_prompts.iter().enumerate() {
+            if let Some((indices, score)) = fuzzy_match(&p.command_name(), filter) {
+                out.push((CommandItem::McpPrompt(idx), Some(indices), score));
+            }
+        }
         // When filtering, sort by ascending score and then by name for stability.
         out.sort_by(|a, b| {
-            a.2.cmp(&b.2).then_with(|| {
-                let an = match a.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                let bn = match b.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                an.cmp(bn)
-            })
+            a.2.cmp(&b.2)
+                .then_with(|| self.item_name(a.0).cmp(&self.item_name(b.0)))
         });
         out
     }
 
+    fn item_name(&self, item: CommandItem) -> String {
+        match item {
+            CommandItem::Builtin(c) => c.command().to_string(),
+            CommandItem::UserPrompt(i) => self.prompts[i].name.clone(),
+            CommandItem::McpPrompt(i) => self.mcp_prompts[i].command_name(),
+        }
+    }
+
     fn filtered_items(&self) -> Vec<CommandItem> {
         self.filtered().into_iter().map(|(c, _, _)| c).collect()
     }
@@ -175,6 +201,19 @@ impl CommandPopup {
                             description,
                         )
                     }
+                    CommandItem::McpPrompt(i) => {
+                        let prompt = &self.mcp_prompts[i];
+                        let description = prompt
+                            .prompt
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "send MCP prompt".to_string());
+                        let description = match prompt.argument_hint() {
+                            Some(hint) => format!("{description} ({hint})"),
+                            None => description,
+                        };
+                        (format!("/{}", prompt.command_name()), description)
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -242,7 +281,7 @@ mod tests {
         let matches = popup.filtered_items();
         let has_init = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "init",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_) => false,
         });
         assert!(
             has_init,
@@ -260,7 +299,9 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "init"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/init'"),
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
+                panic!("unexpected prompt selected for '/init'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -272,7 +313,7 @@ mod tests {
         let matches = popup.filtered_items();
         match matches.first() {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "model"),
-            Some(CommandItem::UserPrompt(_)) => {
+            Some(CommandItem::UserPrompt(_) | CommandItem::McpPrompt(_)) => {
                 panic!("unexpected prompt ranked before '/model' for '/mo'")
             }
             None => panic!("expected at least one match for '/mo'"),
@@ -361,4 +402,57 @@ mod tests {
         let description = rows.first().and_then(|row| row.description.as_deref());
         assert_eq!(description, Some("send saved prompt"));
     }
+
+    fn mcp_review_prompt(server: &str) -> McpPromptCommand {
+        McpPromptCommand {
+            server: server.to_string(),
+            prompt: mcp_types::Prompt {
+                arguments: Some(vec![
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "file".to_string(),
+                        required: Some(true),
+                        title: None,
+                    },
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "focus".to_string(),
+                        required: Some(false),
+                        title: None,
+                    },
+                ]),
+                description: Some("Review a file.".to_string()),
+                name: "review".to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn mcp_prompts_are_listed_with_argument_hints() {
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_mcp_prompts(vec![mcp_review_prompt("docs")]);
+        popup.on_composer_text_change("/docs:rev".to_string());
+
+        let selected = popup.selected_item();
+        assert_eq!(selected, Some(CommandItem::McpPrompt(0)));
+        let rows = popup.rows_from_matches(vec![(CommandItem::McpPrompt(0), None, 0)]);
+        let row = rows.first().expect("row for MCP prompt");
+        assert_eq!(row.name, "/docs:review");
+        assert_eq!(
+            row.description.as_deref(),
+            Some("Review a file. (file [focus])")
+        );
+    }
+
+    #[test]
+    fn mcp_prompts_from_prompts_namespace_are_ignored() {
+        let mut popup = CommandPopup::new(Vec::new());
+        popup.set_mcp_prompts(vec![mcp_review_prompt(PROMPTS_CMD_PREFIX)]);
+        let has_mcp_prompt = popup
+            .filtered_items()
+            .into_iter()
+            .any(|item| matches!(item, CommandItem::McpPrompt(_)));
+        assert!(!has_mcp_prompt);
+    }
 }
@@ -0,0 +1,535 @@
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+use ratatui::widgets::Wrap;
+use serde_json::Map;
+use serde_json::Value;
+
+use codex_core::protocol::ElicitationAction;
+use codex_core::protocol::ElicitationRequestEvent;
+use codex_core::protocol::Op;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::renderable::Renderable;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+
+/// Kind of input rendered for a single property of the requested schema.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldKind {
+    Text,
+    Number { integer: bool },
+    Boolean,
+    Choice(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Text(String),
+    Boolean(bool),
+    /// Index into the field's options; `None` until the user picks one.
+    Choice(Option<usize>),
+}
+
+struct FormField {
+    name: String,
+    label: String,
+    description: Option<String>,
+    required: bool,
+    kind: FieldKind,
+    value: FieldValue,
+}
+
+impl FormField {
+    fn from_schema(name: &str, schema: &Value, required: bool) -> Self {
+        let label = schema
+            .get("title")
+            .and_then(Value::as_str)
+            .unwrap_or(name)
+            .to_string();
+        let description = schema
+            .get("description")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let default = schema.get("default");
+        let options: Option<Vec<String>> = schema.get("enum").and_then(Value::as_array).map(|v| {
+            v.iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        });
+
+        let (kind, value) = match (schema.get("type").and_then(Value::as_str), options) {
+            (_, Some(options)) if !options.is_empty() => {
+                let selected = default
+                    .and_then(Value::as_str)
+                    .and_then(|d| options.iter().position(|o| o == d))
+                    .or(required.then_some(0));
+                (FieldKind::Choice(options), FieldValue::Choice(selected))
+            }
+            (Some("boolean"), _) => (
+                FieldKind::Boolean,
+                FieldValue::Boolean(default.and_then(Value::as_bool).unwrap_or(false)),
+            ),
+            (Some(ty @ ("number" | "integer")), _) => (
+                FieldKind::Number {
+                    integer: ty == "integer",
+                },
+                FieldValue::Text(default.map(Value::to_string).unwrap_or_default()),
+            ),
+            _ => (
+                FieldKind::Text,
+                FieldValue::Text(
+                    default
+                        .and_then(Value::as_str)
+                        .map(str::to_string)
+                        .unwrap_or_default(),
+                ),
+            ),
+        };
+
+        Self {
+            name: name.to_string(),
+            label,
+            description,
+            required,
+            kind,
+            value,
+        }
+    }
+
+    fn display_value(&self) -> String {
+        match (&self.kind, &self.value) {
+            (_, FieldValue::Boolean(true)) => "[x]".to_string(),
+            (_, FieldValue::Boolean(false)) => "[ ]".to_string(),
+            (FieldKind::Choice(options), FieldValue::Choice(Some(idx))) => {
+                format!("‹ {} ›", options[*idx])
+            }
+            (_, FieldValue::Choice(_)) => "‹ (none) ›".to_string(),
+            (_, FieldValue::Text(text)) => text.clone(),
+        }
+    }
+
+    /// Convert the current input into JSON, or explain why it is invalid.
+    fn to_json(&self) -> Result<Option<Value>, String> {
+        let value = match (&self.kind, &self.value) {
+            (_, FieldValue::Boolean(b)) => Some(Value::Bool(*b)),
+            (FieldKind::Choice(options), FieldValue::Choice(idx)) => {
+                idx.map(|idx| Value::String(options[idx].clone()))
+            }
+            (FieldKind::Number { integer }, FieldValue::Text(text)) => {
+                let text = text.trim();
+                if text.is_empty() {
+                    None
+                } else if *integer {
+                    let n: i64 = text
+                        .parse()
+                        .map_err(|_| format!("{} must be a whole number", self.label))?;
+                    Some(Value::from(n))
+                } else {
+                    let n: f64 = text
+                        .parse()
+                        .map_err(|_| format!("{} must be a number", self.label))?;
+                    Some(Value::from(n))
+                }
+            }
+            (_, FieldValue::Text(text)) => (!text.is_empty()).then(|| Value::String(text.clone())),
+            (_, FieldValue::Choice(_)) => None,
+        };
+        if value.is_none() && self.required {
+            return Err(format!("{} is required", self.label));
+        }
+        Ok(value)
+    }
+}
+
+/// Form shown when an MCP server asks the user for structured input.
+pub(crate) struct ElicitationView {
+    request: ElicitationRequestEvent,
+    fields: Vec<FormField>,
+    selected: usize,
+    error: Option<String>,
+    app_event_tx: AppEventSender,
+    complete: bool,
+}
+
+impl ElicitationView {
+    pub(crate) fn new(request: ElicitationRequestEvent, app_event_tx: AppEventSender) -> Self {
+        let required = request
+            .requested_schema
+            .required
+            .clone()
+            .unwrap_or_default();
+        let fields = match &request.requested_schema.properties {
+            Value::Object(properties) => properties
+                .iter()
+                .map(|(name, schema)| {
+                    FormField::from_schema(name, schema, required.iter().any(|r| r == name))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            request,
+            fields,
+            selected: 0,
+            error: None,
+            app_event_tx,
+            complete: false,
+        }
+    }
+
+    fn resolve(&mut self, decision: ElicitationAction, content: Option<Value>) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::ResolveElicitation {
+                server_name: self.request.server_name.clone(),
+                request_id: self.request.id.clone(),
+                decision,
+                content,
+            }));
+        self.complete = true;
+    }
+
+    fn submit(&mut self) {
+        let mut content = Map::new();
+        for field in &self.fields {
+            match field.to_json() {
+                Ok(Some(value)) => {
+                    content.insert(field.name.clone(), value);
+                }
+                Ok(None) => {}
+                Err(message) => {
+                    self.error = Some(message);
+                    return;
+                }
+            }
+        }
+        self.resolve(ElicitationAction::Accept, Some(Value::Object(content)));
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        if self.fields.is_empty() {
+            return;
+        }
+        let len = self.fields.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Toggle a boolean or step through the options of a choice field.
+    fn cycle_selected(&mut self, forward: bool) {
+        let Some(field) = self.fields.get_mut(self.selected) else {
+            return;
+        };
+        match (&field.kind, &mut field.value) {
+            (FieldKind::Boolean, FieldValue::Boolean(b)) => *b = !*b,
+            (FieldKind::Choice(options), FieldValue::Choice(idx)) => {
+                let len = options.len();
+                *idx = Some(match (*idx, forward) {
+                    (None, true) => 0,
+                    (None, false) => len - 1,
+                    (Some(i), true) => (i + 1) % len,
+                    (Some(i), false) => (i + len - 1) % len,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn selected_text_mut(&mut self) -> Option<&mut String> {
+        match self.fields.get_mut(self.selected).map(|f| &mut f.value) {
+            Some(FieldValue::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn header_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from(vec![
+                "MCP server ".bold(),
+                self.request.server_name.clone().bold().cyan(),
+                " is requesting input".bold(),
+            ]),
+            Line::from(self.request.message.clone()),
+        ]
+    }
+
+    fn field_lines(&self) -> Vec<Line<'static>> {
+        self.fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let is_selected = idx == self.selected;
+                let mut spans: Vec<Span<'static>> = Vec::new();
+                spans.push(if is_selected {
+                    "› ".cyan()
+                } else {
+                    "  ".into()
+                });
+                let label = if field.required {
+                    format!("{}*: ", field.label)
+                } else {
+                    format!("{}: ", field.label)
+                };
+                spans.push(if is_selected {
+                    label.cyan()
+                } else {
+                    label.into()
+                });
+                spans.push(field.display_value().into());
+                if let Some(description) = &field.description {
+                    spans.push(format!("  {description}").dim());
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    fn footer_line() -> Line<'static> {
+        Line::from(vec![
+            "Press ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " to submit, ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to decline".into(),
+        ])
+        .dim()
+    }
+
+    fn all_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = self.header_lines();
+        lines.push(Line::from(""));
+        lines.extend(self.field_lines());
+        if let Some(error) = &self.error {
+            lines.push(Line::from(error.clone().red()));
+        }
+        lines.push(Line::from(""));
+        lines.push(Self::footer_line());
+        lines
+    }
+}
+
+impl BottomPaneView for ElicitationView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        match key_event {
+            KeyEvent {
+                code: KeyCode::Esc, ..
+            } => self.resolve(ElicitationAction::Decline, None),
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => self.submit(),
+            KeyEvent {
+                code: KeyCode::Up, ..
+            }
+            | KeyEvent {
+                code: KeyCode::BackTab,
+                ..
+            } => self.move_selection(-1),
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Tab, ..
+            } => self.move_selection(1),
+            KeyEvent {
+                code: KeyCode::Left,
+                ..
+            } => self.cycle_selected(false),
+            KeyEvent {
+                code: KeyCode::Right,
+                ..
+            } => self.cycle_selected(true),
+            KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            } => {
+                if let Some(text) = self.selected_text_mut() {
+                    text.pop();
+                }
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            } if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                match self.selected_text_mut() {
+                    Some(text) => text.push(c),
+                    None if c == ' ' => self.cycle_selected(true),
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.resolve(ElicitationAction::Cancel, None);
+        CancellationEvent::Handled
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        match self.selected_text_mut() {
+            Some(text) if !pasted.is_empty() => {
+                text.push_str(&pasted);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Renderable for ElicitationView {
+    fn desired_height(&self, width: u16) -> u16 {
+        Paragraph::new(self.all_lines())
+            .wrap(Wrap { trim: false })
+            .line_count(width)
+            .try_into()
+            .unwrap_or(u16::MAX)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+        Paragraph::new(self.all_lines())
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ElicitationRequestEvent;
+    use mcp_types::ElicitRequestParamsRequestedSchema;
+    use mcp_types::RequestId;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn make_view() -> (ElicitationView, UnboundedReceiver<AppEvent>) {
+        let (tx, rx) = unbounded_channel::<AppEvent>();
+        let request = ElicitationRequestEvent {
+            server_name: "deploy".to_string(),
+            id: RequestId::Integer(3),
+            message: "Deploy the current build?".to_string(),
+            requested_schema: ElicitRequestParamsRequestedSchema {
+                properties: json!({
+                    "approved": { "type": "boolean", "title": "Approve" },
+                    "environment": { "type": "string", "enum": ["staging", "production"] },
+                    "replicas": { "type": "integer", "description": "How many to roll out" },
+                    "note": { "type": "string" }
+                }),
+                required: Some(vec!["approved".to_string(), "environment".to_string()]),
+                r#type: "object".to_string(),
+            },
+        };
+        (ElicitationView::new(request, AppEventSender::new(tx)), rx)
+    }
+
+    fn render(view: &ElicitationView, width: u16) -> String {
+        let height = view.desired_height(width);
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..area.height)
+            .map(|row| {
+                (0..area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn resolved_op(rx: &mut UnboundedReceiver<AppEvent>) -> Option<Op> {
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(op @ Op::ResolveElicitation { .. }) = ev {
+                return Some(op);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn elicitation_form_snapshot() {
+        let (view, _rx) = make_view();
+        insta::assert_snapshot!("elicitation_form", render(&view, 80));
+    }
+
+    #[test]
+    fn submit_sends_form_values() {
+        let (mut view, mut rx) = make_view();
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        assert_eq!(
+            resolved_op(&mut rx),
+            Some(Op::ResolveElicitation {
+                server_name: "deploy".to_string(),
+                request_id: RequestId::Integer(3),
+                decision: ElicitationAction::Accept,
+                content: Some(json!({
+                    "approved": true,
+                    "environment": "production",
+                    "replicas": 2,
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_number_blocks_submit() {
+        let (mut view, mut rx) = make_view();
+        view.selected = 2;
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(!view.is_complete());
+        assert_eq!(resolved_op(&mut rx), None);
+        assert_eq!(
+            view.error.as_deref(),
+            Some("replicas must be a whole number")
+        );
+    }
+
+    #[test]
+    fn esc_declines() {
+        let (mut view, mut rx) = make_view();
+        view.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+
+        assert!(view.is_complete());
+        assert_eq!(
+            resolved_op(&mut rx),
+            Some(Op::ResolveElicitation {
+                server_name: "deploy".to_string(),
+                request_id: RequestId::Integer(3),
+                decision: ElicitationAction::Decline,
+                content: None,
+            })
+        );
+    }
+}
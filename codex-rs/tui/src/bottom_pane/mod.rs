@@ -8,6 +8,7 @@ use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
 use crate::tui::FrameRequester;
 use bottom_pane_view::BottomPaneView;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_file_search::FileMatch;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -18,16 +19,21 @@ use std::time::Duration;
 mod approval_overlay;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use approval_overlay::sampling_request_lines;
+use elicitation_view::ElicitationView;
 mod bottom_pane_view;
 mod chat_composer;
 mod chat_composer_history;
 mod command_popup;
 pub mod custom_prompt_view;
+mod elicitation_view;
 mod file_search_popup;
 mod footer;
 mod list_selection_view;
 mod prompt_args;
 pub(crate) use list_selection_view::SelectionViewParams;
+pub(crate) use prompt_args::McpPromptCommand;
+pub(crate) use prompt_args::mcp_prompt_text;
 mod feedback_view;
 pub(crate) use feedback_view::feedback_selection_params;
 pub(crate) use feedback_view::feedback_upload_consent_params;
@@ -351,6 +357,11 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<McpPromptCommand>) {
+        self.composer.set_mcp_prompts(prompts);
+        self.request_redraw();
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.composer.is_empty()
     }
@@ -390,6 +401,13 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Called when an MCP server asks the user to fill in a form.
+    pub(crate) fn push_elicitation_request(&mut self, request: ElicitationRequestEvent) {
+        let view = ElicitationView::new(request, self.app_event_tx.clone());
+        self.pause_status_timer_for_modal();
+        self.push_view(Box::new(view));
+    }
+
     fn on_active_view_complete(&mut self) {
         self.resume_status_timer_after_modal();
     }
@@ -1,6 +1,10 @@
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use lazy_static::lazy_static;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::Prompt;
 use regex_lite::Regex;
 use shlex::Shlex;
 use std::collections::HashMap;
@@ -299,7 +303,13 @@ pub fn expand_numeric_placeholders(content: &str, args: &[String]) -> String {
 /// Constructs a command text for a custom prompt with arguments.
 /// Returns the text and the cursor position (inside the first double quote).
 pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+    command_with_arg_placeholders(&format!("{PROMPTS_CMD_PREFIX}:{name}"), args)
+}
+
+/// Constructs `/command key="" …` for the given argument names.
+/// Returns the text and the cursor position (inside the first double quote).
+fn command_with_arg_placeholders(command: &str, args: &[String]) -> (String, usize) {
+    let mut text = format!("/{command}");
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -310,6 +320,137 @@ pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (Str
     (text, cursor)
 }
 
+/// A prompt published by an MCP server, invoked as `/server:prompt`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpPromptCommand {
+    pub server: String,
+    pub prompt: Prompt,
+}
+
+impl McpPromptCommand {
+    /// Command token after '/', e.g. `"server:prompt"`.
+    pub fn command_name(&self) -> String {
+        format!("{}:{}", self.server, self.prompt.name)
+    }
+
+    /// Argument names in the order the server declared them.
+    pub fn argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+
+    fn required_argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .filter(|arg| arg.required.unwrap_or(false))
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+
+    /// Short usage hint built from the argument schema, e.g. `file [focus]`.
+    /// Optional arguments are wrapped in brackets.
+    pub fn argument_hint(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|arg| {
+                if arg.required.unwrap_or(false) {
+                    arg.name.clone()
+                } else {
+                    format!("[{}]", arg.name)
+                }
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Text inserted into the composer when the prompt is selected, with an
+    /// empty `key=""` placeholder per argument. The cursor lands inside the
+    /// first placeholder, or at the end when there are no arguments.
+    pub fn command_with_arg_placeholders(&self) -> (String, usize) {
+        command_with_arg_placeholders(&self.command_name(), &self.argument_names())
+    }
+}
+
+/// A parsed `/server:prompt key=value …` invocation, ready to be sent to the
+/// server via `prompts/get`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpPromptInvocation {
+    pub server: String,
+    pub name: String,
+    pub arguments: HashMap<String, String>,
+}
+
+/// Parses a message of the form `/server:prompt [key=value] …` against the
+/// prompts published by MCP servers.
+///
+/// Returns `Ok(None)` when the text does not name a known MCP prompt. Empty
+/// values are dropped so optional placeholders left blank are not sent, and
+/// required arguments left blank are reported as missing.
+pub fn parse_mcp_prompt_invocation(
+    text: &str,
+    mcp_prompts: &[McpPromptCommand],
+) -> Result<Option<McpPromptInvocation>, PromptExpansionError> {
+    let Some((name, rest)) = parse_slash_name(text) else {
+        return Ok(None);
+    };
+    let Some(prompt) = mcp_prompts.iter().find(|p| p.command_name() == name) else {
+        return Ok(None);
+    };
+
+    let mut inputs = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
+        command: format!("/{name}"),
+        error,
+    })?;
+    inputs.retain(|_, value| !value.is_empty());
+    let missing: Vec<String> = prompt
+        .required_argument_names()
+        .into_iter()
+        .filter(|k| !inputs.contains_key(k))
+        .collect();
+    if !missing.is_empty() {
+        return Err(PromptExpansionError::MissingArgs {
+            command: format!("/{name}"),
+            missing,
+        });
+    }
+    Ok(Some(McpPromptInvocation {
+        server: prompt.server.clone(),
+        name: prompt.prompt.name.clone(),
+        arguments: inputs,
+    }))
+}
+
+/// Flattens the text of a rendered MCP prompt into a single message.
+/// Text blocks and embedded text resources are kept; other content is skipped.
+pub fn mcp_prompt_text(result: &GetPromptResult) -> String {
+    result
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            ContentBlock::TextContent(text) => Some(text.text.as_str()),
+            ContentBlock::EmbeddedResource(embedded) => match &embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(contents) => {
+                    Some(contents.text.as_str())
+                }
+                EmbeddedResourceResource::BlobResourceContents(_) => None,
+            },
+            ContentBlock::ImageContent(_)
+            | ContentBlock::AudioContent(_)
+            | ContentBlock::ResourceLink(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -403,4 +544,89 @@ mod tests {
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    fn review_prompt() -> McpPromptCommand {
+        McpPromptCommand {
+            server: "docs".to_string(),
+            prompt: Prompt {
+                arguments: Some(vec![
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "file".to_string(),
+                        required: Some(true),
+                        title: None,
+                    },
+                    mcp_types::PromptArgument {
+                        description: None,
+                        name: "focus".to_string(),
+                        required: None,
+                        title: None,
+                    },
+                ]),
+                description: None,
+                name: "review".to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_argument_hint_brackets_optional_args() {
+        assert_eq!(
+            review_prompt().argument_hint(),
+            Some("file [focus]".to_string())
+        );
+        assert_eq!(
+            review_prompt().command_with_arg_placeholders(),
+            ("/docs:review file=\"\" focus=\"\"".to_string(), 19)
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_invocation_drops_blank_optional_args() {
+        let out = parse_mcp_prompt_invocation(
+            "/docs:review file=\"src/lib.rs\" focus=\"\"",
+            &[review_prompt()],
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            Some(McpPromptInvocation {
+                server: "docs".to_string(),
+                name: "review".to_string(),
+                arguments: HashMap::from([("file".to_string(), "src/lib.rs".to_string())]),
+            })
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_invocation_reports_blank_required_args() {
+        let err = parse_mcp_prompt_invocation("/docs:review file=\"\"", &[review_prompt()])
+            .unwrap_err()
+            .user_message();
+        assert!(err.contains("Missing required args for /docs:review: file"));
+    }
+
+    #[test]
+    fn unknown_mcp_prompt_is_ignored() {
+        let out = parse_mcp_prompt_invocation("/docs:other", &[review_prompt()]).unwrap();
+        assert_eq!(out, None);
+    }
+
+    #[test]
+    fn mcp_prompt_text_joins_text_messages() {
+        let text = |text: &str| mcp_types::PromptMessage {
+            content: ContentBlock::TextContent(mcp_types::TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role: mcp_types::Role::User,
+        };
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![text("First."), text("Second.")],
+        };
+        assert_eq!(mcp_prompt_text(&result), "First.\n\nSecond.");
+    }
 }
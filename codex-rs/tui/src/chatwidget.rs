@@ -17,6 +17,7 @@ use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -24,8 +25,15 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::HookCompletedEvent;
+use codex_core::protocol::HookOutcome;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::McpGetPromptResponseEvent;
+use codex_core::protocol::McpListPromptsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpServerUpdate;
+use codex_core::protocol::McpServerUpdatedEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
@@ -45,6 +53,7 @@ use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::review_prompts;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
@@ -70,10 +79,12 @@ use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::InputResult;
+use crate::bottom_pane::McpPromptCommand;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::mcp_prompt_text;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
 use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::diff_render::display_path_for;
@@ -352,8 +363,9 @@ impl ChatWidget {
         if let Some(messages) = initial_messages {
             self.replay_initial_messages(messages);
         }
-        // Ask codex-core to enumerate custom prompts for this session.
+        // Ask codex-core to enumerate custom and MCP prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
+        self.submit_op(Op::ListMcpPrompts);
         if let Some(user_message) = self.initial_user_message.take() {
             self.submit_user_message(user_message);
         }
@@ -630,6 +642,22 @@ impl ChatWidget {
         );
     }
 
+    fn on_elicitation_request(&mut self, ev: ElicitationRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_elicitation(ev),
+            |s| s.handle_elicitation_request_now(ev2),
+        );
+    }
+
+    fn on_mcp_sampling_approval_request(&mut self, ev: McpSamplingApprovalRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_sampling_approval(ev),
+            |s| s.handle_mcp_sampling_approval_now(ev2),
+        );
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -725,6 +753,22 @@ impl ChatWidget {
         debug!("BackgroundEvent: {message}");
     }
 
+    /// Only hooks that blocked something or failed are worth surfacing; the
+    /// rest are visible in the rollout.
+    fn on_hook_completed(&mut self, event: HookCompletedEvent) {
+        let command = event.command.join(" ");
+        let reason = event.reason.unwrap_or_default();
+        let message = match event.outcome {
+            HookOutcome::Continued => return,
+            HookOutcome::Blocked => match event.tool_name {
+                Some(tool_name) => format!("Hook `{command}` blocked `{tool_name}`: {reason}"),
+                None => format!("Hook `{command}` blocked the turn: {reason}"),
+            },
+            HookOutcome::Failed => format!("Hook `{command}` failed: {reason}"),
+        };
+        self.on_warning(message);
+    }
+
     fn on_undo_started(&mut self, event: UndoStartedEvent) {
         self.bottom_pane.ensure_status_indicator();
         self.bottom_pane.set_interrupt_hint_visible(false);
@@ -899,6 +943,23 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_elicitation_request_now(&mut self, ev: ElicitationRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        self.bottom_pane.push_elicitation_request(ev);
+        self.request_redraw();
+    }
+
+    pub(crate) fn handle_mcp_sampling_approval_now(&mut self, ev: McpSamplingApprovalRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        let request = ApprovalRequest::McpSampling {
+            server_name: ev.server_name,
+            request_id: ev.id,
+            request: ev.request,
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
@@ -1194,6 +1255,18 @@ impl ChatWidget {
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, args);
+                    }
+                    InputResult::McpPrompt(invocation) => {
+                        let arguments = (!invocation.arguments.is_empty())
+                            .then(|| serde_json::json!(invocation.arguments));
+                        self.submit_op(Op::GetMcpPrompt {
+                            server: invocation.server,
+                            name: invocation.name,
+                            arguments,
+                        });
+                    }
                     InputResult::None => {}
                 }
             }
@@ -1215,6 +1288,39 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: String) {
+        match cmd {
+            SlashCommand::Mcp => self.dispatch_mcp_server_command(&args),
+            _ => self.dispatch_command(cmd),
+        }
+    }
+
+    /// Handle `/mcp restart <server>`, `/mcp enable <server>` and
+    /// `/mcp disable <server>`.
+    fn dispatch_mcp_server_command(&mut self, args: &str) {
+        let mut parts = args.split_whitespace();
+        let op = match (parts.next(), parts.next(), parts.next()) {
+            (Some("restart"), Some(server), None) => Op::RestartMcpServer {
+                server: server.to_string(),
+            },
+            (Some(action @ ("enable" | "disable")), Some(server), None) => {
+                Op::SetMcpServerEnabled {
+                    server: server.to_string(),
+                    enabled: action == "enable",
+                }
+            }
+            _ => {
+                self.add_to_history(history_cell::new_error_event(
+                    "Usage: /mcp restart <server> | /mcp enable <server> | /mcp disable <server>"
+                        .to_string(),
+                ));
+                self.request_redraw();
+                return;
+            }
+        };
+        self.submit_op(op);
+    }
+
     fn dispatch_command(&mut self, cmd: SlashCommand) {
         if !cmd.available_during_task() && self.bottom_pane.is_task_running() {
             let message = format!(
@@ -1334,6 +1440,7 @@ impl ChatWidget {
                                 PathBuf::from("/tmp/test.txt"),
                                 FileChange::Add {
                                     content: "test".to_string(),
+                                    mode: None,
                                 },
                             ),
                             (
@@ -1341,6 +1448,7 @@ impl ChatWidget {
                                 FileChange::Update {
                                     unified_diff: "+test\n-test2".to_string(),
                                     move_path: None,
+                                    mode: None,
                                 },
                             ),
                         ]),
@@ -1547,17 +1655,23 @@ impl ChatWidget {
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
+            EventMsg::ElicitationRequest(ev) => self.on_elicitation_request(ev),
+            EventMsg::McpSamplingApprovalRequest(ev) => self.on_mcp_sampling_approval_request(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::McpListPromptsResponse(ev) => self.on_list_mcp_prompts(ev),
+            EventMsg::McpGetPromptResponse(ev) => self.on_mcp_get_prompt(ev),
+            EventMsg::McpServerUpdated(ev) => self.on_mcp_server_updated(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
+            EventMsg::HookCompleted(ev) => self.on_hook_completed(ev),
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
             EventMsg::StreamError(StreamErrorEvent { message }) => self.on_stream_error(message),
@@ -2274,7 +2388,7 @@ impl ChatWidget {
         let mut header_children: Vec<Box<dyn Renderable>> = Vec::new();
         let mode_label = match self.config.sandbox_policy {
             SandboxPolicy::WorkspaceWrite { .. } => "Auto mode",
-            SandboxPolicy::ReadOnly => "Read-Only mode",
+            SandboxPolicy::ReadOnly { .. } => "Read-Only mode",
             _ => "Auto mode",
         };
         let title_line = Line::from("Unprotected directories found").bold();
@@ -2533,6 +2647,7 @@ impl ChatWidget {
             ev.resources,
             ev.resource_templates,
             &ev.auth_statuses,
+            &ev.auth_expirations,
         ));
     }
 
@@ -2543,6 +2658,77 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    fn on_list_mcp_prompts(&mut self, ev: McpListPromptsResponseEvent) {
+        let prompts: Vec<McpPromptCommand> = ev
+            .prompts
+            .into_iter()
+            .flat_map(|(server, prompts)| {
+                prompts.into_iter().map(move |prompt| McpPromptCommand {
+                    server: server.clone(),
+                    prompt,
+                })
+            })
+            .collect();
+        debug!("received {} MCP prompts", prompts.len());
+        self.bottom_pane.set_mcp_prompts(prompts);
+    }
+
+    fn on_mcp_get_prompt(&mut self, ev: McpGetPromptResponseEvent) {
+        let McpGetPromptResponseEvent {
+            server,
+            name,
+            result,
+        } = ev;
+        match result {
+            Ok(result) => {
+                let text = mcp_prompt_text(&result);
+                if text.is_empty() {
+                    self.add_to_history(history_cell::new_info_event(
+                        format!("MCP prompt /{server}:{name} returned no text."),
+                        None,
+                    ));
+                    return;
+                }
+                self.queue_user_message(UserMessage::from(text));
+            }
+            Err(err) => {
+                self.add_to_history(history_cell::new_error_event(format!(
+                    "Failed to load MCP prompt /{server}:{name}: {err}"
+                )));
+            }
+        }
+    }
+
+    fn on_mcp_server_updated(&mut self, ev: McpServerUpdatedEvent) {
+        let McpServerUpdatedEvent {
+            server,
+            update,
+            result,
+        } = ev;
+        match result {
+            Ok(tool_count) => {
+                let message = match update {
+                    McpServerUpdate::Disabled => format!("MCP server `{server}` disabled."),
+                    McpServerUpdate::Reconnected => format!(
+                        "MCP server `{server}` exited and was restarted ({tool_count} tools)."
+                    ),
+                    McpServerUpdate::Restarted | McpServerUpdate::Enabled => {
+                        format!("MCP server `{server}` {update} ({tool_count} tools).")
+                    }
+                };
+                self.add_info_message(message, None);
+                // The server's prompts may have changed along with its tools.
+                self.submit_op(Op::ListMcpPrompts);
+            }
+            Err(err) => {
+                self.add_to_history(history_cell::new_error_event(format!(
+                    "MCP server `{server}` could not be {update}: {err}"
+                )));
+                self.request_redraw();
+            }
+        }
+    }
+
     pub(crate) fn open_review_popup(&mut self) {
         let mut items: Vec<SelectionItem> = Vec::new();
 
@@ -2561,16 +2747,11 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: review_prompts::uncommitted_changes(),
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -2618,12 +2799,7 @@ impl ChatWidget {
                 name: format!("{current_branch} -> {branch}"),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
-                            user_facing_hint: format!("changes against '{branch}'"),
-                        },
+                        review_request: review_prompts::base_branch(&branch),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2649,21 +2825,13 @@ impl ChatWidget {
         for entry in commits {
             let subject = entry.subject.clone();
             let sha = entry.sha.clone();
-            let short = sha.chars().take(7).collect::<String>();
             let search_val = format!("{subject} {sha}");
 
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
-                        },
+                        review_request: review_prompts::commit(&sha, Some(&subject)),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2689,15 +2857,11 @@ impl ChatWidget {
             "Type instructions and press Enter".to_string(),
             None,
             Box::new(move |prompt: String| {
-                let trimmed = prompt.trim().to_string();
-                if trimmed.is_empty() {
+                if prompt.trim().is_empty() {
                     return;
                 }
                 tx.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt: trimmed.clone(),
-                        user_facing_hint: trimmed,
-                    },
+                    review_request: review_prompts::custom(&prompt),
                 }));
             }),
         );
@@ -2871,21 +3035,13 @@ pub(crate) fn show_review_commit_picker_with_entries(
     for entry in entries {
         let subject = entry.subject.clone();
         let sha = entry.sha.clone();
-        let short = sha.chars().take(7).collect::<String>();
         let search_val = format!("{subject} {sha}");
 
         items.push(SelectionItem {
             name: subject.clone(),
             actions: vec![Box::new(move |tx3: &AppEventSender| {
-                let hint = format!("commit {short}");
-                let prompt = format!(
-                    "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                );
                 tx3.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt,
-                        user_facing_hint: hint,
-                    },
+                    review_request: review_prompts::commit(&sha, Some(&subject)),
                 }));
             })],
             dismiss_on_select: true,
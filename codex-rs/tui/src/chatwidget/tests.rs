@@ -1907,6 +1907,7 @@ fn approval_modal_patch_snapshot() {
         PathBuf::from("README.md"),
         FileChange::Add {
             content: "hello\nworld\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2142,6 +2143,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2182,6 +2184,7 @@ fn apply_patch_events_emit_history_cells() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     let begin = PatchApplyBeginEvent {
@@ -2228,6 +2231,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2246,6 +2250,7 @@ fn apply_patch_manual_approval_adjusts_header() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2275,6 +2280,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2297,6 +2303,7 @@ fn apply_patch_manual_flow_snapshot() {
         PathBuf::from("foo.txt"),
         FileChange::Add {
             content: "hello\n".to_string(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2326,6 +2333,7 @@ fn apply_patch_approval_sends_op_with_submission_id() {
         PathBuf::from("file.rs"),
         FileChange::Add {
             content: "fn main(){}\n".into(),
+            mode: None,
         },
     );
     let ev = ApplyPatchApprovalRequestEvent {
@@ -2363,7 +2371,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -2403,7 +2414,10 @@ fn apply_patch_full_flow_integration_like() {
     let mut changes2 = HashMap::new();
     changes2.insert(
         PathBuf::from("pkg.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-xyz".into(),
@@ -2434,7 +2448,10 @@ fn apply_patch_untrusted_shows_approval_modal() {
     let mut changes = HashMap::new();
     changes.insert(
         PathBuf::from("a.rs"),
-        FileChange::Add { content: "".into() },
+        FileChange::Add {
+            content: "".into(),
+            mode: None,
+        },
     );
     chat.handle_codex_event(Event {
         id: "sub-1".into(),
@@ -2482,6 +2499,7 @@ fn apply_patch_request_shows_diff_summary() {
         FileChange::Add {
             // Two lines (no trailing empty line counted)
             content: "line one\nline two\n".into(),
+            mode: None,
         },
     );
     chat.handle_codex_event(Event {
@@ -2951,3 +2969,109 @@ fn chatwidget_tall() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[test]
+fn mcp_prompt_is_fetched_and_submitted() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "prompts".into(),
+        msg: EventMsg::McpListPromptsResponse(McpListPromptsResponseEvent {
+            prompts: std::collections::HashMap::from([(
+                "docs".to_string(),
+                vec![mcp_types::Prompt {
+                    arguments: Some(vec![mcp_types::PromptArgument {
+                        description: None,
+                        name: "file".to_string(),
+                        required: Some(true),
+                        title: None,
+                    }]),
+                    description: None,
+                    name: "review".to_string(),
+                    title: None,
+                }],
+            )]),
+        }),
+    });
+
+    chat.bottom_pane
+        .set_composer_text("/docs:review file=src/lib.rs".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match op_rx.try_recv() {
+        Ok(Op::GetMcpPrompt {
+            server,
+            name,
+            arguments,
+        }) => {
+            assert_eq!(server, "docs");
+            assert_eq!(name, "review");
+            assert_eq!(arguments, Some(serde_json::json!({ "file": "src/lib.rs" })));
+        }
+        other => panic!("expected Op::GetMcpPrompt, got {other:?}"),
+    }
+
+    chat.handle_codex_event(Event {
+        id: "prompt".into(),
+        msg: EventMsg::McpGetPromptResponse(McpGetPromptResponseEvent {
+            server: "docs".to_string(),
+            name: "review".to_string(),
+            result: Ok(mcp_types::GetPromptResult {
+                description: None,
+                messages: vec![mcp_types::PromptMessage {
+                    content: mcp_types::ContentBlock::TextContent(mcp_types::TextContent {
+                        annotations: None,
+                        text: "Please review src/lib.rs.".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                    role: mcp_types::Role::User,
+                }],
+            }),
+        }),
+    });
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items }) => assert_eq!(
+            items,
+            vec![UserInput::Text {
+                text: "Please review src/lib.rs.".to_string(),
+            }]
+        ),
+        other => panic!("expected Op::UserInput, got {other:?}"),
+    }
+}
+
+#[test]
+fn mcp_server_commands_submit_ops() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.bottom_pane
+        .set_composer_text("/mcp restart docs".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(
+        op_rx.try_recv(),
+        Ok(Op::RestartMcpServer { server }) if server == "docs"
+    );
+
+    chat.bottom_pane
+        .set_composer_text("/mcp disable docs".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(
+        op_rx.try_recv(),
+        Ok(Op::SetMcpServerEnabled { server, enabled: false }) if server == "docs"
+    );
+
+    chat.bottom_pane
+        .set_composer_text("/mcp reload docs".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+
+    // A successful update refreshes the prompts the server publishes.
+    chat.handle_codex_event(Event {
+        id: "mcp".into(),
+        msg: EventMsg::McpServerUpdated(McpServerUpdatedEvent {
+            server: "docs".to_string(),
+            update: McpServerUpdate::Enabled,
+            result: Ok(3),
+        }),
+    });
+    assert_matches!(op_rx.try_recv(), Ok(Op::ListMcpPrompts));
+}
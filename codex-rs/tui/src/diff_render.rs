@@ -100,9 +100,12 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
     let mut rows: Vec<Row> = Vec::new();
     for (path, change) in changes.iter() {
         let (added, removed) = match change {
-            FileChange::Add { content } => (content.lines().count(), 0),
+            FileChange::Add { content, .. } => (content.lines().count(), 0),
             FileChange::Delete { content } => (0, content.lines().count()),
             FileChange::Update { unified_diff, .. } => calculate_add_remove_from_diff(unified_diff),
+            FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. }
+            | FileChange::SetMode { .. } => (0, 0),
         };
         let move_path = match change {
             FileChange::Update {
@@ -153,7 +156,9 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
     let mut header_spans: Vec<RtSpan<'static>> = vec!["• ".dim()];
     if let [row] = &rows[..] {
         let verb = match &row.change {
-            FileChange::Add { .. } => "Added",
+            FileChange::Add { .. }
+            | FileChange::AddBinary { .. }
+            | FileChange::AddSymlink { .. } => "Added",
             FileChange::Delete { .. } => "Deleted",
             _ => "Edited",
         };
@@ -194,8 +199,21 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
 }
 
 fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
+    if let FileChange::Add {
+        mode: Some(mode), ..
+    }
+    | FileChange::AddBinary {
+        mode: Some(mode), ..
+    }
+    | FileChange::Update {
+        mode: Some(mode), ..
+    }
+    | FileChange::SetMode { mode } = change
+    {
+        out.push(RtLine::from(format!("mode → {mode:o}").dim()));
+    }
     match change {
-        FileChange::Add { content } => {
+        FileChange::Add { content, .. } => {
             let line_number_width = line_number_width(content.lines().count());
             for (i, raw) in content.lines().enumerate() {
                 out.extend(push_wrapped_diff_line(
@@ -296,6 +314,16 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                 }
             }
         }
+        FileChange::AddBinary { size, .. } => {
+            out.push(RtLine::from(format!("(binary, {size} bytes)").dim()));
+        }
+        FileChange::AddSymlink { target } => {
+            out.push(RtLine::from(
+                format!("symlink → {}", target.display()).dim(),
+            ));
+        }
+        // Rendered above.
+        FileChange::SetMode { .. } => {}
     }
 }
 
@@ -484,6 +512,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -504,6 +533,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(PathBuf::from("new_name.rs")),
+                mode: None,
             },
         );
 
@@ -524,6 +554,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch_a,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -532,6 +563,7 @@ mod tests {
             PathBuf::from("b.txt"),
             FileChange::Add {
                 content: "new\n".to_string(),
+                mode: None,
             },
         );
 
@@ -547,6 +579,7 @@ mod tests {
             PathBuf::from("new_file.txt"),
             FileChange::Add {
                 content: "alpha\nbeta\n".to_string(),
+                mode: None,
             },
         );
 
@@ -555,6 +588,32 @@ mod tests {
         snapshot_lines("apply_add_block", lines, 80, 10);
     }
 
+    #[test]
+    fn ui_snapshot_apply_binary_symlink_and_mode_block() {
+        let mut changes: HashMap<PathBuf, FileChange> = HashMap::new();
+        changes.insert(
+            PathBuf::from("logo.png"),
+            FileChange::AddBinary {
+                size: 1024,
+                mode: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("link"),
+            FileChange::AddSymlink {
+                target: PathBuf::from("script.sh"),
+            },
+        );
+        changes.insert(
+            PathBuf::from("script.sh"),
+            FileChange::SetMode { mode: 0o755 },
+        );
+
+        let lines = diff_summary_for_tests(&changes);
+
+        snapshot_lines_text("apply_binary_symlink_and_mode_block", &lines);
+    }
+
     #[test]
     fn ui_snapshot_apply_delete_block() {
         // Write a temporary file so the delete renderer can read original content
@@ -590,6 +649,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -613,6 +673,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -640,6 +701,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: None,
+                mode: None,
             },
         );
 
@@ -663,6 +725,7 @@ mod tests {
             FileChange::Update {
                 unified_diff: patch,
                 move_path: Some(abs_new),
+                mode: None,
             },
         );
 
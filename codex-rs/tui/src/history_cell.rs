@@ -26,6 +26,7 @@ use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::config::types::ReasoningSummaryFormat;
+use codex_core::mcp::auth::describe_auth_status;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
@@ -454,6 +455,29 @@ pub fn new_approval_decision_cell(
     ))
 }
 
+/// History cell for an approval that also saved an "always allow" rule for
+/// the current project.
+pub fn new_approval_rule_decision_cell(
+    command: Vec<String>,
+    rule: &codex_core::protocol::ApprovalRule,
+) -> Box<dyn HistoryCell> {
+    let snippet = Span::from(exec_snippet(&command)).dim();
+    let rule = codex_core::approval_rules::describe_rule(rule);
+    Box::new(PrefixedWrappedHistoryCell::new(
+        Line::from(vec![
+            "You ".into(),
+            "approved".bold(),
+            " codex to run ".into(),
+            snippet,
+            " and to always run ".into(),
+            rule.into(),
+            " in this project".bold(),
+        ]),
+        "✔ ".green(),
+        "  ",
+    ))
+}
+
 /// Cyan history cell line showing the current review status.
 pub(crate) fn new_review_status_line(message: String) -> PlainHistoryCell {
     PlainHistoryCell {
@@ -1081,6 +1105,7 @@ pub(crate) fn new_mcp_tools_output(
     resources: HashMap<String, Vec<Resource>>,
     resource_templates: HashMap<String, Vec<ResourceTemplate>>,
     auth_statuses: &HashMap<String, McpAuthStatus>,
+    auth_expirations: &HashMap<String, i64>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = vec![
         "/mcp".magenta().into(),
@@ -1121,7 +1146,8 @@ pub(crate) fn new_mcp_tools_output(
         }
         lines.push(header.into());
         lines.push(vec!["    • Status: ".into(), "enabled".green()].into());
-        lines.push(vec!["    • Auth: ".into(), auth_status.to_string().into()].into());
+        let auth = describe_auth_status(auth_status, auth_expirations.get(server).copied());
+        lines.push(vec!["    • Auth: ".into(), auth.into()].into());
 
         match &cfg.transport {
             McpServerTransportConfig::Stdio {
@@ -1526,6 +1552,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         };
         config.mcp_servers.insert("docs".to_string(), stdio_config);
 
@@ -1545,6 +1572,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         };
         config.mcp_servers.insert("http".to_string(), http_config);
 
@@ -1587,6 +1615,7 @@ mod tests {
             HashMap::new(),
             HashMap::new(),
             &auth_statuses,
+            &HashMap::new(),
         );
         let rendered = render_lines(&cell.display_lines(120)).join("\n");
 
@@ -590,7 +590,10 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig {
+            trust_level: None,
+            execpolicy_files: None,
+        };
         set_windows_sandbox_enabled(false);
 
         let should_show = should_show_trust_screen(&config);
@@ -617,7 +620,10 @@ mod tests {
             temp_dir.path().to_path_buf(),
         )?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig {
+            trust_level: None,
+            execpolicy_files: None,
+        };
         set_windows_sandbox_enabled(true);
 
         let should_show = should_show_trust_screen(&config);
@@ -695,6 +695,7 @@ mod tests {
             PathBuf::from("foo.txt"),
             FileChange::Add {
                 content: "hello\nworld\n".to_string(),
+                mode: None,
             },
         );
         let approval_cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(approval_changes, &cwd));
@@ -705,6 +706,7 @@ mod tests {
             PathBuf::from("foo.txt"),
             FileChange::Add {
                 content: "hello\nworld\n".to_string(),
+                mode: None,
             },
         );
         let apply_begin_cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(apply_changes, &cwd));
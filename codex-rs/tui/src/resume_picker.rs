@@ -10,6 +10,7 @@ use codex_core::ConversationsPage;
 use codex_core::Cursor;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
 use codex_core::RolloutRecorder;
+use codex_core::SessionSearchHit;
 use codex_protocol::items::TurnItem;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
@@ -35,6 +36,7 @@ use codex_protocol::models::ResponseItem;
 
 const PAGE_SIZE: usize = 25;
 const LOAD_NEAR_THRESHOLD: usize = 5;
+const SEARCH_RESULT_LIMIT: usize = 50;
 
 #[derive(Debug, Clone)]
 pub enum ResumeSelection {
@@ -54,17 +56,30 @@ struct PageLoadRequest {
 
 type PageLoader = Arc<dyn Fn(PageLoadRequest) + Send + Sync>;
 
+#[derive(Clone)]
+struct SearchRequest {
+    codex_home: PathBuf,
+    query: String,
+    default_provider: String,
+}
+
+type SearchLoader = Arc<dyn Fn(SearchRequest) + Send + Sync>;
+
 enum BackgroundEvent {
     PageLoaded {
         request_token: usize,
         search_token: Option<usize>,
         page: std::io::Result<ConversationsPage>,
     },
+    SearchCompleted {
+        query: String,
+        hits: std::io::Result<Vec<SessionSearchHit>>,
+    },
 }
 
-/// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// Interactive session picker that lists recorded rollout files with
+/// full-text search and pagination. Shows the first user input as the
+/// preview, relative time (e.g., "5 seconds ago"), and the absolute path.
 pub async fn run_resume_picker(
     tui: &mut Tui,
     codex_home: &Path,
@@ -97,10 +112,32 @@ pub async fn run_resume_picker(
         });
     });
 
+    let search_tx = bg_tx.clone();
+    let search_loader: SearchLoader = Arc::new(move |request: SearchRequest| {
+        let tx = search_tx.clone();
+        tokio::spawn(async move {
+            let provider_filter = vec![request.default_provider.clone()];
+            let hits = RolloutRecorder::search_conversations(
+                &request.codex_home,
+                &request.query,
+                SEARCH_RESULT_LIMIT,
+                INTERACTIVE_SESSION_SOURCES,
+                Some(provider_filter.as_slice()),
+                request.default_provider.as_str(),
+            )
+            .await;
+            let _ = tx.send(BackgroundEvent::SearchCompleted {
+                query: request.query,
+                hits,
+            });
+        });
+    });
+
     let mut state = PickerState::new(
         codex_home.to_path_buf(),
         alt.tui.frame_requester(),
         page_loader,
+        search_loader,
         default_provider.clone(),
     );
     state.load_initial_page().await?;
@@ -172,9 +209,12 @@ struct PickerState {
     scroll_top: usize,
     query: String,
     search_state: SearchState,
+    /// Sessions matching `query` in the full-text index, best match first.
+    search_hits: Vec<Row>,
     next_request_token: usize,
     next_search_token: usize,
     page_loader: PageLoader,
+    search_loader: SearchLoader,
     view_rows: Option<usize>,
     default_provider: String,
 }
@@ -241,6 +281,7 @@ impl PickerState {
         codex_home: PathBuf,
         requester: FrameRequester,
         page_loader: PageLoader,
+        search_loader: SearchLoader,
         default_provider: String,
     ) -> Self {
         Self {
@@ -259,9 +300,11 @@ impl PickerState {
             scroll_top: 0,
             query: String::new(),
             search_state: SearchState::Idle,
+            search_hits: Vec::new(),
             next_request_token: 0,
             next_search_token: 0,
             page_loader,
+            search_loader,
             view_rows: None,
             default_provider,
         }
@@ -382,6 +425,19 @@ impl PickerState {
                 let completed_token = pending.search_token.or(search_token);
                 self.continue_search_if_token_matches(completed_token);
             }
+            BackgroundEvent::SearchCompleted { query, hits } => {
+                if query != self.query {
+                    return Ok(());
+                }
+                match hits {
+                    Ok(hits) => {
+                        self.search_hits = hits.iter().map(row_from_search_hit).collect();
+                        self.apply_filter();
+                    }
+                    // Keep the preview-only filter usable if the index is unreadable.
+                    Err(err) => tracing::warn!("session search failed: {err}"),
+                }
+            }
         }
         Ok(())
     }
@@ -428,6 +484,13 @@ impl PickerState {
                 .filter(|r| r.preview.to_lowercase().contains(&q))
                 .cloned()
                 .collect();
+            let mut shown: HashSet<PathBuf> =
+                self.filtered_rows.iter().map(|r| r.path.clone()).collect();
+            for row in &self.search_hits {
+                if shown.insert(row.path.clone()) {
+                    self.filtered_rows.push(row.clone());
+                }
+            }
         }
         if self.selected >= self.filtered_rows.len() {
             self.selected = self.filtered_rows.len().saturating_sub(1);
@@ -445,11 +508,17 @@ impl PickerState {
         }
         self.query = new_query;
         self.selected = 0;
+        self.search_hits.clear();
         self.apply_filter();
         if self.query.is_empty() {
             self.search_state = SearchState::Idle;
             return;
         }
+        (self.search_loader)(SearchRequest {
+            codex_home: self.codex_home.clone(),
+            query: self.query.clone(),
+            default_provider: self.default_provider.clone(),
+        });
         if !self.filtered_rows.is_empty() {
             self.search_state = SearchState::Idle;
             return;
@@ -619,6 +688,17 @@ fn head_to_row(item: &ConversationItem) -> Row {
     }
 }
 
+fn row_from_search_hit(hit: &SessionSearchHit) -> Row {
+    let created_at = parse_timestamp_str(&hit.created_at);
+    let updated_at = parse_timestamp_str(&hit.updated_at).or(created_at);
+    Row {
+        path: hit.path.clone(),
+        preview: hit.preview.trim().to_string(),
+        created_at,
+        updated_at,
+    }
+}
+
 fn parse_timestamp_str(ts: &str) -> Option<DateTime<Utc>> {
     chrono::DateTime::parse_from_rfc3339(ts)
         .map(|dt| dt.with_timezone(&Utc))
@@ -1087,6 +1167,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
 
@@ -1147,6 +1228,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
 
@@ -1213,6 +1295,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
         state.reset_pagination();
@@ -1242,6 +1325,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
 
@@ -1290,6 +1374,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
 
@@ -1334,6 +1419,7 @@ mod tests {
             PathBuf::from("/tmp"),
             FrameRequester::test_dummy(),
             loader,
+            Arc::new(|_| {}),
             String::from("openai"),
         );
         state.reset_pagination();
@@ -1432,4 +1518,78 @@ mod tests {
         assert!(!state.search_state.is_active());
         assert!(state.pagination.reached_scan_cap);
     }
+
+    #[test]
+    fn set_query_merges_full_text_hits() {
+        let recorded_searches: Arc<Mutex<Vec<SearchRequest>>> = Arc::new(Mutex::new(Vec::new()));
+        let search_sink = recorded_searches.clone();
+        let search_loader: SearchLoader = Arc::new(move |req: SearchRequest| {
+            search_sink.lock().unwrap().push(req);
+        });
+
+        let mut state = PickerState::new(
+            PathBuf::from("/tmp"),
+            FrameRequester::test_dummy(),
+            Arc::new(|_| {}),
+            search_loader,
+            String::from("openai"),
+        );
+        state.reset_pagination();
+        state.ingest_page(page(
+            vec![
+                make_item("/tmp/a.jsonl", "2025-01-02T00:00:00Z", "watcher cleanup"),
+                make_item("/tmp/b.jsonl", "2025-01-01T00:00:00Z", "release notes"),
+            ],
+            None,
+            2,
+            false,
+        ));
+
+        state.set_query("watcher".to_string());
+        let queries: Vec<String> = recorded_searches
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|req| req.query.clone())
+            .collect();
+        assert_eq!(queries, vec!["watcher".to_string()]);
+
+        let hit = |path: &str, preview: &str| SessionSearchHit {
+            path: PathBuf::from(path),
+            conversation_id: codex_protocol::ConversationId::new(),
+            preview: preview.to_string(),
+            model_provider: Some("openai".to_string()),
+            created_at: "2024-12-01T00:00:00Z".to_string(),
+            updated_at: "2024-12-01T00:00:00Z".to_string(),
+            matches: Vec::new(),
+        };
+
+        // Results for a stale query are ignored.
+        state
+            .handle_background_event(BackgroundEvent::SearchCompleted {
+                query: "watch".to_string(),
+                hits: Ok(vec![hit("/tmp/stale.jsonl", "stale")]),
+            })
+            .unwrap();
+        state
+            .handle_background_event(BackgroundEvent::SearchCompleted {
+                query: "watcher".to_string(),
+                hits: Ok(vec![
+                    hit("/tmp/b.jsonl", "release notes"),
+                    hit("/tmp/a.jsonl", "watcher cleanup"),
+                    hit("/tmp/old.jsonl", "fix flaky tests"),
+                ]),
+            })
+            .unwrap();
+
+        let paths: Vec<&str> = state
+            .filtered_rows
+            .iter()
+            .map(|row| row.path.to_str().unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["/tmp/a.jsonl", "/tmp/b.jsonl", "/tmp/old.jsonl"]
+        );
+    }
 }
@@ -47,7 +47,7 @@ impl SlashCommand {
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
-            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Mcp => "list MCP tools; restart, enable or disable a server",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -83,6 +83,12 @@ impl SlashCommand {
         }
     }
 
+    /// Whether the command accepts arguments after its name, e.g.
+    /// `/mcp restart <server>`.
+    pub fn takes_args(self) -> bool {
+        matches!(self, SlashCommand::Mcp)
+    }
+
     fn is_visible(self) -> bool {
         match self {
             SlashCommand::Rollout | SlashCommand::TestApproval => cfg!(debug_assertions),